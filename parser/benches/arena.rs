@@ -0,0 +1,113 @@
+//! Compares the borrowed, boxed AST with the arena AST.
+//!
+//! Run with `cargo bench --bench arena`. For each representation, this reports the time to build, clone and drop the
+//! tree of a generated unit, and the number of allocations and bytes that building it takes.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use hail_parser::intern::Interner;
+use hail_parser::{arena, grammar, scanner};
+
+/// The system allocator, counting every allocation.
+struct Counting;
+
+static ALLOCS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCS.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// The number of routines in the generated unit.
+const ROUTINES: usize = 2000;
+
+/// The number of times each measurement is repeated.
+const RUNS: u32 = 10;
+
+/// Generates a unit with a mix of declarations, expressions and control flow.
+fn source() -> String {
+    let mut source = String::from("type Point = struct { x <- i32, y <- i32 }\n");
+
+    for i in 0..ROUTINES {
+        source.push_str(&format!(
+            "val routine_{i} = routine(point <- &Point, count <- i32) -> i32 {{\n\
+            \x20   val total = point.x * {i} + point.y - count / 2\n\
+            \x20   if total > 10 && count != 0 {{ return total }} else if total < 0 {{ return -total }}\n\
+            \x20   while count > 0 {{ count -= 1; total = routine_{i}(point, count) }}\n\
+            \x20   return Point::{{ x = total, y = count }}.x\n\
+            }}\n",
+            i = i,
+        ));
+    }
+
+    source
+}
+
+/// Runs `f` several times, returning the mean time and the allocations and bytes of the last run.
+fn measure<T>(mut f: impl FnMut() -> T) -> (Duration, usize, usize) {
+    let mut total = Duration::ZERO;
+    let mut allocs = 0;
+    let mut bytes = 0;
+
+    for _ in 0..RUNS {
+        let before = (ALLOCS.load(Ordering::Relaxed), BYTES.load(Ordering::Relaxed));
+        let start = Instant::now();
+        let value = black_box(f());
+        total += start.elapsed();
+        allocs = ALLOCS.load(Ordering::Relaxed) - before.0;
+        bytes = BYTES.load(Ordering::Relaxed) - before.1;
+        drop(value);
+    }
+
+    (total / RUNS, allocs, bytes)
+}
+
+/// Times dropping a value made by `make`, leaving out the time taken to make it.
+fn measure_drop<T>(mut make: impl FnMut() -> T) -> Duration {
+    let mut total = Duration::ZERO;
+
+    for _ in 0..RUNS {
+        let value = make();
+        let start = Instant::now();
+        drop(black_box(value));
+        total += start.elapsed();
+    }
+
+    total / RUNS
+}
+
+fn report(name: &str, (time, allocs, bytes): (Duration, usize, usize)) {
+    println!("{:<24} {:>10.2?} {:>10} allocs {:>12} bytes", name, time, allocs, bytes);
+}
+
+fn main() {
+    let source = source();
+    let parser = grammar::RootStmntsParser::new();
+    let parse = || parser.parse(&source, scanner::Asi::lex(&source)).unwrap();
+    let ast = parse();
+    let unit = arena::lower(&ast, &mut Interner::new());
+
+    println!("{} bytes of source, {} nodes\n", source.len(), unit.nodes);
+
+    report("parse (boxed)", measure(parse));
+    report("lower (arena)", measure(|| arena::lower(&ast, &mut Interner::new())));
+    report("parse + lower (arena)", measure(|| arena::lower(&parse(), &mut Interner::new())));
+    report("clone (boxed)", measure(|| ast.clone()));
+    report("clone (arena)", measure(|| unit.clone()));
+    println!("{:<24} {:>10.2?}", "drop (boxed)", measure_drop(|| ast.clone()));
+    println!("{:<24} {:>10.2?}", "drop (arena)", measure_drop(|| unit.clone()));
+}
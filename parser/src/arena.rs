@@ -0,0 +1,3274 @@
+//! An arena-allocated AST, which owns its identifiers and can outlive the source that it was parsed from.
+//!
+//! The parser's tree borrows every identifier from the source and boxes every recursive child. [`lower`] converts it
+//! into the types in this module, which mirror those in [`ast`](crate::ast) with three differences:
+//!
+//! - Identifiers, numbers and strings are interned as [`Symbol`]s, so a tree only needs its [`Interner`].
+//! - Recursive children are stored in an [`Arena`] and referred to by [`Ref`]s, rather than boxed one by one.
+//! - Every node has a [`NodeId`], numbered parents first in source order, so later passes can keep side tables such
+//!   as types and resolutions in a `Vec` indexed by [`NodeId::index`].
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops;
+
+use crate::ast;
+use crate::intern::{Interner, Symbol};
+use crate::spanned::Spanned;
+
+pub use crate::ast::{AssignOp, BinaryOp, NumKind, Span, UnaryOp};
+
+/// The ID of a node, unique within its unit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// The index of this node, for side tables.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A node with a [`NodeId`].
+pub trait Node {
+    /// The ID of the node.
+    fn node_id(&self) -> NodeId;
+}
+
+/// A reference to a node stored in an [`Arena`].
+pub struct Ref<T> {
+    index: u32,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Ref<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Ref<T> {}
+
+impl<T> PartialEq for Ref<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for Ref<T> {}
+
+impl<T> Hash for Ref<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Ref<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ref({})", self.index)
+    }
+}
+
+/// A node which is stored in an [`Arena`], rather than inline in its parent.
+pub trait Pooled: Sized {
+    /// The nodes of this type in an arena.
+    fn pool(arena: &Arena) -> &Vec<Self>;
+
+    /// The nodes of this type in an arena, for allocating.
+    fn pool_mut(arena: &mut Arena) -> &mut Vec<Self>;
+}
+
+/// The storage for the recursive nodes of a unit.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Arena {
+    /// The global paths of the unit that are referred to by [`Ref`]s.
+    global_paths: Vec<GlobalPath>,
+
+    /// The types of the unit that are referred to by [`Ref`]s.
+    types: Vec<Type>,
+
+    /// The exprs of the unit that are referred to by [`Ref`]s.
+    exprs: Vec<Expr>,
+
+    /// The block exprs of the unit that are referred to by [`Ref`]s.
+    block_exprs: Vec<BlockExpr>,
+
+    /// The flag exprs of the unit that are referred to by [`Ref`]s.
+    flag_exprs: Vec<FlagExpr>,
+}
+
+impl Arena {
+    /// Stores a node, returning a reference to it.
+    pub fn alloc<T: Pooled>(&mut self, node: T) -> Ref<T> {
+        let pool = T::pool_mut(self);
+        pool.push(node);
+
+        Ref { index: (pool.len() - 1) as u32, marker: PhantomData }
+    }
+}
+
+impl<T: Pooled> ops::Index<Ref<T>> for Arena {
+    type Output = T;
+
+    fn index(&self, node: Ref<T>) -> &T {
+        &T::pool(self)[node.index as usize]
+    }
+}
+
+impl Pooled for GlobalPath {
+    fn pool(arena: &Arena) -> &Vec<Self> {
+        &arena.global_paths
+    }
+
+    fn pool_mut(arena: &mut Arena) -> &mut Vec<Self> {
+        &mut arena.global_paths
+    }
+}
+
+impl Pooled for Type {
+    fn pool(arena: &Arena) -> &Vec<Self> {
+        &arena.types
+    }
+
+    fn pool_mut(arena: &mut Arena) -> &mut Vec<Self> {
+        &mut arena.types
+    }
+}
+
+impl Pooled for Expr {
+    fn pool(arena: &Arena) -> &Vec<Self> {
+        &arena.exprs
+    }
+
+    fn pool_mut(arena: &mut Arena) -> &mut Vec<Self> {
+        &mut arena.exprs
+    }
+}
+
+impl Pooled for BlockExpr {
+    fn pool(arena: &Arena) -> &Vec<Self> {
+        &arena.block_exprs
+    }
+
+    fn pool_mut(arena: &mut Arena) -> &mut Vec<Self> {
+        &mut arena.block_exprs
+    }
+}
+
+impl Pooled for FlagExpr {
+    fn pool(arena: &Arena) -> &Vec<Self> {
+        &arena.flag_exprs
+    }
+
+    fn pool_mut(arena: &mut Arena) -> &mut Vec<Self> {
+        &mut arena.flag_exprs
+    }
+}
+
+/// A unit lowered into an arena.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    /// The storage for the recursive nodes of the unit.
+    pub arena: Arena,
+
+    /// The statements in the root of the unit.
+    pub items: Vec<RootStmnt>,
+
+    /// The number of nodes in the unit.
+    pub nodes: usize,
+}
+
+/// Lowers a unit from the parser into an arena, interning its identifiers into `interner`.
+///
+/// Units of the same session should share an interner, so that their symbols can be compared.
+pub fn lower(items: &[ast::RootStmnt], interner: &mut Interner) -> Unit {
+    let mut lower = Lower { arena: Arena::default(), interner, nodes: 0 };
+    let items = items.iter().map(|item| lower.lower_root_stmnt(item)).collect();
+
+    Unit { arena: lower.arena, items, nodes: lower.nodes as usize }
+}
+
+/// The state of [`lower`].
+struct Lower<'i> {
+    arena: Arena,
+    interner: &'i mut Interner,
+    nodes: u32,
+}
+
+impl Lower<'_> {
+    /// Numbers the next node. Parents are numbered before their children, so IDs are in source order.
+    fn node_id(&mut self) -> NodeId {
+        self.nodes += 1;
+        NodeId(self.nodes - 1)
+    }
+
+    /// Lowers a child that the borrowed tree boxes, and stores it in the arena.
+    fn boxed<A, T: Pooled>(&mut self, node: &A, lower: fn(&mut Self, &A) -> T) -> Ref<T> {
+        let node = lower(self, node);
+        self.arena.alloc(node)
+    }
+
+    fn lower_bool(&mut self, node: &ast::Bool) -> Bool {
+        Bool {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            value: node.value,
+        }
+    }
+
+    fn lower_id(&mut self, node: &ast::Id<'_>) -> Id {
+        Id {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            value: self.interner.intern(node.value),
+        }
+    }
+
+    fn lower_num(&mut self, node: &ast::Num<'_>) -> Num {
+        Num {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            kind: node.kind.clone(),
+            value: self.interner.intern(node.value),
+        }
+    }
+
+    fn lower_str(&mut self, node: &ast::Str<'_>) -> Str {
+        Str {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            value: self.interner.intern(&node.value),
+        }
+    }
+
+    fn lower_global_path(&mut self, node: &ast::GlobalPath<'_>) -> GlobalPath {
+        match node {
+            ast::GlobalPath::Id(id) => GlobalPath::Id(self.lower_id(id)),
+            ast::GlobalPath::Branch { span, path, id } => GlobalPath::Branch {
+                span: span.clone(),
+                node_id: self.node_id(),
+                path: self.boxed(&**path, Self::lower_global_path),
+                id: self.lower_id(id),
+            },
+        }
+    }
+
+    fn lower_marker(&mut self, node: &ast::Marker<'_>) -> Marker {
+        Marker {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            name: self.lower_id(&node.name),
+            args: node.args.iter().map(|expr| self.lower_expr(expr)).collect(),
+        }
+    }
+
+    fn lower_routine_arg(&mut self, node: &ast::RoutineArg<'_>) -> RoutineArg {
+        RoutineArg {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            name: self.lower_id(&node.name),
+            ty: self.lower_type(&node.ty),
+        }
+    }
+
+    fn lower_routine(&mut self, node: &ast::Routine<'_>) -> Routine {
+        Routine {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            args: node.args.iter().map(|routine_arg| self.lower_routine_arg(routine_arg)).collect(),
+            returns: node.returns.as_ref().map(|ty| self.lower_type(ty)),
+            markers: node.markers.iter().map(|marker| self.lower_marker(marker)).collect(),
+            block: self.lower_block(&node.block),
+        }
+    }
+
+    fn lower_path_type(&mut self, node: &ast::PathType<'_>) -> PathType {
+        PathType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            left: self.boxed(&*node.left, Self::lower_type),
+            right: self.lower_id(&node.right),
+        }
+    }
+
+    fn lower_routine_type(&mut self, node: &ast::RoutineType<'_>) -> RoutineType {
+        RoutineType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            args: node.args.iter().map(|ty| self.lower_type(ty)).collect(),
+            returns: node.returns.as_ref().map(|ty| self.boxed(&**ty, Self::lower_type)),
+        }
+    }
+
+    fn lower_struct_prop(&mut self, node: &ast::StructProp<'_>) -> StructProp {
+        StructProp {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            markers: node.markers.iter().map(|marker| self.lower_marker(marker)).collect(),
+            shared: node.shared,
+            name: self.lower_id(&node.name),
+            ty: self.lower_type(&node.ty),
+            bits: node.bits.as_ref().map(|num| self.lower_num(num)),
+        }
+    }
+
+    fn lower_struct_type(&mut self, node: &ast::StructType<'_>) -> StructType {
+        StructType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            props: node.props.iter().map(|struct_prop| self.lower_struct_prop(struct_prop)).collect(),
+        }
+    }
+
+    fn lower_union_type(&mut self, node: &ast::UnionType<'_>) -> UnionType {
+        UnionType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            props: node.props.iter().map(|struct_prop| self.lower_struct_prop(struct_prop)).collect(),
+        }
+    }
+
+    fn lower_enum_prop(&mut self, node: &ast::EnumProp<'_>) -> EnumProp {
+        EnumProp {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            markers: node.markers.iter().map(|marker| self.lower_marker(marker)).collect(),
+            name: self.lower_id(&node.name),
+            ty: node.ty.as_ref().map(|ty| self.lower_type(ty)),
+            value: node.value.as_ref().map(|expr| self.lower_expr(expr)),
+        }
+    }
+
+    fn lower_enum_type(&mut self, node: &ast::EnumType<'_>) -> EnumType {
+        EnumType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            props: node.props.iter().map(|enum_prop| self.lower_enum_prop(enum_prop)).collect(),
+        }
+    }
+
+    fn lower_contract_type(&mut self, node: &ast::ContractType<'_>) -> ContractType {
+        ContractType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            items: node.items.iter().map(|application| self.lower_application(application)).collect(),
+        }
+    }
+
+    fn lower_shared_type(&mut self, node: &ast::SharedType<'_>) -> SharedType {
+        SharedType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_type),
+        }
+    }
+
+    fn lower_fluid_type(&mut self, node: &ast::FluidType<'_>) -> FluidType {
+        FluidType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_type),
+        }
+    }
+
+    fn lower_ref_type(&mut self, node: &ast::RefType<'_>) -> RefType {
+        RefType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_type),
+        }
+    }
+
+    fn lower_dyn_type(&mut self, node: &ast::DynType<'_>) -> DynType {
+        DynType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_type),
+        }
+    }
+
+    fn lower_variadic_type(&mut self, node: &ast::VariadicType<'_>) -> VariadicType {
+        VariadicType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            ty: node.ty.as_ref().map(|ty| self.boxed(&**ty, Self::lower_type)),
+        }
+    }
+
+    fn lower_opt_type(&mut self, node: &ast::OptType<'_>) -> OptType {
+        OptType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_type),
+        }
+    }
+
+    fn lower_res_type(&mut self, node: &ast::ResType<'_>) -> ResType {
+        ResType {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            ok: self.boxed(&*node.ok, Self::lower_type),
+            err: self.boxed(&*node.err, Self::lower_type),
+        }
+    }
+
+    fn lower_type(&mut self, node: &ast::Type<'_>) -> Type {
+        match node {
+            ast::Type::Id(id) => Type::Id(self.lower_id(id)),
+            ast::Type::Path(path_type) => Type::Path(self.lower_path_type(path_type)),
+            ast::Type::Routine(routine_type) => Type::Routine(self.lower_routine_type(routine_type)),
+            ast::Type::Closure(routine_type) => Type::Closure(self.lower_routine_type(routine_type)),
+            ast::Type::Struct(struct_type) => Type::Struct(self.lower_struct_type(struct_type)),
+            ast::Type::Union(union_type) => Type::Union(self.lower_union_type(union_type)),
+            ast::Type::Enum(enum_type) => Type::Enum(self.lower_enum_type(enum_type)),
+            ast::Type::Contract(contract_type) => Type::Contract(self.lower_contract_type(contract_type)),
+            ast::Type::Shared(shared_type) => Type::Shared(self.lower_shared_type(shared_type)),
+            ast::Type::Fluid(fluid_type) => Type::Fluid(self.lower_fluid_type(fluid_type)),
+            ast::Type::Ref(ref_type) => Type::Ref(self.lower_ref_type(ref_type)),
+            ast::Type::Dyn(dyn_type) => Type::Dyn(self.lower_dyn_type(dyn_type)),
+            ast::Type::Variadic(variadic_type) => Type::Variadic(self.lower_variadic_type(variadic_type)),
+            ast::Type::Opt(opt_type) => Type::Opt(self.lower_opt_type(opt_type)),
+            ast::Type::Res(res_type) => Type::Res(self.lower_res_type(res_type)),
+        }
+    }
+
+    fn lower_path(&mut self, node: &ast::Path<'_>) -> Path {
+        Path {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            left: self.boxed(&*node.left, Self::lower_expr),
+            right: self.lower_id(&node.right),
+        }
+    }
+
+    fn lower_access(&mut self, node: &ast::Access<'_>) -> Access {
+        Access {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            left: self.boxed(&*node.left, Self::lower_expr),
+            right: self.lower_id(&node.right),
+        }
+    }
+
+    fn lower_call(&mut self, node: &ast::Call<'_>) -> Call {
+        Call {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            left: self.boxed(&*node.left, Self::lower_expr),
+            args: node.args.iter().map(|expr| self.lower_expr(expr)).collect(),
+        }
+    }
+
+    fn lower_unary(&mut self, node: &ast::Unary<'_>) -> Unary {
+        Unary {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            op: node.op.clone(),
+            subject: self.boxed(&*node.subject, Self::lower_expr),
+        }
+    }
+
+    fn lower_as(&mut self, node: &ast::As<'_>) -> As {
+        As {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_expr),
+            ty: self.lower_type(&node.ty),
+        }
+    }
+
+    fn lower_binary(&mut self, node: &ast::Binary<'_>) -> Binary {
+        Binary {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            op: node.op.clone(),
+            left: self.boxed(&*node.left, Self::lower_expr),
+            right: self.boxed(&*node.right, Self::lower_expr),
+        }
+    }
+
+    fn lower_construct_prop(&mut self, node: &ast::ConstructProp<'_>) -> ConstructProp {
+        ConstructProp {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            name: self.lower_id(&node.name),
+            value: self.lower_expr(&node.value),
+        }
+    }
+
+    fn lower_construct(&mut self, node: &ast::Construct<'_>) -> Construct {
+        Construct {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_expr),
+            items: node.items.iter().map(|construct_prop| self.lower_construct_prop(construct_prop)).collect(),
+        }
+    }
+
+    fn lower_construct_enum(&mut self, node: &ast::ConstructEnum<'_>) -> ConstructEnum {
+        ConstructEnum {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_expr),
+            item: self.boxed(&*node.item, Self::lower_expr),
+        }
+    }
+
+    fn lower_wrap(&mut self, node: &ast::Wrap<'_>) -> Wrap {
+        Wrap {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            item: self.boxed(&*node.item, Self::lower_expr),
+        }
+    }
+
+    fn lower_none_lit(&mut self, node: &ast::NoneLit) -> NoneLit {
+        NoneLit {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+        }
+    }
+
+    fn lower_try(&mut self, node: &ast::Try<'_>) -> Try {
+        Try {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_expr),
+        }
+    }
+
+    fn lower_index(&mut self, node: &ast::Index<'_>) -> Index {
+        Index {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.boxed(&*node.subject, Self::lower_expr),
+            index: self.boxed(&*node.index, Self::lower_expr),
+        }
+    }
+
+    fn lower_asm_operand(&mut self, node: &ast::AsmOperand<'_>) -> AsmOperand {
+        AsmOperand {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            constraint: self.lower_str(&node.constraint),
+            expr: self.lower_expr(&node.expr),
+        }
+    }
+
+    fn lower_asm(&mut self, node: &ast::Asm<'_>) -> Asm {
+        Asm {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            template: self.lower_str(&node.template),
+            outputs: node.outputs.iter().map(|asm_operand| self.lower_asm_operand(asm_operand)).collect(),
+            inputs: node.inputs.iter().map(|asm_operand| self.lower_asm_operand(asm_operand)).collect(),
+            clobbers: node.clobbers.iter().map(|str| self.lower_str(str)).collect(),
+            options: node.options.iter().map(|id| self.lower_id(id)).collect(),
+        }
+    }
+
+    fn lower_expr(&mut self, node: &ast::Expr<'_>) -> Expr {
+        match node {
+            ast::Expr::Bool(bool) => Expr::Bool(self.lower_bool(bool)),
+            ast::Expr::Id(id) => Expr::Id(self.lower_id(id)),
+            ast::Expr::Num(num) => Expr::Num(self.lower_num(num)),
+            ast::Expr::Str(str) => Expr::Str(self.lower_str(str)),
+            ast::Expr::Path(path) => Expr::Path(self.lower_path(path)),
+            ast::Expr::Access(access) => Expr::Access(self.lower_access(access)),
+            ast::Expr::Call(call) => Expr::Call(self.lower_call(call)),
+            ast::Expr::Unary(unary) => Expr::Unary(self.lower_unary(unary)),
+            ast::Expr::As(as_) => Expr::As(self.lower_as(as_)),
+            ast::Expr::Binary(binary) => Expr::Binary(self.lower_binary(binary)),
+            ast::Expr::Routine(routine) => Expr::Routine(self.lower_routine(routine)),
+            ast::Expr::Construct(construct) => Expr::Construct(self.lower_construct(construct)),
+            ast::Expr::ConstructEnum(construct_enum) => Expr::ConstructEnum(self.lower_construct_enum(construct_enum)),
+            ast::Expr::Asm(asm) => Expr::Asm(self.lower_asm(asm)),
+            ast::Expr::Ok(wrap) => Expr::Ok(self.lower_wrap(wrap)),
+            ast::Expr::Err(wrap) => Expr::Err(self.lower_wrap(wrap)),
+            ast::Expr::None(none_lit) => Expr::None(self.lower_none_lit(none_lit)),
+            ast::Expr::Try(try_) => Expr::Try(self.lower_try(try_)),
+            ast::Expr::Index(index) => Expr::Index(self.lower_index(index)),
+        }
+    }
+
+    fn lower_assign(&mut self, node: &ast::Assign<'_>) -> Assign {
+        Assign {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            op: node.op.clone(),
+            left: self.lower_expr(&node.left),
+            right: self.lower_expr(&node.right),
+        }
+    }
+
+    fn lower_val(&mut self, node: &ast::Val<'_>) -> Val {
+        Val {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            markers: node.markers.iter().map(|marker| self.lower_marker(marker)).collect(),
+            shared: node.shared,
+            name: self.lower_id(&node.name),
+            ty: node.ty.as_ref().map(|ty| self.lower_type(ty)),
+            value: node.value.as_ref().map(|expr| self.lower_expr(expr)),
+        }
+    }
+
+    fn lower_const(&mut self, node: &ast::Const<'_>) -> Const {
+        Const {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            markers: node.markers.iter().map(|marker| self.lower_marker(marker)).collect(),
+            shared: node.shared,
+            name: self.lower_id(&node.name),
+            ty: node.ty.as_ref().map(|ty| self.lower_type(ty)),
+            value: self.lower_expr(&node.value),
+        }
+    }
+
+    fn lower_type_decl(&mut self, node: &ast::TypeDecl<'_>) -> TypeDecl {
+        TypeDecl {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            markers: node.markers.iter().map(|marker| self.lower_marker(marker)).collect(),
+            shared: node.shared,
+            name: self.lower_id(&node.name),
+            value: node.value.as_ref().map(|ty| self.lower_type(ty)),
+        }
+    }
+
+    fn lower_if_branch(&mut self, node: &ast::IfBranch<'_>) -> IfBranch {
+        match node {
+            ast::IfBranch::ElseIf { span, cond, block } => IfBranch::ElseIf {
+                span: span.clone(),
+                node_id: self.node_id(),
+                cond: self.lower_expr(cond),
+                block: self.lower_block(block),
+            },
+            ast::IfBranch::Else { span, block } => IfBranch::Else {
+                span: span.clone(),
+                node_id: self.node_id(),
+                block: self.lower_block(block),
+            },
+        }
+    }
+
+    fn lower_if(&mut self, node: &ast::If<'_>) -> If {
+        If {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            cond: self.lower_expr(&node.cond),
+            block: self.lower_block(&node.block),
+            branches: node.branches.iter().map(|if_branch| self.lower_if_branch(if_branch)).collect(),
+        }
+    }
+
+    fn lower_while(&mut self, node: &ast::While<'_>) -> While {
+        While {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            label: node.label.as_ref().map(|id| self.lower_id(id)),
+            cond: self.lower_expr(&node.cond),
+            block: self.lower_block(&node.block),
+        }
+    }
+
+    fn lower_match_case(&mut self, node: &ast::MatchCase<'_>) -> MatchCase {
+        MatchCase {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            name: self.lower_id(&node.name),
+            ty: self.lower_type(&node.ty),
+            block: self.lower_block(&node.block),
+        }
+    }
+
+    fn lower_match(&mut self, node: &ast::Match<'_>) -> Match {
+        Match {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            subject: self.lower_expr(&node.subject),
+            cases: node.cases.iter().map(|match_case| self.lower_match_case(match_case)).collect(),
+        }
+    }
+
+    fn lower_break(&mut self, node: &ast::Break<'_>) -> Break {
+        Break {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            label: node.label.as_ref().map(|id| self.lower_id(id)),
+        }
+    }
+
+    fn lower_continue(&mut self, node: &ast::Continue<'_>) -> Continue {
+        Continue {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            label: node.label.as_ref().map(|id| self.lower_id(id)),
+        }
+    }
+
+    fn lower_return(&mut self, node: &ast::Return<'_>) -> Return {
+        Return {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            value: node.value.as_ref().map(|expr| self.lower_expr(expr)),
+        }
+    }
+
+    fn lower_defer(&mut self, node: &ast::Defer<'_>) -> Defer {
+        Defer {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            item: self.boxed(&*node.item, Self::lower_block_expr),
+        }
+    }
+
+    fn lower_flag_expr(&mut self, node: &ast::FlagExpr<'_>) -> FlagExpr {
+        match node {
+            ast::FlagExpr::Name(id) => FlagExpr::Name(self.lower_id(id)),
+            ast::FlagExpr::Value { span, name, value } => FlagExpr::Value {
+                span: span.clone(),
+                node_id: self.node_id(),
+                name: self.lower_id(name),
+                value: self.lower_str(value),
+            },
+            ast::FlagExpr::Any { span, items } => FlagExpr::Any {
+                span: span.clone(),
+                node_id: self.node_id(),
+                items: items.iter().map(|flag_expr| self.lower_flag_expr(flag_expr)).collect(),
+            },
+            ast::FlagExpr::All { span, items } => FlagExpr::All {
+                span: span.clone(),
+                node_id: self.node_id(),
+                items: items.iter().map(|flag_expr| self.lower_flag_expr(flag_expr)).collect(),
+            },
+            ast::FlagExpr::Not { span, item } => FlagExpr::Not {
+                span: span.clone(),
+                node_id: self.node_id(),
+                item: self.boxed(&**item, Self::lower_flag_expr),
+            },
+        }
+    }
+
+    fn lower_flag(&mut self, node: &ast::Flag<'_>) -> Flag {
+        Flag {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            neg: node.neg,
+            expr: self.lower_flag_expr(&node.expr),
+        }
+    }
+
+    fn lower_block_expr(&mut self, node: &ast::BlockExpr<'_>) -> BlockExpr {
+        match node {
+            ast::BlockExpr::Assign(assign) => BlockExpr::Assign(self.lower_assign(assign)),
+            ast::BlockExpr::Val(val) => BlockExpr::Val(self.lower_val(val)),
+            ast::BlockExpr::Call(call) => BlockExpr::Call(self.lower_call(call)),
+            ast::BlockExpr::If(if_) => BlockExpr::If(self.lower_if(if_)),
+            ast::BlockExpr::While(while_) => BlockExpr::While(self.lower_while(while_)),
+            ast::BlockExpr::Match(match_) => BlockExpr::Match(self.lower_match(match_)),
+            ast::BlockExpr::TypeDecl(type_decl) => BlockExpr::TypeDecl(self.lower_type_decl(type_decl)),
+            ast::BlockExpr::Break(break_) => BlockExpr::Break(self.lower_break(break_)),
+            ast::BlockExpr::Continue(continue_) => BlockExpr::Continue(self.lower_continue(continue_)),
+            ast::BlockExpr::Return(return_) => BlockExpr::Return(self.lower_return(return_)),
+            ast::BlockExpr::Defer(defer) => BlockExpr::Defer(self.lower_defer(defer)),
+            ast::BlockExpr::Asm(asm) => BlockExpr::Asm(self.lower_asm(asm)),
+            ast::BlockExpr::Block(flags, block) => BlockExpr::Block(
+                flags.iter().map(|flag| self.lower_flag(flag)).collect(),
+                self.lower_block(block),
+            ),
+        }
+    }
+
+    fn lower_block(&mut self, node: &ast::Block<'_>) -> Block {
+        Block {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            items: node.items.iter().map(|block_expr| self.lower_block_expr(block_expr)).collect(),
+        }
+    }
+
+    fn lower_import_item(&mut self, node: &ast::ImportItem<'_>) -> ImportItem {
+        ImportItem {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            name: self.lower_id(&node.name),
+            as_: node.as_.as_ref().map(|id| self.lower_id(id)),
+        }
+    }
+
+    fn lower_import(&mut self, node: &ast::Import<'_>) -> Import {
+        match node {
+            ast::Import::Single { span, shared, item, from } => Import::Single {
+                span: span.clone(),
+                node_id: self.node_id(),
+                shared: *shared,
+                item: self.lower_import_item(item),
+                from: from.as_ref().map(|global_path| self.lower_global_path(global_path)),
+            },
+            ast::Import::Multi { span, shared, items, from } => Import::Multi {
+                span: span.clone(),
+                node_id: self.node_id(),
+                shared: *shared,
+                items: items.iter().map(|import_item| self.lower_import_item(import_item)).collect(),
+                from: self.lower_global_path(from),
+            },
+            ast::Import::Glob { span, shared, from } => Import::Glob {
+                span: span.clone(),
+                node_id: self.node_id(),
+                shared: *shared,
+                from: self.lower_global_path(from),
+            },
+        }
+    }
+
+    fn lower_application(&mut self, node: &ast::Application<'_>) -> Application {
+        match node {
+            ast::Application::Val(val) => Application::Val(self.lower_val(val)),
+            ast::Application::TypeDecl(type_decl) => Application::TypeDecl(self.lower_type_decl(type_decl)),
+        }
+    }
+
+    fn lower_apply(&mut self, node: &ast::Apply<'_>) -> Apply {
+        Apply {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            markers: node.markers.iter().map(|marker| self.lower_marker(marker)).collect(),
+            subject: self.lower_global_path(&node.subject),
+            to: node.to.as_ref().map(|global_path| self.lower_global_path(global_path)),
+            items: node.items.iter().map(|application| self.lower_application(application)).collect(),
+        }
+    }
+
+    fn lower_module(&mut self, node: &ast::Module<'_>) -> Module {
+        Module {
+            span: node.span.clone(),
+            node_id: self.node_id(),
+            shared: node.shared,
+            name: self.lower_id(&node.name),
+            items: node.items.iter().map(|root_stmnt| self.lower_root_stmnt(root_stmnt)).collect(),
+        }
+    }
+
+    fn lower_root_stmnt(&mut self, node: &ast::RootStmnt<'_>) -> RootStmnt {
+        match node {
+            ast::RootStmnt::Assign(assign) => RootStmnt::Assign(self.lower_assign(assign)),
+            ast::RootStmnt::Val(flags, val) => RootStmnt::Val(
+                flags.iter().map(|flag| self.lower_flag(flag)).collect(),
+                self.lower_val(val),
+            ),
+            ast::RootStmnt::Const(flags, const_) => RootStmnt::Const(
+                flags.iter().map(|flag| self.lower_flag(flag)).collect(),
+                self.lower_const(const_),
+            ),
+            ast::RootStmnt::Call(call) => RootStmnt::Call(self.lower_call(call)),
+            ast::RootStmnt::Import(flags, import) => RootStmnt::Import(
+                flags.iter().map(|flag| self.lower_flag(flag)).collect(),
+                self.lower_import(import),
+            ),
+            ast::RootStmnt::If(if_) => RootStmnt::If(self.lower_if(if_)),
+            ast::RootStmnt::While(while_) => RootStmnt::While(self.lower_while(while_)),
+            ast::RootStmnt::Match(match_) => RootStmnt::Match(self.lower_match(match_)),
+            ast::RootStmnt::TypeDecl(flags, type_decl) => RootStmnt::TypeDecl(
+                flags.iter().map(|flag| self.lower_flag(flag)).collect(),
+                self.lower_type_decl(type_decl),
+            ),
+            ast::RootStmnt::Apply(flags, apply) => RootStmnt::Apply(
+                flags.iter().map(|flag| self.lower_flag(flag)).collect(),
+                self.lower_apply(apply),
+            ),
+            ast::RootStmnt::Block(flags, block) => RootStmnt::Block(
+                flags.iter().map(|flag| self.lower_flag(flag)).collect(),
+                self.lower_block(block),
+            ),
+            ast::RootStmnt::Module(flags, module) => RootStmnt::Module(
+                flags.iter().map(|flag| self.lower_flag(flag)).collect(),
+                self.lower_module(module),
+            ),
+        }
+    }
+}
+
+/// A boolean expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bool {
+    /// The span of the boolean.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The raw value of the boolean.
+    pub value: bool,
+}
+
+/// An identifier expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Id {
+    /// The span of the identifier.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The raw value of the identifier.
+    pub value: Symbol,
+}
+
+/// A number expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Num {
+    /// The span of the number.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The kind of the number.
+    pub kind: NumKind,
+
+    /// The raw value of the number.
+    pub value: Symbol,
+}
+
+/// A string expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Str {
+    /// The span of the string.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The raw value of the string.
+    pub value: Symbol,
+}
+
+/// A path expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GlobalPath {
+    /// A path node.
+    Id(Id),
+
+    /// A path branch.
+    Branch {
+        /// The span of the branch.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// The path to branch off of.
+        path: Ref<GlobalPath>,
+
+        /// The name of the branch.
+        id: Id,
+    },
+}
+
+/// A compiler marker, such as `#inline(always)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    /// The span of this marker.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The name of the marker.
+    pub name: Id,
+
+    /// The arguments of the marker.
+    pub args: Vec<Expr>,
+}
+
+/// An argument in a routine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoutineArg {
+    /// The span of the argument.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The name of the argument.
+    pub name: Id,
+
+    /// The type annotation of the argument.
+    pub ty: Type,
+}
+
+/// A routine declaration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Routine {
+    /// The span of the routine.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The name of the rout
+    pub args: Vec<RoutineArg>,
+
+    /// The value that this routine returns.
+    pub returns: Option<Type>,
+
+    /// A marker in the routine.
+    pub markers: Vec<Marker>,
+
+    /// The block of the routine.
+    pub block: Block,
+}
+
+/// A path in a type expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathType {
+    /// The span of the path.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The left side of the path.
+    pub left: Ref<Type>,
+
+    /// The right side of the path.
+    pub right: Id,
+}
+
+/// A routine type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoutineType {
+    /// The location of this routine.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The arguments of this routine.
+    pub args: Vec<Type>,
+
+    /// The type that this routine returns.
+    pub returns: Option<Ref<Type>>,
+}
+
+/// A struct property.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructProp {
+    /// The location of this property.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The markers of this property.
+    pub markers: Vec<Marker>,
+
+    /// Whether or not the property is shared with other modules.
+    pub shared: bool,
+
+    /// The value of the property.
+    pub name: Id,
+
+    /// The type of this property.
+    pub ty: Type,
+
+    /// The width of this property in bits, if it is a bitfield.
+    pub bits: Option<Num>,
+}
+
+/// A struct type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructType {
+    /// The location of this struct.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The properties of this struct.
+    pub props: Vec<StructProp>,
+}
+
+/// An untagged union type, whose properties all share the same memory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnionType {
+    /// The location of this union.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The properties of this union.
+    pub props: Vec<StructProp>,
+}
+
+/// An enum property.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumProp {
+    /// The location of this property.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The markers of this property.
+    pub markers: Vec<Marker>,
+
+    /// The value of the property.
+    pub name: Id,
+
+    /// The type of this property.
+    pub ty: Option<Type>,
+
+    /// The explicit discriminant of this property.
+    pub value: Option<Expr>,
+}
+
+/// An enum type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumType {
+    /// The location of this enum.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The properties of this enum.
+    pub props: Vec<EnumProp>,
+}
+
+/// A contract type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractType {
+    /// The location of this contract.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The properties of this contract.
+    pub items: Vec<Application>,
+}
+
+/// A shared type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedType {
+    /// The location of this shared type.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of this shared type.
+    pub subject: Ref<Type>,
+}
+
+/// A fluid type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FluidType {
+    /// The location of this fluid type.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of this fluid type.
+    pub subject: Ref<Type>,
+}
+
+/// A ref type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RefType {
+    /// The location of this ref type.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of this ref type.
+    pub subject: Ref<Type>,
+}
+
+/// A contract object type, `dyn Contract`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynType {
+    /// The location of this contract object type.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The contract of this contract object type.
+    pub subject: Ref<Type>,
+}
+
+/// The variadic arguments at the end of a routine's arguments, `...` for C variadics or `...VarArg` for hail
+/// variadics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariadicType {
+    /// The location of this variadic type.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The type that each argument is passed as, if these are hail variadics.
+    pub ty: Option<Ref<Type>>,
+}
+
+/// An option type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptType {
+    /// The location of this option type.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of this option type.
+    pub subject: Ref<Type>,
+}
+
+/// A result type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResType {
+    /// The location of this result type.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The left side of this result type.
+    pub ok: Ref<Type>,
+
+    /// The right side of this result type.
+    pub err: Ref<Type>,
+}
+
+/// A type expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Type {
+    Id(Id),
+    Path(PathType),
+    Routine(RoutineType),
+    Closure(RoutineType),
+    Struct(StructType),
+    Union(UnionType),
+    Enum(EnumType),
+    Contract(ContractType),
+    Shared(SharedType),
+    Fluid(FluidType),
+    Ref(RefType),
+    Dyn(DynType),
+    Variadic(VariadicType),
+    Opt(OptType),
+    Res(ResType),
+}
+
+/// A path expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    /// The span of the path.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The left side of the path.
+    pub left: Ref<Expr>,
+
+    /// The right side of this path.
+    pub right: Id,
+}
+
+/// An access expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Access {
+    /// The span of the access.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The left side of the access.
+    pub left: Ref<Expr>,
+
+    /// The right side of this access.
+    pub right: Id,
+}
+
+/// An call expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+    /// The span of the call.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The left side of the call.
+    pub left: Ref<Expr>,
+
+    /// The arguments of this call.
+    pub args: Vec<Expr>,
+}
+
+/// A unary expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unary {
+    /// The span of this expression.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The operator of this expression.
+    pub op: UnaryOp,
+
+    /// The subject of this expression.
+    pub subject: Ref<Expr>,
+}
+
+/// An `as` expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct As {
+    /// The location of the expressions.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of the expression.
+    pub subject: Ref<Expr>,
+
+    /// The type of the expression.
+    pub ty: Type,
+}
+
+/// A binary expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Binary {
+    /// The span of the expression.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The operator of the expression.
+    pub op: BinaryOp,
+
+    /// The left side of the expression.
+    pub left: Ref<Expr>,
+
+    /// The right side of the expression.
+    pub right: Ref<Expr>,
+}
+
+/// A property in a struct constructor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstructProp {
+    /// The span of the property.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The name of the property.
+    pub name: Id,
+
+    /// The value of the property.
+    pub value: Expr,
+}
+
+/// A struct constructor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Construct {
+    /// The span of the construct.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The name of the construct.
+    pub subject: Ref<Expr>,
+
+    /// The value of the construct.
+    pub items: Vec<ConstructProp>,
+}
+
+/// An enum constructor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstructEnum {
+    /// The span of the enum.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The name of the enum.
+    pub subject: Ref<Expr>,
+
+    /// The properties of the enum.
+    pub item: Ref<Expr>,
+}
+
+/// An `ok(value)` or `err(value)` constructor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wrap {
+    /// The span of the constructor.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The value being wrapped.
+    pub item: Ref<Expr>,
+}
+
+/// A `none` literal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoneLit {
+    /// The span of the literal.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+}
+
+/// A `?` expression, which unwraps an option or result and returns early on `none` or `err`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Try {
+    /// The span of the expression.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The option or result being unwrapped.
+    pub subject: Ref<Expr>,
+}
+
+/// An index expression, such as `list[i]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Index {
+    /// The span of the expression.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The value being indexed.
+    pub subject: Ref<Expr>,
+
+    /// The index.
+    pub index: Ref<Expr>,
+}
+
+/// An operand of an inline assembly block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsmOperand {
+    /// The span of the operand.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The constraint of the operand, such as `"=r"`.
+    pub constraint: Str,
+
+    /// The value bound to the operand.
+    pub expr: Expr,
+}
+
+/// An inline assembly block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Asm {
+    /// The span of the block.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The assembly template.
+    pub template: Str,
+
+    /// The output operands of the block.
+    pub outputs: Vec<AsmOperand>,
+
+    /// The input operands of the block.
+    pub inputs: Vec<AsmOperand>,
+
+    /// The registers and memory clobbered by the block.
+    pub clobbers: Vec<Str>,
+
+    /// The options of the block, such as `volatile`.
+    pub options: Vec<Id>,
+}
+
+/// An expression from hail source.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Bool(Bool),
+    Id(Id),
+    Num(Num),
+    Str(Str),
+    Path(Path),
+    Access(Access),
+    Call(Call),
+    Unary(Unary),
+    As(As),
+    Binary(Binary),
+    Routine(Routine),
+    Construct(Construct),
+    ConstructEnum(ConstructEnum),
+    Asm(Asm),
+    Ok(Wrap),
+    Err(Wrap),
+    None(NoneLit),
+    Try(Try),
+    Index(Index),
+}
+
+/// An assignment expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assign {
+    /// The span of the expression.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The assignment operator to use.
+    pub op: AssignOp,
+
+    /// The subject to assign to.
+    pub left: Expr,
+
+    /// The right side of the expression.
+    pub right: Expr,
+}
+
+/// A variable declaration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Val {
+    /// The span of the variable.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The markers of the variable.
+    pub markers: Vec<Marker>,
+
+    /// Whether or not the variable is shared with other modules.
+    pub shared: bool,
+
+    /// The name of the variable.
+    pub name: Id,
+
+    /// The type annotation of the variable.
+    pub ty: Option<Type>,
+
+    /// The value of the variable.
+    pub value: Option<Expr>,
+}
+
+/// A compile time constant declaration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Const {
+    /// The span of the constant.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The markers of the constant.
+    pub markers: Vec<Marker>,
+
+    /// Whether or not the constant is shared with other modules.
+    pub shared: bool,
+
+    /// The name of the constant.
+    pub name: Id,
+
+    /// The type annotation of the constant.
+    pub ty: Option<Type>,
+
+    /// The value of the constant.
+    pub value: Expr,
+}
+
+/// A type declaration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeDecl {
+    /// The span of the type declaration.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The markers of the type declaration.
+    pub markers: Vec<Marker>,
+
+    /// Whether or not the type is shared with other modules.
+    pub shared: bool,
+
+    /// The name of the type declaration.
+    pub name: Id,
+
+    /// The value of the type declaration.
+    pub value: Option<Type>,
+}
+
+/// A branch after an `if` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IfBranch {
+    /// An `else if` statement.
+    ElseIf {
+        /// The span of the `if` branch.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// The condition of the `if` branch.
+        cond: Expr,
+
+        /// The block of the `if` branch.
+        block: Block,
+    },
+
+    /// An `else` statement.
+    Else {
+        /// The span of the `if` branch.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// The block of the `if` branch.
+        block: Block,
+    },
+}
+
+/// An `if` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct If {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The condition of the statement.
+    pub cond: Expr,
+
+    /// The block of the statement.
+    pub block: Block,
+
+    /// The branches of the statement.
+    pub branches: Vec<IfBranch>,
+}
+
+/// A `while` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct While {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The label of the statement.
+    pub label: Option<Id>,
+
+    /// The condition of the statement.
+    pub cond: Expr,
+
+    /// The block of the statement.
+    pub block: Block,
+}
+
+/// A case in a match statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchCase {
+    /// The span of the case.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The name of the case.
+    pub name: Id,
+
+    /// The type of the case.
+    pub ty: Type,
+
+    /// The block of the case.
+    pub block: Block,
+}
+
+/// A `match` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of the statement.
+    pub subject: Expr,
+
+    /// The cases of the statement.
+    pub cases: Vec<MatchCase>,
+}
+
+/// A `break` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Break {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of the statement.
+    pub label: Option<Id>,
+}
+
+/// A `continue` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Continue {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of the statement.
+    pub label: Option<Id>,
+}
+
+/// A `return` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Return {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The subject of the statement.
+    pub value: Option<Expr>,
+}
+
+/// A `defer` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Defer {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The statement to run when the enclosing block exits.
+    pub item: Ref<BlockExpr>,
+}
+
+/// A compile time flag expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlagExpr {
+    /// A flag which is either set or not, such as `os_windows`.
+    Name(Id),
+
+    /// A key/value flag, such as `target_arch("x86_64")`.
+    Value {
+        /// The span of the flag.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// The key of the flag.
+        name: Id,
+
+        /// The value that the flag must have.
+        value: Str,
+    },
+
+    /// Satisfied if any of the flags are, such as `any(os_linux, os_macos)`.
+    Any {
+        /// The span of the flag.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// The flags to check.
+        items: Vec<FlagExpr>,
+    },
+
+    /// Satisfied if all of the flags are, such as `all(os_linux, debug)`.
+    All {
+        /// The span of the flag.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// The flags to check.
+        items: Vec<FlagExpr>,
+    },
+
+    /// Satisfied if the flag is not, such as `not(debug)`.
+    Not {
+        /// The span of the flag.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// The flag to check.
+        item: Ref<FlagExpr>,
+    },
+}
+
+/// Compile time flags, for conditional compilation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Flag {
+    /// The span of the flag.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// Whether or not the flag is negative.
+    pub neg: bool,
+
+    /// The expression of the flag.
+    pub expr: FlagExpr,
+}
+
+/// An expression in a block.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlockExpr {
+    Assign(Assign),
+    Val(Val),
+    Call(Call),
+    If(If),
+    While(While),
+    Match(Match),
+    TypeDecl(TypeDecl),
+    Break(Break),
+    Continue(Continue),
+    Return(Return),
+    Defer(Defer),
+    Asm(Asm),
+    Block(Vec<Flag>, Block),
+}
+
+/// A code block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    /// The location of the block.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The items in the block.
+    pub items: Vec<BlockExpr>,
+}
+
+/// An item being imported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportItem {
+    /// The span of the item.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The name of the item.
+    pub name: Id,
+
+    /// The alias to use, if any.
+    pub as_: Option<Id>,
+}
+
+/// An import statement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Import {
+    /// A single item import.
+    Single {
+        /// The location of the import.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// Whether or not the import is shared.
+        shared: bool,
+
+        /// The name of the item to import.
+        item: ImportItem,
+
+        /// The module that the item is from.
+        from: Option<GlobalPath>,
+    },
+
+    /// A single item import.
+    Multi {
+        /// The location of the import.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// Whether or not the import is shared.
+        shared: bool,
+
+        /// The name of the item to import.
+        items: Vec<ImportItem>,
+
+        /// The module that the item is from.
+        from: GlobalPath,
+    },
+
+    /// An import of every item in a module, `import * from module`.
+    Glob {
+        /// The location of the import.
+        span: Span,
+
+        /// The ID of this node.
+        node_id: NodeId,
+
+        /// Whether or not the import is shared.
+        shared: bool,
+
+        /// The module to import the items of.
+        from: GlobalPath,
+    },
+}
+
+/// An application in an apply statement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Application {
+    Val(Val),
+    TypeDecl(TypeDecl),
+}
+
+/// An apply statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Apply {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// The markers of the statement.
+    pub markers: Vec<Marker>,
+
+    /// The subject to apply to, or the contract being applied.
+    pub subject: GlobalPath,
+
+    /// The subject to apply to.
+    pub to: Option<GlobalPath>,
+
+    /// The items being applied to the subject.
+    pub items: Vec<Application>,
+}
+
+/// An inline module declaration, `module name { ... }`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    /// The span of the module.
+    pub span: Span,
+
+    /// The ID of this node.
+    pub node_id: NodeId,
+
+    /// Whether or not the module is shared with other modules.
+    pub shared: bool,
+
+    /// The name of the module.
+    pub name: Id,
+
+    /// The statements in the root of the module.
+    pub items: Vec<RootStmnt>,
+}
+
+/// A statement in the root of a hail unit.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RootStmnt {
+    Assign(Assign),
+    Val(Vec<Flag>, Val),
+    Const(Vec<Flag>, Const),
+    Call(Call),
+    Import(Vec<Flag>, Import),
+    If(If),
+    While(While),
+    Match(Match),
+    TypeDecl(Vec<Flag>, TypeDecl),
+    Apply(Vec<Flag>, Apply),
+    Block(Vec<Flag>, Block),
+    Module(Vec<Flag>, Module),
+}
+
+impl Node for Bool {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Id {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Num {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Str {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for GlobalPath {
+    fn node_id(&self) -> NodeId {
+        match self {
+            GlobalPath::Id(node) => node.node_id(),
+            GlobalPath::Branch { node_id, .. } => *node_id,
+        }
+    }
+}
+
+impl Node for Marker {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for RoutineArg {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Routine {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for PathType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for RoutineType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for StructProp {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for StructType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for UnionType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for EnumProp {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for EnumType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for ContractType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for SharedType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for FluidType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for RefType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for DynType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for VariadicType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for OptType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for ResType {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Type {
+    fn node_id(&self) -> NodeId {
+        match self {
+            Type::Id(node) => node.node_id(),
+            Type::Path(node) => node.node_id(),
+            Type::Routine(node) => node.node_id(),
+            Type::Closure(node) => node.node_id(),
+            Type::Struct(node) => node.node_id(),
+            Type::Union(node) => node.node_id(),
+            Type::Enum(node) => node.node_id(),
+            Type::Contract(node) => node.node_id(),
+            Type::Shared(node) => node.node_id(),
+            Type::Fluid(node) => node.node_id(),
+            Type::Ref(node) => node.node_id(),
+            Type::Dyn(node) => node.node_id(),
+            Type::Variadic(node) => node.node_id(),
+            Type::Opt(node) => node.node_id(),
+            Type::Res(node) => node.node_id(),
+        }
+    }
+}
+
+impl Node for Path {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Access {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Call {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Unary {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for As {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Binary {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for ConstructProp {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Construct {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for ConstructEnum {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Wrap {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for NoneLit {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Try {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Index {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for AsmOperand {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Asm {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Expr {
+    fn node_id(&self) -> NodeId {
+        match self {
+            Expr::Bool(node) => node.node_id(),
+            Expr::Id(node) => node.node_id(),
+            Expr::Num(node) => node.node_id(),
+            Expr::Str(node) => node.node_id(),
+            Expr::Path(node) => node.node_id(),
+            Expr::Access(node) => node.node_id(),
+            Expr::Call(node) => node.node_id(),
+            Expr::Unary(node) => node.node_id(),
+            Expr::As(node) => node.node_id(),
+            Expr::Binary(node) => node.node_id(),
+            Expr::Routine(node) => node.node_id(),
+            Expr::Construct(node) => node.node_id(),
+            Expr::ConstructEnum(node) => node.node_id(),
+            Expr::Asm(node) => node.node_id(),
+            Expr::Ok(node) => node.node_id(),
+            Expr::Err(node) => node.node_id(),
+            Expr::None(node) => node.node_id(),
+            Expr::Try(node) => node.node_id(),
+            Expr::Index(node) => node.node_id(),
+        }
+    }
+}
+
+impl Node for Assign {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Val {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Const {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for TypeDecl {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for IfBranch {
+    fn node_id(&self) -> NodeId {
+        match self {
+            IfBranch::ElseIf { node_id, .. } => *node_id,
+            IfBranch::Else { node_id, .. } => *node_id,
+        }
+    }
+}
+
+impl Node for If {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for While {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for MatchCase {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Match {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Break {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Continue {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Return {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Defer {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for FlagExpr {
+    fn node_id(&self) -> NodeId {
+        match self {
+            FlagExpr::Name(node) => node.node_id(),
+            FlagExpr::Value { node_id, .. } => *node_id,
+            FlagExpr::Any { node_id, .. } => *node_id,
+            FlagExpr::All { node_id, .. } => *node_id,
+            FlagExpr::Not { node_id, .. } => *node_id,
+        }
+    }
+}
+
+impl Node for Flag {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for BlockExpr {
+    fn node_id(&self) -> NodeId {
+        match self {
+            BlockExpr::Assign(node) => node.node_id(),
+            BlockExpr::Val(node) => node.node_id(),
+            BlockExpr::Call(node) => node.node_id(),
+            BlockExpr::If(node) => node.node_id(),
+            BlockExpr::While(node) => node.node_id(),
+            BlockExpr::Match(node) => node.node_id(),
+            BlockExpr::TypeDecl(node) => node.node_id(),
+            BlockExpr::Break(node) => node.node_id(),
+            BlockExpr::Continue(node) => node.node_id(),
+            BlockExpr::Return(node) => node.node_id(),
+            BlockExpr::Defer(node) => node.node_id(),
+            BlockExpr::Asm(node) => node.node_id(),
+            BlockExpr::Block(_, node) => node.node_id(),
+        }
+    }
+}
+
+impl Node for Block {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for ImportItem {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Import {
+    fn node_id(&self) -> NodeId {
+        match self {
+            Import::Single { node_id, .. } => *node_id,
+            Import::Multi { node_id, .. } => *node_id,
+            Import::Glob { node_id, .. } => *node_id,
+        }
+    }
+}
+
+impl Node for Application {
+    fn node_id(&self) -> NodeId {
+        match self {
+            Application::Val(node) => node.node_id(),
+            Application::TypeDecl(node) => node.node_id(),
+        }
+    }
+}
+
+impl Node for Apply {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for Module {
+    fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+}
+
+impl Node for RootStmnt {
+    fn node_id(&self) -> NodeId {
+        match self {
+            RootStmnt::Assign(node) => node.node_id(),
+            RootStmnt::Val(_, node) => node.node_id(),
+            RootStmnt::Const(_, node) => node.node_id(),
+            RootStmnt::Call(node) => node.node_id(),
+            RootStmnt::Import(_, node) => node.node_id(),
+            RootStmnt::If(node) => node.node_id(),
+            RootStmnt::While(node) => node.node_id(),
+            RootStmnt::Match(node) => node.node_id(),
+            RootStmnt::TypeDecl(_, node) => node.node_id(),
+            RootStmnt::Apply(_, node) => node.node_id(),
+            RootStmnt::Block(_, node) => node.node_id(),
+            RootStmnt::Module(_, node) => node.node_id(),
+        }
+    }
+}
+
+impl Spanned for Bool {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Id {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Num {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Str {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for GlobalPath {
+    fn span(&self) -> &Span {
+        match self {
+            GlobalPath::Id(node) => node.span(),
+            GlobalPath::Branch { span, .. } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            GlobalPath::Id(node) => node.span_mut(),
+            GlobalPath::Branch { span, .. } => span,
+        }
+    }
+}
+
+impl Spanned for Marker {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for RoutineArg {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Routine {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for PathType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for RoutineType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for StructProp {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for StructType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for UnionType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for EnumProp {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for EnumType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ContractType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for SharedType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for FluidType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for RefType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for DynType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for VariadicType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for OptType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ResType {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Type {
+    fn span(&self) -> &Span {
+        match self {
+            Type::Id(node) => node.span(),
+            Type::Path(node) => node.span(),
+            Type::Routine(node) => node.span(),
+            Type::Closure(node) => node.span(),
+            Type::Struct(node) => node.span(),
+            Type::Union(node) => node.span(),
+            Type::Enum(node) => node.span(),
+            Type::Contract(node) => node.span(),
+            Type::Shared(node) => node.span(),
+            Type::Fluid(node) => node.span(),
+            Type::Ref(node) => node.span(),
+            Type::Dyn(node) => node.span(),
+            Type::Variadic(node) => node.span(),
+            Type::Opt(node) => node.span(),
+            Type::Res(node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Type::Id(node) => node.span_mut(),
+            Type::Path(node) => node.span_mut(),
+            Type::Routine(node) => node.span_mut(),
+            Type::Closure(node) => node.span_mut(),
+            Type::Struct(node) => node.span_mut(),
+            Type::Union(node) => node.span_mut(),
+            Type::Enum(node) => node.span_mut(),
+            Type::Contract(node) => node.span_mut(),
+            Type::Shared(node) => node.span_mut(),
+            Type::Fluid(node) => node.span_mut(),
+            Type::Ref(node) => node.span_mut(),
+            Type::Dyn(node) => node.span_mut(),
+            Type::Variadic(node) => node.span_mut(),
+            Type::Opt(node) => node.span_mut(),
+            Type::Res(node) => node.span_mut(),
+        }
+    }
+}
+
+impl Spanned for Path {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Access {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Call {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Unary {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for As {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Binary {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ConstructProp {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Construct {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ConstructEnum {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Wrap {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for NoneLit {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Try {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Index {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for AsmOperand {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Asm {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Expr {
+    fn span(&self) -> &Span {
+        match self {
+            Expr::Bool(node) => node.span(),
+            Expr::Id(node) => node.span(),
+            Expr::Num(node) => node.span(),
+            Expr::Str(node) => node.span(),
+            Expr::Path(node) => node.span(),
+            Expr::Access(node) => node.span(),
+            Expr::Call(node) => node.span(),
+            Expr::Unary(node) => node.span(),
+            Expr::As(node) => node.span(),
+            Expr::Binary(node) => node.span(),
+            Expr::Routine(node) => node.span(),
+            Expr::Construct(node) => node.span(),
+            Expr::ConstructEnum(node) => node.span(),
+            Expr::Asm(node) => node.span(),
+            Expr::Ok(node) => node.span(),
+            Expr::Err(node) => node.span(),
+            Expr::None(node) => node.span(),
+            Expr::Try(node) => node.span(),
+            Expr::Index(node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Expr::Bool(node) => node.span_mut(),
+            Expr::Id(node) => node.span_mut(),
+            Expr::Num(node) => node.span_mut(),
+            Expr::Str(node) => node.span_mut(),
+            Expr::Path(node) => node.span_mut(),
+            Expr::Access(node) => node.span_mut(),
+            Expr::Call(node) => node.span_mut(),
+            Expr::Unary(node) => node.span_mut(),
+            Expr::As(node) => node.span_mut(),
+            Expr::Binary(node) => node.span_mut(),
+            Expr::Routine(node) => node.span_mut(),
+            Expr::Construct(node) => node.span_mut(),
+            Expr::ConstructEnum(node) => node.span_mut(),
+            Expr::Asm(node) => node.span_mut(),
+            Expr::Ok(node) => node.span_mut(),
+            Expr::Err(node) => node.span_mut(),
+            Expr::None(node) => node.span_mut(),
+            Expr::Try(node) => node.span_mut(),
+            Expr::Index(node) => node.span_mut(),
+        }
+    }
+}
+
+impl Spanned for Assign {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Val {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Const {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for TypeDecl {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for IfBranch {
+    fn span(&self) -> &Span {
+        match self {
+            IfBranch::ElseIf { span, .. } => span,
+            IfBranch::Else { span, .. } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            IfBranch::ElseIf { span, .. } => span,
+            IfBranch::Else { span, .. } => span,
+        }
+    }
+}
+
+impl Spanned for If {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for While {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for MatchCase {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Match {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Break {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Continue {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Return {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Defer {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for FlagExpr {
+    fn span(&self) -> &Span {
+        match self {
+            FlagExpr::Name(node) => node.span(),
+            FlagExpr::Value { span, .. } => span,
+            FlagExpr::Any { span, .. } => span,
+            FlagExpr::All { span, .. } => span,
+            FlagExpr::Not { span, .. } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            FlagExpr::Name(node) => node.span_mut(),
+            FlagExpr::Value { span, .. } => span,
+            FlagExpr::Any { span, .. } => span,
+            FlagExpr::All { span, .. } => span,
+            FlagExpr::Not { span, .. } => span,
+        }
+    }
+}
+
+impl Spanned for Flag {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for BlockExpr {
+    fn span(&self) -> &Span {
+        match self {
+            BlockExpr::Assign(node) => node.span(),
+            BlockExpr::Val(node) => node.span(),
+            BlockExpr::Call(node) => node.span(),
+            BlockExpr::If(node) => node.span(),
+            BlockExpr::While(node) => node.span(),
+            BlockExpr::Match(node) => node.span(),
+            BlockExpr::TypeDecl(node) => node.span(),
+            BlockExpr::Break(node) => node.span(),
+            BlockExpr::Continue(node) => node.span(),
+            BlockExpr::Return(node) => node.span(),
+            BlockExpr::Defer(node) => node.span(),
+            BlockExpr::Asm(node) => node.span(),
+            BlockExpr::Block(_, node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            BlockExpr::Assign(node) => node.span_mut(),
+            BlockExpr::Val(node) => node.span_mut(),
+            BlockExpr::Call(node) => node.span_mut(),
+            BlockExpr::If(node) => node.span_mut(),
+            BlockExpr::While(node) => node.span_mut(),
+            BlockExpr::Match(node) => node.span_mut(),
+            BlockExpr::TypeDecl(node) => node.span_mut(),
+            BlockExpr::Break(node) => node.span_mut(),
+            BlockExpr::Continue(node) => node.span_mut(),
+            BlockExpr::Return(node) => node.span_mut(),
+            BlockExpr::Defer(node) => node.span_mut(),
+            BlockExpr::Asm(node) => node.span_mut(),
+            BlockExpr::Block(_, node) => node.span_mut(),
+        }
+    }
+}
+
+impl Spanned for Block {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ImportItem {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Import {
+    fn span(&self) -> &Span {
+        match self {
+            Import::Single { span, .. } => span,
+            Import::Multi { span, .. } => span,
+            Import::Glob { span, .. } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Import::Single { span, .. } => span,
+            Import::Multi { span, .. } => span,
+            Import::Glob { span, .. } => span,
+        }
+    }
+}
+
+impl Spanned for Application {
+    fn span(&self) -> &Span {
+        match self {
+            Application::Val(node) => node.span(),
+            Application::TypeDecl(node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Application::Val(node) => node.span_mut(),
+            Application::TypeDecl(node) => node.span_mut(),
+        }
+    }
+}
+
+impl Spanned for Apply {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Module {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for RootStmnt {
+    fn span(&self) -> &Span {
+        match self {
+            RootStmnt::Assign(node) => node.span(),
+            RootStmnt::Val(_, node) => node.span(),
+            RootStmnt::Const(_, node) => node.span(),
+            RootStmnt::Call(node) => node.span(),
+            RootStmnt::Import(_, node) => node.span(),
+            RootStmnt::If(node) => node.span(),
+            RootStmnt::While(node) => node.span(),
+            RootStmnt::Match(node) => node.span(),
+            RootStmnt::TypeDecl(_, node) => node.span(),
+            RootStmnt::Apply(_, node) => node.span(),
+            RootStmnt::Block(_, node) => node.span(),
+            RootStmnt::Module(_, node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            RootStmnt::Assign(node) => node.span_mut(),
+            RootStmnt::Val(_, node) => node.span_mut(),
+            RootStmnt::Const(_, node) => node.span_mut(),
+            RootStmnt::Call(node) => node.span_mut(),
+            RootStmnt::Import(_, node) => node.span_mut(),
+            RootStmnt::If(node) => node.span_mut(),
+            RootStmnt::While(node) => node.span_mut(),
+            RootStmnt::Match(node) => node.span_mut(),
+            RootStmnt::TypeDecl(_, node) => node.span_mut(),
+            RootStmnt::Apply(_, node) => node.span_mut(),
+            RootStmnt::Block(_, node) => node.span_mut(),
+            RootStmnt::Module(_, node) => node.span_mut(),
+        }
+    }
+}
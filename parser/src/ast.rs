@@ -1,12 +1,23 @@
 //! The AST expressions that can be found in hail units.
 
+use std::borrow::Cow;
 use std::ops::Range;
 
-/// The location of an expression.
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The version of the JSON schema that the AST serializes to, which goes up whenever its shape changes.
+///
+/// The schema is described in `docs/ast-json.md`.
+#[cfg(feature = "serde")]
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The location of an expression, as byte offsets into its unit. It serializes as `{ "start", "end" }`.
 pub type Span = Range<usize>;
 
 /// A boolean expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Bool {
     /// The span of the boolean.
     pub span: Span,
@@ -17,6 +28,8 @@ pub struct Bool {
 
 /// An identifier expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Id<'a> {
     /// The span of the identifier.
     pub span: Span,
@@ -27,6 +40,7 @@ pub struct Id<'a> {
 
 /// The kind of a number.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NumKind {
     Int,
     XInt,
@@ -36,6 +50,8 @@ pub enum NumKind {
 
 /// A number expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Num<'a> {
     /// The span of the number.
     pub span: Span,
@@ -49,16 +65,22 @@ pub struct Num<'a> {
 
 /// A string expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Str<'a> {
     /// The span of the string.
     pub span: Span,
 
-    /// The raw value of the string.
-    pub value: &'a str,
+    /// The raw value of the string, which is borrowed from the source. It is only owned when deserialized from
+    /// text which escapes it, as JSON escapes the quotes around it.
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub value: Cow<'a, str>,
 }
 
 /// A path expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum GlobalPath<'a> {
     /// A path node.
     Id(Id<'a>),
@@ -76,18 +98,25 @@ pub enum GlobalPath<'a> {
     }
 }
 
-/// A compiler marker.
+/// A compiler marker, such as `#inline(always)`.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Marker<'a> {
     /// The span of this marker.
     pub span: Span,
 
     /// The name of the marker.
     pub name: Id<'a>,
+
+    /// The arguments of the marker.
+    pub args: Vec<Expr<'a>>,
 }
 
 /// An argument in a routine.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct RoutineArg<'a> {
     /// The span of the argument.
     pub span: Span,
@@ -101,6 +130,8 @@ pub struct RoutineArg<'a> {
 
 /// A routine declaration.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Routine<'a> {
     /// The span of the routine.
     pub span: Span,
@@ -120,6 +151,8 @@ pub struct Routine<'a> {
 
 /// A path in a type expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct PathType<'a> {
     /// The span of the path.
     pub span: Span,
@@ -133,6 +166,8 @@ pub struct PathType<'a> {
 
 /// A routine type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct RoutineType<'a> {
     /// The location of this routine.
     pub span: Span,
@@ -146,19 +181,32 @@ pub struct RoutineType<'a> {
 
 /// A struct property.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct StructProp<'a> {
     /// The location of this property.
     pub span: Span,
 
+    /// The markers of this property.
+    pub markers: Vec<Marker<'a>>,
+
+    /// Whether or not the property is shared with other modules.
+    pub shared: bool,
+
     /// The value of the property.
     pub name: Id<'a>,
 
     /// The type of this property.
     pub ty: Type<'a>,
+
+    /// The width of this property in bits, if it is a bitfield.
+    pub bits: Option<Num<'a>>,
 }
 
 /// A struct type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct StructType<'a> {
     /// The location of this struct.
     pub span: Span,
@@ -167,21 +215,43 @@ pub struct StructType<'a> {
     pub props: Vec<StructProp<'a>>,
 }
 
+/// An untagged union type, whose properties all share the same memory.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct UnionType<'a> {
+    /// The location of this union.
+    pub span: Span,
+
+    /// The properties of this union.
+    pub props: Vec<StructProp<'a>>,
+}
+
 /// An enum property.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct EnumProp<'a> {
     /// The location of this property.
     pub span: Span,
 
+    /// The markers of this property.
+    pub markers: Vec<Marker<'a>>,
+
     /// The value of the property.
     pub name: Id<'a>,
 
     /// The type of this property.
     pub ty: Option<Type<'a>>,
+
+    /// The explicit discriminant of this property.
+    pub value: Option<Expr<'a>>,
 }
 
 /// An enum type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct EnumType<'a> {
     /// The location of this enum.
     pub span: Span,
@@ -192,6 +262,8 @@ pub struct EnumType<'a> {
 
 /// A contract type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct ContractType<'a> {
     /// The location of this contract.
     pub span: Span,
@@ -202,6 +274,8 @@ pub struct ContractType<'a> {
 
 /// A shared type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct SharedType<'a> {
     /// The location of this shared type.
     pub span: Span,
@@ -212,6 +286,8 @@ pub struct SharedType<'a> {
 
 /// A fluid type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct FluidType<'a> {
     /// The location of this fluid type.
     pub span: Span,
@@ -222,6 +298,8 @@ pub struct FluidType<'a> {
 
 /// A ref type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct RefType<'a> {
     /// The location of this ref type.
     pub span: Span,
@@ -230,8 +308,35 @@ pub struct RefType<'a> {
     pub subject: Box<Type<'a>>,
 }
 
+/// A contract object type, `dyn Contract`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct DynType<'a> {
+    /// The location of this contract object type.
+    pub span: Span,
+
+    /// The contract of this contract object type.
+    pub subject: Box<Type<'a>>,
+}
+
+/// The variadic arguments at the end of a routine's arguments, `...` for C variadics or `...VarArg` for hail
+/// variadics.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct VariadicType<'a> {
+    /// The location of this variadic type.
+    pub span: Span,
+
+    /// The type that each argument is passed as, if these are hail variadics.
+    pub ty: Option<Box<Type<'a>>>,
+}
+
 /// An option type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct OptType<'a> {
     /// The location of this option type.
     pub span: Span,
@@ -242,6 +347,8 @@ pub struct OptType<'a> {
 
 /// A result type.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct ResType<'a> {
     /// The location of this result type.
     pub span: Span,
@@ -255,22 +362,30 @@ pub struct ResType<'a> {
 
 /// A type expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum Type<'a> {
     Id(Id<'a>),
     Path(PathType<'a>),
     Routine(RoutineType<'a>),
+    Closure(RoutineType<'a>),
     Struct(StructType<'a>),
+    Union(UnionType<'a>),
     Enum(EnumType<'a>),
     Contract(ContractType<'a>),
     Shared(SharedType<'a>),
     Fluid(FluidType<'a>),
     Ref(RefType<'a>),
+    Dyn(DynType<'a>),
+    Variadic(VariadicType<'a>),
     Opt(OptType<'a>),
     Res(ResType<'a>),
 }
 
 /// A path expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Path<'a> {
     /// The span of the path.
     pub span: Span,
@@ -284,6 +399,8 @@ pub struct Path<'a> {
 
 /// An access expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Access<'a> {
     /// The span of the access.
     pub span: Span,
@@ -297,6 +414,8 @@ pub struct Access<'a> {
 
 /// An call expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Call<'a> {
     /// The span of the call.
     pub span: Span,
@@ -310,6 +429,7 @@ pub struct Call<'a> {
 
 /// Operators for unary expressions.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum UnaryOp {
     Min,
     Star,
@@ -321,6 +441,8 @@ pub enum UnaryOp {
 
 /// A unary expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Unary<'a> {
     /// The span of this expression.
     pub span: Span,
@@ -334,6 +456,8 @@ pub struct Unary<'a> {
 
 /// An `as` expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct As<'a> {
     /// The location of the expressions.
     pub span: Span,
@@ -347,6 +471,7 @@ pub struct As<'a> {
 
 /// The binary operator.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BinaryOp {
     Star,
     Slash,
@@ -366,10 +491,13 @@ pub enum BinaryOp {
     GtEq,
     AmpAmp,
     PipePipe,
+    QuestQuest,
 }
 
 /// A binary expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Binary<'a> {
     /// The span of the expression.
     pub span: Span,
@@ -386,6 +514,8 @@ pub struct Binary<'a> {
 
 /// A property in a struct constructor.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct ConstructProp<'a> {
     /// The span of the property.
     pub span: Span,
@@ -399,6 +529,8 @@ pub struct ConstructProp<'a> {
 
 /// A struct constructor.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Construct<'a> {
     /// The span of the construct.
     pub span: Span,
@@ -412,6 +544,8 @@ pub struct Construct<'a> {
 
 /// An enum constructor.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct ConstructEnum<'a> {
     /// The span of the enum.
     pub span: Span,
@@ -423,8 +557,96 @@ pub struct ConstructEnum<'a> {
     pub item: Box<Expr<'a>>,
 }
 
+/// An `ok(value)` or `err(value)` constructor.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Wrap<'a> {
+    /// The span of the constructor.
+    pub span: Span,
+
+    /// The value being wrapped.
+    pub item: Box<Expr<'a>>,
+}
+
+/// A `none` literal.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NoneLit {
+    /// The span of the literal.
+    pub span: Span,
+}
+
+/// A `?` expression, which unwraps an option or result and returns early on `none` or `err`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Try<'a> {
+    /// The span of the expression.
+    pub span: Span,
+
+    /// The option or result being unwrapped.
+    pub subject: Box<Expr<'a>>,
+}
+
+/// An index expression, such as `list[i]`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Index<'a> {
+    /// The span of the expression.
+    pub span: Span,
+
+    /// The value being indexed.
+    pub subject: Box<Expr<'a>>,
+
+    /// The index.
+    pub index: Box<Expr<'a>>,
+}
+
+/// An operand of an inline assembly block.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct AsmOperand<'a> {
+    /// The span of the operand.
+    pub span: Span,
+
+    /// The constraint of the operand, such as `"=r"`.
+    pub constraint: Str<'a>,
+
+    /// The value bound to the operand.
+    pub expr: Expr<'a>,
+}
+
+/// An inline assembly block.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Asm<'a> {
+    /// The span of the block.
+    pub span: Span,
+
+    /// The assembly template.
+    pub template: Str<'a>,
+
+    /// The output operands of the block.
+    pub outputs: Vec<AsmOperand<'a>>,
+
+    /// The input operands of the block.
+    pub inputs: Vec<AsmOperand<'a>>,
+
+    /// The registers and memory clobbered by the block.
+    pub clobbers: Vec<Str<'a>>,
+
+    /// The options of the block, such as `volatile`.
+    pub options: Vec<Id<'a>>,
+}
+
 /// An expression from hail source.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum Expr<'a> {
     Bool(Bool),
     Id(Id<'a>),
@@ -439,10 +661,17 @@ pub enum Expr<'a> {
     Routine(Routine<'a>),
     Construct(Construct<'a>),
     ConstructEnum(ConstructEnum<'a>),
+    Asm(Asm<'a>),
+    Ok(Wrap<'a>),
+    Err(Wrap<'a>),
+    None(NoneLit),
+    Try(Try<'a>),
+    Index(Index<'a>),
 }
 
 /// The operator of an assignment expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AssignOp {
     Eq,
     PlusEq,
@@ -459,6 +688,8 @@ pub enum AssignOp {
 
 /// An assignment expression.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Assign<'a> {
     /// The span of the expression.
     pub span: Span,
@@ -475,10 +706,18 @@ pub struct Assign<'a> {
 
 /// A variable declaration.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Val<'a> {
     /// The span of the variable.
     pub span: Span,
 
+    /// The markers of the variable.
+    pub markers: Vec<Marker<'a>>,
+
+    /// Whether or not the variable is shared with other modules.
+    pub shared: bool,
+
     /// The name of the variable.
     pub name: Id<'a>,
 
@@ -489,12 +728,44 @@ pub struct Val<'a> {
     pub value: Option<Expr<'a>>,
 }
 
+/// A compile time constant declaration.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Const<'a> {
+    /// The span of the constant.
+    pub span: Span,
+
+    /// The markers of the constant.
+    pub markers: Vec<Marker<'a>>,
+
+    /// Whether or not the constant is shared with other modules.
+    pub shared: bool,
+
+    /// The name of the constant.
+    pub name: Id<'a>,
+
+    /// The type annotation of the constant.
+    pub ty: Option<Type<'a>>,
+
+    /// The value of the constant.
+    pub value: Expr<'a>,
+}
+
 /// A type declaration.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct TypeDecl<'a> {
     /// The span of the type declaration.
     pub span: Span,
 
+    /// The markers of the type declaration.
+    pub markers: Vec<Marker<'a>>,
+
+    /// Whether or not the type is shared with other modules.
+    pub shared: bool,
+
     /// The name of the type declaration.
     pub name: Id<'a>,
 
@@ -504,6 +775,8 @@ pub struct TypeDecl<'a> {
 
 /// A branch after an `if` statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum IfBranch<'a> {
     /// An `else if` statement.
     ElseIf {
@@ -529,6 +802,8 @@ pub enum IfBranch<'a> {
 
 /// An `if` statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct If<'a> {
     /// The span of the statement.
     pub span: Span,
@@ -545,6 +820,8 @@ pub struct If<'a> {
 
 /// A `while` statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct While<'a> {
     /// The span of the statement.
     pub span: Span,
@@ -561,6 +838,8 @@ pub struct While<'a> {
 
 /// A case in a match statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct MatchCase<'a> {
     /// The span of the case.
     pub span: Span,
@@ -577,6 +856,8 @@ pub struct MatchCase<'a> {
 
 /// A `match` statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Match<'a> {
     /// The span of the statement.
     pub span: Span,
@@ -590,6 +871,8 @@ pub struct Match<'a> {
 
 /// A `break` statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Break<'a> {
     /// The span of the statement.
     pub span: Span,
@@ -600,6 +883,8 @@ pub struct Break<'a> {
 
 /// A `continue` statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Continue<'a> {
     /// The span of the statement.
     pub span: Span,
@@ -610,6 +895,8 @@ pub struct Continue<'a> {
 
 /// A `return` statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Return<'a> {
     /// The span of the statement.
     pub span: Span,
@@ -618,8 +905,70 @@ pub struct Return<'a> {
     pub value: Option<Expr<'a>>,
 }
 
+/// A `defer` statement.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Defer<'a> {
+    /// The span of the statement.
+    pub span: Span,
+
+    /// The statement to run when the enclosing block exits.
+    pub item: Box<BlockExpr<'a>>,
+}
+
+/// A compile time flag expression.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub enum FlagExpr<'a> {
+    /// A flag which is either set or not, such as `os_windows`.
+    Name(Id<'a>),
+
+    /// A key/value flag, such as `target_arch("x86_64")`.
+    Value {
+        /// The span of the flag.
+        span: Span,
+
+        /// The key of the flag.
+        name: Id<'a>,
+
+        /// The value that the flag must have.
+        value: Str<'a>,
+    },
+
+    /// Satisfied if any of the flags are, such as `any(os_linux, os_macos)`.
+    Any {
+        /// The span of the flag.
+        span: Span,
+
+        /// The flags to check.
+        items: Vec<FlagExpr<'a>>,
+    },
+
+    /// Satisfied if all of the flags are, such as `all(os_linux, debug)`.
+    All {
+        /// The span of the flag.
+        span: Span,
+
+        /// The flags to check.
+        items: Vec<FlagExpr<'a>>,
+    },
+
+    /// Satisfied if the flag is not, such as `not(debug)`.
+    Not {
+        /// The span of the flag.
+        span: Span,
+
+        /// The flag to check.
+        item: Box<FlagExpr<'a>>,
+    },
+}
+
 /// Compile time flags, for conditional compilation.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Flag<'a> {
     /// The span of the flag.
     pub span: Span,
@@ -627,12 +976,14 @@ pub struct Flag<'a> {
     /// Whether or not the flag is negative.
     pub neg: bool,
 
-    /// The name of the flag.
-    pub name: Id<'a>,
+    /// The expression of the flag.
+    pub expr: FlagExpr<'a>,
 }
 
 /// An expression in a block.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum BlockExpr<'a> {
     Assign(Assign<'a>),
     Val(Val<'a>),
@@ -644,11 +995,15 @@ pub enum BlockExpr<'a> {
     Break(Break<'a>),
     Continue(Continue<'a>),
     Return(Return<'a>),
+    Defer(Defer<'a>),
+    Asm(Asm<'a>),
     Block(Vec<Flag<'a>>, Box<Block<'a>>),
 }
 
 /// A code block.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Block<'a> {
     /// The location of the block.
     pub span: Span,
@@ -659,6 +1014,8 @@ pub struct Block<'a> {
 
 /// An item being imported.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct ImportItem<'a> {
     /// The span of the item.
     pub span: Span,
@@ -672,6 +1029,8 @@ pub struct ImportItem<'a> {
 
 /// An import statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum Import<'a> {
     /// A single item import.
     Single {
@@ -685,7 +1044,7 @@ pub enum Import<'a> {
         item: ImportItem<'a>,
 
         /// The module that the item is from.
-        from: Option<Id<'a>>,
+        from: Option<GlobalPath<'a>>,
     },
 
     /// A single item import.
@@ -700,12 +1059,26 @@ pub enum Import<'a> {
         items: Vec<ImportItem<'a>>,
 
         /// The module that the item is from.
-        from: Id<'a>,
+        from: GlobalPath<'a>,
+    },
+
+    /// An import of every item in a module, `import * from module`.
+    Glob {
+        /// The location of the import.
+        span: Span,
+
+        /// Whether or not the import is shared.
+        shared: bool,
+
+        /// The module to import the items of.
+        from: GlobalPath<'a>,
     },
 }
 
 /// An application in an apply statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum Application<'a> {
     Val(Val<'a>),
     TypeDecl(TypeDecl<'a>),
@@ -713,10 +1086,15 @@ pub enum Application<'a> {
 
 /// An apply statement.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub struct Apply<'a> {
     /// The span of the statement.
     pub span: Span,
 
+    /// The markers of the statement.
+    pub markers: Vec<Marker<'a>>,
+
     /// The subject to apply to, or the contract being applied.
     pub subject: GlobalPath<'a>,
 
@@ -727,11 +1105,32 @@ pub struct Apply<'a> {
     pub items: Vec<Application<'a>>,
 }
 
+/// An inline module declaration, `module name { ... }`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Module<'a> {
+    /// The span of the module.
+    pub span: Span,
+
+    /// Whether or not the module is shared with other modules.
+    pub shared: bool,
+
+    /// The name of the module.
+    pub name: Id<'a>,
+
+    /// The statements in the root of the module.
+    pub items: Vec<RootStmnt<'a>>,
+}
+
 /// A statement in the root of a hail unit.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum RootStmnt<'a> {
     Assign(Assign<'a>),
     Val(Vec<Flag<'a>>, Val<'a>),
+    Const(Vec<Flag<'a>>, Const<'a>),
     Call(Call<'a>),
     Import(Vec<Flag<'a>>, Import<'a>),
     If(If<'a>),
@@ -740,4 +1139,5 @@ pub enum RootStmnt<'a> {
     TypeDecl(Vec<Flag<'a>>, TypeDecl<'a>),
     Apply(Vec<Flag<'a>>, Apply<'a>),
     Block(Vec<Flag<'a>>, Block<'a>),
+    Module(Vec<Flag<'a>>, Module<'a>),
 }
\ No newline at end of file
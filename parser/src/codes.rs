@@ -0,0 +1,55 @@
+//! The codes that diagnostics carry, with long explanations of what they mean.
+
+/// A registered diagnostic code, such as `E0001`.
+#[derive(Clone, Debug)]
+pub struct Code {
+    /// The code itself, such as `E0001`.
+    pub code: &'static str,
+
+    /// A short description of the problem, such as `invalid token`.
+    pub title: &'static str,
+
+    /// A long explanation of the problem in markdown, with an example that causes it and how to fix it.
+    ///
+    /// Examples that cause the problem come first, in a code block marked `hail`.
+    pub explanation: &'static str,
+}
+
+/// Registers a code.
+const fn code(code: &'static str, title: &'static str, explanation: &'static str) -> Code {
+    Code { code, title, explanation }
+}
+
+/// Every code that the lexer, parser and compiler report.
+///
+/// Codes are never reused. When a diagnostic is no longer reported, its code stays here so that it can still be
+/// explained.
+pub const CODES: &[Code] = &[
+    code("E0001", "invalid token", include_str!("codes/E0001.md")),
+    code("E0002", "unexpected token", include_str!("codes/E0002.md")),
+    code("E0003", "unexpected end of file", include_str!("codes/E0003.md")),
+    code("E0004", "invalid flag expression", include_str!("codes/E0004.md")),
+    code("E0005", "invalid variadic arguments", include_str!("codes/E0005.md")),
+    code("E0006", "invalid bitfield", include_str!("codes/E0006.md")),
+    code("E0007", "changing something that is not `fluid`", include_str!("codes/E0007.md")),
+    code("E0008", "unknown item or method", include_str!("codes/E0008.md")),
+    code("E0009", "invalid contract object", include_str!("codes/E0009.md")),
+    code("E0010", "closure used as a plain routine", include_str!("codes/E0010.md")),
+    code("E0011", "operator without a contract", include_str!("codes/E0011.md")),
+    code("E0012", "invalid enum discriminant or cast", include_str!("codes/E0012.md")),
+    code("E0013", "union property read", include_str!("codes/E0013.md")),
+    code("E0014", "invalid use of `?`", include_str!("codes/E0014.md")),
+    code("E0015", "invalid inline assembly", include_str!("codes/E0015.md")),
+    code("E0016", "invalid control flow", include_str!("codes/E0016.md")),
+    code("E0017", "invalid application", include_str!("codes/E0017.md")),
+    code("E0018", "unresolved import", include_str!("codes/E0018.md")),
+    code("E0019", "private item or property", include_str!("codes/E0019.md")),
+    code("E0020", "module initialization cycle", include_str!("codes/E0020.md")),
+    code("E0021", "invalid attribute", include_str!("codes/E0021.md")),
+    code("E0022", "constant evaluation failed", include_str!("codes/E0022.md")),
+];
+
+/// Finds the registered code with the provided name, ignoring case.
+pub fn lookup(name: &str) -> Option<&'static Code> {
+    CODES.iter().find(|code| code.code.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,1717 @@
+//! A lossless concrete syntax tree, which keeps every byte of the source that it was parsed from.
+//!
+//! The tree comes in two layers. The green tree is immutable and only knows the kind, text and width of each element,
+//! so it can be shared between trees. The red tree of [`SyntaxNode`]s and [`SyntaxToken`]s is built over it on
+//! demand, and knows where each element is and what its parent is.
+//!
+//! Whitespace, line breaks and comments are kept as trivia tokens, and the semicolons that [`Asi`] inserts are kept as
+//! empty tokens marked as synthetic, so the text of a tree is always exactly its source. There is a node for every
+//! struct and every enum variant of the [`ast`](crate::ast), and [`cst_ast`](crate::cst_ast) gives a typed view of
+//! them.
+
+use std::fmt;
+use std::rc::Rc;
+
+use crate::ast::{self, Span};
+use crate::diagnostics::Diagnostic;
+use crate::scanner::{Asi, Tok};
+use crate::spanned::Spanned;
+use crate::visit::{self, Visit};
+
+/// The kind of a token or node.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SyntaxKind {
+    /// Spaces and tabs.
+    Whitespace,
+
+    /// A line break, `\n`, `\r` or `\r\n`.
+    Newline,
+
+    /// A comment, `// ...`.
+    Comment,
+
+    /// Text which is not a token.
+    Error,
+
+    /// `#`
+    Hash,
+
+    /// `::`
+    ColCol,
+
+    /// `:`
+    Col,
+
+    /// `...`
+    DotDotDot,
+
+    /// `.`
+    Dot,
+
+    /// `??`
+    QuestQuest,
+
+    /// `?`
+    Quest,
+
+    /// `<-`
+    LArrow,
+
+    /// `->`
+    RArrow,
+
+    /// `=>`
+    FatArrow,
+
+    /// `-=`
+    MinEq,
+
+    /// `-`
+    Min,
+
+    /// `*=`
+    StarEq,
+
+    /// `*`
+    Star,
+
+    /// `!=`
+    BangEq,
+
+    /// `!`
+    Bang,
+
+    /// `&&`
+    AmpAmp,
+
+    /// `&=`
+    AmpEq,
+
+    /// `&`
+    Amp,
+
+    /// `/=`
+    SlashEq,
+
+    /// `/`
+    Slash,
+
+    /// `%=`
+    PercEq,
+
+    /// `%`
+    Perc,
+
+    /// `+=`
+    PlusEq,
+
+    /// `+`
+    Plus,
+
+    /// `<<=`
+    LtLtEq,
+
+    /// `<<`
+    LtLt,
+
+    /// `<=`
+    LtEq,
+
+    /// `<`
+    Lt,
+
+    /// `>>=`
+    GtGtEq,
+
+    /// `>>`
+    GtGt,
+
+    /// `>=`
+    GtEq,
+
+    /// `>`
+    Gt,
+
+    /// `^=`
+    CaretEq,
+
+    /// `^`
+    Caret,
+
+    /// `|=`
+    PipeEq,
+
+    /// `||`
+    PipePipe,
+
+    /// `|`
+    Pipe,
+
+    /// `==`
+    EqEq,
+
+    /// `=`
+    Eq,
+
+    /// `[`
+    LBrack,
+
+    /// `]`
+    RBrack,
+
+    /// `(`
+    LParen,
+
+    /// `)`
+    RParen,
+
+    /// `{`
+    LCurly,
+
+    /// `}`
+    RCurly,
+
+    /// `@`
+    At,
+
+    /// `,`
+    Comma,
+
+    /// `;`
+    Semi,
+
+    /// `true`
+    KTrue,
+
+    /// `false`
+    KFalse,
+
+    /// `fluid`
+    KFluid,
+
+    /// `as`
+    KAs,
+
+    /// `routine`
+    KRoutine,
+
+    /// `closure`
+    KClosure,
+
+    /// `val`
+    KVal,
+
+    /// `shared`
+    KShared,
+
+    /// `import`
+    KImport,
+
+    /// `from`
+    KFrom,
+
+    /// `if`
+    KIf,
+
+    /// `else`
+    KElse,
+
+    /// `while`
+    KWhile,
+
+    /// `match`
+    KMatch,
+
+    /// `struct`
+    KStruct,
+
+    /// `type`
+    KType,
+
+    /// `enum`
+    KEnum,
+
+    /// `break`
+    KBreak,
+
+    /// `continue`
+    KContinue,
+
+    /// `return`
+    KReturn,
+
+    /// `apply`
+    KApply,
+
+    /// `contract`
+    KContract,
+
+    /// `to`
+    KTo,
+
+    /// `defer`
+    KDefer,
+
+    /// `asm`
+    KAsm,
+
+    /// `const`
+    KConst,
+
+    /// `ok`
+    KOk,
+
+    /// `err`
+    KErr,
+
+    /// `none`
+    KNone,
+
+    /// `module`
+    KModule,
+
+    /// `union`
+    KUnion,
+
+    /// `dyn`
+    KDyn,
+
+    /// An identifier token.
+    Ident,
+
+    /// A plain integer token.
+    IntLit,
+
+    /// A hexadecimal integer token.
+    XIntLit,
+
+    /// A binary integer token.
+    BIntLit,
+
+    /// A floating point number token.
+    FloatLit,
+
+    /// A string token.
+    StrLit,
+
+    /// The root of a unit.
+    Root,
+
+    /// A boolean expression.
+    Bool,
+
+    /// An identifier expression.
+    Id,
+
+    /// A number expression.
+    Num,
+
+    /// A string expression.
+    Str,
+
+    /// `GlobalPath::Id`.
+    GlobalPathId,
+
+    /// `GlobalPath::Branch`.
+    GlobalPathBranch,
+
+    /// A compiler marker, such as `#inline(always)`.
+    Marker,
+
+    /// An argument in a routine.
+    RoutineArg,
+
+    /// A routine declaration.
+    Routine,
+
+    /// A path in a type expression.
+    PathType,
+
+    /// A routine type.
+    RoutineType,
+
+    /// A struct property.
+    StructProp,
+
+    /// A struct type.
+    StructType,
+
+    /// An untagged union type, whose properties all share the same memory.
+    UnionType,
+
+    /// An enum property.
+    EnumProp,
+
+    /// An enum type.
+    EnumType,
+
+    /// A contract type.
+    ContractType,
+
+    /// A shared type.
+    SharedType,
+
+    /// A fluid type.
+    FluidType,
+
+    /// A ref type.
+    RefType,
+
+    /// A contract object type, `dyn Contract`.
+    DynType,
+
+    /// The variadic arguments at the end of a routine's arguments, `...` for C variadics or `...VarArg` for hail
+    VariadicType,
+
+    /// An option type.
+    OptType,
+
+    /// A result type.
+    ResType,
+
+    /// `Type::Id`.
+    TypeId,
+
+    /// `Type::Path`.
+    TypePath,
+
+    /// `Type::Routine`.
+    TypeRoutine,
+
+    /// `Type::Closure`.
+    TypeClosure,
+
+    /// `Type::Struct`.
+    TypeStruct,
+
+    /// `Type::Union`.
+    TypeUnion,
+
+    /// `Type::Enum`.
+    TypeEnum,
+
+    /// `Type::Contract`.
+    TypeContract,
+
+    /// `Type::Shared`.
+    TypeShared,
+
+    /// `Type::Fluid`.
+    TypeFluid,
+
+    /// `Type::Ref`.
+    TypeRef,
+
+    /// `Type::Dyn`.
+    TypeDyn,
+
+    /// `Type::Variadic`.
+    TypeVariadic,
+
+    /// `Type::Opt`.
+    TypeOpt,
+
+    /// `Type::Res`.
+    TypeRes,
+
+    /// A path expression.
+    Path,
+
+    /// An access expression.
+    Access,
+
+    /// An call expression.
+    Call,
+
+    /// A unary expression.
+    Unary,
+
+    /// An `as` expression.
+    As,
+
+    /// A binary expression.
+    Binary,
+
+    /// A property in a struct constructor.
+    ConstructProp,
+
+    /// A struct constructor.
+    Construct,
+
+    /// An enum constructor.
+    ConstructEnum,
+
+    /// An `ok(value)` or `err(value)` constructor.
+    Wrap,
+
+    /// A `none` literal.
+    NoneLit,
+
+    /// A `?` expression, which unwraps an option or result and returns early on `none` or `err`.
+    Try,
+
+    /// An index expression, such as `list[i]`.
+    Index,
+
+    /// An operand of an inline assembly block.
+    AsmOperand,
+
+    /// An inline assembly block.
+    Asm,
+
+    /// `Expr::Bool`.
+    ExprBool,
+
+    /// `Expr::Id`.
+    ExprId,
+
+    /// `Expr::Num`.
+    ExprNum,
+
+    /// `Expr::Str`.
+    ExprStr,
+
+    /// `Expr::Path`.
+    ExprPath,
+
+    /// `Expr::Access`.
+    ExprAccess,
+
+    /// `Expr::Call`.
+    ExprCall,
+
+    /// `Expr::Unary`.
+    ExprUnary,
+
+    /// `Expr::As`.
+    ExprAs,
+
+    /// `Expr::Binary`.
+    ExprBinary,
+
+    /// `Expr::Routine`.
+    ExprRoutine,
+
+    /// `Expr::Construct`.
+    ExprConstruct,
+
+    /// `Expr::ConstructEnum`.
+    ExprConstructEnum,
+
+    /// `Expr::Asm`.
+    ExprAsm,
+
+    /// `Expr::Ok`.
+    ExprOk,
+
+    /// `Expr::Err`.
+    ExprErr,
+
+    /// `Expr::None`.
+    ExprNone,
+
+    /// `Expr::Try`.
+    ExprTry,
+
+    /// `Expr::Index`.
+    ExprIndex,
+
+    /// An assignment expression.
+    Assign,
+
+    /// A variable declaration.
+    Val,
+
+    /// A compile time constant declaration.
+    Const,
+
+    /// A type declaration.
+    TypeDecl,
+
+    /// `IfBranch::ElseIf`.
+    IfBranchElseIf,
+
+    /// `IfBranch::Else`.
+    IfBranchElse,
+
+    /// An `if` statement.
+    If,
+
+    /// A `while` statement.
+    While,
+
+    /// A case in a match statement.
+    MatchCase,
+
+    /// A `match` statement.
+    Match,
+
+    /// A `break` statement.
+    Break,
+
+    /// A `continue` statement.
+    Continue,
+
+    /// A `return` statement.
+    Return,
+
+    /// A `defer` statement.
+    Defer,
+
+    /// `FlagExpr::Name`.
+    FlagExprName,
+
+    /// `FlagExpr::Value`.
+    FlagExprValue,
+
+    /// `FlagExpr::Any`.
+    FlagExprAny,
+
+    /// `FlagExpr::All`.
+    FlagExprAll,
+
+    /// `FlagExpr::Not`.
+    FlagExprNot,
+
+    /// Compile time flags, for conditional compilation.
+    Flag,
+
+    /// `BlockExpr::Assign`.
+    BlockExprAssign,
+
+    /// `BlockExpr::Val`.
+    BlockExprVal,
+
+    /// `BlockExpr::Call`.
+    BlockExprCall,
+
+    /// `BlockExpr::If`.
+    BlockExprIf,
+
+    /// `BlockExpr::While`.
+    BlockExprWhile,
+
+    /// `BlockExpr::Match`.
+    BlockExprMatch,
+
+    /// `BlockExpr::TypeDecl`.
+    BlockExprTypeDecl,
+
+    /// `BlockExpr::Break`.
+    BlockExprBreak,
+
+    /// `BlockExpr::Continue`.
+    BlockExprContinue,
+
+    /// `BlockExpr::Return`.
+    BlockExprReturn,
+
+    /// `BlockExpr::Defer`.
+    BlockExprDefer,
+
+    /// `BlockExpr::Asm`.
+    BlockExprAsm,
+
+    /// `BlockExpr::Block`.
+    BlockExprBlock,
+
+    /// A code block.
+    Block,
+
+    /// An item being imported.
+    ImportItem,
+
+    /// `Import::Single`.
+    ImportSingle,
+
+    /// `Import::Multi`.
+    ImportMulti,
+
+    /// `Import::Glob`.
+    ImportGlob,
+
+    /// `Application::Val`.
+    ApplicationVal,
+
+    /// `Application::TypeDecl`.
+    ApplicationTypeDecl,
+
+    /// An apply statement.
+    Apply,
+
+    /// An inline module declaration, `module name { ... }`.
+    Module,
+
+    /// `RootStmnt::Assign`.
+    RootStmntAssign,
+
+    /// `RootStmnt::Val`.
+    RootStmntVal,
+
+    /// `RootStmnt::Const`.
+    RootStmntConst,
+
+    /// `RootStmnt::Call`.
+    RootStmntCall,
+
+    /// `RootStmnt::Import`.
+    RootStmntImport,
+
+    /// `RootStmnt::If`.
+    RootStmntIf,
+
+    /// `RootStmnt::While`.
+    RootStmntWhile,
+
+    /// `RootStmnt::Match`.
+    RootStmntMatch,
+
+    /// `RootStmnt::TypeDecl`.
+    RootStmntTypeDecl,
+
+    /// `RootStmnt::Apply`.
+    RootStmntApply,
+
+    /// `RootStmnt::Block`.
+    RootStmntBlock,
+
+    /// `RootStmnt::Module`.
+    RootStmntModule,
+}
+
+impl SyntaxKind {
+    /// Whether or not this is the kind of a token.
+    pub fn is_token(self) -> bool {
+        self < SyntaxKind::Root
+    }
+
+    /// Whether or not this is the kind of trivia, which the grammar skips.
+    pub fn is_trivia(self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::Newline | SyntaxKind::Comment)
+    }
+}
+
+impl From<Tok<'_>> for SyntaxKind {
+    fn from(tok: Tok) -> Self {
+        match tok {
+            Tok::Hash => SyntaxKind::Hash,
+            Tok::ColCol => SyntaxKind::ColCol,
+            Tok::Col => SyntaxKind::Col,
+            Tok::DotDotDot => SyntaxKind::DotDotDot,
+            Tok::Dot => SyntaxKind::Dot,
+            Tok::QuestQuest => SyntaxKind::QuestQuest,
+            Tok::Quest => SyntaxKind::Quest,
+            Tok::LArrow => SyntaxKind::LArrow,
+            Tok::RArrow => SyntaxKind::RArrow,
+            Tok::FatArrow => SyntaxKind::FatArrow,
+            Tok::MinEq => SyntaxKind::MinEq,
+            Tok::Min => SyntaxKind::Min,
+            Tok::StarEq => SyntaxKind::StarEq,
+            Tok::Star => SyntaxKind::Star,
+            Tok::BangEq => SyntaxKind::BangEq,
+            Tok::Bang => SyntaxKind::Bang,
+            Tok::AmpAmp => SyntaxKind::AmpAmp,
+            Tok::AmpEq => SyntaxKind::AmpEq,
+            Tok::Amp => SyntaxKind::Amp,
+            Tok::SlashEq => SyntaxKind::SlashEq,
+            Tok::Slash => SyntaxKind::Slash,
+            Tok::PercEq => SyntaxKind::PercEq,
+            Tok::Perc => SyntaxKind::Perc,
+            Tok::PlusEq => SyntaxKind::PlusEq,
+            Tok::Plus => SyntaxKind::Plus,
+            Tok::LtLtEq => SyntaxKind::LtLtEq,
+            Tok::LtLt => SyntaxKind::LtLt,
+            Tok::LtEq => SyntaxKind::LtEq,
+            Tok::Lt => SyntaxKind::Lt,
+            Tok::GtGtEq => SyntaxKind::GtGtEq,
+            Tok::GtGt => SyntaxKind::GtGt,
+            Tok::GtEq => SyntaxKind::GtEq,
+            Tok::Gt => SyntaxKind::Gt,
+            Tok::CaretEq => SyntaxKind::CaretEq,
+            Tok::Caret => SyntaxKind::Caret,
+            Tok::PipeEq => SyntaxKind::PipeEq,
+            Tok::PipePipe => SyntaxKind::PipePipe,
+            Tok::Pipe => SyntaxKind::Pipe,
+            Tok::EqEq => SyntaxKind::EqEq,
+            Tok::Eq => SyntaxKind::Eq,
+            Tok::LBrack => SyntaxKind::LBrack,
+            Tok::RBrack => SyntaxKind::RBrack,
+            Tok::LParen => SyntaxKind::LParen,
+            Tok::RParen => SyntaxKind::RParen,
+            Tok::LCurly => SyntaxKind::LCurly,
+            Tok::RCurly => SyntaxKind::RCurly,
+            Tok::At => SyntaxKind::At,
+            Tok::Comma => SyntaxKind::Comma,
+            Tok::Semi => SyntaxKind::Semi,
+            Tok::KTrue => SyntaxKind::KTrue,
+            Tok::KFalse => SyntaxKind::KFalse,
+            Tok::KFluid => SyntaxKind::KFluid,
+            Tok::KAs => SyntaxKind::KAs,
+            Tok::KRoutine => SyntaxKind::KRoutine,
+            Tok::KClosure => SyntaxKind::KClosure,
+            Tok::KVal => SyntaxKind::KVal,
+            Tok::KShared => SyntaxKind::KShared,
+            Tok::KImport => SyntaxKind::KImport,
+            Tok::KFrom => SyntaxKind::KFrom,
+            Tok::KIf => SyntaxKind::KIf,
+            Tok::KElse => SyntaxKind::KElse,
+            Tok::KWhile => SyntaxKind::KWhile,
+            Tok::KMatch => SyntaxKind::KMatch,
+            Tok::KStruct => SyntaxKind::KStruct,
+            Tok::KType => SyntaxKind::KType,
+            Tok::KEnum => SyntaxKind::KEnum,
+            Tok::KBreak => SyntaxKind::KBreak,
+            Tok::KContinue => SyntaxKind::KContinue,
+            Tok::KReturn => SyntaxKind::KReturn,
+            Tok::KApply => SyntaxKind::KApply,
+            Tok::KContract => SyntaxKind::KContract,
+            Tok::KTo => SyntaxKind::KTo,
+            Tok::KDefer => SyntaxKind::KDefer,
+            Tok::KAsm => SyntaxKind::KAsm,
+            Tok::KConst => SyntaxKind::KConst,
+            Tok::KOk => SyntaxKind::KOk,
+            Tok::KErr => SyntaxKind::KErr,
+            Tok::KNone => SyntaxKind::KNone,
+            Tok::KModule => SyntaxKind::KModule,
+            Tok::KUnion => SyntaxKind::KUnion,
+            Tok::KDyn => SyntaxKind::KDyn,
+            Tok::Id(_) => SyntaxKind::Ident,
+            Tok::Int(_) => SyntaxKind::IntLit,
+            Tok::XInt(_) => SyntaxKind::XIntLit,
+            Tok::BInt(_) => SyntaxKind::BIntLit,
+            Tok::Float(_) => SyntaxKind::FloatLit,
+            Tok::Str(_) => SyntaxKind::StrLit,
+        }
+    }
+}
+
+/// A token of the green tree.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GreenToken {
+    /// The kind of the token.
+    kind: SyntaxKind,
+
+    /// The text of the token, which is empty if it is synthetic.
+    text: Box<str>,
+
+    /// Whether or not the token was inserted rather than written.
+    synthetic: bool,
+}
+
+impl GreenToken {
+    /// Creates a token.
+    pub fn new(kind: SyntaxKind, text: &str, synthetic: bool) -> Self {
+        Self { kind, text: text.into(), synthetic }
+    }
+
+    /// The kind of the token.
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// The text of the token.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether or not the token was inserted rather than written, such as a semicolon inserted at a line break.
+    pub fn is_synthetic(&self) -> bool {
+        self.synthetic
+    }
+}
+
+/// A node of the green tree.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GreenNode {
+    /// The kind of the node.
+    kind: SyntaxKind,
+
+    /// The length of the text of the node.
+    width: usize,
+
+    /// The tokens and nodes in the node.
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    /// Creates a node.
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let width = children.iter().map(GreenElement::width).sum();
+        Self { kind, width, children }
+    }
+
+    /// The kind of the node.
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// The length of the text of the node.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The tokens and nodes in the node.
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+}
+
+/// A token or node of the green tree.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenElement {
+    /// The kind of the element.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            GreenElement::Node(node) => node.kind,
+            GreenElement::Token(token) => token.kind,
+        }
+    }
+
+    /// The length of the text of the element.
+    pub fn width(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.width,
+            GreenElement::Token(token) => token.text.len(),
+        }
+    }
+}
+
+/// A node of the red tree.
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+/// The data of a [`SyntaxNode`].
+struct NodeData {
+    /// The green node that this node is over.
+    green: Rc<GreenNode>,
+
+    /// The parent of this node, if it is not the root.
+    parent: Option<SyntaxNode>,
+
+    /// The position of this node in the source.
+    offset: usize,
+}
+
+impl SyntaxNode {
+    /// Creates the root of a red tree over a green node.
+    pub fn new_root(green: Rc<GreenNode>) -> Self {
+        Self(Rc::new(NodeData { green, parent: None, offset: 0 }))
+    }
+
+    /// The green node that this node is over.
+    pub fn green(&self) -> &Rc<GreenNode> {
+        &self.0.green
+    }
+
+    /// The kind of the node.
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind
+    }
+
+    /// The location of the node, including any trivia inside it.
+    pub fn span(&self) -> Span {
+        self.0.offset..self.0.offset + self.0.green.width
+    }
+
+    /// The text of the node, byte for byte.
+    pub fn text(&self) -> String {
+        self.to_string()
+    }
+
+    /// The parent of the node, if it is not the root.
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.clone()
+    }
+
+    /// The tokens and nodes directly in this node.
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> + '_ {
+        let mut offset = self.0.offset;
+
+        self.0.green.children.iter().map(move |child| {
+            let element = match child {
+                GreenElement::Node(green) => SyntaxElement::Node(SyntaxNode(Rc::new(NodeData {
+                    green: green.clone(),
+                    parent: Some(self.clone()),
+                    offset,
+                }))),
+                GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                    parent: self.clone(),
+                    green: green.clone(),
+                    offset,
+                }),
+            };
+
+            offset += child.width();
+            element
+        })
+    }
+
+    /// The nodes directly in this node.
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.children_with_tokens().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// The tokens directly in this node.
+    pub fn tokens(&self) -> impl Iterator<Item = SyntaxToken> + '_ {
+        self.children_with_tokens().filter_map(|child| match child {
+            SyntaxElement::Token(token) => Some(token),
+            SyntaxElement::Node(_) => None,
+        })
+    }
+
+    /// This node and every node in it, parents first in source order.
+    pub fn descendants(&self) -> Vec<SyntaxNode> {
+        let mut out = vec![self.clone()];
+
+        for child in self.children() {
+            out.extend(child.descendants());
+        }
+
+        out
+    }
+
+    /// Every token in this node, in source order.
+    pub fn descendant_tokens(&self) -> Vec<SyntaxToken> {
+        let mut out = Vec::new();
+
+        for child in self.children_with_tokens() {
+            match child {
+                SyntaxElement::Node(node) => out.extend(node.descendant_tokens()),
+                SyntaxElement::Token(token) => out.push(token),
+            }
+        }
+
+        out
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl std::hash::Hash for SyntaxNode {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        Rc::as_ptr(&self.0.green).hash(state);
+        self.0.offset.hash(state);
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in self.descendant_tokens() {
+            write!(f, "{}", token.text())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = f.width().unwrap_or(0);
+        writeln!(f, "{:indent$}{:?}@{:?}", "", self.kind(), self.span(), indent = indent)?;
+
+        for child in self.children_with_tokens() {
+            match child {
+                SyntaxElement::Node(node) => write!(f, "{:width$?}", node, width = indent + 2)?,
+                SyntaxElement::Token(token) => writeln!(f, "{:indent$}{:?}", "", token, indent = indent + 2)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A token of the red tree.
+#[derive(Clone)]
+pub struct SyntaxToken {
+    /// The node that this token is in.
+    parent: SyntaxNode,
+
+    /// The green token that this token is over.
+    green: Rc<GreenToken>,
+
+    /// The position of this token in the source.
+    offset: usize,
+}
+
+impl SyntaxToken {
+    /// The green token that this token is over.
+    pub fn green(&self) -> &Rc<GreenToken> {
+        &self.green
+    }
+
+    /// The kind of the token.
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind
+    }
+
+    /// The text of the token.
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    /// The location of the token, which is empty if it is synthetic.
+    pub fn span(&self) -> Span {
+        self.offset..self.offset + self.green.text.len()
+    }
+
+    /// Whether or not the token was inserted rather than written, such as a semicolon inserted at a line break.
+    pub fn is_synthetic(&self) -> bool {
+        self.green.synthetic
+    }
+
+    /// Whether or not the token is trivia, which the grammar skips.
+    pub fn is_trivia(&self) -> bool {
+        self.green.kind.is_trivia()
+    }
+
+    /// The node that this token is in.
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+}
+
+impl PartialEq for SyntaxToken {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl Eq for SyntaxToken {}
+
+impl fmt::Display for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text())
+    }
+}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_synthetic() {
+            write!(f, "{:?}@{:?} (synthetic)", self.kind(), self.span())
+        } else {
+            write!(f, "{:?}@{:?} {:?}", self.kind(), self.span(), self.text())
+        }
+    }
+}
+
+/// A token or node of the red tree.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    /// The kind of the element.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind(),
+            SyntaxElement::Token(token) => token.kind(),
+        }
+    }
+
+    /// The location of the element.
+    pub fn span(&self) -> Span {
+        match self {
+            SyntaxElement::Node(node) => node.span(),
+            SyntaxElement::Token(token) => token.span(),
+        }
+    }
+}
+
+/// A token of a unit, including trivia.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+    /// The kind of the token.
+    pub kind: SyntaxKind,
+
+    /// The location of the token, which is empty if it is synthetic.
+    pub span: Span,
+
+    /// Whether or not the token was inserted rather than written.
+    pub synthetic: bool,
+}
+
+/// Splits a unit into tokens which cover every byte of it, keeping trivia and marking inserted semicolons.
+pub fn tokenize(src: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut end = 0;
+
+    for (start, tok, stop) in Asi::lex(src).flatten() {
+        trivia(src, end..start, &mut tokens);
+
+        if tok == Tok::Semi && &src[start..stop] != ";" {
+            // an inserted semicolon has the span of the line break it was inserted at, which is kept as trivia.
+            tokens.push(Token { kind: SyntaxKind::Semi, span: start..start, synthetic: true });
+            end = start;
+        } else {
+            tokens.push(Token { kind: tok.into(), span: start..stop, synthetic: false });
+            end = stop;
+        }
+    }
+
+    trivia(src, end..src.len(), &mut tokens);
+    tokens
+}
+
+/// Splits the text between two tokens into trivia. Anything that is not trivia is an error, as the lexer rejected it.
+fn trivia(src: &str, span: Span, tokens: &mut Vec<Token>) {
+    let mut start = span.start;
+
+    while start < span.end {
+        let rest = &src[start..span.end];
+        let (kind, len) = if rest.starts_with("\r\n") {
+            (SyntaxKind::Newline, 2)
+        } else if rest.starts_with(['\n', '\r']) {
+            (SyntaxKind::Newline, 1)
+        } else if rest.starts_with("//") {
+            (SyntaxKind::Comment, rest.find(['\n', '\r']).unwrap_or(rest.len()))
+        } else if rest.starts_with([' ', '\t']) {
+            (SyntaxKind::Whitespace, rest.find(|c| c != ' ' && c != '\t').unwrap_or(rest.len()))
+        } else {
+            (SyntaxKind::Error, rest.find([' ', '\t', '\n', '\r']).unwrap_or(rest.len()))
+        };
+
+        tokens.push(Token { kind, span: start..start + len, synthetic: false });
+        start += len;
+    }
+}
+
+/// A unit parsed into a lossless syntax tree.
+pub struct Parse {
+    /// The root of the tree.
+    pub syntax: SyntaxNode,
+
+    /// The error that the unit failed to parse with, if any. The tree still holds every token: the root statements
+    /// before the error keep their nodes, and the tokens from the statement with the error onwards go directly in the
+    /// root.
+    pub error: Option<Diagnostic>,
+}
+
+/// Parses a unit into a lossless syntax tree.
+pub fn parse(src: &str) -> Parse {
+    match crate::parse(src) {
+        Ok(ast) => Parse { syntax: build(src, &ast), error: None },
+        Err(error) => Parse { syntax: build(src, &parse_before(src, &error)), error: Some(error) },
+    }
+}
+
+/// Parses the root statements of a unit which come before the error that it failed to parse with.
+fn parse_before<'a>(src: &'a str, error: &Diagnostic) -> Vec<ast::RootStmnt<'a>> {
+    let offset = error.primary().map_or(src.len(), |label| label.span.start);
+
+    // root statements end at semicolons outside of any brackets, whether written or inserted.
+    let mut depth = 0usize;
+    let mut ends = Vec::new();
+    for token in tokenize(src).into_iter().take_while(|token| token.span.end <= offset) {
+        match token.kind {
+            SyntaxKind::LParen | SyntaxKind::LBrack | SyntaxKind::LCurly => depth += 1,
+            SyntaxKind::RParen | SyntaxKind::RBrack | SyntaxKind::RCurly => depth = depth.saturating_sub(1),
+            SyntaxKind::Semi if depth == 0 => ends.push(token.span.end),
+            _ => {}
+        }
+    }
+
+    // an inserted semicolon may depend on what follows it, so back off until the statements parse on their own.
+    ends.into_iter().rev().find_map(|end| crate::parse(&src[..end]).ok()).unwrap_or_default()
+}
+
+/// Builds the lossless syntax tree of a unit from the AST that was parsed from it.
+///
+/// Each token goes in the innermost node whose span contains it, so trivia between two nodes goes in their parent.
+pub fn build(src: &str, ast: &[ast::RootStmnt]) -> SyntaxNode {
+    let mut collect = Collect(Vec::new());
+
+    for stmnt in ast {
+        collect.visit_root_stmnt(stmnt);
+    }
+
+    // nodes are collected parents first in source order, so each node starts after the one before it.
+    let mut nodes = collect.0.into_iter().peekable();
+    let mut stack = vec![(SyntaxKind::Root, usize::MAX, Vec::new())];
+
+    for token in tokenize(src) {
+        let start = token.span.start;
+        close(&mut stack, start);
+
+        while let Some((kind, span)) = nodes.next_if(|(_, span)| span.start <= start) {
+            stack.push((kind, span.end, Vec::new()));
+            close(&mut stack, start);
+        }
+
+        let green = GreenToken::new(token.kind, &src[token.span], token.synthetic);
+        stack.last_mut().unwrap().2.push(GreenElement::Token(Rc::new(green)));
+    }
+
+    close(&mut stack, usize::MAX);
+
+    let (kind, _, children) = stack.pop().unwrap();
+    SyntaxNode::new_root(Rc::new(GreenNode::new(kind, children)))
+}
+
+/// Finishes the open nodes which end at or before `offset`, adding each to its parent. The root is never finished.
+fn close(stack: &mut Vec<(SyntaxKind, usize, Vec<GreenElement>)>, offset: usize) {
+    while stack.len() > 1 && stack.last().unwrap().1 <= offset {
+        let (kind, _, children) = stack.pop().unwrap();
+        let node = GreenElement::Node(Rc::new(GreenNode::new(kind, children)));
+        stack.last_mut().unwrap().2.push(node);
+    }
+}
+
+/// Collects the kind and span of every node of an AST, parents first in source order.
+struct Collect(Vec<(SyntaxKind, Span)>);
+
+impl<'a> Visit<'a> for Collect {
+    fn visit_bool(&mut self, node: &ast::Bool) {
+        self.0.push((SyntaxKind::Bool, node.span.clone()));
+        visit::visit_bool(self, node);
+    }
+
+    fn visit_id(&mut self, node: &ast::Id<'a>) {
+        self.0.push((SyntaxKind::Id, node.span.clone()));
+        visit::visit_id(self, node);
+    }
+
+    fn visit_num(&mut self, node: &ast::Num<'a>) {
+        self.0.push((SyntaxKind::Num, node.span.clone()));
+        visit::visit_num(self, node);
+    }
+
+    fn visit_str(&mut self, node: &ast::Str<'a>) {
+        self.0.push((SyntaxKind::Str, node.span.clone()));
+        visit::visit_str(self, node);
+    }
+
+    fn visit_global_path(&mut self, node: &ast::GlobalPath<'a>) {
+        let kind = match node {
+            ast::GlobalPath::Id(node) => (SyntaxKind::GlobalPathId, node.span().clone()),
+            ast::GlobalPath::Branch { span, .. } => (SyntaxKind::GlobalPathBranch, span.clone()),
+        };
+
+        self.0.push(kind);
+        visit::visit_global_path(self, node);
+    }
+
+    fn visit_marker(&mut self, node: &ast::Marker<'a>) {
+        self.0.push((SyntaxKind::Marker, node.span.clone()));
+        visit::visit_marker(self, node);
+    }
+
+    fn visit_routine_arg(&mut self, node: &ast::RoutineArg<'a>) {
+        self.0.push((SyntaxKind::RoutineArg, node.span.clone()));
+        visit::visit_routine_arg(self, node);
+    }
+
+    fn visit_routine(&mut self, node: &ast::Routine<'a>) {
+        self.0.push((SyntaxKind::Routine, node.span.clone()));
+        visit::visit_routine(self, node);
+    }
+
+    fn visit_path_type(&mut self, node: &ast::PathType<'a>) {
+        self.0.push((SyntaxKind::PathType, node.span.clone()));
+        visit::visit_path_type(self, node);
+    }
+
+    fn visit_routine_type(&mut self, node: &ast::RoutineType<'a>) {
+        self.0.push((SyntaxKind::RoutineType, node.span.clone()));
+        visit::visit_routine_type(self, node);
+    }
+
+    fn visit_struct_prop(&mut self, node: &ast::StructProp<'a>) {
+        self.0.push((SyntaxKind::StructProp, node.span.clone()));
+        visit::visit_struct_prop(self, node);
+    }
+
+    fn visit_struct_type(&mut self, node: &ast::StructType<'a>) {
+        self.0.push((SyntaxKind::StructType, node.span.clone()));
+        visit::visit_struct_type(self, node);
+    }
+
+    fn visit_union_type(&mut self, node: &ast::UnionType<'a>) {
+        self.0.push((SyntaxKind::UnionType, node.span.clone()));
+        visit::visit_union_type(self, node);
+    }
+
+    fn visit_enum_prop(&mut self, node: &ast::EnumProp<'a>) {
+        self.0.push((SyntaxKind::EnumProp, node.span.clone()));
+        visit::visit_enum_prop(self, node);
+    }
+
+    fn visit_enum_type(&mut self, node: &ast::EnumType<'a>) {
+        self.0.push((SyntaxKind::EnumType, node.span.clone()));
+        visit::visit_enum_type(self, node);
+    }
+
+    fn visit_contract_type(&mut self, node: &ast::ContractType<'a>) {
+        self.0.push((SyntaxKind::ContractType, node.span.clone()));
+        visit::visit_contract_type(self, node);
+    }
+
+    fn visit_shared_type(&mut self, node: &ast::SharedType<'a>) {
+        self.0.push((SyntaxKind::SharedType, node.span.clone()));
+        visit::visit_shared_type(self, node);
+    }
+
+    fn visit_fluid_type(&mut self, node: &ast::FluidType<'a>) {
+        self.0.push((SyntaxKind::FluidType, node.span.clone()));
+        visit::visit_fluid_type(self, node);
+    }
+
+    fn visit_ref_type(&mut self, node: &ast::RefType<'a>) {
+        self.0.push((SyntaxKind::RefType, node.span.clone()));
+        visit::visit_ref_type(self, node);
+    }
+
+    fn visit_dyn_type(&mut self, node: &ast::DynType<'a>) {
+        self.0.push((SyntaxKind::DynType, node.span.clone()));
+        visit::visit_dyn_type(self, node);
+    }
+
+    fn visit_variadic_type(&mut self, node: &ast::VariadicType<'a>) {
+        self.0.push((SyntaxKind::VariadicType, node.span.clone()));
+        visit::visit_variadic_type(self, node);
+    }
+
+    fn visit_opt_type(&mut self, node: &ast::OptType<'a>) {
+        self.0.push((SyntaxKind::OptType, node.span.clone()));
+        visit::visit_opt_type(self, node);
+    }
+
+    fn visit_res_type(&mut self, node: &ast::ResType<'a>) {
+        self.0.push((SyntaxKind::ResType, node.span.clone()));
+        visit::visit_res_type(self, node);
+    }
+
+    fn visit_type(&mut self, node: &ast::Type<'a>) {
+        let kind = match node {
+            ast::Type::Id(node) => (SyntaxKind::TypeId, node.span().clone()),
+            ast::Type::Path(node) => (SyntaxKind::TypePath, node.span().clone()),
+            ast::Type::Routine(node) => (SyntaxKind::TypeRoutine, node.span().clone()),
+            ast::Type::Closure(node) => (SyntaxKind::TypeClosure, node.span().clone()),
+            ast::Type::Struct(node) => (SyntaxKind::TypeStruct, node.span().clone()),
+            ast::Type::Union(node) => (SyntaxKind::TypeUnion, node.span().clone()),
+            ast::Type::Enum(node) => (SyntaxKind::TypeEnum, node.span().clone()),
+            ast::Type::Contract(node) => (SyntaxKind::TypeContract, node.span().clone()),
+            ast::Type::Shared(node) => (SyntaxKind::TypeShared, node.span().clone()),
+            ast::Type::Fluid(node) => (SyntaxKind::TypeFluid, node.span().clone()),
+            ast::Type::Ref(node) => (SyntaxKind::TypeRef, node.span().clone()),
+            ast::Type::Dyn(node) => (SyntaxKind::TypeDyn, node.span().clone()),
+            ast::Type::Variadic(node) => (SyntaxKind::TypeVariadic, node.span().clone()),
+            ast::Type::Opt(node) => (SyntaxKind::TypeOpt, node.span().clone()),
+            ast::Type::Res(node) => (SyntaxKind::TypeRes, node.span().clone()),
+        };
+
+        self.0.push(kind);
+        visit::visit_type(self, node);
+    }
+
+    fn visit_path(&mut self, node: &ast::Path<'a>) {
+        self.0.push((SyntaxKind::Path, node.span.clone()));
+        visit::visit_path(self, node);
+    }
+
+    fn visit_access(&mut self, node: &ast::Access<'a>) {
+        self.0.push((SyntaxKind::Access, node.span.clone()));
+        visit::visit_access(self, node);
+    }
+
+    fn visit_call(&mut self, node: &ast::Call<'a>) {
+        self.0.push((SyntaxKind::Call, node.span.clone()));
+        visit::visit_call(self, node);
+    }
+
+    fn visit_unary(&mut self, node: &ast::Unary<'a>) {
+        self.0.push((SyntaxKind::Unary, node.span.clone()));
+        visit::visit_unary(self, node);
+    }
+
+    fn visit_as(&mut self, node: &ast::As<'a>) {
+        self.0.push((SyntaxKind::As, node.span.clone()));
+        visit::visit_as(self, node);
+    }
+
+    fn visit_binary(&mut self, node: &ast::Binary<'a>) {
+        self.0.push((SyntaxKind::Binary, node.span.clone()));
+        visit::visit_binary(self, node);
+    }
+
+    fn visit_construct_prop(&mut self, node: &ast::ConstructProp<'a>) {
+        self.0.push((SyntaxKind::ConstructProp, node.span.clone()));
+        visit::visit_construct_prop(self, node);
+    }
+
+    fn visit_construct(&mut self, node: &ast::Construct<'a>) {
+        self.0.push((SyntaxKind::Construct, node.span.clone()));
+        visit::visit_construct(self, node);
+    }
+
+    fn visit_construct_enum(&mut self, node: &ast::ConstructEnum<'a>) {
+        self.0.push((SyntaxKind::ConstructEnum, node.span.clone()));
+        visit::visit_construct_enum(self, node);
+    }
+
+    fn visit_wrap(&mut self, node: &ast::Wrap<'a>) {
+        self.0.push((SyntaxKind::Wrap, node.span.clone()));
+        visit::visit_wrap(self, node);
+    }
+
+    fn visit_none_lit(&mut self, node: &ast::NoneLit) {
+        self.0.push((SyntaxKind::NoneLit, node.span.clone()));
+        visit::visit_none_lit(self, node);
+    }
+
+    fn visit_try(&mut self, node: &ast::Try<'a>) {
+        self.0.push((SyntaxKind::Try, node.span.clone()));
+        visit::visit_try(self, node);
+    }
+
+    fn visit_index(&mut self, node: &ast::Index<'a>) {
+        self.0.push((SyntaxKind::Index, node.span.clone()));
+        visit::visit_index(self, node);
+    }
+
+    fn visit_asm_operand(&mut self, node: &ast::AsmOperand<'a>) {
+        self.0.push((SyntaxKind::AsmOperand, node.span.clone()));
+        visit::visit_asm_operand(self, node);
+    }
+
+    fn visit_asm(&mut self, node: &ast::Asm<'a>) {
+        self.0.push((SyntaxKind::Asm, node.span.clone()));
+        visit::visit_asm(self, node);
+    }
+
+    fn visit_expr(&mut self, node: &ast::Expr<'a>) {
+        let kind = match node {
+            ast::Expr::Bool(node) => (SyntaxKind::ExprBool, node.span().clone()),
+            ast::Expr::Id(node) => (SyntaxKind::ExprId, node.span().clone()),
+            ast::Expr::Num(node) => (SyntaxKind::ExprNum, node.span().clone()),
+            ast::Expr::Str(node) => (SyntaxKind::ExprStr, node.span().clone()),
+            ast::Expr::Path(node) => (SyntaxKind::ExprPath, node.span().clone()),
+            ast::Expr::Access(node) => (SyntaxKind::ExprAccess, node.span().clone()),
+            ast::Expr::Call(node) => (SyntaxKind::ExprCall, node.span().clone()),
+            ast::Expr::Unary(node) => (SyntaxKind::ExprUnary, node.span().clone()),
+            ast::Expr::As(node) => (SyntaxKind::ExprAs, node.span().clone()),
+            ast::Expr::Binary(node) => (SyntaxKind::ExprBinary, node.span().clone()),
+            ast::Expr::Routine(node) => (SyntaxKind::ExprRoutine, node.span().clone()),
+            ast::Expr::Construct(node) => (SyntaxKind::ExprConstruct, node.span().clone()),
+            ast::Expr::ConstructEnum(node) => (SyntaxKind::ExprConstructEnum, node.span().clone()),
+            ast::Expr::Asm(node) => (SyntaxKind::ExprAsm, node.span().clone()),
+            ast::Expr::Ok(node) => (SyntaxKind::ExprOk, node.span().clone()),
+            ast::Expr::Err(node) => (SyntaxKind::ExprErr, node.span().clone()),
+            ast::Expr::None(node) => (SyntaxKind::ExprNone, node.span().clone()),
+            ast::Expr::Try(node) => (SyntaxKind::ExprTry, node.span().clone()),
+            ast::Expr::Index(node) => (SyntaxKind::ExprIndex, node.span().clone()),
+        };
+
+        self.0.push(kind);
+        visit::visit_expr(self, node);
+    }
+
+    fn visit_assign(&mut self, node: &ast::Assign<'a>) {
+        self.0.push((SyntaxKind::Assign, node.span.clone()));
+        visit::visit_assign(self, node);
+    }
+
+    fn visit_val(&mut self, node: &ast::Val<'a>) {
+        self.0.push((SyntaxKind::Val, node.span.clone()));
+        visit::visit_val(self, node);
+    }
+
+    fn visit_const(&mut self, node: &ast::Const<'a>) {
+        self.0.push((SyntaxKind::Const, node.span.clone()));
+        visit::visit_const(self, node);
+    }
+
+    fn visit_type_decl(&mut self, node: &ast::TypeDecl<'a>) {
+        self.0.push((SyntaxKind::TypeDecl, node.span.clone()));
+        visit::visit_type_decl(self, node);
+    }
+
+    fn visit_if_branch(&mut self, node: &ast::IfBranch<'a>) {
+        let kind = match node {
+            ast::IfBranch::ElseIf { span, .. } => (SyntaxKind::IfBranchElseIf, span.clone()),
+            ast::IfBranch::Else { span, .. } => (SyntaxKind::IfBranchElse, span.clone()),
+        };
+
+        self.0.push(kind);
+        visit::visit_if_branch(self, node);
+    }
+
+    fn visit_if(&mut self, node: &ast::If<'a>) {
+        self.0.push((SyntaxKind::If, node.span.clone()));
+        visit::visit_if(self, node);
+    }
+
+    fn visit_while(&mut self, node: &ast::While<'a>) {
+        self.0.push((SyntaxKind::While, node.span.clone()));
+        visit::visit_while(self, node);
+    }
+
+    fn visit_match_case(&mut self, node: &ast::MatchCase<'a>) {
+        self.0.push((SyntaxKind::MatchCase, node.span.clone()));
+        visit::visit_match_case(self, node);
+    }
+
+    fn visit_match(&mut self, node: &ast::Match<'a>) {
+        self.0.push((SyntaxKind::Match, node.span.clone()));
+        visit::visit_match(self, node);
+    }
+
+    fn visit_break(&mut self, node: &ast::Break<'a>) {
+        self.0.push((SyntaxKind::Break, node.span.clone()));
+        visit::visit_break(self, node);
+    }
+
+    fn visit_continue(&mut self, node: &ast::Continue<'a>) {
+        self.0.push((SyntaxKind::Continue, node.span.clone()));
+        visit::visit_continue(self, node);
+    }
+
+    fn visit_return(&mut self, node: &ast::Return<'a>) {
+        self.0.push((SyntaxKind::Return, node.span.clone()));
+        visit::visit_return(self, node);
+    }
+
+    fn visit_defer(&mut self, node: &ast::Defer<'a>) {
+        self.0.push((SyntaxKind::Defer, node.span.clone()));
+        visit::visit_defer(self, node);
+    }
+
+    fn visit_flag_expr(&mut self, node: &ast::FlagExpr<'a>) {
+        let kind = match node {
+            ast::FlagExpr::Name(node) => (SyntaxKind::FlagExprName, node.span().clone()),
+            ast::FlagExpr::Value { span, .. } => (SyntaxKind::FlagExprValue, span.clone()),
+            ast::FlagExpr::Any { span, .. } => (SyntaxKind::FlagExprAny, span.clone()),
+            ast::FlagExpr::All { span, .. } => (SyntaxKind::FlagExprAll, span.clone()),
+            ast::FlagExpr::Not { span, .. } => (SyntaxKind::FlagExprNot, span.clone()),
+        };
+
+        self.0.push(kind);
+        visit::visit_flag_expr(self, node);
+    }
+
+    fn visit_flag(&mut self, node: &ast::Flag<'a>) {
+        self.0.push((SyntaxKind::Flag, node.span.clone()));
+        visit::visit_flag(self, node);
+    }
+
+    fn visit_block_expr(&mut self, node: &ast::BlockExpr<'a>) {
+        let kind = match node {
+            ast::BlockExpr::Assign(node) => (SyntaxKind::BlockExprAssign, node.span().clone()),
+            ast::BlockExpr::Val(node) => (SyntaxKind::BlockExprVal, node.span().clone()),
+            ast::BlockExpr::Call(node) => (SyntaxKind::BlockExprCall, node.span().clone()),
+            ast::BlockExpr::If(node) => (SyntaxKind::BlockExprIf, node.span().clone()),
+            ast::BlockExpr::While(node) => (SyntaxKind::BlockExprWhile, node.span().clone()),
+            ast::BlockExpr::Match(node) => (SyntaxKind::BlockExprMatch, node.span().clone()),
+            ast::BlockExpr::TypeDecl(node) => (SyntaxKind::BlockExprTypeDecl, node.span().clone()),
+            ast::BlockExpr::Break(node) => (SyntaxKind::BlockExprBreak, node.span().clone()),
+            ast::BlockExpr::Continue(node) => (SyntaxKind::BlockExprContinue, node.span().clone()),
+            ast::BlockExpr::Return(node) => (SyntaxKind::BlockExprReturn, node.span().clone()),
+            ast::BlockExpr::Defer(node) => (SyntaxKind::BlockExprDefer, node.span().clone()),
+            ast::BlockExpr::Asm(node) => (SyntaxKind::BlockExprAsm, node.span().clone()),
+            ast::BlockExpr::Block(flags, node) => {
+                let start = flags.first().map_or(node.span().start, |flag| flag.span.start);
+                (SyntaxKind::BlockExprBlock, start..node.span().end)
+            },
+        };
+
+        self.0.push(kind);
+        visit::visit_block_expr(self, node);
+    }
+
+    fn visit_block(&mut self, node: &ast::Block<'a>) {
+        self.0.push((SyntaxKind::Block, node.span.clone()));
+        visit::visit_block(self, node);
+    }
+
+    fn visit_import_item(&mut self, node: &ast::ImportItem<'a>) {
+        self.0.push((SyntaxKind::ImportItem, node.span.clone()));
+        visit::visit_import_item(self, node);
+    }
+
+    fn visit_import(&mut self, node: &ast::Import<'a>) {
+        let kind = match node {
+            ast::Import::Single { span, .. } => (SyntaxKind::ImportSingle, span.clone()),
+            ast::Import::Multi { span, .. } => (SyntaxKind::ImportMulti, span.clone()),
+            ast::Import::Glob { span, .. } => (SyntaxKind::ImportGlob, span.clone()),
+        };
+
+        self.0.push(kind);
+        visit::visit_import(self, node);
+    }
+
+    fn visit_application(&mut self, node: &ast::Application<'a>) {
+        let kind = match node {
+            ast::Application::Val(node) => (SyntaxKind::ApplicationVal, node.span().clone()),
+            ast::Application::TypeDecl(node) => (SyntaxKind::ApplicationTypeDecl, node.span().clone()),
+        };
+
+        self.0.push(kind);
+        visit::visit_application(self, node);
+    }
+
+    fn visit_apply(&mut self, node: &ast::Apply<'a>) {
+        self.0.push((SyntaxKind::Apply, node.span.clone()));
+        visit::visit_apply(self, node);
+    }
+
+    fn visit_module(&mut self, node: &ast::Module<'a>) {
+        self.0.push((SyntaxKind::Module, node.span.clone()));
+        visit::visit_module(self, node);
+    }
+
+    fn visit_root_stmnt(&mut self, node: &ast::RootStmnt<'a>) {
+        let kind = match node {
+            ast::RootStmnt::Assign(node) => (SyntaxKind::RootStmntAssign, node.span().clone()),
+            ast::RootStmnt::Val(flags, node) => {
+                let start = flags.first().map_or(node.span().start, |flag| flag.span.start);
+                (SyntaxKind::RootStmntVal, start..node.span().end)
+            },
+            ast::RootStmnt::Const(flags, node) => {
+                let start = flags.first().map_or(node.span().start, |flag| flag.span.start);
+                (SyntaxKind::RootStmntConst, start..node.span().end)
+            },
+            ast::RootStmnt::Call(node) => (SyntaxKind::RootStmntCall, node.span().clone()),
+            ast::RootStmnt::Import(flags, node) => {
+                let start = flags.first().map_or(node.span().start, |flag| flag.span.start);
+                (SyntaxKind::RootStmntImport, start..node.span().end)
+            },
+            ast::RootStmnt::If(node) => (SyntaxKind::RootStmntIf, node.span().clone()),
+            ast::RootStmnt::While(node) => (SyntaxKind::RootStmntWhile, node.span().clone()),
+            ast::RootStmnt::Match(node) => (SyntaxKind::RootStmntMatch, node.span().clone()),
+            ast::RootStmnt::TypeDecl(flags, node) => {
+                let start = flags.first().map_or(node.span().start, |flag| flag.span.start);
+                (SyntaxKind::RootStmntTypeDecl, start..node.span().end)
+            },
+            ast::RootStmnt::Apply(flags, node) => {
+                let start = flags.first().map_or(node.span().start, |flag| flag.span.start);
+                (SyntaxKind::RootStmntApply, start..node.span().end)
+            },
+            ast::RootStmnt::Block(flags, node) => {
+                let start = flags.first().map_or(node.span().start, |flag| flag.span.start);
+                (SyntaxKind::RootStmntBlock, start..node.span().end)
+            },
+            ast::RootStmnt::Module(flags, node) => {
+                let start = flags.first().map_or(node.span().start, |flag| flag.span.start);
+                (SyntaxKind::RootStmntModule, start..node.span().end)
+            },
+        };
+
+        self.0.push(kind);
+        visit::visit_root_stmnt(self, node);
+    }
+}
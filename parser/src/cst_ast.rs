@@ -0,0 +1,2611 @@
+//! A typed view over the lossless syntax tree.
+//!
+//! Each type wraps a [`SyntaxNode`] and mirrors a type of the [`ast`](crate::ast). Children are found by their kind
+//! and position whenever they are asked for, rather than stored, so the view is as cheap to make as the tree itself.
+
+use crate::ast::{AssignOp, BinaryOp, NumKind, UnaryOp};
+use crate::cst::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// A typed view of a node.
+pub trait AstNode: Sized {
+    /// Whether or not nodes of a kind can be viewed as this type.
+    fn can_cast(kind: SyntaxKind) -> bool;
+
+    /// Views a node as this type, if it is of the right kind.
+    fn cast(node: SyntaxNode) -> Option<Self>;
+
+    /// The node being viewed.
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+/// The root of a unit.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Root(SyntaxNode);
+
+impl AstNode for Root {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Root
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Root {
+    /// The statements in the root of the unit.
+    pub fn items(&self) -> Vec<RootStmnt> {
+        support::children(&self.0)
+    }
+}
+
+/// A boolean expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Bool(SyntaxNode);
+
+impl AstNode for Bool {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Bool
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Bool {
+    /// The raw value of the boolean.
+    pub fn value(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KTrue).is_some()
+    }
+}
+
+/// An identifier expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Id(SyntaxNode);
+
+impl AstNode for Id {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Id
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Id {
+    /// The raw value of the identifier.
+    pub fn value(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::Ident)
+    }
+}
+
+/// A number expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Num(SyntaxNode);
+
+impl AstNode for Num {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Num
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Num {
+    /// The kind of the number.
+    pub fn kind(&self) -> Option<NumKind> {
+        self.value().and_then(|token| Self::num_kind(token.kind()))
+    }
+
+    /// The raw value of the number.
+    pub fn value(&self) -> Option<SyntaxToken> {
+        self.0.tokens().find(|token| Self::num_kind(token.kind()).is_some())
+    }
+
+    /// The kind of number that a token is, if it is a number.
+    fn num_kind(kind: SyntaxKind) -> Option<NumKind> {
+        match kind {
+            SyntaxKind::IntLit => Some(NumKind::Int),
+            SyntaxKind::XIntLit => Some(NumKind::XInt),
+            SyntaxKind::BIntLit => Some(NumKind::BInt),
+            SyntaxKind::FloatLit => Some(NumKind::Float),
+            _ => None,
+        }
+    }
+}
+
+/// A string expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Str(SyntaxNode);
+
+impl AstNode for Str {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Str
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Str {
+    /// The raw value of the string.
+    pub fn value(&self) -> Option<SyntaxToken> {
+        support::token(&self.0, SyntaxKind::StrLit)
+    }
+}
+
+/// A path expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum GlobalPath {
+    Id(Id),
+    Branch(GlobalPathBranch),
+}
+
+impl AstNode for GlobalPath {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::GlobalPathId | SyntaxKind::GlobalPathBranch)
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::GlobalPathId => support::child(&node, 0).map(GlobalPath::Id),
+            SyntaxKind::GlobalPathBranch => GlobalPathBranch::cast(node).map(GlobalPath::Branch),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            GlobalPath::Id(node) => node.syntax(),
+            GlobalPath::Branch(node) => node.syntax(),
+        }
+    }
+}
+
+impl GlobalPath {
+    /// The flags of the statement.
+    pub fn flags(&self) -> Vec<Flag> {
+        self.syntax().parent().map_or_else(Vec::new, |parent| support::children(&parent))
+    }
+}
+
+/// A path branch.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GlobalPathBranch(SyntaxNode);
+
+impl AstNode for GlobalPathBranch {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::GlobalPathBranch
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl GlobalPathBranch {
+    /// The path to branch off of.
+    pub fn path(&self) -> Option<GlobalPath> {
+        support::child(&self.0, 0)
+    }
+
+    /// The name of the branch.
+    pub fn id(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A compiler marker, such as `#inline(always)`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Marker(SyntaxNode);
+
+impl AstNode for Marker {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Marker
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Marker {
+    /// The name of the marker.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The arguments of the marker.
+    pub fn args(&self) -> Vec<Expr> {
+        support::children(&self.0)
+    }
+}
+
+/// An argument in a routine.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RoutineArg(SyntaxNode);
+
+impl AstNode for RoutineArg {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::RoutineArg
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl RoutineArg {
+    /// The name of the argument.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The type annotation of the argument.
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A routine declaration.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Routine(SyntaxNode);
+
+impl AstNode for Routine {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Routine
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Routine {
+    /// The name of the rout
+    pub fn args(&self) -> Vec<RoutineArg> {
+        support::children(&self.0)
+    }
+
+    /// The value that this routine returns.
+    pub fn returns(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    /// A marker in the routine.
+    pub fn markers(&self) -> Vec<Marker> {
+        support::children(&self.0)
+    }
+
+    /// The block of the routine.
+    pub fn block(&self) -> Option<Block> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A path in a type expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PathType(SyntaxNode);
+
+impl AstNode for PathType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::PathType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl PathType {
+    /// The left side of the path.
+    pub fn left(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    /// The right side of the path.
+    pub fn right(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A routine type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RoutineType(SyntaxNode);
+
+impl AstNode for RoutineType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::RoutineType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl RoutineType {
+    /// The arguments of this routine.
+    pub fn args(&self) -> Vec<Type> {
+        support::section(&self.0, SyntaxKind::RArrow, 0)
+    }
+
+    /// The type that this routine returns.
+    pub fn returns(&self) -> Option<Type> {
+        support::section(&self.0, SyntaxKind::RArrow, 1).into_iter().next()
+    }
+}
+
+/// A struct property.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct StructProp(SyntaxNode);
+
+impl AstNode for StructProp {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::StructProp
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl StructProp {
+    /// The markers of this property.
+    pub fn markers(&self) -> Vec<Marker> {
+        support::children(&self.0)
+    }
+
+    /// Whether or not the property is shared with other modules.
+    pub fn shared(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KShared).is_some()
+    }
+
+    /// The value of the property.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The type of this property.
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    /// The width of this property in bits, if it is a bitfield.
+    pub fn bits(&self) -> Option<Num> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A struct type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct StructType(SyntaxNode);
+
+impl AstNode for StructType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::StructType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl StructType {
+    /// The properties of this struct.
+    pub fn props(&self) -> Vec<StructProp> {
+        support::children(&self.0)
+    }
+}
+
+/// An untagged union type, whose properties all share the same memory.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UnionType(SyntaxNode);
+
+impl AstNode for UnionType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::UnionType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl UnionType {
+    /// The properties of this union.
+    pub fn props(&self) -> Vec<StructProp> {
+        support::children(&self.0)
+    }
+}
+
+/// An enum property.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EnumProp(SyntaxNode);
+
+impl AstNode for EnumProp {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::EnumProp
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl EnumProp {
+    /// The markers of this property.
+    pub fn markers(&self) -> Vec<Marker> {
+        support::children(&self.0)
+    }
+
+    /// The value of the property.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The type of this property.
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    /// The explicit discriminant of this property.
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An enum type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EnumType(SyntaxNode);
+
+impl AstNode for EnumType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::EnumType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl EnumType {
+    /// The properties of this enum.
+    pub fn props(&self) -> Vec<EnumProp> {
+        support::children(&self.0)
+    }
+}
+
+/// A contract type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ContractType(SyntaxNode);
+
+impl AstNode for ContractType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ContractType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl ContractType {
+    /// The properties of this contract.
+    pub fn items(&self) -> Vec<Application> {
+        support::children(&self.0)
+    }
+}
+
+/// A shared type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SharedType(SyntaxNode);
+
+impl AstNode for SharedType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::SharedType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl SharedType {
+    /// The subject of this shared type.
+    pub fn subject(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A fluid type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FluidType(SyntaxNode);
+
+impl AstNode for FluidType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FluidType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl FluidType {
+    /// The subject of this fluid type.
+    pub fn subject(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A ref type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RefType(SyntaxNode);
+
+impl AstNode for RefType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::RefType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl RefType {
+    /// The subject of this ref type.
+    pub fn subject(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A contract object type, `dyn Contract`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DynType(SyntaxNode);
+
+impl AstNode for DynType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::DynType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl DynType {
+    /// The contract of this contract object type.
+    pub fn subject(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// The variadic arguments at the end of a routine's arguments, `...` for C variadics or `...VarArg` for hail
+/// variadics.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct VariadicType(SyntaxNode);
+
+impl AstNode for VariadicType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::VariadicType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl VariadicType {
+    /// The type that each argument is passed as, if these are hail variadics.
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An option type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct OptType(SyntaxNode);
+
+impl AstNode for OptType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::OptType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl OptType {
+    /// The subject of this option type.
+    pub fn subject(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A result type.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ResType(SyntaxNode);
+
+impl AstNode for ResType {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ResType
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl ResType {
+    /// The left side of this result type.
+    pub fn ok(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    /// The right side of this result type.
+    pub fn err(&self) -> Option<Type> {
+        support::child(&self.0, 1)
+    }
+}
+
+/// A type expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Type {
+    Id(Id),
+    Path(PathType),
+    Routine(RoutineType),
+    Closure(RoutineType),
+    Struct(StructType),
+    Union(UnionType),
+    Enum(EnumType),
+    Contract(ContractType),
+    Shared(SharedType),
+    Fluid(FluidType),
+    Ref(RefType),
+    Dyn(DynType),
+    Variadic(VariadicType),
+    Opt(OptType),
+    Res(ResType),
+}
+
+impl AstNode for Type {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::TypeId
+            | SyntaxKind::TypePath
+            | SyntaxKind::TypeRoutine
+            | SyntaxKind::TypeClosure
+            | SyntaxKind::TypeStruct
+            | SyntaxKind::TypeUnion
+            | SyntaxKind::TypeEnum
+            | SyntaxKind::TypeContract
+            | SyntaxKind::TypeShared
+            | SyntaxKind::TypeFluid
+            | SyntaxKind::TypeRef
+            | SyntaxKind::TypeDyn
+            | SyntaxKind::TypeVariadic
+            | SyntaxKind::TypeOpt
+            | SyntaxKind::TypeRes
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::TypeId => support::child(&node, 0).map(Type::Id),
+            SyntaxKind::TypePath => support::child(&node, 0).map(Type::Path),
+            SyntaxKind::TypeRoutine => support::child(&node, 0).map(Type::Routine),
+            SyntaxKind::TypeClosure => support::child(&node, 0).map(Type::Closure),
+            SyntaxKind::TypeStruct => support::child(&node, 0).map(Type::Struct),
+            SyntaxKind::TypeUnion => support::child(&node, 0).map(Type::Union),
+            SyntaxKind::TypeEnum => support::child(&node, 0).map(Type::Enum),
+            SyntaxKind::TypeContract => support::child(&node, 0).map(Type::Contract),
+            SyntaxKind::TypeShared => support::child(&node, 0).map(Type::Shared),
+            SyntaxKind::TypeFluid => support::child(&node, 0).map(Type::Fluid),
+            SyntaxKind::TypeRef => support::child(&node, 0).map(Type::Ref),
+            SyntaxKind::TypeDyn => support::child(&node, 0).map(Type::Dyn),
+            SyntaxKind::TypeVariadic => support::child(&node, 0).map(Type::Variadic),
+            SyntaxKind::TypeOpt => support::child(&node, 0).map(Type::Opt),
+            SyntaxKind::TypeRes => support::child(&node, 0).map(Type::Res),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Type::Id(node) => node.syntax(),
+            Type::Path(node) => node.syntax(),
+            Type::Routine(node) => node.syntax(),
+            Type::Closure(node) => node.syntax(),
+            Type::Struct(node) => node.syntax(),
+            Type::Union(node) => node.syntax(),
+            Type::Enum(node) => node.syntax(),
+            Type::Contract(node) => node.syntax(),
+            Type::Shared(node) => node.syntax(),
+            Type::Fluid(node) => node.syntax(),
+            Type::Ref(node) => node.syntax(),
+            Type::Dyn(node) => node.syntax(),
+            Type::Variadic(node) => node.syntax(),
+            Type::Opt(node) => node.syntax(),
+            Type::Res(node) => node.syntax(),
+        }
+    }
+}
+
+/// A path expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Path(SyntaxNode);
+
+impl AstNode for Path {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Path
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Path {
+    /// The left side of the path.
+    pub fn left(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The right side of this path.
+    pub fn right(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An access expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Access(SyntaxNode);
+
+impl AstNode for Access {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Access
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Access {
+    /// The left side of the access.
+    pub fn left(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The right side of this access.
+    pub fn right(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An call expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Call(SyntaxNode);
+
+impl AstNode for Call {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Call
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Call {
+    /// The left side of the call.
+    pub fn left(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The arguments of this call.
+    pub fn args(&self) -> Vec<Expr> {
+        support::children(&self.0).into_iter().skip(1).collect()
+    }
+}
+
+/// A unary expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Unary(SyntaxNode);
+
+impl AstNode for Unary {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Unary
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Unary {
+    /// The operator of this expression.
+    pub fn op(&self) -> Option<UnaryOp> {
+        self.0.tokens().find_map(|token| Self::operator(token.kind()))
+    }
+
+    /// The subject of this expression.
+    pub fn subject(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The operator that a token is, if it is a unary operator.
+    fn operator(kind: SyntaxKind) -> Option<UnaryOp> {
+        match kind {
+            SyntaxKind::Min => Some(UnaryOp::Min),
+            SyntaxKind::Star => Some(UnaryOp::Star),
+            SyntaxKind::Bang => Some(UnaryOp::Bang),
+            SyntaxKind::Amp => Some(UnaryOp::Borrow),
+            SyntaxKind::KFluid => Some(UnaryOp::Fluid),
+            SyntaxKind::KShared => Some(UnaryOp::Shared),
+            _ => None,
+        }
+    }
+}
+
+/// An `as` expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct As(SyntaxNode);
+
+impl AstNode for As {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::As
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl As {
+    /// The subject of the expression.
+    pub fn subject(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The type of the expression.
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A binary expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Binary(SyntaxNode);
+
+impl AstNode for Binary {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Binary
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Binary {
+    /// The operator of the expression.
+    pub fn op(&self) -> Option<BinaryOp> {
+        self.0.tokens().find_map(|token| Self::operator(token.kind()))
+    }
+
+    /// The left side of the expression.
+    pub fn left(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The right side of the expression.
+    pub fn right(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+
+    /// The operator that a token is, if it is a binary operator.
+    fn operator(kind: SyntaxKind) -> Option<BinaryOp> {
+        match kind {
+            SyntaxKind::Star => Some(BinaryOp::Star),
+            SyntaxKind::Slash => Some(BinaryOp::Slash),
+            SyntaxKind::Perc => Some(BinaryOp::Perc),
+            SyntaxKind::Plus => Some(BinaryOp::Plus),
+            SyntaxKind::Min => Some(BinaryOp::Min),
+            SyntaxKind::LtLt => Some(BinaryOp::LtLt),
+            SyntaxKind::GtGt => Some(BinaryOp::GtGt),
+            SyntaxKind::Amp => Some(BinaryOp::Amp),
+            SyntaxKind::Caret => Some(BinaryOp::Caret),
+            SyntaxKind::Pipe => Some(BinaryOp::Pipe),
+            SyntaxKind::EqEq => Some(BinaryOp::EqEq),
+            SyntaxKind::BangEq => Some(BinaryOp::BangEq),
+            SyntaxKind::Lt => Some(BinaryOp::Lt),
+            SyntaxKind::Gt => Some(BinaryOp::Gt),
+            SyntaxKind::LtEq => Some(BinaryOp::LtEq),
+            SyntaxKind::GtEq => Some(BinaryOp::GtEq),
+            SyntaxKind::AmpAmp => Some(BinaryOp::AmpAmp),
+            SyntaxKind::PipePipe => Some(BinaryOp::PipePipe),
+            SyntaxKind::QuestQuest => Some(BinaryOp::QuestQuest),
+            _ => None,
+        }
+    }
+}
+
+/// A property in a struct constructor.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ConstructProp(SyntaxNode);
+
+impl AstNode for ConstructProp {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ConstructProp
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl ConstructProp {
+    /// The name of the property.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The value of the property.
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A struct constructor.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Construct(SyntaxNode);
+
+impl AstNode for Construct {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Construct
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Construct {
+    /// The name of the construct.
+    pub fn subject(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The value of the construct.
+    pub fn items(&self) -> Vec<ConstructProp> {
+        support::children(&self.0)
+    }
+}
+
+/// An enum constructor.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ConstructEnum(SyntaxNode);
+
+impl AstNode for ConstructEnum {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ConstructEnum
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl ConstructEnum {
+    /// The name of the enum.
+    pub fn subject(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The properties of the enum.
+    pub fn item(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+}
+
+/// An `ok(value)` or `err(value)` constructor.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Wrap(SyntaxNode);
+
+impl AstNode for Wrap {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Wrap
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Wrap {
+    /// The value being wrapped.
+    pub fn item(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A `none` literal.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct NoneLit(SyntaxNode);
+
+impl AstNode for NoneLit {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::NoneLit
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+/// A `?` expression, which unwraps an option or result and returns early on `none` or `err`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Try(SyntaxNode);
+
+impl AstNode for Try {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Try
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Try {
+    /// The option or result being unwrapped.
+    pub fn subject(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An index expression, such as `list[i]`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Index(SyntaxNode);
+
+impl AstNode for Index {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Index
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Index {
+    /// The value being indexed.
+    pub fn subject(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The index.
+    pub fn index(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+}
+
+/// An operand of an inline assembly block.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AsmOperand(SyntaxNode);
+
+impl AstNode for AsmOperand {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::AsmOperand
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl AsmOperand {
+    /// The constraint of the operand, such as `"=r"`.
+    pub fn constraint(&self) -> Option<Str> {
+        support::child(&self.0, 0)
+    }
+
+    /// The value bound to the operand.
+    pub fn expr(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An inline assembly block.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Asm(SyntaxNode);
+
+impl AstNode for Asm {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Asm
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Asm {
+    /// The assembly template.
+    pub fn template(&self) -> Option<Str> {
+        support::child(&self.0, 0)
+    }
+
+    /// The output operands of the block.
+    pub fn outputs(&self) -> Vec<AsmOperand> {
+        support::section(&self.0, SyntaxKind::Col, 1)
+    }
+
+    /// The input operands of the block.
+    pub fn inputs(&self) -> Vec<AsmOperand> {
+        support::section(&self.0, SyntaxKind::Col, 2)
+    }
+
+    /// The registers and memory clobbered by the block.
+    pub fn clobbers(&self) -> Vec<Str> {
+        support::children(&self.0).into_iter().skip(1).collect()
+    }
+
+    /// The options of the block, such as `volatile`.
+    pub fn options(&self) -> Vec<Id> {
+        support::children(&self.0)
+    }
+}
+
+/// An expression from hail source.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Expr {
+    Bool(Bool),
+    Id(Id),
+    Num(Num),
+    Str(Str),
+    Path(Path),
+    Access(Access),
+    Call(Call),
+    Unary(Unary),
+    As(As),
+    Binary(Binary),
+    Routine(Routine),
+    Construct(Construct),
+    ConstructEnum(ConstructEnum),
+    Asm(Asm),
+    Ok(Wrap),
+    Err(Wrap),
+    None(NoneLit),
+    Try(Try),
+    Index(Index),
+}
+
+impl AstNode for Expr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::ExprBool
+            | SyntaxKind::ExprId
+            | SyntaxKind::ExprNum
+            | SyntaxKind::ExprStr
+            | SyntaxKind::ExprPath
+            | SyntaxKind::ExprAccess
+            | SyntaxKind::ExprCall
+            | SyntaxKind::ExprUnary
+            | SyntaxKind::ExprAs
+            | SyntaxKind::ExprBinary
+            | SyntaxKind::ExprRoutine
+            | SyntaxKind::ExprConstruct
+            | SyntaxKind::ExprConstructEnum
+            | SyntaxKind::ExprAsm
+            | SyntaxKind::ExprOk
+            | SyntaxKind::ExprErr
+            | SyntaxKind::ExprNone
+            | SyntaxKind::ExprTry
+            | SyntaxKind::ExprIndex
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::ExprBool => support::child(&node, 0).map(Expr::Bool),
+            SyntaxKind::ExprId => support::child(&node, 0).map(Expr::Id),
+            SyntaxKind::ExprNum => support::child(&node, 0).map(Expr::Num),
+            SyntaxKind::ExprStr => support::child(&node, 0).map(Expr::Str),
+            SyntaxKind::ExprPath => support::child(&node, 0).map(Expr::Path),
+            SyntaxKind::ExprAccess => support::child(&node, 0).map(Expr::Access),
+            SyntaxKind::ExprCall => support::child(&node, 0).map(Expr::Call),
+            SyntaxKind::ExprUnary => support::child(&node, 0).map(Expr::Unary),
+            SyntaxKind::ExprAs => support::child(&node, 0).map(Expr::As),
+            SyntaxKind::ExprBinary => support::child(&node, 0).map(Expr::Binary),
+            SyntaxKind::ExprRoutine => support::child(&node, 0).map(Expr::Routine),
+            SyntaxKind::ExprConstruct => support::child(&node, 0).map(Expr::Construct),
+            SyntaxKind::ExprConstructEnum => support::child(&node, 0).map(Expr::ConstructEnum),
+            SyntaxKind::ExprAsm => support::child(&node, 0).map(Expr::Asm),
+            SyntaxKind::ExprOk => support::child(&node, 0).map(Expr::Ok),
+            SyntaxKind::ExprErr => support::child(&node, 0).map(Expr::Err),
+            SyntaxKind::ExprNone => support::child(&node, 0).map(Expr::None),
+            SyntaxKind::ExprTry => support::child(&node, 0).map(Expr::Try),
+            SyntaxKind::ExprIndex => support::child(&node, 0).map(Expr::Index),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Expr::Bool(node) => node.syntax(),
+            Expr::Id(node) => node.syntax(),
+            Expr::Num(node) => node.syntax(),
+            Expr::Str(node) => node.syntax(),
+            Expr::Path(node) => node.syntax(),
+            Expr::Access(node) => node.syntax(),
+            Expr::Call(node) => node.syntax(),
+            Expr::Unary(node) => node.syntax(),
+            Expr::As(node) => node.syntax(),
+            Expr::Binary(node) => node.syntax(),
+            Expr::Routine(node) => node.syntax(),
+            Expr::Construct(node) => node.syntax(),
+            Expr::ConstructEnum(node) => node.syntax(),
+            Expr::Asm(node) => node.syntax(),
+            Expr::Ok(node) => node.syntax(),
+            Expr::Err(node) => node.syntax(),
+            Expr::None(node) => node.syntax(),
+            Expr::Try(node) => node.syntax(),
+            Expr::Index(node) => node.syntax(),
+        }
+    }
+}
+
+/// An assignment expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Assign(SyntaxNode);
+
+impl AstNode for Assign {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Assign
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Assign {
+    /// The assignment operator to use.
+    pub fn op(&self) -> Option<AssignOp> {
+        self.0.tokens().find_map(|token| Self::operator(token.kind()))
+    }
+
+    /// The subject to assign to.
+    pub fn left(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The right side of the expression.
+    pub fn right(&self) -> Option<Expr> {
+        support::child(&self.0, 1)
+    }
+
+    /// The operator that a token is, if it is an assignment operator.
+    fn operator(kind: SyntaxKind) -> Option<AssignOp> {
+        match kind {
+            SyntaxKind::Eq => Some(AssignOp::Eq),
+            SyntaxKind::PlusEq => Some(AssignOp::PlusEq),
+            SyntaxKind::MinEq => Some(AssignOp::MinEq),
+            SyntaxKind::StarEq => Some(AssignOp::StarEq),
+            SyntaxKind::SlashEq => Some(AssignOp::SlashEq),
+            SyntaxKind::PercEq => Some(AssignOp::PercEq),
+            SyntaxKind::AmpEq => Some(AssignOp::AmpEq),
+            SyntaxKind::PipeEq => Some(AssignOp::PipeEq),
+            SyntaxKind::CaretEq => Some(AssignOp::CaretEq),
+            SyntaxKind::LtLtEq => Some(AssignOp::LtLtEq),
+            SyntaxKind::GtGtEq => Some(AssignOp::GtGtEq),
+            _ => None,
+        }
+    }
+}
+
+/// A variable declaration.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Val(SyntaxNode);
+
+impl AstNode for Val {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Val
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Val {
+    /// The markers of the variable.
+    pub fn markers(&self) -> Vec<Marker> {
+        support::children(&self.0)
+    }
+
+    /// Whether or not the variable is shared with other modules.
+    pub fn shared(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KShared).is_some()
+    }
+
+    /// The name of the variable.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The type annotation of the variable.
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    /// The value of the variable.
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A compile time constant declaration.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Const(SyntaxNode);
+
+impl AstNode for Const {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Const
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Const {
+    /// The markers of the constant.
+    pub fn markers(&self) -> Vec<Marker> {
+        support::children(&self.0)
+    }
+
+    /// Whether or not the constant is shared with other modules.
+    pub fn shared(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KShared).is_some()
+    }
+
+    /// The name of the constant.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The type annotation of the constant.
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    /// The value of the constant.
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A type declaration.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct TypeDecl(SyntaxNode);
+
+impl AstNode for TypeDecl {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::TypeDecl
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl TypeDecl {
+    /// The markers of the type declaration.
+    pub fn markers(&self) -> Vec<Marker> {
+        support::children(&self.0)
+    }
+
+    /// Whether or not the type is shared with other modules.
+    pub fn shared(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KShared).is_some()
+    }
+
+    /// The name of the type declaration.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The value of the type declaration.
+    pub fn value(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A branch after an `if` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum IfBranch {
+    ElseIf(IfBranchElseIf),
+    Else(IfBranchElse),
+}
+
+impl AstNode for IfBranch {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::IfBranchElseIf | SyntaxKind::IfBranchElse)
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::IfBranchElseIf => IfBranchElseIf::cast(node).map(IfBranch::ElseIf),
+            SyntaxKind::IfBranchElse => IfBranchElse::cast(node).map(IfBranch::Else),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            IfBranch::ElseIf(node) => node.syntax(),
+            IfBranch::Else(node) => node.syntax(),
+        }
+    }
+}
+
+impl IfBranch {
+    /// The flags of the statement.
+    pub fn flags(&self) -> Vec<Flag> {
+        self.syntax().parent().map_or_else(Vec::new, |parent| support::children(&parent))
+    }
+}
+
+/// An `else if` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct IfBranchElseIf(SyntaxNode);
+
+impl AstNode for IfBranchElseIf {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::IfBranchElseIf
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl IfBranchElseIf {
+    /// The condition of the `if` branch.
+    pub fn cond(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The block of the `if` branch.
+    pub fn block(&self) -> Option<Block> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An `else` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct IfBranchElse(SyntaxNode);
+
+impl AstNode for IfBranchElse {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::IfBranchElse
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl IfBranchElse {
+    /// The block of the `if` branch.
+    pub fn block(&self) -> Option<Block> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An `if` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct If(SyntaxNode);
+
+impl AstNode for If {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::If
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl If {
+    /// The condition of the statement.
+    pub fn cond(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The block of the statement.
+    pub fn block(&self) -> Option<Block> {
+        support::child(&self.0, 0)
+    }
+
+    /// The branches of the statement.
+    pub fn branches(&self) -> Vec<IfBranch> {
+        support::children(&self.0)
+    }
+}
+
+/// A `while` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct While(SyntaxNode);
+
+impl AstNode for While {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::While
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl While {
+    /// The label of the statement.
+    pub fn label(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The condition of the statement.
+    pub fn cond(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The block of the statement.
+    pub fn block(&self) -> Option<Block> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A case in a match statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MatchCase(SyntaxNode);
+
+impl AstNode for MatchCase {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::MatchCase
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl MatchCase {
+    /// The name of the case.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The type of the case.
+    pub fn ty(&self) -> Option<Type> {
+        support::child(&self.0, 0)
+    }
+
+    /// The block of the case.
+    pub fn block(&self) -> Option<Block> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A `match` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Match(SyntaxNode);
+
+impl AstNode for Match {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Match
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Match {
+    /// The subject of the statement.
+    pub fn subject(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+
+    /// The cases of the statement.
+    pub fn cases(&self) -> Vec<MatchCase> {
+        support::children(&self.0)
+    }
+}
+
+/// A `break` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Break(SyntaxNode);
+
+impl AstNode for Break {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Break
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Break {
+    /// The subject of the statement.
+    pub fn label(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A `continue` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Continue(SyntaxNode);
+
+impl AstNode for Continue {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Continue
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Continue {
+    /// The subject of the statement.
+    pub fn label(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A `return` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Return(SyntaxNode);
+
+impl AstNode for Return {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Return
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Return {
+    /// The subject of the statement.
+    pub fn value(&self) -> Option<Expr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A `defer` statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Defer(SyntaxNode);
+
+impl AstNode for Defer {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Defer
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Defer {
+    /// The statement to run when the enclosing block exits.
+    pub fn item(&self) -> Option<BlockExpr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A compile time flag expression.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum FlagExpr {
+    Name(Id),
+    Value(FlagExprValue),
+    Any(FlagExprAny),
+    All(FlagExprAll),
+    Not(FlagExprNot),
+}
+
+impl AstNode for FlagExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::FlagExprName
+            | SyntaxKind::FlagExprValue
+            | SyntaxKind::FlagExprAny
+            | SyntaxKind::FlagExprAll
+            | SyntaxKind::FlagExprNot
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::FlagExprName => support::child(&node, 0).map(FlagExpr::Name),
+            SyntaxKind::FlagExprValue => FlagExprValue::cast(node).map(FlagExpr::Value),
+            SyntaxKind::FlagExprAny => FlagExprAny::cast(node).map(FlagExpr::Any),
+            SyntaxKind::FlagExprAll => FlagExprAll::cast(node).map(FlagExpr::All),
+            SyntaxKind::FlagExprNot => FlagExprNot::cast(node).map(FlagExpr::Not),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            FlagExpr::Name(node) => node.syntax(),
+            FlagExpr::Value(node) => node.syntax(),
+            FlagExpr::Any(node) => node.syntax(),
+            FlagExpr::All(node) => node.syntax(),
+            FlagExpr::Not(node) => node.syntax(),
+        }
+    }
+}
+
+impl FlagExpr {
+    /// The flags of the statement.
+    pub fn flags(&self) -> Vec<Flag> {
+        self.syntax().parent().map_or_else(Vec::new, |parent| support::children(&parent))
+    }
+}
+
+/// A key/value flag, such as `target_arch("x86_64")`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FlagExprValue(SyntaxNode);
+
+impl AstNode for FlagExprValue {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FlagExprValue
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl FlagExprValue {
+    /// The key of the flag.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The value that the flag must have.
+    pub fn value(&self) -> Option<Str> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// Satisfied if any of the flags are, such as `any(os_linux, os_macos)`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FlagExprAny(SyntaxNode);
+
+impl AstNode for FlagExprAny {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FlagExprAny
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl FlagExprAny {
+    /// The flags to check.
+    pub fn items(&self) -> Vec<FlagExpr> {
+        support::children(&self.0)
+    }
+}
+
+/// Satisfied if all of the flags are, such as `all(os_linux, debug)`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FlagExprAll(SyntaxNode);
+
+impl AstNode for FlagExprAll {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FlagExprAll
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl FlagExprAll {
+    /// The flags to check.
+    pub fn items(&self) -> Vec<FlagExpr> {
+        support::children(&self.0)
+    }
+}
+
+/// Satisfied if the flag is not, such as `not(debug)`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct FlagExprNot(SyntaxNode);
+
+impl AstNode for FlagExprNot {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::FlagExprNot
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl FlagExprNot {
+    /// The flag to check.
+    pub fn item(&self) -> Option<FlagExpr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// Compile time flags, for conditional compilation.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Flag(SyntaxNode);
+
+impl AstNode for Flag {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Flag
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Flag {
+    /// Whether or not the flag is negative.
+    pub fn neg(&self) -> bool {
+        support::token(&self.0, SyntaxKind::Bang).is_some()
+    }
+
+    /// The expression of the flag.
+    pub fn expr(&self) -> Option<FlagExpr> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An expression in a block.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum BlockExpr {
+    Assign(Assign),
+    Val(Val),
+    Call(Call),
+    If(If),
+    While(While),
+    Match(Match),
+    TypeDecl(TypeDecl),
+    Break(Break),
+    Continue(Continue),
+    Return(Return),
+    Defer(Defer),
+    Asm(Asm),
+    Block(Block),
+}
+
+impl AstNode for BlockExpr {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::BlockExprAssign
+            | SyntaxKind::BlockExprVal
+            | SyntaxKind::BlockExprCall
+            | SyntaxKind::BlockExprIf
+            | SyntaxKind::BlockExprWhile
+            | SyntaxKind::BlockExprMatch
+            | SyntaxKind::BlockExprTypeDecl
+            | SyntaxKind::BlockExprBreak
+            | SyntaxKind::BlockExprContinue
+            | SyntaxKind::BlockExprReturn
+            | SyntaxKind::BlockExprDefer
+            | SyntaxKind::BlockExprAsm
+            | SyntaxKind::BlockExprBlock
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::BlockExprAssign => support::child(&node, 0).map(BlockExpr::Assign),
+            SyntaxKind::BlockExprVal => support::child(&node, 0).map(BlockExpr::Val),
+            SyntaxKind::BlockExprCall => support::child(&node, 0).map(BlockExpr::Call),
+            SyntaxKind::BlockExprIf => support::child(&node, 0).map(BlockExpr::If),
+            SyntaxKind::BlockExprWhile => support::child(&node, 0).map(BlockExpr::While),
+            SyntaxKind::BlockExprMatch => support::child(&node, 0).map(BlockExpr::Match),
+            SyntaxKind::BlockExprTypeDecl => support::child(&node, 0).map(BlockExpr::TypeDecl),
+            SyntaxKind::BlockExprBreak => support::child(&node, 0).map(BlockExpr::Break),
+            SyntaxKind::BlockExprContinue => support::child(&node, 0).map(BlockExpr::Continue),
+            SyntaxKind::BlockExprReturn => support::child(&node, 0).map(BlockExpr::Return),
+            SyntaxKind::BlockExprDefer => support::child(&node, 0).map(BlockExpr::Defer),
+            SyntaxKind::BlockExprAsm => support::child(&node, 0).map(BlockExpr::Asm),
+            SyntaxKind::BlockExprBlock => support::child(&node, 0).map(BlockExpr::Block),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            BlockExpr::Assign(node) => node.syntax(),
+            BlockExpr::Val(node) => node.syntax(),
+            BlockExpr::Call(node) => node.syntax(),
+            BlockExpr::If(node) => node.syntax(),
+            BlockExpr::While(node) => node.syntax(),
+            BlockExpr::Match(node) => node.syntax(),
+            BlockExpr::TypeDecl(node) => node.syntax(),
+            BlockExpr::Break(node) => node.syntax(),
+            BlockExpr::Continue(node) => node.syntax(),
+            BlockExpr::Return(node) => node.syntax(),
+            BlockExpr::Defer(node) => node.syntax(),
+            BlockExpr::Asm(node) => node.syntax(),
+            BlockExpr::Block(node) => node.syntax(),
+        }
+    }
+}
+
+impl BlockExpr {
+    /// The flags of the statement.
+    pub fn flags(&self) -> Vec<Flag> {
+        self.syntax().parent().map_or_else(Vec::new, |parent| support::children(&parent))
+    }
+}
+
+/// A code block.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Block(SyntaxNode);
+
+impl AstNode for Block {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Block
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Block {
+    /// The items in the block.
+    pub fn items(&self) -> Vec<BlockExpr> {
+        support::children(&self.0)
+    }
+}
+
+/// An item being imported.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ImportItem(SyntaxNode);
+
+impl AstNode for ImportItem {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ImportItem
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl ImportItem {
+    /// The name of the item.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The alias to use, if any.
+    pub fn as_(&self) -> Option<Id> {
+        support::child(&self.0, 1)
+    }
+}
+
+/// An import statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Import {
+    Single(ImportSingle),
+    Multi(ImportMulti),
+    Glob(ImportGlob),
+}
+
+impl AstNode for Import {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::ImportSingle | SyntaxKind::ImportMulti | SyntaxKind::ImportGlob)
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::ImportSingle => ImportSingle::cast(node).map(Import::Single),
+            SyntaxKind::ImportMulti => ImportMulti::cast(node).map(Import::Multi),
+            SyntaxKind::ImportGlob => ImportGlob::cast(node).map(Import::Glob),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Import::Single(node) => node.syntax(),
+            Import::Multi(node) => node.syntax(),
+            Import::Glob(node) => node.syntax(),
+        }
+    }
+}
+
+impl Import {
+    /// The flags of the statement.
+    pub fn flags(&self) -> Vec<Flag> {
+        self.syntax().parent().map_or_else(Vec::new, |parent| support::children(&parent))
+    }
+}
+
+/// A single item import.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ImportSingle(SyntaxNode);
+
+impl AstNode for ImportSingle {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ImportSingle
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl ImportSingle {
+    /// Whether or not the import is shared.
+    pub fn shared(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KShared).is_some()
+    }
+
+    /// The name of the item to import.
+    pub fn item(&self) -> Option<ImportItem> {
+        support::child(&self.0, 0)
+    }
+
+    /// The module that the item is from.
+    pub fn from(&self) -> Option<GlobalPath> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// A single item import.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ImportMulti(SyntaxNode);
+
+impl AstNode for ImportMulti {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ImportMulti
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl ImportMulti {
+    /// Whether or not the import is shared.
+    pub fn shared(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KShared).is_some()
+    }
+
+    /// The name of the item to import.
+    pub fn items(&self) -> Vec<ImportItem> {
+        support::children(&self.0)
+    }
+
+    /// The module that the item is from.
+    pub fn from(&self) -> Option<GlobalPath> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An import of every item in a module, `import * from module`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ImportGlob(SyntaxNode);
+
+impl AstNode for ImportGlob {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::ImportGlob
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl ImportGlob {
+    /// Whether or not the import is shared.
+    pub fn shared(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KShared).is_some()
+    }
+
+    /// The module to import the items of.
+    pub fn from(&self) -> Option<GlobalPath> {
+        support::child(&self.0, 0)
+    }
+}
+
+/// An application in an apply statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Application {
+    Val(Val),
+    TypeDecl(TypeDecl),
+}
+
+impl AstNode for Application {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::ApplicationVal | SyntaxKind::ApplicationTypeDecl)
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::ApplicationVal => support::child(&node, 0).map(Application::Val),
+            SyntaxKind::ApplicationTypeDecl => support::child(&node, 0).map(Application::TypeDecl),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Application::Val(node) => node.syntax(),
+            Application::TypeDecl(node) => node.syntax(),
+        }
+    }
+}
+
+/// An apply statement.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Apply(SyntaxNode);
+
+impl AstNode for Apply {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Apply
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Apply {
+    /// The markers of the statement.
+    pub fn markers(&self) -> Vec<Marker> {
+        support::children(&self.0)
+    }
+
+    /// The subject to apply to, or the contract being applied.
+    pub fn subject(&self) -> Option<GlobalPath> {
+        support::child(&self.0, 0)
+    }
+
+    /// The subject to apply to.
+    pub fn to(&self) -> Option<GlobalPath> {
+        support::child(&self.0, 1)
+    }
+
+    /// The items being applied to the subject.
+    pub fn items(&self) -> Vec<Application> {
+        support::children(&self.0)
+    }
+}
+
+/// An inline module declaration, `module name { ... }`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Module(SyntaxNode);
+
+impl AstNode for Module {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::Module
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        if Self::can_cast(node.kind()) { Some(Self(node)) } else { None }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl Module {
+    /// Whether or not the module is shared with other modules.
+    pub fn shared(&self) -> bool {
+        support::token(&self.0, SyntaxKind::KShared).is_some()
+    }
+
+    /// The name of the module.
+    pub fn name(&self) -> Option<Id> {
+        support::child(&self.0, 0)
+    }
+
+    /// The statements in the root of the module.
+    pub fn items(&self) -> Vec<RootStmnt> {
+        support::children(&self.0)
+    }
+}
+
+/// A statement in the root of a hail unit.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum RootStmnt {
+    Assign(Assign),
+    Val(Val),
+    Const(Const),
+    Call(Call),
+    Import(Import),
+    If(If),
+    While(While),
+    Match(Match),
+    TypeDecl(TypeDecl),
+    Apply(Apply),
+    Block(Block),
+    Module(Module),
+}
+
+impl AstNode for RootStmnt {
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            SyntaxKind::RootStmntAssign
+            | SyntaxKind::RootStmntVal
+            | SyntaxKind::RootStmntConst
+            | SyntaxKind::RootStmntCall
+            | SyntaxKind::RootStmntImport
+            | SyntaxKind::RootStmntIf
+            | SyntaxKind::RootStmntWhile
+            | SyntaxKind::RootStmntMatch
+            | SyntaxKind::RootStmntTypeDecl
+            | SyntaxKind::RootStmntApply
+            | SyntaxKind::RootStmntBlock
+            | SyntaxKind::RootStmntModule
+        )
+    }
+
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::RootStmntAssign => support::child(&node, 0).map(RootStmnt::Assign),
+            SyntaxKind::RootStmntVal => support::child(&node, 0).map(RootStmnt::Val),
+            SyntaxKind::RootStmntConst => support::child(&node, 0).map(RootStmnt::Const),
+            SyntaxKind::RootStmntCall => support::child(&node, 0).map(RootStmnt::Call),
+            SyntaxKind::RootStmntImport => support::child(&node, 0).map(RootStmnt::Import),
+            SyntaxKind::RootStmntIf => support::child(&node, 0).map(RootStmnt::If),
+            SyntaxKind::RootStmntWhile => support::child(&node, 0).map(RootStmnt::While),
+            SyntaxKind::RootStmntMatch => support::child(&node, 0).map(RootStmnt::Match),
+            SyntaxKind::RootStmntTypeDecl => support::child(&node, 0).map(RootStmnt::TypeDecl),
+            SyntaxKind::RootStmntApply => support::child(&node, 0).map(RootStmnt::Apply),
+            SyntaxKind::RootStmntBlock => support::child(&node, 0).map(RootStmnt::Block),
+            SyntaxKind::RootStmntModule => support::child(&node, 0).map(RootStmnt::Module),
+            _ => None,
+        }
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            RootStmnt::Assign(node) => node.syntax(),
+            RootStmnt::Val(node) => node.syntax(),
+            RootStmnt::Const(node) => node.syntax(),
+            RootStmnt::Call(node) => node.syntax(),
+            RootStmnt::Import(node) => node.syntax(),
+            RootStmnt::If(node) => node.syntax(),
+            RootStmnt::While(node) => node.syntax(),
+            RootStmnt::Match(node) => node.syntax(),
+            RootStmnt::TypeDecl(node) => node.syntax(),
+            RootStmnt::Apply(node) => node.syntax(),
+            RootStmnt::Block(node) => node.syntax(),
+            RootStmnt::Module(node) => node.syntax(),
+        }
+    }
+}
+
+impl RootStmnt {
+    /// The flags of the statement.
+    pub fn flags(&self) -> Vec<Flag> {
+        self.syntax().parent().map_or_else(Vec::new, |parent| support::children(&parent))
+    }
+}
+
+/// Helpers for finding the children of a node.
+mod support {
+    use super::AstNode;
+    use crate::cst::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+    /// The children of a node which can be viewed as `N`.
+    pub fn children<N: AstNode>(parent: &SyntaxNode) -> Vec<N> {
+        parent.children().filter_map(N::cast).collect()
+    }
+
+    /// The `nth` child of a node which can be viewed as `N`.
+    pub fn child<N: AstNode>(parent: &SyntaxNode, nth: usize) -> Option<N> {
+        parent.children().filter_map(N::cast).nth(nth)
+    }
+
+    /// The first token of a kind directly in a node.
+    pub fn token(parent: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+        parent.tokens().find(|token| token.kind() == kind)
+    }
+
+    /// The children of a node which can be viewed as `N`, between the `index`th token of a kind and the next one.
+    pub fn section<N: AstNode>(parent: &SyntaxNode, separator: SyntaxKind, index: usize) -> Vec<N> {
+        let mut seen = 0;
+        let mut out = Vec::new();
+
+        for child in parent.children_with_tokens() {
+            match child {
+                SyntaxElement::Token(token) if token.kind() == separator => seen += 1,
+                SyntaxElement::Node(node) if seen == index => out.extend(N::cast(node)),
+                _ => (),
+            }
+        }
+
+        out
+    }
+}
@@ -0,0 +1,444 @@
+//! Diagnostics, which every pass of the compiler reports its errors and warnings through.
+//!
+//! A [`Diagnostic`] points into the source of a unit with labeled spans, and is rendered as snippets of that source
+//! with the spans underlined. The sources are kept in [`Files`], so that diagnostics can be rendered after the units
+//! they point into have been parsed and dropped.
+
+use std::fmt::Write;
+
+use lalrpop_util::ParseError;
+
+use crate::ast::Span;
+use crate::scanner::Tok;
+
+/// How severe a diagnostic is.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Severity {
+    /// A problem which stops compilation.
+    Error,
+
+    /// A likely mistake, which doesn't stop compilation.
+    Warning,
+}
+
+impl Severity {
+    /// The name of the severity, as it is rendered.
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+
+    /// The ANSI color that the severity is rendered in.
+    fn color(self) -> &'static str {
+        match self {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+        }
+    }
+}
+
+/// Whether a label marks the cause of a diagnostic or something related to it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LabelStyle {
+    /// The cause of the diagnostic, underlined with `^`.
+    Primary,
+
+    /// Something related to the cause, underlined with `-`.
+    Secondary,
+}
+
+/// A span of source code, with a message about it.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Label {
+    /// Whether the label marks the cause of the diagnostic.
+    pub style: LabelStyle,
+
+    /// The location of the label.
+    pub span: Span,
+
+    /// The message of the label, which may be empty.
+    pub message: String,
+}
+
+/// Whether a line under a diagnostic gives context or a suggestion.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NoteKind {
+    /// Extra context about the problem.
+    Note,
+
+    /// A suggestion for fixing the problem.
+    Help,
+}
+
+impl NoteKind {
+    /// The name of the kind, as it is rendered.
+    pub fn name(self) -> &'static str {
+        match self {
+            NoteKind::Note => "note",
+            NoteKind::Help => "help",
+        }
+    }
+}
+
+/// A suggested edit to the source, which replaces a span with new text.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Replacement {
+    /// The location to replace, which is empty to insert text.
+    pub span: Span,
+
+    /// The text to replace it with, which is empty to remove the span.
+    pub text: String,
+}
+
+/// A line under a diagnostic, which isn't tied to a span unless it suggests an edit.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Note {
+    /// Whether the line gives context or a suggestion.
+    pub kind: NoteKind,
+
+    /// The text of the line.
+    pub message: String,
+
+    /// The edit that the line suggests, if any.
+    pub replacement: Option<Replacement>,
+}
+
+/// An error or warning found in a unit.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Diagnostic {
+    /// How severe the diagnostic is.
+    pub severity: Severity,
+
+    /// The code of the diagnostic, such as `E0001`, if it has one.
+    pub code: Option<&'static str>,
+
+    /// A description of the problem.
+    pub message: String,
+
+    /// The file that the labels of the diagnostic point into, if it is about a file.
+    pub file: Option<FileId>,
+
+    /// The spans that the diagnostic points to, with its primary label first.
+    pub labels: Vec<Label>,
+
+    /// Notes and help lines about the problem, in the order they are rendered.
+    pub notes: Vec<Note>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic with no labels.
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self { severity, code: None, message: message.into(), file: None, labels: vec![], notes: vec![] }
+    }
+
+    /// Creates an error with no labels.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// Creates a warning with no labels.
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    /// Sets the code of the diagnostic.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Sets the file that the labels of the diagnostic point into.
+    pub fn with_file(mut self, file: FileId) -> Self {
+        self.file = Some(file);
+        self
+    }
+
+    /// Adds a label marking the cause of the diagnostic.
+    pub fn with_primary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label { style: LabelStyle::Primary, span, message: message.into() });
+        self
+    }
+
+    /// Adds a label marking something related to the cause of the diagnostic.
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label { style: LabelStyle::Secondary, span, message: message.into() });
+        self
+    }
+
+    /// Adds a note, giving extra context about the problem.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(Note { kind: NoteKind::Note, message: note.into(), replacement: None });
+        self
+    }
+
+    /// Adds a help line, suggesting how to fix the problem.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.notes.push(Note { kind: NoteKind::Help, message: help.into(), replacement: None });
+        self
+    }
+
+    /// Adds a help line, suggesting that a span of the source is replaced with new text.
+    pub fn with_suggestion(mut self, help: impl Into<String>, span: Span, text: impl Into<String>) -> Self {
+        let replacement = Some(Replacement { span, text: text.into() });
+        self.notes.push(Note { kind: NoteKind::Help, message: help.into(), replacement });
+        self
+    }
+
+    /// Whether or not the diagnostic stops compilation.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// The label marking the cause of the diagnostic, or its first label if none do.
+    pub fn primary(&self) -> Option<&Label> {
+        self.labels.iter().find(|label| label.style == LabelStyle::Primary).or_else(|| self.labels.first())
+    }
+
+    /// Renders the diagnostic, with a snippet of the source under the labels, optionally in color.
+    ///
+    /// ```text
+    /// error[E0002]: unexpected `)`
+    ///  --> main.hl:1:9
+    ///   |
+    /// 1 | val x = )
+    ///   |         ^ expected an expression
+    /// ```
+    pub fn render(&self, files: &Files, color: bool) -> String {
+        let paint = |style: &str, text: &str| if color { format!("{}{}{}", style, text, RESET) } else { text.to_string() };
+        let blue = format!("{}{}", BOLD, BLUE);
+        let severity = format!("{}{}", BOLD, self.severity.color());
+        let mut out = String::new();
+
+        let header = match self.code {
+            Some(code) => format!("{}[{}]", self.severity.name(), code),
+            None => self.severity.name().to_string(),
+        };
+        let _ = writeln!(out, "{}{}", paint(&severity, &header), paint(BOLD, &format!(": {}", self.message)));
+
+        // labels are shown under the line that they start on, lines in order and labels left to right.
+        let file = self.file.filter(|_| !self.labels.is_empty());
+        let mut labels: Vec<_> = match file {
+            Some(file) => self.labels.iter().map(|label| (files.line_col(file, label.span.start), label)).collect(),
+            None => vec![],
+        };
+        labels.sort_by_key(|(position, _)| *position);
+
+        let width = labels.last().map_or(0, |((line, _), _)| line.to_string().len());
+        let pad = " ".repeat(width);
+        let gutter = paint(&blue, &format!("{} |", pad));
+
+        if let (Some(file), Some(primary)) = (file, self.primary()) {
+            let (line, column) = files.line_col(file, primary.span.start);
+            let _ = writeln!(out, "{}{} {}:{}:{}", pad, paint(&blue, "-->"), files.name(file), line, column);
+            let _ = writeln!(out, "{}", gutter);
+
+            let mut last = None;
+            for &((line, _), label) in &labels {
+                let text = files.line(file, line);
+
+                if last != Some(line) {
+                    if matches!(last, Some(last) if line - last > 1) {
+                        let _ = writeln!(out, "{}", paint(&blue, "..."));
+                    }
+
+                    let number = paint(&blue, &format!("{:>width$} |", line, width = width));
+                    let _ = writeln!(out, "{} {}", number, expand_tabs(text));
+                    last = Some(line);
+                }
+
+                // spans which run onto later lines are underlined to the end of the line they start on.
+                let start = files.line_start(file, line);
+                let from = label.span.start.clamp(start, start + text.len()) - start;
+                let to = label.span.end.clamp(start + from, start + text.len()) - start;
+
+                let (mark, style) = match label.style {
+                    LabelStyle::Primary => ("^", &severity),
+                    LabelStyle::Secondary => ("-", &blue),
+                };
+                let mut underline = mark.repeat(display_width(&text[from..to]).max(1));
+                if !label.message.is_empty() {
+                    underline = format!("{} {}", underline, label.message);
+                }
+
+                let _ = writeln!(out, "{} {}{}", gutter, " ".repeat(display_width(&text[..from])), paint(style, &underline));
+            }
+
+            if !self.notes.is_empty() {
+                let _ = writeln!(out, "{}", gutter);
+            }
+        }
+
+        let equals = paint(&blue, &format!("{} =", pad));
+        for note in &self.notes {
+            let _ = match &note.replacement {
+                Some(replacement) if !replacement.text.is_empty() => {
+                    writeln!(out, "{} {}: {}: `{}`", equals, paint(BOLD, note.kind.name()), note.message, replacement.text)
+                },
+                _ => writeln!(out, "{} {}: {}", equals, paint(BOLD, note.kind.name()), note.message),
+            };
+        }
+
+        out
+    }
+}
+
+/// Builds the diagnostic for a unit that failed to parse.
+pub fn from_parse_error(src: &str, error: ParseError<usize, Tok<'_>, Diagnostic>) -> Diagnostic {
+    match error {
+        ParseError::User { error } => error,
+        ParseError::InvalidToken { location } => {
+            let end = location + src[location..].chars().next().map_or(0, char::len_utf8);
+
+            Diagnostic::error(format!("invalid token `{}`", &src[location..end]))
+                .with_code("E0001")
+                .with_primary(location..end, "not a valid token")
+        },
+        ParseError::UnrecognizedEOF { location, expected } => {
+            Diagnostic::error("unexpected end of file")
+                .with_code("E0003")
+                .with_primary(location..location, describe_expected(&expected))
+        },
+        ParseError::UnrecognizedToken { token: (start, tok, end), expected } => {
+            let message = match &src[start..end] {
+                // an inserted semicolon has the span of the line break it was inserted at.
+                text if tok == Tok::Semi && text != ";" => "unexpected end of line".to_string(),
+                text => format!("unexpected `{}`", text),
+            };
+
+            Diagnostic::error(message).with_code("E0002").with_primary(start..end, describe_expected(&expected))
+        },
+        ParseError::ExtraToken { token: (start, _, end) } => {
+            Diagnostic::error(format!("unexpected `{}`", &src[start..end]))
+                .with_code("E0002")
+                .with_primary(start..end, "expected the end of the file")
+        },
+    }
+}
+
+/// Describes the tokens that the parser expected, which it names the way they are written in the grammar.
+fn describe_expected(expected: &[String]) -> String {
+    let names: Vec<_> = expected
+        .iter()
+        .map(|name| match name.trim_matches('"') {
+            "id" => "an identifier".to_string(),
+            "int" | "xint" | "bint" | "float" => "a number".to_string(),
+            "str" => "a string".to_string(),
+            name => format!("`{}`", name.replace("\\\"", "\"")),
+        })
+        .fold(Vec::new(), |mut names, name| {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+
+            names
+        });
+
+    match names.as_slice() {
+        [] => "unexpected here".into(),
+        [name] => format!("expected {}", name),
+        [rest @ .., last] if names.len() <= 6 => format!("expected one of {} or {}", rest.join(", "), last),
+        _ => format!("expected one of {} possible tokens", names.len()),
+    }
+}
+
+/// The ANSI escapes that diagnostics are rendered with.
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[31m";
+const YELLOW: &str = "\x1b[33m";
+const BLUE: &str = "\x1b[34m";
+
+/// How many columns a tab is rendered as.
+const TAB_WIDTH: usize = 4;
+
+/// Replaces the tabs in a line of source with spaces, so that labels line up under it.
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// The number of columns that some source takes up once its tabs are expanded.
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c == '\t' { TAB_WIDTH } else { 1 }).sum()
+}
+
+/// A file in [`Files`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FileId(u32);
+
+impl FileId {
+    /// The index of the file in its [`Files`].
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A source file that diagnostics point into.
+#[derive(Clone, Debug)]
+struct File {
+    /// The name of the file, as it is rendered.
+    name: String,
+
+    /// The contents of the file.
+    source: String,
+
+    /// The offset of the start of each line.
+    lines: Vec<usize>,
+}
+
+/// The source files that diagnostics point into.
+#[derive(Clone, Debug, Default)]
+pub struct Files {
+    files: Vec<File>,
+}
+
+impl Files {
+    /// Creates an empty set of files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file, returning its id.
+    pub fn add(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        let source = source.into();
+        let lines = std::iter::once(0).chain(source.match_indices('\n').map(|(i, _)| i + 1)).collect();
+
+        self.files.push(File { name: name.into(), source, lines });
+        FileId(self.files.len() as u32 - 1)
+    }
+
+    /// The name of a file.
+    pub fn name(&self, file: FileId) -> &str {
+        &self.files[file.index()].name
+    }
+
+    /// The contents of a file.
+    pub fn source(&self, file: FileId) -> &str {
+        &self.files[file.index()].source
+    }
+
+    /// The line and column, both starting at one, of an offset into a file. Columns count characters, not bytes.
+    pub fn line_col(&self, file: FileId, offset: usize) -> (usize, usize) {
+        let file = &self.files[file.index()];
+        let offset = offset.min(file.source.len());
+        let line = file.lines.partition_point(|&start| start <= offset);
+        let start = file.lines[line - 1];
+
+        (line, file.source[start..offset].chars().count() + 1)
+    }
+
+    /// The offset of the start of a line, starting at one, in a file.
+    pub fn line_start(&self, file: FileId, line: usize) -> usize {
+        self.files[file.index()].lines[line - 1]
+    }
+
+    /// The text of a line, starting at one, in a file, without its line break.
+    pub fn line(&self, file: FileId, line: usize) -> &str {
+        let file = &self.files[file.index()];
+        let start = file.lines[line - 1];
+        let end = file.lines.get(line).copied().unwrap_or(file.source.len());
+
+        file.source[start..end].trim_end_matches(['\n', '\r'])
+    }
+}
@@ -0,0 +1,988 @@
+//! A consuming walk over the AST, which rebuilds the tree as it goes.
+//!
+//! Every method of [`Fold`] has a default that calls the free function of the same name, which folds each child of
+//! the node in source order and rebuilds the node from the results. The defaults leave the tree unchanged.
+
+use crate::ast::*;
+
+/// A folder over the AST.
+pub trait Fold<'a> {
+    /// Folds a boolean literal.
+    fn fold_bool(&mut self, node: Bool) -> Bool {
+        fold_bool(self, node)
+    }
+
+    /// Folds an identifier.
+    fn fold_id(&mut self, node: Id<'a>) -> Id<'a> {
+        fold_id(self, node)
+    }
+
+    /// Folds a number literal.
+    fn fold_num(&mut self, node: Num<'a>) -> Num<'a> {
+        fold_num(self, node)
+    }
+
+    /// Folds a string literal.
+    fn fold_str(&mut self, node: Str<'a>) -> Str<'a> {
+        fold_str(self, node)
+    }
+
+    /// Folds a path in the root of a unit.
+    fn fold_global_path(&mut self, node: GlobalPath<'a>) -> GlobalPath<'a> {
+        fold_global_path(self, node)
+    }
+
+    /// Folds a marker.
+    fn fold_marker(&mut self, node: Marker<'a>) -> Marker<'a> {
+        fold_marker(self, node)
+    }
+
+    /// Folds an argument of a routine literal.
+    fn fold_routine_arg(&mut self, node: RoutineArg<'a>) -> RoutineArg<'a> {
+        fold_routine_arg(self, node)
+    }
+
+    /// Folds a routine literal.
+    fn fold_routine(&mut self, node: Routine<'a>) -> Routine<'a> {
+        fold_routine(self, node)
+    }
+
+    /// Folds a path in a type.
+    fn fold_path_type(&mut self, node: PathType<'a>) -> PathType<'a> {
+        fold_path_type(self, node)
+    }
+
+    /// Folds a routine or closure type.
+    fn fold_routine_type(&mut self, node: RoutineType<'a>) -> RoutineType<'a> {
+        fold_routine_type(self, node)
+    }
+
+    /// Folds a property of a struct or union type.
+    fn fold_struct_prop(&mut self, node: StructProp<'a>) -> StructProp<'a> {
+        fold_struct_prop(self, node)
+    }
+
+    /// Folds a struct type.
+    fn fold_struct_type(&mut self, node: StructType<'a>) -> StructType<'a> {
+        fold_struct_type(self, node)
+    }
+
+    /// Folds a union type.
+    fn fold_union_type(&mut self, node: UnionType<'a>) -> UnionType<'a> {
+        fold_union_type(self, node)
+    }
+
+    /// Folds a property of an enum type.
+    fn fold_enum_prop(&mut self, node: EnumProp<'a>) -> EnumProp<'a> {
+        fold_enum_prop(self, node)
+    }
+
+    /// Folds an enum type.
+    fn fold_enum_type(&mut self, node: EnumType<'a>) -> EnumType<'a> {
+        fold_enum_type(self, node)
+    }
+
+    /// Folds a contract type.
+    fn fold_contract_type(&mut self, node: ContractType<'a>) -> ContractType<'a> {
+        fold_contract_type(self, node)
+    }
+
+    /// Folds a `shared` type.
+    fn fold_shared_type(&mut self, node: SharedType<'a>) -> SharedType<'a> {
+        fold_shared_type(self, node)
+    }
+
+    /// Folds a `fluid` type.
+    fn fold_fluid_type(&mut self, node: FluidType<'a>) -> FluidType<'a> {
+        fold_fluid_type(self, node)
+    }
+
+    /// Folds a reference type.
+    fn fold_ref_type(&mut self, node: RefType<'a>) -> RefType<'a> {
+        fold_ref_type(self, node)
+    }
+
+    /// Folds a contract object type.
+    fn fold_dyn_type(&mut self, node: DynType<'a>) -> DynType<'a> {
+        fold_dyn_type(self, node)
+    }
+
+    /// Folds the variadic arguments of a routine type.
+    fn fold_variadic_type(&mut self, node: VariadicType<'a>) -> VariadicType<'a> {
+        fold_variadic_type(self, node)
+    }
+
+    /// Folds an option type.
+    fn fold_opt_type(&mut self, node: OptType<'a>) -> OptType<'a> {
+        fold_opt_type(self, node)
+    }
+
+    /// Folds a result type.
+    fn fold_res_type(&mut self, node: ResType<'a>) -> ResType<'a> {
+        fold_res_type(self, node)
+    }
+
+    /// Folds a type.
+    fn fold_type(&mut self, node: Type<'a>) -> Type<'a> {
+        fold_type(self, node)
+    }
+
+    /// Folds a path expression.
+    fn fold_path(&mut self, node: Path<'a>) -> Path<'a> {
+        fold_path(self, node)
+    }
+
+    /// Folds an access expression.
+    fn fold_access(&mut self, node: Access<'a>) -> Access<'a> {
+        fold_access(self, node)
+    }
+
+    /// Folds a call.
+    fn fold_call(&mut self, node: Call<'a>) -> Call<'a> {
+        fold_call(self, node)
+    }
+
+    /// Folds a unary expression.
+    fn fold_unary(&mut self, node: Unary<'a>) -> Unary<'a> {
+        fold_unary(self, node)
+    }
+
+    /// Folds a cast.
+    fn fold_as(&mut self, node: As<'a>) -> As<'a> {
+        fold_as(self, node)
+    }
+
+    /// Folds a binary expression.
+    fn fold_binary(&mut self, node: Binary<'a>) -> Binary<'a> {
+        fold_binary(self, node)
+    }
+
+    /// Folds a property of a struct construction.
+    fn fold_construct_prop(&mut self, node: ConstructProp<'a>) -> ConstructProp<'a> {
+        fold_construct_prop(self, node)
+    }
+
+    /// Folds a struct construction.
+    fn fold_construct(&mut self, node: Construct<'a>) -> Construct<'a> {
+        fold_construct(self, node)
+    }
+
+    /// Folds an enum construction.
+    fn fold_construct_enum(&mut self, node: ConstructEnum<'a>) -> ConstructEnum<'a> {
+        fold_construct_enum(self, node)
+    }
+
+    /// Folds an `ok` or `err` constructor.
+    fn fold_wrap(&mut self, node: Wrap<'a>) -> Wrap<'a> {
+        fold_wrap(self, node)
+    }
+
+    /// Folds a `none` literal.
+    fn fold_none_lit(&mut self, node: NoneLit) -> NoneLit {
+        fold_none_lit(self, node)
+    }
+
+    /// Folds a `?` expression.
+    fn fold_try(&mut self, node: Try<'a>) -> Try<'a> {
+        fold_try(self, node)
+    }
+
+    /// Folds an index expression.
+    fn fold_index(&mut self, node: Index<'a>) -> Index<'a> {
+        fold_index(self, node)
+    }
+
+    /// Folds an operand of an inline assembly block.
+    fn fold_asm_operand(&mut self, node: AsmOperand<'a>) -> AsmOperand<'a> {
+        fold_asm_operand(self, node)
+    }
+
+    /// Folds an inline assembly block.
+    fn fold_asm(&mut self, node: Asm<'a>) -> Asm<'a> {
+        fold_asm(self, node)
+    }
+
+    /// Folds an expression.
+    fn fold_expr(&mut self, node: Expr<'a>) -> Expr<'a> {
+        fold_expr(self, node)
+    }
+
+    /// Folds an assignment.
+    fn fold_assign(&mut self, node: Assign<'a>) -> Assign<'a> {
+        fold_assign(self, node)
+    }
+
+    /// Folds a variable declaration.
+    fn fold_val(&mut self, node: Val<'a>) -> Val<'a> {
+        fold_val(self, node)
+    }
+
+    /// Folds a constant declaration.
+    fn fold_const(&mut self, node: Const<'a>) -> Const<'a> {
+        fold_const(self, node)
+    }
+
+    /// Folds a type declaration.
+    fn fold_type_decl(&mut self, node: TypeDecl<'a>) -> TypeDecl<'a> {
+        fold_type_decl(self, node)
+    }
+
+    /// Folds an `else if` or `else` branch.
+    fn fold_if_branch(&mut self, node: IfBranch<'a>) -> IfBranch<'a> {
+        fold_if_branch(self, node)
+    }
+
+    /// Folds an `if` statement.
+    fn fold_if(&mut self, node: If<'a>) -> If<'a> {
+        fold_if(self, node)
+    }
+
+    /// Folds a `while` loop.
+    fn fold_while(&mut self, node: While<'a>) -> While<'a> {
+        fold_while(self, node)
+    }
+
+    /// Folds a case of a `match` statement.
+    fn fold_match_case(&mut self, node: MatchCase<'a>) -> MatchCase<'a> {
+        fold_match_case(self, node)
+    }
+
+    /// Folds a `match` statement.
+    fn fold_match(&mut self, node: Match<'a>) -> Match<'a> {
+        fold_match(self, node)
+    }
+
+    /// Folds a `break` statement.
+    fn fold_break(&mut self, node: Break<'a>) -> Break<'a> {
+        fold_break(self, node)
+    }
+
+    /// Folds a `continue` statement.
+    fn fold_continue(&mut self, node: Continue<'a>) -> Continue<'a> {
+        fold_continue(self, node)
+    }
+
+    /// Folds a `return` statement.
+    fn fold_return(&mut self, node: Return<'a>) -> Return<'a> {
+        fold_return(self, node)
+    }
+
+    /// Folds a `defer` statement.
+    fn fold_defer(&mut self, node: Defer<'a>) -> Defer<'a> {
+        fold_defer(self, node)
+    }
+
+    /// Folds a flag condition.
+    fn fold_flag_expr(&mut self, node: FlagExpr<'a>) -> FlagExpr<'a> {
+        fold_flag_expr(self, node)
+    }
+
+    /// Folds a flag on a statement.
+    fn fold_flag(&mut self, node: Flag<'a>) -> Flag<'a> {
+        fold_flag(self, node)
+    }
+
+    /// Folds a statement in a block.
+    fn fold_block_expr(&mut self, node: BlockExpr<'a>) -> BlockExpr<'a> {
+        fold_block_expr(self, node)
+    }
+
+    /// Folds a block.
+    fn fold_block(&mut self, node: Block<'a>) -> Block<'a> {
+        fold_block(self, node)
+    }
+
+    /// Folds an item of an import.
+    fn fold_import_item(&mut self, node: ImportItem<'a>) -> ImportItem<'a> {
+        fold_import_item(self, node)
+    }
+
+    /// Folds an import.
+    fn fold_import(&mut self, node: Import<'a>) -> Import<'a> {
+        fold_import(self, node)
+    }
+
+    /// Folds an item of a contract or `apply` statement.
+    fn fold_application(&mut self, node: Application<'a>) -> Application<'a> {
+        fold_application(self, node)
+    }
+
+    /// Folds an `apply` statement.
+    fn fold_apply(&mut self, node: Apply<'a>) -> Apply<'a> {
+        fold_apply(self, node)
+    }
+
+    /// Folds an inline module.
+    fn fold_module(&mut self, node: Module<'a>) -> Module<'a> {
+        fold_module(self, node)
+    }
+
+    /// Folds a statement in the root of a unit.
+    fn fold_root_stmnt(&mut self, node: RootStmnt<'a>) -> RootStmnt<'a> {
+        fold_root_stmnt(self, node)
+    }
+}
+
+/// Folds the children of a boolean literal.
+pub fn fold_bool<'a, F: Fold<'a> + ?Sized>(_f: &mut F, node: Bool) -> Bool {
+    node
+}
+
+/// Folds the children of an identifier.
+pub fn fold_id<'a, F: Fold<'a> + ?Sized>(_f: &mut F, node: Id<'a>) -> Id<'a> {
+    node
+}
+
+/// Folds the children of a number literal.
+pub fn fold_num<'a, F: Fold<'a> + ?Sized>(_f: &mut F, node: Num<'a>) -> Num<'a> {
+    node
+}
+
+/// Folds the children of a string literal.
+pub fn fold_str<'a, F: Fold<'a> + ?Sized>(_f: &mut F, node: Str<'a>) -> Str<'a> {
+    node
+}
+
+/// Folds the children of a path in the root of a unit.
+pub fn fold_global_path<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: GlobalPath<'a>) -> GlobalPath<'a> {
+    match node {
+        GlobalPath::Id(id) => GlobalPath::Id(f.fold_id(id)),
+        GlobalPath::Branch { span, path, id } => GlobalPath::Branch {
+            span,
+            path: Box::new(f.fold_global_path(*path)),
+            id: f.fold_id(id),
+        },
+    }
+}
+
+/// Folds the children of a marker.
+pub fn fold_marker<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Marker<'a>) -> Marker<'a> {
+    Marker {
+        span: node.span,
+        name: f.fold_id(node.name),
+        args: node.args.into_iter().map(|expr| f.fold_expr(expr)).collect(),
+    }
+}
+
+/// Folds the children of an argument of a routine literal.
+pub fn fold_routine_arg<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: RoutineArg<'a>) -> RoutineArg<'a> {
+    RoutineArg {
+        span: node.span,
+        name: f.fold_id(node.name),
+        ty: f.fold_type(node.ty),
+    }
+}
+
+/// Folds the children of a routine literal.
+pub fn fold_routine<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Routine<'a>) -> Routine<'a> {
+    Routine {
+        span: node.span,
+        args: node.args.into_iter().map(|routine_arg| f.fold_routine_arg(routine_arg)).collect(),
+        returns: node.returns.map(|ty| f.fold_type(ty)),
+        markers: node.markers.into_iter().map(|marker| f.fold_marker(marker)).collect(),
+        block: f.fold_block(node.block),
+    }
+}
+
+/// Folds the children of a path in a type.
+pub fn fold_path_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: PathType<'a>) -> PathType<'a> {
+    PathType {
+        span: node.span,
+        left: Box::new(f.fold_type(*node.left)),
+        right: f.fold_id(node.right),
+    }
+}
+
+/// Folds the children of a routine or closure type.
+pub fn fold_routine_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: RoutineType<'a>) -> RoutineType<'a> {
+    RoutineType {
+        span: node.span,
+        args: node.args.into_iter().map(|ty| f.fold_type(ty)).collect(),
+        returns: node.returns.map(|ty| Box::new(f.fold_type(*ty))),
+    }
+}
+
+/// Folds the children of a property of a struct or union type.
+pub fn fold_struct_prop<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: StructProp<'a>) -> StructProp<'a> {
+    StructProp {
+        span: node.span,
+        markers: node.markers.into_iter().map(|marker| f.fold_marker(marker)).collect(),
+        shared: node.shared,
+        name: f.fold_id(node.name),
+        ty: f.fold_type(node.ty),
+        bits: node.bits.map(|num| f.fold_num(num)),
+    }
+}
+
+/// Folds the children of a struct type.
+pub fn fold_struct_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: StructType<'a>) -> StructType<'a> {
+    StructType {
+        span: node.span,
+        props: node.props.into_iter().map(|struct_prop| f.fold_struct_prop(struct_prop)).collect(),
+    }
+}
+
+/// Folds the children of a union type.
+pub fn fold_union_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: UnionType<'a>) -> UnionType<'a> {
+    UnionType {
+        span: node.span,
+        props: node.props.into_iter().map(|struct_prop| f.fold_struct_prop(struct_prop)).collect(),
+    }
+}
+
+/// Folds the children of a property of an enum type.
+pub fn fold_enum_prop<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: EnumProp<'a>) -> EnumProp<'a> {
+    EnumProp {
+        span: node.span,
+        markers: node.markers.into_iter().map(|marker| f.fold_marker(marker)).collect(),
+        name: f.fold_id(node.name),
+        ty: node.ty.map(|ty| f.fold_type(ty)),
+        value: node.value.map(|expr| f.fold_expr(expr)),
+    }
+}
+
+/// Folds the children of an enum type.
+pub fn fold_enum_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: EnumType<'a>) -> EnumType<'a> {
+    EnumType {
+        span: node.span,
+        props: node.props.into_iter().map(|enum_prop| f.fold_enum_prop(enum_prop)).collect(),
+    }
+}
+
+/// Folds the children of a contract type.
+pub fn fold_contract_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: ContractType<'a>) -> ContractType<'a> {
+    ContractType {
+        span: node.span,
+        items: node.items.into_iter().map(|application| f.fold_application(application)).collect(),
+    }
+}
+
+/// Folds the children of a `shared` type.
+pub fn fold_shared_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: SharedType<'a>) -> SharedType<'a> {
+    SharedType {
+        span: node.span,
+        subject: Box::new(f.fold_type(*node.subject)),
+    }
+}
+
+/// Folds the children of a `fluid` type.
+pub fn fold_fluid_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: FluidType<'a>) -> FluidType<'a> {
+    FluidType {
+        span: node.span,
+        subject: Box::new(f.fold_type(*node.subject)),
+    }
+}
+
+/// Folds the children of a reference type.
+pub fn fold_ref_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: RefType<'a>) -> RefType<'a> {
+    RefType {
+        span: node.span,
+        subject: Box::new(f.fold_type(*node.subject)),
+    }
+}
+
+/// Folds the children of a contract object type.
+pub fn fold_dyn_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: DynType<'a>) -> DynType<'a> {
+    DynType {
+        span: node.span,
+        subject: Box::new(f.fold_type(*node.subject)),
+    }
+}
+
+/// Folds the children of the variadic arguments of a routine type.
+pub fn fold_variadic_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: VariadicType<'a>) -> VariadicType<'a> {
+    VariadicType {
+        span: node.span,
+        ty: node.ty.map(|ty| Box::new(f.fold_type(*ty))),
+    }
+}
+
+/// Folds the children of an option type.
+pub fn fold_opt_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: OptType<'a>) -> OptType<'a> {
+    OptType {
+        span: node.span,
+        subject: Box::new(f.fold_type(*node.subject)),
+    }
+}
+
+/// Folds the children of a result type.
+pub fn fold_res_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: ResType<'a>) -> ResType<'a> {
+    ResType {
+        span: node.span,
+        ok: Box::new(f.fold_type(*node.ok)),
+        err: Box::new(f.fold_type(*node.err)),
+    }
+}
+
+/// Folds the children of a type.
+pub fn fold_type<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Type<'a>) -> Type<'a> {
+    match node {
+        Type::Id(id) => Type::Id(f.fold_id(id)),
+        Type::Path(path_type) => Type::Path(f.fold_path_type(path_type)),
+        Type::Routine(routine_type) => Type::Routine(f.fold_routine_type(routine_type)),
+        Type::Closure(routine_type) => Type::Closure(f.fold_routine_type(routine_type)),
+        Type::Struct(struct_type) => Type::Struct(f.fold_struct_type(struct_type)),
+        Type::Union(union_type) => Type::Union(f.fold_union_type(union_type)),
+        Type::Enum(enum_type) => Type::Enum(f.fold_enum_type(enum_type)),
+        Type::Contract(contract_type) => Type::Contract(f.fold_contract_type(contract_type)),
+        Type::Shared(shared_type) => Type::Shared(f.fold_shared_type(shared_type)),
+        Type::Fluid(fluid_type) => Type::Fluid(f.fold_fluid_type(fluid_type)),
+        Type::Ref(ref_type) => Type::Ref(f.fold_ref_type(ref_type)),
+        Type::Dyn(dyn_type) => Type::Dyn(f.fold_dyn_type(dyn_type)),
+        Type::Variadic(variadic_type) => Type::Variadic(f.fold_variadic_type(variadic_type)),
+        Type::Opt(opt_type) => Type::Opt(f.fold_opt_type(opt_type)),
+        Type::Res(res_type) => Type::Res(f.fold_res_type(res_type)),
+    }
+}
+
+/// Folds the children of a path expression.
+pub fn fold_path<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Path<'a>) -> Path<'a> {
+    Path {
+        span: node.span,
+        left: Box::new(f.fold_expr(*node.left)),
+        right: f.fold_id(node.right),
+    }
+}
+
+/// Folds the children of an access expression.
+pub fn fold_access<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Access<'a>) -> Access<'a> {
+    Access {
+        span: node.span,
+        left: Box::new(f.fold_expr(*node.left)),
+        right: f.fold_id(node.right),
+    }
+}
+
+/// Folds the children of a call.
+pub fn fold_call<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Call<'a>) -> Call<'a> {
+    Call {
+        span: node.span,
+        left: Box::new(f.fold_expr(*node.left)),
+        args: node.args.into_iter().map(|expr| f.fold_expr(expr)).collect(),
+    }
+}
+
+/// Folds the children of a unary expression.
+pub fn fold_unary<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Unary<'a>) -> Unary<'a> {
+    Unary {
+        span: node.span,
+        op: node.op,
+        subject: Box::new(f.fold_expr(*node.subject)),
+    }
+}
+
+/// Folds the children of a cast.
+pub fn fold_as<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: As<'a>) -> As<'a> {
+    As {
+        span: node.span,
+        subject: Box::new(f.fold_expr(*node.subject)),
+        ty: f.fold_type(node.ty),
+    }
+}
+
+/// Folds the children of a binary expression.
+pub fn fold_binary<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Binary<'a>) -> Binary<'a> {
+    Binary {
+        span: node.span,
+        op: node.op,
+        left: Box::new(f.fold_expr(*node.left)),
+        right: Box::new(f.fold_expr(*node.right)),
+    }
+}
+
+/// Folds the children of a property of a struct construction.
+pub fn fold_construct_prop<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: ConstructProp<'a>) -> ConstructProp<'a> {
+    ConstructProp {
+        span: node.span,
+        name: f.fold_id(node.name),
+        value: f.fold_expr(node.value),
+    }
+}
+
+/// Folds the children of a struct construction.
+pub fn fold_construct<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Construct<'a>) -> Construct<'a> {
+    Construct {
+        span: node.span,
+        subject: Box::new(f.fold_expr(*node.subject)),
+        items: node.items.into_iter().map(|construct_prop| f.fold_construct_prop(construct_prop)).collect(),
+    }
+}
+
+/// Folds the children of an enum construction.
+pub fn fold_construct_enum<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: ConstructEnum<'a>) -> ConstructEnum<'a> {
+    ConstructEnum {
+        span: node.span,
+        subject: Box::new(f.fold_expr(*node.subject)),
+        item: Box::new(f.fold_expr(*node.item)),
+    }
+}
+
+/// Folds the children of an `ok` or `err` constructor.
+pub fn fold_wrap<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Wrap<'a>) -> Wrap<'a> {
+    Wrap {
+        span: node.span,
+        item: Box::new(f.fold_expr(*node.item)),
+    }
+}
+
+/// Folds the children of a `none` literal.
+pub fn fold_none_lit<'a, F: Fold<'a> + ?Sized>(_f: &mut F, node: NoneLit) -> NoneLit {
+    node
+}
+
+/// Folds the children of a `?` expression.
+pub fn fold_try<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Try<'a>) -> Try<'a> {
+    Try {
+        span: node.span,
+        subject: Box::new(f.fold_expr(*node.subject)),
+    }
+}
+
+/// Folds the children of an index expression.
+pub fn fold_index<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Index<'a>) -> Index<'a> {
+    Index {
+        span: node.span,
+        subject: Box::new(f.fold_expr(*node.subject)),
+        index: Box::new(f.fold_expr(*node.index)),
+    }
+}
+
+/// Folds the children of an operand of an inline assembly block.
+pub fn fold_asm_operand<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: AsmOperand<'a>) -> AsmOperand<'a> {
+    AsmOperand {
+        span: node.span,
+        constraint: f.fold_str(node.constraint),
+        expr: f.fold_expr(node.expr),
+    }
+}
+
+/// Folds the children of an inline assembly block.
+pub fn fold_asm<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Asm<'a>) -> Asm<'a> {
+    Asm {
+        span: node.span,
+        template: f.fold_str(node.template),
+        outputs: node.outputs.into_iter().map(|asm_operand| f.fold_asm_operand(asm_operand)).collect(),
+        inputs: node.inputs.into_iter().map(|asm_operand| f.fold_asm_operand(asm_operand)).collect(),
+        clobbers: node.clobbers.into_iter().map(|str| f.fold_str(str)).collect(),
+        options: node.options.into_iter().map(|id| f.fold_id(id)).collect(),
+    }
+}
+
+/// Folds the children of an expression.
+pub fn fold_expr<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Expr<'a>) -> Expr<'a> {
+    match node {
+        Expr::Bool(bool) => Expr::Bool(f.fold_bool(bool)),
+        Expr::Id(id) => Expr::Id(f.fold_id(id)),
+        Expr::Num(num) => Expr::Num(f.fold_num(num)),
+        Expr::Str(str) => Expr::Str(f.fold_str(str)),
+        Expr::Path(path) => Expr::Path(f.fold_path(path)),
+        Expr::Access(access) => Expr::Access(f.fold_access(access)),
+        Expr::Call(call) => Expr::Call(f.fold_call(call)),
+        Expr::Unary(unary) => Expr::Unary(f.fold_unary(unary)),
+        Expr::As(as_) => Expr::As(f.fold_as(as_)),
+        Expr::Binary(binary) => Expr::Binary(f.fold_binary(binary)),
+        Expr::Routine(routine) => Expr::Routine(f.fold_routine(routine)),
+        Expr::Construct(construct) => Expr::Construct(f.fold_construct(construct)),
+        Expr::ConstructEnum(construct_enum) => Expr::ConstructEnum(f.fold_construct_enum(construct_enum)),
+        Expr::Asm(asm) => Expr::Asm(f.fold_asm(asm)),
+        Expr::Ok(wrap) => Expr::Ok(f.fold_wrap(wrap)),
+        Expr::Err(wrap) => Expr::Err(f.fold_wrap(wrap)),
+        Expr::None(none_lit) => Expr::None(f.fold_none_lit(none_lit)),
+        Expr::Try(try_) => Expr::Try(f.fold_try(try_)),
+        Expr::Index(index) => Expr::Index(f.fold_index(index)),
+    }
+}
+
+/// Folds the children of an assignment.
+pub fn fold_assign<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Assign<'a>) -> Assign<'a> {
+    Assign {
+        span: node.span,
+        op: node.op,
+        left: f.fold_expr(node.left),
+        right: f.fold_expr(node.right),
+    }
+}
+
+/// Folds the children of a variable declaration.
+pub fn fold_val<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Val<'a>) -> Val<'a> {
+    Val {
+        span: node.span,
+        markers: node.markers.into_iter().map(|marker| f.fold_marker(marker)).collect(),
+        shared: node.shared,
+        name: f.fold_id(node.name),
+        ty: node.ty.map(|ty| f.fold_type(ty)),
+        value: node.value.map(|expr| f.fold_expr(expr)),
+    }
+}
+
+/// Folds the children of a constant declaration.
+pub fn fold_const<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Const<'a>) -> Const<'a> {
+    Const {
+        span: node.span,
+        markers: node.markers.into_iter().map(|marker| f.fold_marker(marker)).collect(),
+        shared: node.shared,
+        name: f.fold_id(node.name),
+        ty: node.ty.map(|ty| f.fold_type(ty)),
+        value: f.fold_expr(node.value),
+    }
+}
+
+/// Folds the children of a type declaration.
+pub fn fold_type_decl<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: TypeDecl<'a>) -> TypeDecl<'a> {
+    TypeDecl {
+        span: node.span,
+        markers: node.markers.into_iter().map(|marker| f.fold_marker(marker)).collect(),
+        shared: node.shared,
+        name: f.fold_id(node.name),
+        value: node.value.map(|ty| f.fold_type(ty)),
+    }
+}
+
+/// Folds the children of an `else if` or `else` branch.
+pub fn fold_if_branch<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: IfBranch<'a>) -> IfBranch<'a> {
+    match node {
+        IfBranch::ElseIf { span, cond, block } => IfBranch::ElseIf {
+            span,
+            cond: f.fold_expr(cond),
+            block: f.fold_block(block),
+        },
+        IfBranch::Else { span, block } => IfBranch::Else {
+            span,
+            block: f.fold_block(block),
+        },
+    }
+}
+
+/// Folds the children of an `if` statement.
+pub fn fold_if<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: If<'a>) -> If<'a> {
+    If {
+        span: node.span,
+        cond: f.fold_expr(node.cond),
+        block: f.fold_block(node.block),
+        branches: node.branches.into_iter().map(|if_branch| f.fold_if_branch(if_branch)).collect(),
+    }
+}
+
+/// Folds the children of a `while` loop.
+pub fn fold_while<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: While<'a>) -> While<'a> {
+    While {
+        span: node.span,
+        label: node.label.map(|id| f.fold_id(id)),
+        cond: f.fold_expr(node.cond),
+        block: f.fold_block(node.block),
+    }
+}
+
+/// Folds the children of a case of a `match` statement.
+pub fn fold_match_case<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: MatchCase<'a>) -> MatchCase<'a> {
+    MatchCase {
+        span: node.span,
+        name: f.fold_id(node.name),
+        ty: f.fold_type(node.ty),
+        block: f.fold_block(node.block),
+    }
+}
+
+/// Folds the children of a `match` statement.
+pub fn fold_match<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Match<'a>) -> Match<'a> {
+    Match {
+        span: node.span,
+        subject: f.fold_expr(node.subject),
+        cases: node.cases.into_iter().map(|match_case| f.fold_match_case(match_case)).collect(),
+    }
+}
+
+/// Folds the children of a `break` statement.
+pub fn fold_break<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Break<'a>) -> Break<'a> {
+    Break {
+        span: node.span,
+        label: node.label.map(|id| f.fold_id(id)),
+    }
+}
+
+/// Folds the children of a `continue` statement.
+pub fn fold_continue<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Continue<'a>) -> Continue<'a> {
+    Continue {
+        span: node.span,
+        label: node.label.map(|id| f.fold_id(id)),
+    }
+}
+
+/// Folds the children of a `return` statement.
+pub fn fold_return<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Return<'a>) -> Return<'a> {
+    Return {
+        span: node.span,
+        value: node.value.map(|expr| f.fold_expr(expr)),
+    }
+}
+
+/// Folds the children of a `defer` statement.
+pub fn fold_defer<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Defer<'a>) -> Defer<'a> {
+    Defer {
+        span: node.span,
+        item: Box::new(f.fold_block_expr(*node.item)),
+    }
+}
+
+/// Folds the children of a flag condition.
+pub fn fold_flag_expr<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: FlagExpr<'a>) -> FlagExpr<'a> {
+    match node {
+        FlagExpr::Name(id) => FlagExpr::Name(f.fold_id(id)),
+        FlagExpr::Value { span, name, value } => FlagExpr::Value {
+            span,
+            name: f.fold_id(name),
+            value: f.fold_str(value),
+        },
+        FlagExpr::Any { span, items } => FlagExpr::Any {
+            span,
+            items: items.into_iter().map(|flag_expr| f.fold_flag_expr(flag_expr)).collect(),
+        },
+        FlagExpr::All { span, items } => FlagExpr::All {
+            span,
+            items: items.into_iter().map(|flag_expr| f.fold_flag_expr(flag_expr)).collect(),
+        },
+        FlagExpr::Not { span, item } => FlagExpr::Not {
+            span,
+            item: Box::new(f.fold_flag_expr(*item)),
+        },
+    }
+}
+
+/// Folds the children of a flag on a statement.
+pub fn fold_flag<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Flag<'a>) -> Flag<'a> {
+    Flag {
+        span: node.span,
+        neg: node.neg,
+        expr: f.fold_flag_expr(node.expr),
+    }
+}
+
+/// Folds the children of a statement in a block.
+pub fn fold_block_expr<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: BlockExpr<'a>) -> BlockExpr<'a> {
+    match node {
+        BlockExpr::Assign(assign) => BlockExpr::Assign(f.fold_assign(assign)),
+        BlockExpr::Val(val) => BlockExpr::Val(f.fold_val(val)),
+        BlockExpr::Call(call) => BlockExpr::Call(f.fold_call(call)),
+        BlockExpr::If(if_) => BlockExpr::If(f.fold_if(if_)),
+        BlockExpr::While(while_) => BlockExpr::While(f.fold_while(while_)),
+        BlockExpr::Match(match_) => BlockExpr::Match(f.fold_match(match_)),
+        BlockExpr::TypeDecl(type_decl) => BlockExpr::TypeDecl(f.fold_type_decl(type_decl)),
+        BlockExpr::Break(break_) => BlockExpr::Break(f.fold_break(break_)),
+        BlockExpr::Continue(continue_) => BlockExpr::Continue(f.fold_continue(continue_)),
+        BlockExpr::Return(return_) => BlockExpr::Return(f.fold_return(return_)),
+        BlockExpr::Defer(defer) => BlockExpr::Defer(f.fold_defer(defer)),
+        BlockExpr::Asm(asm) => BlockExpr::Asm(f.fold_asm(asm)),
+        BlockExpr::Block(flags, block) => BlockExpr::Block(
+            flags.into_iter().map(|flag| f.fold_flag(flag)).collect(),
+            Box::new(f.fold_block(*block)),
+        ),
+    }
+}
+
+/// Folds the children of a block.
+pub fn fold_block<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Block<'a>) -> Block<'a> {
+    Block {
+        span: node.span,
+        items: node.items.into_iter().map(|block_expr| f.fold_block_expr(block_expr)).collect(),
+    }
+}
+
+/// Folds the children of an item of an import.
+pub fn fold_import_item<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: ImportItem<'a>) -> ImportItem<'a> {
+    ImportItem {
+        span: node.span,
+        name: f.fold_id(node.name),
+        as_: node.as_.map(|id| f.fold_id(id)),
+    }
+}
+
+/// Folds the children of an import.
+pub fn fold_import<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Import<'a>) -> Import<'a> {
+    match node {
+        Import::Single { span, shared, item, from } => Import::Single {
+            span,
+            shared,
+            item: f.fold_import_item(item),
+            from: from.map(|global_path| f.fold_global_path(global_path)),
+        },
+        Import::Multi { span, shared, items, from } => Import::Multi {
+            span,
+            shared,
+            items: items.into_iter().map(|import_item| f.fold_import_item(import_item)).collect(),
+            from: f.fold_global_path(from),
+        },
+        Import::Glob { span, shared, from } => Import::Glob {
+            span,
+            shared,
+            from: f.fold_global_path(from),
+        },
+    }
+}
+
+/// Folds the children of an item of a contract or `apply` statement.
+pub fn fold_application<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Application<'a>) -> Application<'a> {
+    match node {
+        Application::Val(val) => Application::Val(f.fold_val(val)),
+        Application::TypeDecl(type_decl) => Application::TypeDecl(f.fold_type_decl(type_decl)),
+    }
+}
+
+/// Folds the children of an `apply` statement.
+pub fn fold_apply<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Apply<'a>) -> Apply<'a> {
+    Apply {
+        span: node.span,
+        markers: node.markers.into_iter().map(|marker| f.fold_marker(marker)).collect(),
+        subject: f.fold_global_path(node.subject),
+        to: node.to.map(|global_path| f.fold_global_path(global_path)),
+        items: node.items.into_iter().map(|application| f.fold_application(application)).collect(),
+    }
+}
+
+/// Folds the children of an inline module.
+pub fn fold_module<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Module<'a>) -> Module<'a> {
+    Module {
+        span: node.span,
+        shared: node.shared,
+        name: f.fold_id(node.name),
+        items: node.items.into_iter().map(|root_stmnt| f.fold_root_stmnt(root_stmnt)).collect(),
+    }
+}
+
+/// Folds the children of a statement in the root of a unit.
+pub fn fold_root_stmnt<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: RootStmnt<'a>) -> RootStmnt<'a> {
+    match node {
+        RootStmnt::Assign(assign) => RootStmnt::Assign(f.fold_assign(assign)),
+        RootStmnt::Val(flags, val) => RootStmnt::Val(
+            flags.into_iter().map(|flag| f.fold_flag(flag)).collect(),
+            f.fold_val(val),
+        ),
+        RootStmnt::Const(flags, const_) => RootStmnt::Const(
+            flags.into_iter().map(|flag| f.fold_flag(flag)).collect(),
+            f.fold_const(const_),
+        ),
+        RootStmnt::Call(call) => RootStmnt::Call(f.fold_call(call)),
+        RootStmnt::Import(flags, import) => RootStmnt::Import(
+            flags.into_iter().map(|flag| f.fold_flag(flag)).collect(),
+            f.fold_import(import),
+        ),
+        RootStmnt::If(if_) => RootStmnt::If(f.fold_if(if_)),
+        RootStmnt::While(while_) => RootStmnt::While(f.fold_while(while_)),
+        RootStmnt::Match(match_) => RootStmnt::Match(f.fold_match(match_)),
+        RootStmnt::TypeDecl(flags, type_decl) => RootStmnt::TypeDecl(
+            flags.into_iter().map(|flag| f.fold_flag(flag)).collect(),
+            f.fold_type_decl(type_decl),
+        ),
+        RootStmnt::Apply(flags, apply) => RootStmnt::Apply(
+            flags.into_iter().map(|flag| f.fold_flag(flag)).collect(),
+            f.fold_apply(apply),
+        ),
+        RootStmnt::Block(flags, block) => RootStmnt::Block(
+            flags.into_iter().map(|flag| f.fold_flag(flag)).collect(),
+            f.fold_block(block),
+        ),
+        RootStmnt::Module(flags, module) => RootStmnt::Module(
+            flags.into_iter().map(|flag| f.fold_flag(flag)).collect(),
+            f.fold_module(module),
+        ),
+    }
+}
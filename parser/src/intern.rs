@@ -0,0 +1,150 @@
+//! Interned strings, so that identifiers can be stored and compared as integers.
+
+use std::collections::HashMap;
+
+/// An interned string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The index of this symbol in its interner.
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// The symbols of the keywords, which every interner starts with.
+pub mod kw {
+    use super::Symbol;
+
+    pub const TRUE: Symbol = Symbol(0);
+    pub const FALSE: Symbol = Symbol(1);
+    pub const FLUID: Symbol = Symbol(2);
+    pub const AS: Symbol = Symbol(3);
+    pub const ROUTINE: Symbol = Symbol(4);
+    pub const CLOSURE: Symbol = Symbol(5);
+    pub const VAL: Symbol = Symbol(6);
+    pub const SHARED: Symbol = Symbol(7);
+    pub const IMPORT: Symbol = Symbol(8);
+    pub const FROM: Symbol = Symbol(9);
+    pub const IF: Symbol = Symbol(10);
+    pub const ELSE: Symbol = Symbol(11);
+    pub const WHILE: Symbol = Symbol(12);
+    pub const MATCH: Symbol = Symbol(13);
+    pub const STRUCT: Symbol = Symbol(14);
+    pub const TYPE: Symbol = Symbol(15);
+    pub const ENUM: Symbol = Symbol(16);
+    pub const BREAK: Symbol = Symbol(17);
+    pub const CONTINUE: Symbol = Symbol(18);
+    pub const RETURN: Symbol = Symbol(19);
+    pub const APPLY: Symbol = Symbol(20);
+    pub const CONTRACT: Symbol = Symbol(21);
+    pub const TO: Symbol = Symbol(22);
+    pub const DEFER: Symbol = Symbol(23);
+    pub const ASM: Symbol = Symbol(24);
+    pub const CONST: Symbol = Symbol(25);
+    pub const OK: Symbol = Symbol(26);
+    pub const ERR: Symbol = Symbol(27);
+    pub const NONE: Symbol = Symbol(28);
+    pub const MODULE: Symbol = Symbol(29);
+    pub const UNION: Symbol = Symbol(30);
+    pub const DYN: Symbol = Symbol(31);
+
+    /// The text of every keyword, in the order of their symbols.
+    pub(super) const ALL: &[&str] = &[
+        "true",
+        "false",
+        "fluid",
+        "as",
+        "routine",
+        "closure",
+        "val",
+        "shared",
+        "import",
+        "from",
+        "if",
+        "else",
+        "while",
+        "match",
+        "struct",
+        "type",
+        "enum",
+        "break",
+        "continue",
+        "return",
+        "apply",
+        "contract",
+        "to",
+        "defer",
+        "asm",
+        "const",
+        "ok",
+        "err",
+        "none",
+        "module",
+        "union",
+        "dyn",
+    ];
+}
+
+/// A table of interned strings.
+#[derive(Clone, Debug)]
+pub struct Interner {
+    /// The symbol of each string.
+    symbols: HashMap<Box<str>, Symbol>,
+
+    /// The string of each symbol.
+    strings: Vec<Box<str>>,
+}
+
+impl Interner {
+    /// Creates an interner with the keywords already interned.
+    pub fn new() -> Self {
+        let mut interner = Self { symbols: HashMap::new(), strings: Vec::new() };
+
+        for keyword in kw::ALL {
+            interner.intern(keyword);
+        }
+
+        interner
+    }
+
+    /// Interns a string, returning the symbol it already has if it has been interned before.
+    pub fn intern(&mut self, value: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(value) {
+            return *symbol;
+        }
+
+        let symbol = Symbol(self.strings.len() as u32);
+        self.symbols.insert(value.into(), symbol);
+        self.strings.push(value.into());
+
+        symbol
+    }
+
+    /// The symbol of a string, if it has been interned.
+    pub fn get(&self, value: &str) -> Option<Symbol> {
+        self.symbols.get(value).copied()
+    }
+
+    /// The string of a symbol.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    /// The number of interned strings.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether or not no strings have been interned. Never true, as the keywords always are.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
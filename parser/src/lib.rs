@@ -1,6 +1,23 @@
+pub mod arena;
 pub mod ast;
+pub mod codes;
+pub mod cst;
+pub mod cst_ast;
+pub mod diagnostics;
+pub mod fold;
+pub mod intern;
 pub mod scanner;
+pub mod spanned;
+pub mod visit;
+pub mod visit_mut;
 
 use lalrpop_util::lalrpop_mod;
 
-lalrpop_mod!(pub grammar);
\ No newline at end of file
+lalrpop_mod!(pub grammar);
+
+/// Parses a unit, reporting the first syntax error that it finds.
+pub fn parse(src: &str) -> Result<Vec<ast::RootStmnt<'_>>, diagnostics::Diagnostic> {
+    grammar::RootStmntsParser::new()
+        .parse(src, scanner::Asi::lex(src))
+        .map_err(|error| diagnostics::from_parse_error(src, error))
+}
\ No newline at end of file
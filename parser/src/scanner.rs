@@ -4,6 +4,8 @@
 
 use logos::{Lexer, Logos};
 
+use crate::diagnostics::Diagnostic;
+
 /// This is the raw lexer which will later be wrapped by an automatic semicolon inserter.
 #[derive(Clone, Copy, Debug, Eq, Logos, PartialEq)]
 pub enum RawTok {
@@ -12,7 +14,7 @@ pub enum RawTok {
     Break,
 
     /// A punctuator token.
-    #[regex("#|::|:|\\.|\\?|<-|->|<=|=>|-=|-|\\*=|\\*|!=|!|&&|&=|&|/=|/|%=|%|\\+=|\\+|<<=|<<|<=|<|>>=|>>|>=|>|\\^=|\\^|\\|=|\\|\\||\\||==|=|\\[|\\]|\\(|\\)|\\{|\\}|@|,|;")]
+    #[regex("#|::|:|\\.\\.\\.|\\.|\\?\\?|\\?|<-|->|<=|=>|-=|-|\\*=|\\*|!=|!|&&|&=|&|/=|/|%=|%|\\+=|\\+|<<=|<<|<=|<|>>=|>>|>=|>|\\^=|\\^|\\|=|\\|\\||\\||==|=|\\[|\\]|\\(|\\)|\\{|\\}|@|,|;")]
     Punct,
 
     /// An identifier token.
@@ -58,9 +60,15 @@ pub enum Tok<'a> {
     /// `:`
     Col,
 
+    /// `...`
+    DotDotDot,
+
     /// `.`
     Dot,
 
+    /// `??`
+    QuestQuest,
+
     /// `?`
     Quest,
 
@@ -205,6 +213,9 @@ pub enum Tok<'a> {
     /// `routine`
     KRoutine,
 
+    /// `closure`
+    KClosure,
+
     /// `val`
     KVal,
 
@@ -256,6 +267,33 @@ pub enum Tok<'a> {
     /// `to`
     KTo,
 
+    /// `defer`
+    KDefer,
+
+    /// `asm`
+    KAsm,
+
+    /// `const`
+    KConst,
+
+    /// `ok`
+    KOk,
+
+    /// `err`
+    KErr,
+
+    /// `none`
+    KNone,
+
+    /// `module`
+    KModule,
+
+    /// `union`
+    KUnion,
+
+    /// `dyn`
+    KDyn,
+
     /// An identifier token.
     Id(&'a str),
 
@@ -319,7 +357,7 @@ impl<'a> Asi<'a> {
 }
 
 impl<'a> Iterator for Asi<'a> {
-    type Item = Result<(usize, Tok<'a>, usize), ()>;
+    type Item = Result<(usize, Tok<'a>, usize), Diagnostic>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.skip(); // insert semicolons?
@@ -341,7 +379,9 @@ impl<'a> Iterator for Asi<'a> {
                         "#" => Tok::Hash,
                         "::" => Tok::ColCol,
                         ":" => Tok::Col,
+                        "..." => Tok::DotDotDot,
                         "." => Tok::Dot,
+                        "??" => Tok::QuestQuest,
                         "?" => {
                             self.can_insert = true;
                             Tok::Quest
@@ -423,6 +463,10 @@ impl<'a> Iterator for Asi<'a> {
                             self.can_insert = false;
                             Tok::KRoutine
                         },
+                        "closure" => {
+                            self.can_insert = false;
+                            Tok::KClosure
+                        },
                         "val" => {
                             self.can_insert = false;
                             Tok::KVal
@@ -491,6 +535,39 @@ impl<'a> Iterator for Asi<'a> {
                             self.can_insert = false;
                             Tok::KTo
                         },
+                        "defer" => {
+                            self.can_insert = false;
+                            Tok::KDefer
+                        },
+                        "asm" => {
+                            self.can_insert = false;
+                            Tok::KAsm
+                        },
+                        "const" => {
+                            self.can_insert = false;
+                            Tok::KConst
+                        },
+                        "ok" => {
+                            self.can_insert = false;
+                            Tok::KOk
+                        },
+                        "err" => {
+                            self.can_insert = false;
+                            Tok::KErr
+                        },
+                        "none" => Tok::KNone,
+                        "module" => {
+                            self.can_insert = false;
+                            Tok::KModule
+                        },
+                        "union" => {
+                            self.can_insert = false;
+                            Tok::KUnion
+                        },
+                        "dyn" => {
+                            self.can_insert = false;
+                            Tok::KDyn
+                        },
                         _ => Tok::Id(slice),
                     };
 
@@ -522,7 +599,25 @@ impl<'a> Iterator for Asi<'a> {
                     let span = self.private.span();
                     return Some(Ok((span.start, Tok::Str(self.private.slice()), span.end)));
                 },
-                RawTok::Err => return Some(Err(())),
+                RawTok::Err => {
+                    let span = self.private.span();
+                    let error = match self.private.slice() {
+                        // strings can run over lines, so an unterminated one is marked where it starts.
+                        slice if slice.starts_with('"') => {
+                            let end = span.start + slice.find(['\n', '\r']).unwrap_or(slice.len());
+
+                            Diagnostic::error("unterminated string")
+                                .with_code("E0001")
+                                .with_primary(span.start..span.start + 1, "this string is never closed")
+                                .with_suggestion("close it at the end of the line", end..end, "\"")
+                        },
+                        slice => Diagnostic::error(format!("invalid token `{}`", slice))
+                            .with_code("E0001")
+                            .with_primary(span, "not a valid token"),
+                    };
+
+                    return Some(Err(error));
+                },
                 // line breaks are skipped, so there is no chance of finding them.
                 _ => unreachable!(),
             }
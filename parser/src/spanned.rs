@@ -0,0 +1,1424 @@
+//! Uniform access to the spans of AST nodes, and equality that ignores them.
+
+use crate::ast::*;
+
+/// A node with a location in its source.
+///
+/// For statements with flags, such as `@debug val x = 1`, the span covers the statement but not its flags.
+pub trait Spanned {
+    /// The location of the node.
+    fn span(&self) -> &Span;
+
+    /// The location of the node, for passes that move it.
+    fn span_mut(&mut self) -> &mut Span;
+}
+
+/// Structural equality that ignores spans, so that trees parsed from differently formatted sources compare equal.
+pub trait SpanlessEq {
+    /// Whether `self` and `other` are the same tree, regardless of where their nodes are.
+    fn spanless_eq(&self, other: &Self) -> bool;
+}
+
+impl<T: SpanlessEq> SpanlessEq for Box<T> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        (**self).spanless_eq(other)
+    }
+}
+
+impl<T: SpanlessEq> SpanlessEq for Option<T> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(left), Some(right)) => left.spanless_eq(right),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: SpanlessEq> SpanlessEq for Vec<T> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(left, right)| left.spanless_eq(right))
+    }
+}
+
+impl Spanned for Bool {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Id<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Num<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Str<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for GlobalPath<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            GlobalPath::Id(node) => node.span(),
+            GlobalPath::Branch { span, .. } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            GlobalPath::Id(node) => node.span_mut(),
+            GlobalPath::Branch { span, .. } => span,
+        }
+    }
+}
+
+impl Spanned for Marker<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for RoutineArg<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Routine<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for PathType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for RoutineType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for StructProp<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for StructType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for UnionType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for EnumProp<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for EnumType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ContractType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for SharedType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for FluidType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for RefType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for DynType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for VariadicType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for OptType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ResType<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Type<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            Type::Id(node) => node.span(),
+            Type::Path(node) => node.span(),
+            Type::Routine(node) => node.span(),
+            Type::Closure(node) => node.span(),
+            Type::Struct(node) => node.span(),
+            Type::Union(node) => node.span(),
+            Type::Enum(node) => node.span(),
+            Type::Contract(node) => node.span(),
+            Type::Shared(node) => node.span(),
+            Type::Fluid(node) => node.span(),
+            Type::Ref(node) => node.span(),
+            Type::Dyn(node) => node.span(),
+            Type::Variadic(node) => node.span(),
+            Type::Opt(node) => node.span(),
+            Type::Res(node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Type::Id(node) => node.span_mut(),
+            Type::Path(node) => node.span_mut(),
+            Type::Routine(node) => node.span_mut(),
+            Type::Closure(node) => node.span_mut(),
+            Type::Struct(node) => node.span_mut(),
+            Type::Union(node) => node.span_mut(),
+            Type::Enum(node) => node.span_mut(),
+            Type::Contract(node) => node.span_mut(),
+            Type::Shared(node) => node.span_mut(),
+            Type::Fluid(node) => node.span_mut(),
+            Type::Ref(node) => node.span_mut(),
+            Type::Dyn(node) => node.span_mut(),
+            Type::Variadic(node) => node.span_mut(),
+            Type::Opt(node) => node.span_mut(),
+            Type::Res(node) => node.span_mut(),
+        }
+    }
+}
+
+impl Spanned for Path<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Access<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Call<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Unary<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for As<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Binary<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ConstructProp<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Construct<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ConstructEnum<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Wrap<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for NoneLit {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Try<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Index<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for AsmOperand<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Asm<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Expr<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            Expr::Bool(node) => node.span(),
+            Expr::Id(node) => node.span(),
+            Expr::Num(node) => node.span(),
+            Expr::Str(node) => node.span(),
+            Expr::Path(node) => node.span(),
+            Expr::Access(node) => node.span(),
+            Expr::Call(node) => node.span(),
+            Expr::Unary(node) => node.span(),
+            Expr::As(node) => node.span(),
+            Expr::Binary(node) => node.span(),
+            Expr::Routine(node) => node.span(),
+            Expr::Construct(node) => node.span(),
+            Expr::ConstructEnum(node) => node.span(),
+            Expr::Asm(node) => node.span(),
+            Expr::Ok(node) => node.span(),
+            Expr::Err(node) => node.span(),
+            Expr::None(node) => node.span(),
+            Expr::Try(node) => node.span(),
+            Expr::Index(node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Expr::Bool(node) => node.span_mut(),
+            Expr::Id(node) => node.span_mut(),
+            Expr::Num(node) => node.span_mut(),
+            Expr::Str(node) => node.span_mut(),
+            Expr::Path(node) => node.span_mut(),
+            Expr::Access(node) => node.span_mut(),
+            Expr::Call(node) => node.span_mut(),
+            Expr::Unary(node) => node.span_mut(),
+            Expr::As(node) => node.span_mut(),
+            Expr::Binary(node) => node.span_mut(),
+            Expr::Routine(node) => node.span_mut(),
+            Expr::Construct(node) => node.span_mut(),
+            Expr::ConstructEnum(node) => node.span_mut(),
+            Expr::Asm(node) => node.span_mut(),
+            Expr::Ok(node) => node.span_mut(),
+            Expr::Err(node) => node.span_mut(),
+            Expr::None(node) => node.span_mut(),
+            Expr::Try(node) => node.span_mut(),
+            Expr::Index(node) => node.span_mut(),
+        }
+    }
+}
+
+impl Spanned for Assign<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Val<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Const<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for TypeDecl<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for IfBranch<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            IfBranch::ElseIf { span, .. } => span,
+            IfBranch::Else { span, .. } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            IfBranch::ElseIf { span, .. } => span,
+            IfBranch::Else { span, .. } => span,
+        }
+    }
+}
+
+impl Spanned for If<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for While<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for MatchCase<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Match<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Break<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Continue<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Return<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Defer<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for FlagExpr<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            FlagExpr::Name(node) => node.span(),
+            FlagExpr::Value { span, .. } => span,
+            FlagExpr::Any { span, .. } => span,
+            FlagExpr::All { span, .. } => span,
+            FlagExpr::Not { span, .. } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            FlagExpr::Name(node) => node.span_mut(),
+            FlagExpr::Value { span, .. } => span,
+            FlagExpr::Any { span, .. } => span,
+            FlagExpr::All { span, .. } => span,
+            FlagExpr::Not { span, .. } => span,
+        }
+    }
+}
+
+impl Spanned for Flag<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for BlockExpr<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            BlockExpr::Assign(node) => node.span(),
+            BlockExpr::Val(node) => node.span(),
+            BlockExpr::Call(node) => node.span(),
+            BlockExpr::If(node) => node.span(),
+            BlockExpr::While(node) => node.span(),
+            BlockExpr::Match(node) => node.span(),
+            BlockExpr::TypeDecl(node) => node.span(),
+            BlockExpr::Break(node) => node.span(),
+            BlockExpr::Continue(node) => node.span(),
+            BlockExpr::Return(node) => node.span(),
+            BlockExpr::Defer(node) => node.span(),
+            BlockExpr::Asm(node) => node.span(),
+            BlockExpr::Block(_, node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            BlockExpr::Assign(node) => node.span_mut(),
+            BlockExpr::Val(node) => node.span_mut(),
+            BlockExpr::Call(node) => node.span_mut(),
+            BlockExpr::If(node) => node.span_mut(),
+            BlockExpr::While(node) => node.span_mut(),
+            BlockExpr::Match(node) => node.span_mut(),
+            BlockExpr::TypeDecl(node) => node.span_mut(),
+            BlockExpr::Break(node) => node.span_mut(),
+            BlockExpr::Continue(node) => node.span_mut(),
+            BlockExpr::Return(node) => node.span_mut(),
+            BlockExpr::Defer(node) => node.span_mut(),
+            BlockExpr::Asm(node) => node.span_mut(),
+            BlockExpr::Block(_, node) => node.span_mut(),
+        }
+    }
+}
+
+impl Spanned for Block<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for ImportItem<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Import<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            Import::Single { span, .. } => span,
+            Import::Multi { span, .. } => span,
+            Import::Glob { span, .. } => span,
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Import::Single { span, .. } => span,
+            Import::Multi { span, .. } => span,
+            Import::Glob { span, .. } => span,
+        }
+    }
+}
+
+impl Spanned for Application<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            Application::Val(node) => node.span(),
+            Application::TypeDecl(node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Application::Val(node) => node.span_mut(),
+            Application::TypeDecl(node) => node.span_mut(),
+        }
+    }
+}
+
+impl Spanned for Apply<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for Module<'_> {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        &mut self.span
+    }
+}
+
+impl Spanned for RootStmnt<'_> {
+    fn span(&self) -> &Span {
+        match self {
+            RootStmnt::Assign(node) => node.span(),
+            RootStmnt::Val(_, node) => node.span(),
+            RootStmnt::Const(_, node) => node.span(),
+            RootStmnt::Call(node) => node.span(),
+            RootStmnt::Import(_, node) => node.span(),
+            RootStmnt::If(node) => node.span(),
+            RootStmnt::While(node) => node.span(),
+            RootStmnt::Match(node) => node.span(),
+            RootStmnt::TypeDecl(_, node) => node.span(),
+            RootStmnt::Apply(_, node) => node.span(),
+            RootStmnt::Block(_, node) => node.span(),
+            RootStmnt::Module(_, node) => node.span(),
+        }
+    }
+
+    fn span_mut(&mut self) -> &mut Span {
+        match self {
+            RootStmnt::Assign(node) => node.span_mut(),
+            RootStmnt::Val(_, node) => node.span_mut(),
+            RootStmnt::Const(_, node) => node.span_mut(),
+            RootStmnt::Call(node) => node.span_mut(),
+            RootStmnt::Import(_, node) => node.span_mut(),
+            RootStmnt::If(node) => node.span_mut(),
+            RootStmnt::While(node) => node.span_mut(),
+            RootStmnt::Match(node) => node.span_mut(),
+            RootStmnt::TypeDecl(_, node) => node.span_mut(),
+            RootStmnt::Apply(_, node) => node.span_mut(),
+            RootStmnt::Block(_, node) => node.span_mut(),
+            RootStmnt::Module(_, node) => node.span_mut(),
+        }
+    }
+}
+
+impl SpanlessEq for Bool {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl SpanlessEq for Id<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl SpanlessEq for Num<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.value == other.value
+    }
+}
+
+impl SpanlessEq for Str<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl SpanlessEq for GlobalPath<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (GlobalPath::Id(id), GlobalPath::Id(other_id)) => id.spanless_eq(other_id),
+            (GlobalPath::Branch { path, id, .. }, GlobalPath::Branch { path: other_path, id: other_id, .. }) => {
+                path.spanless_eq(other_path)
+                    && id.spanless_eq(other_id)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Marker<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.name.spanless_eq(&other.name)
+            && self.args.spanless_eq(&other.args)
+    }
+}
+
+impl SpanlessEq for RoutineArg<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.name.spanless_eq(&other.name)
+            && self.ty.spanless_eq(&other.ty)
+    }
+}
+
+impl SpanlessEq for Routine<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.args.spanless_eq(&other.args)
+            && self.returns.spanless_eq(&other.returns)
+            && self.markers.spanless_eq(&other.markers)
+            && self.block.spanless_eq(&other.block)
+    }
+}
+
+impl SpanlessEq for PathType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.left.spanless_eq(&other.left)
+            && self.right.spanless_eq(&other.right)
+    }
+}
+
+impl SpanlessEq for RoutineType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.args.spanless_eq(&other.args)
+            && self.returns.spanless_eq(&other.returns)
+    }
+}
+
+impl SpanlessEq for StructProp<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.markers.spanless_eq(&other.markers)
+            && self.shared == other.shared
+            && self.name.spanless_eq(&other.name)
+            && self.ty.spanless_eq(&other.ty)
+            && self.bits.spanless_eq(&other.bits)
+    }
+}
+
+impl SpanlessEq for StructType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.props.spanless_eq(&other.props)
+    }
+}
+
+impl SpanlessEq for UnionType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.props.spanless_eq(&other.props)
+    }
+}
+
+impl SpanlessEq for EnumProp<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.markers.spanless_eq(&other.markers)
+            && self.name.spanless_eq(&other.name)
+            && self.ty.spanless_eq(&other.ty)
+            && self.value.spanless_eq(&other.value)
+    }
+}
+
+impl SpanlessEq for EnumType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.props.spanless_eq(&other.props)
+    }
+}
+
+impl SpanlessEq for ContractType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.items.spanless_eq(&other.items)
+    }
+}
+
+impl SpanlessEq for SharedType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+    }
+}
+
+impl SpanlessEq for FluidType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+    }
+}
+
+impl SpanlessEq for RefType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+    }
+}
+
+impl SpanlessEq for DynType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+    }
+}
+
+impl SpanlessEq for VariadicType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.ty.spanless_eq(&other.ty)
+    }
+}
+
+impl SpanlessEq for OptType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+    }
+}
+
+impl SpanlessEq for ResType<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.ok.spanless_eq(&other.ok)
+            && self.err.spanless_eq(&other.err)
+    }
+}
+
+impl SpanlessEq for Type<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Type::Id(id), Type::Id(other_id)) => id.spanless_eq(other_id),
+            (Type::Path(path_type), Type::Path(other_path_type)) => path_type.spanless_eq(other_path_type),
+            (Type::Routine(routine_type), Type::Routine(other_routine_type)) => {
+                routine_type.spanless_eq(other_routine_type)
+            }
+            (Type::Closure(routine_type), Type::Closure(other_routine_type)) => {
+                routine_type.spanless_eq(other_routine_type)
+            }
+            (Type::Struct(struct_type), Type::Struct(other_struct_type)) => struct_type.spanless_eq(other_struct_type),
+            (Type::Union(union_type), Type::Union(other_union_type)) => union_type.spanless_eq(other_union_type),
+            (Type::Enum(enum_type), Type::Enum(other_enum_type)) => enum_type.spanless_eq(other_enum_type),
+            (Type::Contract(contract_type), Type::Contract(other_contract_type)) => {
+                contract_type.spanless_eq(other_contract_type)
+            }
+            (Type::Shared(shared_type), Type::Shared(other_shared_type)) => shared_type.spanless_eq(other_shared_type),
+            (Type::Fluid(fluid_type), Type::Fluid(other_fluid_type)) => fluid_type.spanless_eq(other_fluid_type),
+            (Type::Ref(ref_type), Type::Ref(other_ref_type)) => ref_type.spanless_eq(other_ref_type),
+            (Type::Dyn(dyn_type), Type::Dyn(other_dyn_type)) => dyn_type.spanless_eq(other_dyn_type),
+            (Type::Variadic(variadic_type), Type::Variadic(other_variadic_type)) => {
+                variadic_type.spanless_eq(other_variadic_type)
+            }
+            (Type::Opt(opt_type), Type::Opt(other_opt_type)) => opt_type.spanless_eq(other_opt_type),
+            (Type::Res(res_type), Type::Res(other_res_type)) => res_type.spanless_eq(other_res_type),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Path<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.left.spanless_eq(&other.left)
+            && self.right.spanless_eq(&other.right)
+    }
+}
+
+impl SpanlessEq for Access<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.left.spanless_eq(&other.left)
+            && self.right.spanless_eq(&other.right)
+    }
+}
+
+impl SpanlessEq for Call<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.left.spanless_eq(&other.left)
+            && self.args.spanless_eq(&other.args)
+    }
+}
+
+impl SpanlessEq for Unary<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.op == other.op
+            && self.subject.spanless_eq(&other.subject)
+    }
+}
+
+impl SpanlessEq for As<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+            && self.ty.spanless_eq(&other.ty)
+    }
+}
+
+impl SpanlessEq for Binary<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.op == other.op
+            && self.left.spanless_eq(&other.left)
+            && self.right.spanless_eq(&other.right)
+    }
+}
+
+impl SpanlessEq for ConstructProp<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.name.spanless_eq(&other.name)
+            && self.value.spanless_eq(&other.value)
+    }
+}
+
+impl SpanlessEq for Construct<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+            && self.items.spanless_eq(&other.items)
+    }
+}
+
+impl SpanlessEq for ConstructEnum<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+            && self.item.spanless_eq(&other.item)
+    }
+}
+
+impl SpanlessEq for Wrap<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.item.spanless_eq(&other.item)
+    }
+}
+
+impl SpanlessEq for NoneLit {
+    fn spanless_eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl SpanlessEq for Try<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+    }
+}
+
+impl SpanlessEq for Index<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+            && self.index.spanless_eq(&other.index)
+    }
+}
+
+impl SpanlessEq for AsmOperand<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.constraint.spanless_eq(&other.constraint)
+            && self.expr.spanless_eq(&other.expr)
+    }
+}
+
+impl SpanlessEq for Asm<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.template.spanless_eq(&other.template)
+            && self.outputs.spanless_eq(&other.outputs)
+            && self.inputs.spanless_eq(&other.inputs)
+            && self.clobbers.spanless_eq(&other.clobbers)
+            && self.options.spanless_eq(&other.options)
+    }
+}
+
+impl SpanlessEq for Expr<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Expr::Bool(bool), Expr::Bool(other_bool)) => bool.spanless_eq(other_bool),
+            (Expr::Id(id), Expr::Id(other_id)) => id.spanless_eq(other_id),
+            (Expr::Num(num), Expr::Num(other_num)) => num.spanless_eq(other_num),
+            (Expr::Str(str), Expr::Str(other_str)) => str.spanless_eq(other_str),
+            (Expr::Path(path), Expr::Path(other_path)) => path.spanless_eq(other_path),
+            (Expr::Access(access), Expr::Access(other_access)) => access.spanless_eq(other_access),
+            (Expr::Call(call), Expr::Call(other_call)) => call.spanless_eq(other_call),
+            (Expr::Unary(unary), Expr::Unary(other_unary)) => unary.spanless_eq(other_unary),
+            (Expr::As(as_), Expr::As(other_as)) => as_.spanless_eq(other_as),
+            (Expr::Binary(binary), Expr::Binary(other_binary)) => binary.spanless_eq(other_binary),
+            (Expr::Routine(routine), Expr::Routine(other_routine)) => routine.spanless_eq(other_routine),
+            (Expr::Construct(construct), Expr::Construct(other_construct)) => construct.spanless_eq(other_construct),
+            (Expr::ConstructEnum(construct_enum), Expr::ConstructEnum(other_construct_enum)) => {
+                construct_enum.spanless_eq(other_construct_enum)
+            }
+            (Expr::Asm(asm), Expr::Asm(other_asm)) => asm.spanless_eq(other_asm),
+            (Expr::Ok(wrap), Expr::Ok(other_wrap)) => wrap.spanless_eq(other_wrap),
+            (Expr::Err(wrap), Expr::Err(other_wrap)) => wrap.spanless_eq(other_wrap),
+            (Expr::None(none_lit), Expr::None(other_none_lit)) => none_lit.spanless_eq(other_none_lit),
+            (Expr::Try(try_), Expr::Try(other_try)) => try_.spanless_eq(other_try),
+            (Expr::Index(index), Expr::Index(other_index)) => index.spanless_eq(other_index),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Assign<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.op == other.op
+            && self.left.spanless_eq(&other.left)
+            && self.right.spanless_eq(&other.right)
+    }
+}
+
+impl SpanlessEq for Val<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.markers.spanless_eq(&other.markers)
+            && self.shared == other.shared
+            && self.name.spanless_eq(&other.name)
+            && self.ty.spanless_eq(&other.ty)
+            && self.value.spanless_eq(&other.value)
+    }
+}
+
+impl SpanlessEq for Const<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.markers.spanless_eq(&other.markers)
+            && self.shared == other.shared
+            && self.name.spanless_eq(&other.name)
+            && self.ty.spanless_eq(&other.ty)
+            && self.value.spanless_eq(&other.value)
+    }
+}
+
+impl SpanlessEq for TypeDecl<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.markers.spanless_eq(&other.markers)
+            && self.shared == other.shared
+            && self.name.spanless_eq(&other.name)
+            && self.value.spanless_eq(&other.value)
+    }
+}
+
+impl SpanlessEq for IfBranch<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (IfBranch::ElseIf { cond, block, .. }, IfBranch::ElseIf { cond: other_cond, block: other_block, .. }) => {
+                cond.spanless_eq(other_cond)
+                    && block.spanless_eq(other_block)
+            }
+            (IfBranch::Else { block, .. }, IfBranch::Else { block: other_block, .. }) => block.spanless_eq(other_block),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for If<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.cond.spanless_eq(&other.cond)
+            && self.block.spanless_eq(&other.block)
+            && self.branches.spanless_eq(&other.branches)
+    }
+}
+
+impl SpanlessEq for While<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.label.spanless_eq(&other.label)
+            && self.cond.spanless_eq(&other.cond)
+            && self.block.spanless_eq(&other.block)
+    }
+}
+
+impl SpanlessEq for MatchCase<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.name.spanless_eq(&other.name)
+            && self.ty.spanless_eq(&other.ty)
+            && self.block.spanless_eq(&other.block)
+    }
+}
+
+impl SpanlessEq for Match<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.subject.spanless_eq(&other.subject)
+            && self.cases.spanless_eq(&other.cases)
+    }
+}
+
+impl SpanlessEq for Break<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.label.spanless_eq(&other.label)
+    }
+}
+
+impl SpanlessEq for Continue<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.label.spanless_eq(&other.label)
+    }
+}
+
+impl SpanlessEq for Return<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.value.spanless_eq(&other.value)
+    }
+}
+
+impl SpanlessEq for Defer<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.item.spanless_eq(&other.item)
+    }
+}
+
+impl SpanlessEq for FlagExpr<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (FlagExpr::Name(id), FlagExpr::Name(other_id)) => id.spanless_eq(other_id),
+            (FlagExpr::Value { name, value, .. }, FlagExpr::Value { name: other_name, value: other_value, .. }) => {
+                name.spanless_eq(other_name)
+                    && value.spanless_eq(other_value)
+            }
+            (FlagExpr::Any { items, .. }, FlagExpr::Any { items: other_items, .. }) => items.spanless_eq(other_items),
+            (FlagExpr::All { items, .. }, FlagExpr::All { items: other_items, .. }) => items.spanless_eq(other_items),
+            (FlagExpr::Not { item, .. }, FlagExpr::Not { item: other_item, .. }) => item.spanless_eq(other_item),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Flag<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.neg == other.neg
+            && self.expr.spanless_eq(&other.expr)
+    }
+}
+
+impl SpanlessEq for BlockExpr<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BlockExpr::Assign(assign), BlockExpr::Assign(other_assign)) => assign.spanless_eq(other_assign),
+            (BlockExpr::Val(val), BlockExpr::Val(other_val)) => val.spanless_eq(other_val),
+            (BlockExpr::Call(call), BlockExpr::Call(other_call)) => call.spanless_eq(other_call),
+            (BlockExpr::If(if_), BlockExpr::If(other_if)) => if_.spanless_eq(other_if),
+            (BlockExpr::While(while_), BlockExpr::While(other_while)) => while_.spanless_eq(other_while),
+            (BlockExpr::Match(match_), BlockExpr::Match(other_match)) => match_.spanless_eq(other_match),
+            (BlockExpr::TypeDecl(type_decl), BlockExpr::TypeDecl(other_type_decl)) => {
+                type_decl.spanless_eq(other_type_decl)
+            }
+            (BlockExpr::Break(break_), BlockExpr::Break(other_break)) => break_.spanless_eq(other_break),
+            (BlockExpr::Continue(continue_), BlockExpr::Continue(other_continue)) => {
+                continue_.spanless_eq(other_continue)
+            }
+            (BlockExpr::Return(return_), BlockExpr::Return(other_return)) => return_.spanless_eq(other_return),
+            (BlockExpr::Defer(defer), BlockExpr::Defer(other_defer)) => defer.spanless_eq(other_defer),
+            (BlockExpr::Asm(asm), BlockExpr::Asm(other_asm)) => asm.spanless_eq(other_asm),
+            (BlockExpr::Block(flags, block), BlockExpr::Block(other_flags, other_block)) => {
+                flags.spanless_eq(other_flags)
+                    && block.spanless_eq(other_block)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Block<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.items.spanless_eq(&other.items)
+    }
+}
+
+impl SpanlessEq for ImportItem<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.name.spanless_eq(&other.name)
+            && self.as_.spanless_eq(&other.as_)
+    }
+}
+
+impl SpanlessEq for Import<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Import::Single { shared, item, from, .. }, Import::Single { shared: other_shared, item: other_item, from: other_from, .. }) => {
+                shared == other_shared
+                    && item.spanless_eq(other_item)
+                    && from.spanless_eq(other_from)
+            }
+            (Import::Multi { shared, items, from, .. }, Import::Multi { shared: other_shared, items: other_items, from: other_from, .. }) => {
+                shared == other_shared
+                    && items.spanless_eq(other_items)
+                    && from.spanless_eq(other_from)
+            }
+            (Import::Glob { shared, from, .. }, Import::Glob { shared: other_shared, from: other_from, .. }) => {
+                shared == other_shared
+                    && from.spanless_eq(other_from)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Application<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Application::Val(val), Application::Val(other_val)) => val.spanless_eq(other_val),
+            (Application::TypeDecl(type_decl), Application::TypeDecl(other_type_decl)) => {
+                type_decl.spanless_eq(other_type_decl)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Apply<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.markers.spanless_eq(&other.markers)
+            && self.subject.spanless_eq(&other.subject)
+            && self.to.spanless_eq(&other.to)
+            && self.items.spanless_eq(&other.items)
+    }
+}
+
+impl SpanlessEq for Module<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.shared == other.shared
+            && self.name.spanless_eq(&other.name)
+            && self.items.spanless_eq(&other.items)
+    }
+}
+
+impl SpanlessEq for RootStmnt<'_> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RootStmnt::Assign(assign), RootStmnt::Assign(other_assign)) => assign.spanless_eq(other_assign),
+            (RootStmnt::Val(flags, val), RootStmnt::Val(other_flags, other_val)) => {
+                flags.spanless_eq(other_flags)
+                    && val.spanless_eq(other_val)
+            }
+            (RootStmnt::Const(flags, const_), RootStmnt::Const(other_flags, other_const)) => {
+                flags.spanless_eq(other_flags)
+                    && const_.spanless_eq(other_const)
+            }
+            (RootStmnt::Call(call), RootStmnt::Call(other_call)) => call.spanless_eq(other_call),
+            (RootStmnt::Import(flags, import), RootStmnt::Import(other_flags, other_import)) => {
+                flags.spanless_eq(other_flags)
+                    && import.spanless_eq(other_import)
+            }
+            (RootStmnt::If(if_), RootStmnt::If(other_if)) => if_.spanless_eq(other_if),
+            (RootStmnt::While(while_), RootStmnt::While(other_while)) => while_.spanless_eq(other_while),
+            (RootStmnt::Match(match_), RootStmnt::Match(other_match)) => match_.spanless_eq(other_match),
+            (RootStmnt::TypeDecl(flags, type_decl), RootStmnt::TypeDecl(other_flags, other_type_decl)) => {
+                flags.spanless_eq(other_flags)
+                    && type_decl.spanless_eq(other_type_decl)
+            }
+            (RootStmnt::Apply(flags, apply), RootStmnt::Apply(other_flags, other_apply)) => {
+                flags.spanless_eq(other_flags)
+                    && apply.spanless_eq(other_apply)
+            }
+            (RootStmnt::Block(flags, block), RootStmnt::Block(other_flags, other_block)) => {
+                flags.spanless_eq(other_flags)
+                    && block.spanless_eq(other_block)
+            }
+            (RootStmnt::Module(flags, module), RootStmnt::Module(other_flags, other_module)) => {
+                flags.spanless_eq(other_flags)
+                    && module.spanless_eq(other_module)
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,915 @@
+//! A read-only walk over the AST.
+//!
+//! Every method of [`Visit`] has a default that calls the free function of the same name, which visits each child
+//! of the node in source order. Override a method to act on a node, and call the free function from it to keep
+//! walking into its children.
+
+use crate::ast::*;
+
+/// A read-only visitor over the AST.
+pub trait Visit<'a> {
+    /// Visits a boolean literal.
+    fn visit_bool(&mut self, node: &Bool) {
+        visit_bool(self, node);
+    }
+
+    /// Visits an identifier.
+    fn visit_id(&mut self, node: &Id<'a>) {
+        visit_id(self, node);
+    }
+
+    /// Visits a number literal.
+    fn visit_num(&mut self, node: &Num<'a>) {
+        visit_num(self, node);
+    }
+
+    /// Visits a string literal.
+    fn visit_str(&mut self, node: &Str<'a>) {
+        visit_str(self, node);
+    }
+
+    /// Visits a path in the root of a unit.
+    fn visit_global_path(&mut self, node: &GlobalPath<'a>) {
+        visit_global_path(self, node);
+    }
+
+    /// Visits a marker.
+    fn visit_marker(&mut self, node: &Marker<'a>) {
+        visit_marker(self, node);
+    }
+
+    /// Visits an argument of a routine literal.
+    fn visit_routine_arg(&mut self, node: &RoutineArg<'a>) {
+        visit_routine_arg(self, node);
+    }
+
+    /// Visits a routine literal.
+    fn visit_routine(&mut self, node: &Routine<'a>) {
+        visit_routine(self, node);
+    }
+
+    /// Visits a path in a type.
+    fn visit_path_type(&mut self, node: &PathType<'a>) {
+        visit_path_type(self, node);
+    }
+
+    /// Visits a routine or closure type.
+    fn visit_routine_type(&mut self, node: &RoutineType<'a>) {
+        visit_routine_type(self, node);
+    }
+
+    /// Visits a property of a struct or union type.
+    fn visit_struct_prop(&mut self, node: &StructProp<'a>) {
+        visit_struct_prop(self, node);
+    }
+
+    /// Visits a struct type.
+    fn visit_struct_type(&mut self, node: &StructType<'a>) {
+        visit_struct_type(self, node);
+    }
+
+    /// Visits a union type.
+    fn visit_union_type(&mut self, node: &UnionType<'a>) {
+        visit_union_type(self, node);
+    }
+
+    /// Visits a property of an enum type.
+    fn visit_enum_prop(&mut self, node: &EnumProp<'a>) {
+        visit_enum_prop(self, node);
+    }
+
+    /// Visits an enum type.
+    fn visit_enum_type(&mut self, node: &EnumType<'a>) {
+        visit_enum_type(self, node);
+    }
+
+    /// Visits a contract type.
+    fn visit_contract_type(&mut self, node: &ContractType<'a>) {
+        visit_contract_type(self, node);
+    }
+
+    /// Visits a `shared` type.
+    fn visit_shared_type(&mut self, node: &SharedType<'a>) {
+        visit_shared_type(self, node);
+    }
+
+    /// Visits a `fluid` type.
+    fn visit_fluid_type(&mut self, node: &FluidType<'a>) {
+        visit_fluid_type(self, node);
+    }
+
+    /// Visits a reference type.
+    fn visit_ref_type(&mut self, node: &RefType<'a>) {
+        visit_ref_type(self, node);
+    }
+
+    /// Visits a contract object type.
+    fn visit_dyn_type(&mut self, node: &DynType<'a>) {
+        visit_dyn_type(self, node);
+    }
+
+    /// Visits the variadic arguments of a routine type.
+    fn visit_variadic_type(&mut self, node: &VariadicType<'a>) {
+        visit_variadic_type(self, node);
+    }
+
+    /// Visits an option type.
+    fn visit_opt_type(&mut self, node: &OptType<'a>) {
+        visit_opt_type(self, node);
+    }
+
+    /// Visits a result type.
+    fn visit_res_type(&mut self, node: &ResType<'a>) {
+        visit_res_type(self, node);
+    }
+
+    /// Visits a type.
+    fn visit_type(&mut self, node: &Type<'a>) {
+        visit_type(self, node);
+    }
+
+    /// Visits a path expression.
+    fn visit_path(&mut self, node: &Path<'a>) {
+        visit_path(self, node);
+    }
+
+    /// Visits an access expression.
+    fn visit_access(&mut self, node: &Access<'a>) {
+        visit_access(self, node);
+    }
+
+    /// Visits a call.
+    fn visit_call(&mut self, node: &Call<'a>) {
+        visit_call(self, node);
+    }
+
+    /// Visits a unary expression.
+    fn visit_unary(&mut self, node: &Unary<'a>) {
+        visit_unary(self, node);
+    }
+
+    /// Visits a cast.
+    fn visit_as(&mut self, node: &As<'a>) {
+        visit_as(self, node);
+    }
+
+    /// Visits a binary expression.
+    fn visit_binary(&mut self, node: &Binary<'a>) {
+        visit_binary(self, node);
+    }
+
+    /// Visits a property of a struct construction.
+    fn visit_construct_prop(&mut self, node: &ConstructProp<'a>) {
+        visit_construct_prop(self, node);
+    }
+
+    /// Visits a struct construction.
+    fn visit_construct(&mut self, node: &Construct<'a>) {
+        visit_construct(self, node);
+    }
+
+    /// Visits an enum construction.
+    fn visit_construct_enum(&mut self, node: &ConstructEnum<'a>) {
+        visit_construct_enum(self, node);
+    }
+
+    /// Visits an `ok` or `err` constructor.
+    fn visit_wrap(&mut self, node: &Wrap<'a>) {
+        visit_wrap(self, node);
+    }
+
+    /// Visits a `none` literal.
+    fn visit_none_lit(&mut self, node: &NoneLit) {
+        visit_none_lit(self, node);
+    }
+
+    /// Visits a `?` expression.
+    fn visit_try(&mut self, node: &Try<'a>) {
+        visit_try(self, node);
+    }
+
+    /// Visits an index expression.
+    fn visit_index(&mut self, node: &Index<'a>) {
+        visit_index(self, node);
+    }
+
+    /// Visits an operand of an inline assembly block.
+    fn visit_asm_operand(&mut self, node: &AsmOperand<'a>) {
+        visit_asm_operand(self, node);
+    }
+
+    /// Visits an inline assembly block.
+    fn visit_asm(&mut self, node: &Asm<'a>) {
+        visit_asm(self, node);
+    }
+
+    /// Visits an expression.
+    fn visit_expr(&mut self, node: &Expr<'a>) {
+        visit_expr(self, node);
+    }
+
+    /// Visits an assignment.
+    fn visit_assign(&mut self, node: &Assign<'a>) {
+        visit_assign(self, node);
+    }
+
+    /// Visits a variable declaration.
+    fn visit_val(&mut self, node: &Val<'a>) {
+        visit_val(self, node);
+    }
+
+    /// Visits a constant declaration.
+    fn visit_const(&mut self, node: &Const<'a>) {
+        visit_const(self, node);
+    }
+
+    /// Visits a type declaration.
+    fn visit_type_decl(&mut self, node: &TypeDecl<'a>) {
+        visit_type_decl(self, node);
+    }
+
+    /// Visits an `else if` or `else` branch.
+    fn visit_if_branch(&mut self, node: &IfBranch<'a>) {
+        visit_if_branch(self, node);
+    }
+
+    /// Visits an `if` statement.
+    fn visit_if(&mut self, node: &If<'a>) {
+        visit_if(self, node);
+    }
+
+    /// Visits a `while` loop.
+    fn visit_while(&mut self, node: &While<'a>) {
+        visit_while(self, node);
+    }
+
+    /// Visits a case of a `match` statement.
+    fn visit_match_case(&mut self, node: &MatchCase<'a>) {
+        visit_match_case(self, node);
+    }
+
+    /// Visits a `match` statement.
+    fn visit_match(&mut self, node: &Match<'a>) {
+        visit_match(self, node);
+    }
+
+    /// Visits a `break` statement.
+    fn visit_break(&mut self, node: &Break<'a>) {
+        visit_break(self, node);
+    }
+
+    /// Visits a `continue` statement.
+    fn visit_continue(&mut self, node: &Continue<'a>) {
+        visit_continue(self, node);
+    }
+
+    /// Visits a `return` statement.
+    fn visit_return(&mut self, node: &Return<'a>) {
+        visit_return(self, node);
+    }
+
+    /// Visits a `defer` statement.
+    fn visit_defer(&mut self, node: &Defer<'a>) {
+        visit_defer(self, node);
+    }
+
+    /// Visits a flag condition.
+    fn visit_flag_expr(&mut self, node: &FlagExpr<'a>) {
+        visit_flag_expr(self, node);
+    }
+
+    /// Visits a flag on a statement.
+    fn visit_flag(&mut self, node: &Flag<'a>) {
+        visit_flag(self, node);
+    }
+
+    /// Visits a statement in a block.
+    fn visit_block_expr(&mut self, node: &BlockExpr<'a>) {
+        visit_block_expr(self, node);
+    }
+
+    /// Visits a block.
+    fn visit_block(&mut self, node: &Block<'a>) {
+        visit_block(self, node);
+    }
+
+    /// Visits an item of an import.
+    fn visit_import_item(&mut self, node: &ImportItem<'a>) {
+        visit_import_item(self, node);
+    }
+
+    /// Visits an import.
+    fn visit_import(&mut self, node: &Import<'a>) {
+        visit_import(self, node);
+    }
+
+    /// Visits an item of a contract or `apply` statement.
+    fn visit_application(&mut self, node: &Application<'a>) {
+        visit_application(self, node);
+    }
+
+    /// Visits an `apply` statement.
+    fn visit_apply(&mut self, node: &Apply<'a>) {
+        visit_apply(self, node);
+    }
+
+    /// Visits an inline module.
+    fn visit_module(&mut self, node: &Module<'a>) {
+        visit_module(self, node);
+    }
+
+    /// Visits a statement in the root of a unit.
+    fn visit_root_stmnt(&mut self, node: &RootStmnt<'a>) {
+        visit_root_stmnt(self, node);
+    }
+}
+
+/// Visits the children of a boolean literal.
+pub fn visit_bool<'a, V: Visit<'a> + ?Sized>(_v: &mut V, _node: &Bool) {
+}
+
+/// Visits the children of an identifier.
+pub fn visit_id<'a, V: Visit<'a> + ?Sized>(_v: &mut V, _node: &Id<'a>) {
+}
+
+/// Visits the children of a number literal.
+pub fn visit_num<'a, V: Visit<'a> + ?Sized>(_v: &mut V, _node: &Num<'a>) {
+}
+
+/// Visits the children of a string literal.
+pub fn visit_str<'a, V: Visit<'a> + ?Sized>(_v: &mut V, _node: &Str<'a>) {
+}
+
+/// Visits the children of a path in the root of a unit.
+pub fn visit_global_path<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &GlobalPath<'a>) {
+    match node {
+        GlobalPath::Id(id) => v.visit_id(id),
+        GlobalPath::Branch { path, id, .. } => {
+            v.visit_global_path(path);
+            v.visit_id(id);
+        }
+    }
+}
+
+/// Visits the children of a marker.
+pub fn visit_marker<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Marker<'a>) {
+    v.visit_id(&node.name);
+    for expr in &node.args {
+        v.visit_expr(expr);
+    }
+}
+
+/// Visits the children of an argument of a routine literal.
+pub fn visit_routine_arg<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &RoutineArg<'a>) {
+    v.visit_id(&node.name);
+    v.visit_type(&node.ty);
+}
+
+/// Visits the children of a routine literal.
+pub fn visit_routine<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Routine<'a>) {
+    for routine_arg in &node.args {
+        v.visit_routine_arg(routine_arg);
+    }
+    if let Some(ty) = &node.returns {
+        v.visit_type(ty);
+    }
+    for marker in &node.markers {
+        v.visit_marker(marker);
+    }
+    v.visit_block(&node.block);
+}
+
+/// Visits the children of a path in a type.
+pub fn visit_path_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &PathType<'a>) {
+    v.visit_type(&node.left);
+    v.visit_id(&node.right);
+}
+
+/// Visits the children of a routine or closure type.
+pub fn visit_routine_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &RoutineType<'a>) {
+    for ty in &node.args {
+        v.visit_type(ty);
+    }
+    if let Some(ty) = &node.returns {
+        v.visit_type(ty);
+    }
+}
+
+/// Visits the children of a property of a struct or union type.
+pub fn visit_struct_prop<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &StructProp<'a>) {
+    for marker in &node.markers {
+        v.visit_marker(marker);
+    }
+    v.visit_id(&node.name);
+    v.visit_type(&node.ty);
+    if let Some(num) = &node.bits {
+        v.visit_num(num);
+    }
+}
+
+/// Visits the children of a struct type.
+pub fn visit_struct_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &StructType<'a>) {
+    for struct_prop in &node.props {
+        v.visit_struct_prop(struct_prop);
+    }
+}
+
+/// Visits the children of a union type.
+pub fn visit_union_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &UnionType<'a>) {
+    for struct_prop in &node.props {
+        v.visit_struct_prop(struct_prop);
+    }
+}
+
+/// Visits the children of a property of an enum type.
+pub fn visit_enum_prop<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &EnumProp<'a>) {
+    for marker in &node.markers {
+        v.visit_marker(marker);
+    }
+    v.visit_id(&node.name);
+    if let Some(ty) = &node.ty {
+        v.visit_type(ty);
+    }
+    if let Some(expr) = &node.value {
+        v.visit_expr(expr);
+    }
+}
+
+/// Visits the children of an enum type.
+pub fn visit_enum_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &EnumType<'a>) {
+    for enum_prop in &node.props {
+        v.visit_enum_prop(enum_prop);
+    }
+}
+
+/// Visits the children of a contract type.
+pub fn visit_contract_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &ContractType<'a>) {
+    for application in &node.items {
+        v.visit_application(application);
+    }
+}
+
+/// Visits the children of a `shared` type.
+pub fn visit_shared_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &SharedType<'a>) {
+    v.visit_type(&node.subject);
+}
+
+/// Visits the children of a `fluid` type.
+pub fn visit_fluid_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &FluidType<'a>) {
+    v.visit_type(&node.subject);
+}
+
+/// Visits the children of a reference type.
+pub fn visit_ref_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &RefType<'a>) {
+    v.visit_type(&node.subject);
+}
+
+/// Visits the children of a contract object type.
+pub fn visit_dyn_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &DynType<'a>) {
+    v.visit_type(&node.subject);
+}
+
+/// Visits the children of the variadic arguments of a routine type.
+pub fn visit_variadic_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &VariadicType<'a>) {
+    if let Some(ty) = &node.ty {
+        v.visit_type(ty);
+    }
+}
+
+/// Visits the children of an option type.
+pub fn visit_opt_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &OptType<'a>) {
+    v.visit_type(&node.subject);
+}
+
+/// Visits the children of a result type.
+pub fn visit_res_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &ResType<'a>) {
+    v.visit_type(&node.ok);
+    v.visit_type(&node.err);
+}
+
+/// Visits the children of a type.
+pub fn visit_type<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Type<'a>) {
+    match node {
+        Type::Id(id) => v.visit_id(id),
+        Type::Path(path_type) => v.visit_path_type(path_type),
+        Type::Routine(routine_type) => v.visit_routine_type(routine_type),
+        Type::Closure(routine_type) => v.visit_routine_type(routine_type),
+        Type::Struct(struct_type) => v.visit_struct_type(struct_type),
+        Type::Union(union_type) => v.visit_union_type(union_type),
+        Type::Enum(enum_type) => v.visit_enum_type(enum_type),
+        Type::Contract(contract_type) => v.visit_contract_type(contract_type),
+        Type::Shared(shared_type) => v.visit_shared_type(shared_type),
+        Type::Fluid(fluid_type) => v.visit_fluid_type(fluid_type),
+        Type::Ref(ref_type) => v.visit_ref_type(ref_type),
+        Type::Dyn(dyn_type) => v.visit_dyn_type(dyn_type),
+        Type::Variadic(variadic_type) => v.visit_variadic_type(variadic_type),
+        Type::Opt(opt_type) => v.visit_opt_type(opt_type),
+        Type::Res(res_type) => v.visit_res_type(res_type),
+    }
+}
+
+/// Visits the children of a path expression.
+pub fn visit_path<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Path<'a>) {
+    v.visit_expr(&node.left);
+    v.visit_id(&node.right);
+}
+
+/// Visits the children of an access expression.
+pub fn visit_access<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Access<'a>) {
+    v.visit_expr(&node.left);
+    v.visit_id(&node.right);
+}
+
+/// Visits the children of a call.
+pub fn visit_call<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Call<'a>) {
+    v.visit_expr(&node.left);
+    for expr in &node.args {
+        v.visit_expr(expr);
+    }
+}
+
+/// Visits the children of a unary expression.
+pub fn visit_unary<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Unary<'a>) {
+    v.visit_expr(&node.subject);
+}
+
+/// Visits the children of a cast.
+pub fn visit_as<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &As<'a>) {
+    v.visit_expr(&node.subject);
+    v.visit_type(&node.ty);
+}
+
+/// Visits the children of a binary expression.
+pub fn visit_binary<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Binary<'a>) {
+    v.visit_expr(&node.left);
+    v.visit_expr(&node.right);
+}
+
+/// Visits the children of a property of a struct construction.
+pub fn visit_construct_prop<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &ConstructProp<'a>) {
+    v.visit_id(&node.name);
+    v.visit_expr(&node.value);
+}
+
+/// Visits the children of a struct construction.
+pub fn visit_construct<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Construct<'a>) {
+    v.visit_expr(&node.subject);
+    for construct_prop in &node.items {
+        v.visit_construct_prop(construct_prop);
+    }
+}
+
+/// Visits the children of an enum construction.
+pub fn visit_construct_enum<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &ConstructEnum<'a>) {
+    v.visit_expr(&node.subject);
+    v.visit_expr(&node.item);
+}
+
+/// Visits the children of an `ok` or `err` constructor.
+pub fn visit_wrap<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Wrap<'a>) {
+    v.visit_expr(&node.item);
+}
+
+/// Visits the children of a `none` literal.
+pub fn visit_none_lit<'a, V: Visit<'a> + ?Sized>(_v: &mut V, _node: &NoneLit) {
+}
+
+/// Visits the children of a `?` expression.
+pub fn visit_try<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Try<'a>) {
+    v.visit_expr(&node.subject);
+}
+
+/// Visits the children of an index expression.
+pub fn visit_index<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Index<'a>) {
+    v.visit_expr(&node.subject);
+    v.visit_expr(&node.index);
+}
+
+/// Visits the children of an operand of an inline assembly block.
+pub fn visit_asm_operand<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &AsmOperand<'a>) {
+    v.visit_str(&node.constraint);
+    v.visit_expr(&node.expr);
+}
+
+/// Visits the children of an inline assembly block.
+pub fn visit_asm<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Asm<'a>) {
+    v.visit_str(&node.template);
+    for asm_operand in &node.outputs {
+        v.visit_asm_operand(asm_operand);
+    }
+    for asm_operand in &node.inputs {
+        v.visit_asm_operand(asm_operand);
+    }
+    for str in &node.clobbers {
+        v.visit_str(str);
+    }
+    for id in &node.options {
+        v.visit_id(id);
+    }
+}
+
+/// Visits the children of an expression.
+pub fn visit_expr<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Expr<'a>) {
+    match node {
+        Expr::Bool(bool) => v.visit_bool(bool),
+        Expr::Id(id) => v.visit_id(id),
+        Expr::Num(num) => v.visit_num(num),
+        Expr::Str(str) => v.visit_str(str),
+        Expr::Path(path) => v.visit_path(path),
+        Expr::Access(access) => v.visit_access(access),
+        Expr::Call(call) => v.visit_call(call),
+        Expr::Unary(unary) => v.visit_unary(unary),
+        Expr::As(as_) => v.visit_as(as_),
+        Expr::Binary(binary) => v.visit_binary(binary),
+        Expr::Routine(routine) => v.visit_routine(routine),
+        Expr::Construct(construct) => v.visit_construct(construct),
+        Expr::ConstructEnum(construct_enum) => v.visit_construct_enum(construct_enum),
+        Expr::Asm(asm) => v.visit_asm(asm),
+        Expr::Ok(wrap) => v.visit_wrap(wrap),
+        Expr::Err(wrap) => v.visit_wrap(wrap),
+        Expr::None(none_lit) => v.visit_none_lit(none_lit),
+        Expr::Try(try_) => v.visit_try(try_),
+        Expr::Index(index) => v.visit_index(index),
+    }
+}
+
+/// Visits the children of an assignment.
+pub fn visit_assign<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Assign<'a>) {
+    v.visit_expr(&node.left);
+    v.visit_expr(&node.right);
+}
+
+/// Visits the children of a variable declaration.
+pub fn visit_val<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Val<'a>) {
+    for marker in &node.markers {
+        v.visit_marker(marker);
+    }
+    v.visit_id(&node.name);
+    if let Some(ty) = &node.ty {
+        v.visit_type(ty);
+    }
+    if let Some(expr) = &node.value {
+        v.visit_expr(expr);
+    }
+}
+
+/// Visits the children of a constant declaration.
+pub fn visit_const<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Const<'a>) {
+    for marker in &node.markers {
+        v.visit_marker(marker);
+    }
+    v.visit_id(&node.name);
+    if let Some(ty) = &node.ty {
+        v.visit_type(ty);
+    }
+    v.visit_expr(&node.value);
+}
+
+/// Visits the children of a type declaration.
+pub fn visit_type_decl<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &TypeDecl<'a>) {
+    for marker in &node.markers {
+        v.visit_marker(marker);
+    }
+    v.visit_id(&node.name);
+    if let Some(ty) = &node.value {
+        v.visit_type(ty);
+    }
+}
+
+/// Visits the children of an `else if` or `else` branch.
+pub fn visit_if_branch<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &IfBranch<'a>) {
+    match node {
+        IfBranch::ElseIf { cond, block, .. } => {
+            v.visit_expr(cond);
+            v.visit_block(block);
+        }
+        IfBranch::Else { block, .. } => v.visit_block(block),
+    }
+}
+
+/// Visits the children of an `if` statement.
+pub fn visit_if<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &If<'a>) {
+    v.visit_expr(&node.cond);
+    v.visit_block(&node.block);
+    for if_branch in &node.branches {
+        v.visit_if_branch(if_branch);
+    }
+}
+
+/// Visits the children of a `while` loop.
+pub fn visit_while<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &While<'a>) {
+    if let Some(id) = &node.label {
+        v.visit_id(id);
+    }
+    v.visit_expr(&node.cond);
+    v.visit_block(&node.block);
+}
+
+/// Visits the children of a case of a `match` statement.
+pub fn visit_match_case<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &MatchCase<'a>) {
+    v.visit_id(&node.name);
+    v.visit_type(&node.ty);
+    v.visit_block(&node.block);
+}
+
+/// Visits the children of a `match` statement.
+pub fn visit_match<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Match<'a>) {
+    v.visit_expr(&node.subject);
+    for match_case in &node.cases {
+        v.visit_match_case(match_case);
+    }
+}
+
+/// Visits the children of a `break` statement.
+pub fn visit_break<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Break<'a>) {
+    if let Some(id) = &node.label {
+        v.visit_id(id);
+    }
+}
+
+/// Visits the children of a `continue` statement.
+pub fn visit_continue<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Continue<'a>) {
+    if let Some(id) = &node.label {
+        v.visit_id(id);
+    }
+}
+
+/// Visits the children of a `return` statement.
+pub fn visit_return<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Return<'a>) {
+    if let Some(expr) = &node.value {
+        v.visit_expr(expr);
+    }
+}
+
+/// Visits the children of a `defer` statement.
+pub fn visit_defer<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Defer<'a>) {
+    v.visit_block_expr(&node.item);
+}
+
+/// Visits the children of a flag condition.
+pub fn visit_flag_expr<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &FlagExpr<'a>) {
+    match node {
+        FlagExpr::Name(id) => v.visit_id(id),
+        FlagExpr::Value { name, value, .. } => {
+            v.visit_id(name);
+            v.visit_str(value);
+        }
+        FlagExpr::Any { items, .. } => {
+            for flag_expr in items {
+                v.visit_flag_expr(flag_expr);
+            }
+        }
+        FlagExpr::All { items, .. } => {
+            for flag_expr in items {
+                v.visit_flag_expr(flag_expr);
+            }
+        }
+        FlagExpr::Not { item, .. } => v.visit_flag_expr(item),
+    }
+}
+
+/// Visits the children of a flag on a statement.
+pub fn visit_flag<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Flag<'a>) {
+    v.visit_flag_expr(&node.expr);
+}
+
+/// Visits the children of a statement in a block.
+pub fn visit_block_expr<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &BlockExpr<'a>) {
+    match node {
+        BlockExpr::Assign(assign) => v.visit_assign(assign),
+        BlockExpr::Val(val) => v.visit_val(val),
+        BlockExpr::Call(call) => v.visit_call(call),
+        BlockExpr::If(if_) => v.visit_if(if_),
+        BlockExpr::While(while_) => v.visit_while(while_),
+        BlockExpr::Match(match_) => v.visit_match(match_),
+        BlockExpr::TypeDecl(type_decl) => v.visit_type_decl(type_decl),
+        BlockExpr::Break(break_) => v.visit_break(break_),
+        BlockExpr::Continue(continue_) => v.visit_continue(continue_),
+        BlockExpr::Return(return_) => v.visit_return(return_),
+        BlockExpr::Defer(defer) => v.visit_defer(defer),
+        BlockExpr::Asm(asm) => v.visit_asm(asm),
+        BlockExpr::Block(flags, block) => {
+            for flag in flags {
+                v.visit_flag(flag);
+            }
+            v.visit_block(block);
+        }
+    }
+}
+
+/// Visits the children of a block.
+pub fn visit_block<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Block<'a>) {
+    for block_expr in &node.items {
+        v.visit_block_expr(block_expr);
+    }
+}
+
+/// Visits the children of an item of an import.
+pub fn visit_import_item<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &ImportItem<'a>) {
+    v.visit_id(&node.name);
+    if let Some(id) = &node.as_ {
+        v.visit_id(id);
+    }
+}
+
+/// Visits the children of an import.
+pub fn visit_import<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Import<'a>) {
+    match node {
+        Import::Single { item, from, .. } => {
+            v.visit_import_item(item);
+            if let Some(global_path) = from {
+                v.visit_global_path(global_path);
+            }
+        }
+        Import::Multi { items, from, .. } => {
+            for import_item in items {
+                v.visit_import_item(import_item);
+            }
+            v.visit_global_path(from);
+        }
+        Import::Glob { from, .. } => v.visit_global_path(from),
+    }
+}
+
+/// Visits the children of an item of a contract or `apply` statement.
+pub fn visit_application<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Application<'a>) {
+    match node {
+        Application::Val(val) => v.visit_val(val),
+        Application::TypeDecl(type_decl) => v.visit_type_decl(type_decl),
+    }
+}
+
+/// Visits the children of an `apply` statement.
+pub fn visit_apply<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Apply<'a>) {
+    for marker in &node.markers {
+        v.visit_marker(marker);
+    }
+    v.visit_global_path(&node.subject);
+    if let Some(global_path) = &node.to {
+        v.visit_global_path(global_path);
+    }
+    for application in &node.items {
+        v.visit_application(application);
+    }
+}
+
+/// Visits the children of an inline module.
+pub fn visit_module<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &Module<'a>) {
+    v.visit_id(&node.name);
+    for root_stmnt in &node.items {
+        v.visit_root_stmnt(root_stmnt);
+    }
+}
+
+/// Visits the children of a statement in the root of a unit.
+pub fn visit_root_stmnt<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &RootStmnt<'a>) {
+    match node {
+        RootStmnt::Assign(assign) => v.visit_assign(assign),
+        RootStmnt::Val(flags, val) => {
+            for flag in flags {
+                v.visit_flag(flag);
+            }
+            v.visit_val(val);
+        }
+        RootStmnt::Const(flags, const_) => {
+            for flag in flags {
+                v.visit_flag(flag);
+            }
+            v.visit_const(const_);
+        }
+        RootStmnt::Call(call) => v.visit_call(call),
+        RootStmnt::Import(flags, import) => {
+            for flag in flags {
+                v.visit_flag(flag);
+            }
+            v.visit_import(import);
+        }
+        RootStmnt::If(if_) => v.visit_if(if_),
+        RootStmnt::While(while_) => v.visit_while(while_),
+        RootStmnt::Match(match_) => v.visit_match(match_),
+        RootStmnt::TypeDecl(flags, type_decl) => {
+            for flag in flags {
+                v.visit_flag(flag);
+            }
+            v.visit_type_decl(type_decl);
+        }
+        RootStmnt::Apply(flags, apply) => {
+            for flag in flags {
+                v.visit_flag(flag);
+            }
+            v.visit_apply(apply);
+        }
+        RootStmnt::Block(flags, block) => {
+            for flag in flags {
+                v.visit_flag(flag);
+            }
+            v.visit_block(block);
+        }
+        RootStmnt::Module(flags, module) => {
+            for flag in flags {
+                v.visit_flag(flag);
+            }
+            v.visit_module(module);
+        }
+    }
+}
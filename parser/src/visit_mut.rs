@@ -0,0 +1,913 @@
+//! A mutable walk over the AST, for passes that rewrite nodes in place.
+//!
+//! This mirrors [`Visit`](crate::visit::Visit), but every method takes a mutable reference to its node.
+
+use crate::ast::*;
+
+/// A mutable visitor over the AST.
+pub trait VisitMut<'a> {
+    /// Visits a boolean literal.
+    fn visit_bool_mut(&mut self, node: &mut Bool) {
+        visit_bool_mut(self, node);
+    }
+
+    /// Visits an identifier.
+    fn visit_id_mut(&mut self, node: &mut Id<'a>) {
+        visit_id_mut(self, node);
+    }
+
+    /// Visits a number literal.
+    fn visit_num_mut(&mut self, node: &mut Num<'a>) {
+        visit_num_mut(self, node);
+    }
+
+    /// Visits a string literal.
+    fn visit_str_mut(&mut self, node: &mut Str<'a>) {
+        visit_str_mut(self, node);
+    }
+
+    /// Visits a path in the root of a unit.
+    fn visit_global_path_mut(&mut self, node: &mut GlobalPath<'a>) {
+        visit_global_path_mut(self, node);
+    }
+
+    /// Visits a marker.
+    fn visit_marker_mut(&mut self, node: &mut Marker<'a>) {
+        visit_marker_mut(self, node);
+    }
+
+    /// Visits an argument of a routine literal.
+    fn visit_routine_arg_mut(&mut self, node: &mut RoutineArg<'a>) {
+        visit_routine_arg_mut(self, node);
+    }
+
+    /// Visits a routine literal.
+    fn visit_routine_mut(&mut self, node: &mut Routine<'a>) {
+        visit_routine_mut(self, node);
+    }
+
+    /// Visits a path in a type.
+    fn visit_path_type_mut(&mut self, node: &mut PathType<'a>) {
+        visit_path_type_mut(self, node);
+    }
+
+    /// Visits a routine or closure type.
+    fn visit_routine_type_mut(&mut self, node: &mut RoutineType<'a>) {
+        visit_routine_type_mut(self, node);
+    }
+
+    /// Visits a property of a struct or union type.
+    fn visit_struct_prop_mut(&mut self, node: &mut StructProp<'a>) {
+        visit_struct_prop_mut(self, node);
+    }
+
+    /// Visits a struct type.
+    fn visit_struct_type_mut(&mut self, node: &mut StructType<'a>) {
+        visit_struct_type_mut(self, node);
+    }
+
+    /// Visits a union type.
+    fn visit_union_type_mut(&mut self, node: &mut UnionType<'a>) {
+        visit_union_type_mut(self, node);
+    }
+
+    /// Visits a property of an enum type.
+    fn visit_enum_prop_mut(&mut self, node: &mut EnumProp<'a>) {
+        visit_enum_prop_mut(self, node);
+    }
+
+    /// Visits an enum type.
+    fn visit_enum_type_mut(&mut self, node: &mut EnumType<'a>) {
+        visit_enum_type_mut(self, node);
+    }
+
+    /// Visits a contract type.
+    fn visit_contract_type_mut(&mut self, node: &mut ContractType<'a>) {
+        visit_contract_type_mut(self, node);
+    }
+
+    /// Visits a `shared` type.
+    fn visit_shared_type_mut(&mut self, node: &mut SharedType<'a>) {
+        visit_shared_type_mut(self, node);
+    }
+
+    /// Visits a `fluid` type.
+    fn visit_fluid_type_mut(&mut self, node: &mut FluidType<'a>) {
+        visit_fluid_type_mut(self, node);
+    }
+
+    /// Visits a reference type.
+    fn visit_ref_type_mut(&mut self, node: &mut RefType<'a>) {
+        visit_ref_type_mut(self, node);
+    }
+
+    /// Visits a contract object type.
+    fn visit_dyn_type_mut(&mut self, node: &mut DynType<'a>) {
+        visit_dyn_type_mut(self, node);
+    }
+
+    /// Visits the variadic arguments of a routine type.
+    fn visit_variadic_type_mut(&mut self, node: &mut VariadicType<'a>) {
+        visit_variadic_type_mut(self, node);
+    }
+
+    /// Visits an option type.
+    fn visit_opt_type_mut(&mut self, node: &mut OptType<'a>) {
+        visit_opt_type_mut(self, node);
+    }
+
+    /// Visits a result type.
+    fn visit_res_type_mut(&mut self, node: &mut ResType<'a>) {
+        visit_res_type_mut(self, node);
+    }
+
+    /// Visits a type.
+    fn visit_type_mut(&mut self, node: &mut Type<'a>) {
+        visit_type_mut(self, node);
+    }
+
+    /// Visits a path expression.
+    fn visit_path_mut(&mut self, node: &mut Path<'a>) {
+        visit_path_mut(self, node);
+    }
+
+    /// Visits an access expression.
+    fn visit_access_mut(&mut self, node: &mut Access<'a>) {
+        visit_access_mut(self, node);
+    }
+
+    /// Visits a call.
+    fn visit_call_mut(&mut self, node: &mut Call<'a>) {
+        visit_call_mut(self, node);
+    }
+
+    /// Visits a unary expression.
+    fn visit_unary_mut(&mut self, node: &mut Unary<'a>) {
+        visit_unary_mut(self, node);
+    }
+
+    /// Visits a cast.
+    fn visit_as_mut(&mut self, node: &mut As<'a>) {
+        visit_as_mut(self, node);
+    }
+
+    /// Visits a binary expression.
+    fn visit_binary_mut(&mut self, node: &mut Binary<'a>) {
+        visit_binary_mut(self, node);
+    }
+
+    /// Visits a property of a struct construction.
+    fn visit_construct_prop_mut(&mut self, node: &mut ConstructProp<'a>) {
+        visit_construct_prop_mut(self, node);
+    }
+
+    /// Visits a struct construction.
+    fn visit_construct_mut(&mut self, node: &mut Construct<'a>) {
+        visit_construct_mut(self, node);
+    }
+
+    /// Visits an enum construction.
+    fn visit_construct_enum_mut(&mut self, node: &mut ConstructEnum<'a>) {
+        visit_construct_enum_mut(self, node);
+    }
+
+    /// Visits an `ok` or `err` constructor.
+    fn visit_wrap_mut(&mut self, node: &mut Wrap<'a>) {
+        visit_wrap_mut(self, node);
+    }
+
+    /// Visits a `none` literal.
+    fn visit_none_lit_mut(&mut self, node: &mut NoneLit) {
+        visit_none_lit_mut(self, node);
+    }
+
+    /// Visits a `?` expression.
+    fn visit_try_mut(&mut self, node: &mut Try<'a>) {
+        visit_try_mut(self, node);
+    }
+
+    /// Visits an index expression.
+    fn visit_index_mut(&mut self, node: &mut Index<'a>) {
+        visit_index_mut(self, node);
+    }
+
+    /// Visits an operand of an inline assembly block.
+    fn visit_asm_operand_mut(&mut self, node: &mut AsmOperand<'a>) {
+        visit_asm_operand_mut(self, node);
+    }
+
+    /// Visits an inline assembly block.
+    fn visit_asm_mut(&mut self, node: &mut Asm<'a>) {
+        visit_asm_mut(self, node);
+    }
+
+    /// Visits an expression.
+    fn visit_expr_mut(&mut self, node: &mut Expr<'a>) {
+        visit_expr_mut(self, node);
+    }
+
+    /// Visits an assignment.
+    fn visit_assign_mut(&mut self, node: &mut Assign<'a>) {
+        visit_assign_mut(self, node);
+    }
+
+    /// Visits a variable declaration.
+    fn visit_val_mut(&mut self, node: &mut Val<'a>) {
+        visit_val_mut(self, node);
+    }
+
+    /// Visits a constant declaration.
+    fn visit_const_mut(&mut self, node: &mut Const<'a>) {
+        visit_const_mut(self, node);
+    }
+
+    /// Visits a type declaration.
+    fn visit_type_decl_mut(&mut self, node: &mut TypeDecl<'a>) {
+        visit_type_decl_mut(self, node);
+    }
+
+    /// Visits an `else if` or `else` branch.
+    fn visit_if_branch_mut(&mut self, node: &mut IfBranch<'a>) {
+        visit_if_branch_mut(self, node);
+    }
+
+    /// Visits an `if` statement.
+    fn visit_if_mut(&mut self, node: &mut If<'a>) {
+        visit_if_mut(self, node);
+    }
+
+    /// Visits a `while` loop.
+    fn visit_while_mut(&mut self, node: &mut While<'a>) {
+        visit_while_mut(self, node);
+    }
+
+    /// Visits a case of a `match` statement.
+    fn visit_match_case_mut(&mut self, node: &mut MatchCase<'a>) {
+        visit_match_case_mut(self, node);
+    }
+
+    /// Visits a `match` statement.
+    fn visit_match_mut(&mut self, node: &mut Match<'a>) {
+        visit_match_mut(self, node);
+    }
+
+    /// Visits a `break` statement.
+    fn visit_break_mut(&mut self, node: &mut Break<'a>) {
+        visit_break_mut(self, node);
+    }
+
+    /// Visits a `continue` statement.
+    fn visit_continue_mut(&mut self, node: &mut Continue<'a>) {
+        visit_continue_mut(self, node);
+    }
+
+    /// Visits a `return` statement.
+    fn visit_return_mut(&mut self, node: &mut Return<'a>) {
+        visit_return_mut(self, node);
+    }
+
+    /// Visits a `defer` statement.
+    fn visit_defer_mut(&mut self, node: &mut Defer<'a>) {
+        visit_defer_mut(self, node);
+    }
+
+    /// Visits a flag condition.
+    fn visit_flag_expr_mut(&mut self, node: &mut FlagExpr<'a>) {
+        visit_flag_expr_mut(self, node);
+    }
+
+    /// Visits a flag on a statement.
+    fn visit_flag_mut(&mut self, node: &mut Flag<'a>) {
+        visit_flag_mut(self, node);
+    }
+
+    /// Visits a statement in a block.
+    fn visit_block_expr_mut(&mut self, node: &mut BlockExpr<'a>) {
+        visit_block_expr_mut(self, node);
+    }
+
+    /// Visits a block.
+    fn visit_block_mut(&mut self, node: &mut Block<'a>) {
+        visit_block_mut(self, node);
+    }
+
+    /// Visits an item of an import.
+    fn visit_import_item_mut(&mut self, node: &mut ImportItem<'a>) {
+        visit_import_item_mut(self, node);
+    }
+
+    /// Visits an import.
+    fn visit_import_mut(&mut self, node: &mut Import<'a>) {
+        visit_import_mut(self, node);
+    }
+
+    /// Visits an item of a contract or `apply` statement.
+    fn visit_application_mut(&mut self, node: &mut Application<'a>) {
+        visit_application_mut(self, node);
+    }
+
+    /// Visits an `apply` statement.
+    fn visit_apply_mut(&mut self, node: &mut Apply<'a>) {
+        visit_apply_mut(self, node);
+    }
+
+    /// Visits an inline module.
+    fn visit_module_mut(&mut self, node: &mut Module<'a>) {
+        visit_module_mut(self, node);
+    }
+
+    /// Visits a statement in the root of a unit.
+    fn visit_root_stmnt_mut(&mut self, node: &mut RootStmnt<'a>) {
+        visit_root_stmnt_mut(self, node);
+    }
+}
+
+/// Visits the children of a boolean literal.
+pub fn visit_bool_mut<'a, V: VisitMut<'a> + ?Sized>(_v: &mut V, _node: &mut Bool) {
+}
+
+/// Visits the children of an identifier.
+pub fn visit_id_mut<'a, V: VisitMut<'a> + ?Sized>(_v: &mut V, _node: &mut Id<'a>) {
+}
+
+/// Visits the children of a number literal.
+pub fn visit_num_mut<'a, V: VisitMut<'a> + ?Sized>(_v: &mut V, _node: &mut Num<'a>) {
+}
+
+/// Visits the children of a string literal.
+pub fn visit_str_mut<'a, V: VisitMut<'a> + ?Sized>(_v: &mut V, _node: &mut Str<'a>) {
+}
+
+/// Visits the children of a path in the root of a unit.
+pub fn visit_global_path_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut GlobalPath<'a>) {
+    match node {
+        GlobalPath::Id(id) => v.visit_id_mut(id),
+        GlobalPath::Branch { path, id, .. } => {
+            v.visit_global_path_mut(path);
+            v.visit_id_mut(id);
+        }
+    }
+}
+
+/// Visits the children of a marker.
+pub fn visit_marker_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Marker<'a>) {
+    v.visit_id_mut(&mut node.name);
+    for expr in &mut node.args {
+        v.visit_expr_mut(expr);
+    }
+}
+
+/// Visits the children of an argument of a routine literal.
+pub fn visit_routine_arg_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut RoutineArg<'a>) {
+    v.visit_id_mut(&mut node.name);
+    v.visit_type_mut(&mut node.ty);
+}
+
+/// Visits the children of a routine literal.
+pub fn visit_routine_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Routine<'a>) {
+    for routine_arg in &mut node.args {
+        v.visit_routine_arg_mut(routine_arg);
+    }
+    if let Some(ty) = &mut node.returns {
+        v.visit_type_mut(ty);
+    }
+    for marker in &mut node.markers {
+        v.visit_marker_mut(marker);
+    }
+    v.visit_block_mut(&mut node.block);
+}
+
+/// Visits the children of a path in a type.
+pub fn visit_path_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut PathType<'a>) {
+    v.visit_type_mut(&mut node.left);
+    v.visit_id_mut(&mut node.right);
+}
+
+/// Visits the children of a routine or closure type.
+pub fn visit_routine_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut RoutineType<'a>) {
+    for ty in &mut node.args {
+        v.visit_type_mut(ty);
+    }
+    if let Some(ty) = &mut node.returns {
+        v.visit_type_mut(ty);
+    }
+}
+
+/// Visits the children of a property of a struct or union type.
+pub fn visit_struct_prop_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut StructProp<'a>) {
+    for marker in &mut node.markers {
+        v.visit_marker_mut(marker);
+    }
+    v.visit_id_mut(&mut node.name);
+    v.visit_type_mut(&mut node.ty);
+    if let Some(num) = &mut node.bits {
+        v.visit_num_mut(num);
+    }
+}
+
+/// Visits the children of a struct type.
+pub fn visit_struct_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut StructType<'a>) {
+    for struct_prop in &mut node.props {
+        v.visit_struct_prop_mut(struct_prop);
+    }
+}
+
+/// Visits the children of a union type.
+pub fn visit_union_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut UnionType<'a>) {
+    for struct_prop in &mut node.props {
+        v.visit_struct_prop_mut(struct_prop);
+    }
+}
+
+/// Visits the children of a property of an enum type.
+pub fn visit_enum_prop_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut EnumProp<'a>) {
+    for marker in &mut node.markers {
+        v.visit_marker_mut(marker);
+    }
+    v.visit_id_mut(&mut node.name);
+    if let Some(ty) = &mut node.ty {
+        v.visit_type_mut(ty);
+    }
+    if let Some(expr) = &mut node.value {
+        v.visit_expr_mut(expr);
+    }
+}
+
+/// Visits the children of an enum type.
+pub fn visit_enum_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut EnumType<'a>) {
+    for enum_prop in &mut node.props {
+        v.visit_enum_prop_mut(enum_prop);
+    }
+}
+
+/// Visits the children of a contract type.
+pub fn visit_contract_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut ContractType<'a>) {
+    for application in &mut node.items {
+        v.visit_application_mut(application);
+    }
+}
+
+/// Visits the children of a `shared` type.
+pub fn visit_shared_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut SharedType<'a>) {
+    v.visit_type_mut(&mut node.subject);
+}
+
+/// Visits the children of a `fluid` type.
+pub fn visit_fluid_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut FluidType<'a>) {
+    v.visit_type_mut(&mut node.subject);
+}
+
+/// Visits the children of a reference type.
+pub fn visit_ref_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut RefType<'a>) {
+    v.visit_type_mut(&mut node.subject);
+}
+
+/// Visits the children of a contract object type.
+pub fn visit_dyn_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut DynType<'a>) {
+    v.visit_type_mut(&mut node.subject);
+}
+
+/// Visits the children of the variadic arguments of a routine type.
+pub fn visit_variadic_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut VariadicType<'a>) {
+    if let Some(ty) = &mut node.ty {
+        v.visit_type_mut(ty);
+    }
+}
+
+/// Visits the children of an option type.
+pub fn visit_opt_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut OptType<'a>) {
+    v.visit_type_mut(&mut node.subject);
+}
+
+/// Visits the children of a result type.
+pub fn visit_res_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut ResType<'a>) {
+    v.visit_type_mut(&mut node.ok);
+    v.visit_type_mut(&mut node.err);
+}
+
+/// Visits the children of a type.
+pub fn visit_type_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Type<'a>) {
+    match node {
+        Type::Id(id) => v.visit_id_mut(id),
+        Type::Path(path_type) => v.visit_path_type_mut(path_type),
+        Type::Routine(routine_type) => v.visit_routine_type_mut(routine_type),
+        Type::Closure(routine_type) => v.visit_routine_type_mut(routine_type),
+        Type::Struct(struct_type) => v.visit_struct_type_mut(struct_type),
+        Type::Union(union_type) => v.visit_union_type_mut(union_type),
+        Type::Enum(enum_type) => v.visit_enum_type_mut(enum_type),
+        Type::Contract(contract_type) => v.visit_contract_type_mut(contract_type),
+        Type::Shared(shared_type) => v.visit_shared_type_mut(shared_type),
+        Type::Fluid(fluid_type) => v.visit_fluid_type_mut(fluid_type),
+        Type::Ref(ref_type) => v.visit_ref_type_mut(ref_type),
+        Type::Dyn(dyn_type) => v.visit_dyn_type_mut(dyn_type),
+        Type::Variadic(variadic_type) => v.visit_variadic_type_mut(variadic_type),
+        Type::Opt(opt_type) => v.visit_opt_type_mut(opt_type),
+        Type::Res(res_type) => v.visit_res_type_mut(res_type),
+    }
+}
+
+/// Visits the children of a path expression.
+pub fn visit_path_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Path<'a>) {
+    v.visit_expr_mut(&mut node.left);
+    v.visit_id_mut(&mut node.right);
+}
+
+/// Visits the children of an access expression.
+pub fn visit_access_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Access<'a>) {
+    v.visit_expr_mut(&mut node.left);
+    v.visit_id_mut(&mut node.right);
+}
+
+/// Visits the children of a call.
+pub fn visit_call_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Call<'a>) {
+    v.visit_expr_mut(&mut node.left);
+    for expr in &mut node.args {
+        v.visit_expr_mut(expr);
+    }
+}
+
+/// Visits the children of a unary expression.
+pub fn visit_unary_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Unary<'a>) {
+    v.visit_expr_mut(&mut node.subject);
+}
+
+/// Visits the children of a cast.
+pub fn visit_as_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut As<'a>) {
+    v.visit_expr_mut(&mut node.subject);
+    v.visit_type_mut(&mut node.ty);
+}
+
+/// Visits the children of a binary expression.
+pub fn visit_binary_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Binary<'a>) {
+    v.visit_expr_mut(&mut node.left);
+    v.visit_expr_mut(&mut node.right);
+}
+
+/// Visits the children of a property of a struct construction.
+pub fn visit_construct_prop_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut ConstructProp<'a>) {
+    v.visit_id_mut(&mut node.name);
+    v.visit_expr_mut(&mut node.value);
+}
+
+/// Visits the children of a struct construction.
+pub fn visit_construct_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Construct<'a>) {
+    v.visit_expr_mut(&mut node.subject);
+    for construct_prop in &mut node.items {
+        v.visit_construct_prop_mut(construct_prop);
+    }
+}
+
+/// Visits the children of an enum construction.
+pub fn visit_construct_enum_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut ConstructEnum<'a>) {
+    v.visit_expr_mut(&mut node.subject);
+    v.visit_expr_mut(&mut node.item);
+}
+
+/// Visits the children of an `ok` or `err` constructor.
+pub fn visit_wrap_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Wrap<'a>) {
+    v.visit_expr_mut(&mut node.item);
+}
+
+/// Visits the children of a `none` literal.
+pub fn visit_none_lit_mut<'a, V: VisitMut<'a> + ?Sized>(_v: &mut V, _node: &mut NoneLit) {
+}
+
+/// Visits the children of a `?` expression.
+pub fn visit_try_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Try<'a>) {
+    v.visit_expr_mut(&mut node.subject);
+}
+
+/// Visits the children of an index expression.
+pub fn visit_index_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Index<'a>) {
+    v.visit_expr_mut(&mut node.subject);
+    v.visit_expr_mut(&mut node.index);
+}
+
+/// Visits the children of an operand of an inline assembly block.
+pub fn visit_asm_operand_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut AsmOperand<'a>) {
+    v.visit_str_mut(&mut node.constraint);
+    v.visit_expr_mut(&mut node.expr);
+}
+
+/// Visits the children of an inline assembly block.
+pub fn visit_asm_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Asm<'a>) {
+    v.visit_str_mut(&mut node.template);
+    for asm_operand in &mut node.outputs {
+        v.visit_asm_operand_mut(asm_operand);
+    }
+    for asm_operand in &mut node.inputs {
+        v.visit_asm_operand_mut(asm_operand);
+    }
+    for str in &mut node.clobbers {
+        v.visit_str_mut(str);
+    }
+    for id in &mut node.options {
+        v.visit_id_mut(id);
+    }
+}
+
+/// Visits the children of an expression.
+pub fn visit_expr_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Expr<'a>) {
+    match node {
+        Expr::Bool(bool) => v.visit_bool_mut(bool),
+        Expr::Id(id) => v.visit_id_mut(id),
+        Expr::Num(num) => v.visit_num_mut(num),
+        Expr::Str(str) => v.visit_str_mut(str),
+        Expr::Path(path) => v.visit_path_mut(path),
+        Expr::Access(access) => v.visit_access_mut(access),
+        Expr::Call(call) => v.visit_call_mut(call),
+        Expr::Unary(unary) => v.visit_unary_mut(unary),
+        Expr::As(as_) => v.visit_as_mut(as_),
+        Expr::Binary(binary) => v.visit_binary_mut(binary),
+        Expr::Routine(routine) => v.visit_routine_mut(routine),
+        Expr::Construct(construct) => v.visit_construct_mut(construct),
+        Expr::ConstructEnum(construct_enum) => v.visit_construct_enum_mut(construct_enum),
+        Expr::Asm(asm) => v.visit_asm_mut(asm),
+        Expr::Ok(wrap) => v.visit_wrap_mut(wrap),
+        Expr::Err(wrap) => v.visit_wrap_mut(wrap),
+        Expr::None(none_lit) => v.visit_none_lit_mut(none_lit),
+        Expr::Try(try_) => v.visit_try_mut(try_),
+        Expr::Index(index) => v.visit_index_mut(index),
+    }
+}
+
+/// Visits the children of an assignment.
+pub fn visit_assign_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Assign<'a>) {
+    v.visit_expr_mut(&mut node.left);
+    v.visit_expr_mut(&mut node.right);
+}
+
+/// Visits the children of a variable declaration.
+pub fn visit_val_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Val<'a>) {
+    for marker in &mut node.markers {
+        v.visit_marker_mut(marker);
+    }
+    v.visit_id_mut(&mut node.name);
+    if let Some(ty) = &mut node.ty {
+        v.visit_type_mut(ty);
+    }
+    if let Some(expr) = &mut node.value {
+        v.visit_expr_mut(expr);
+    }
+}
+
+/// Visits the children of a constant declaration.
+pub fn visit_const_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Const<'a>) {
+    for marker in &mut node.markers {
+        v.visit_marker_mut(marker);
+    }
+    v.visit_id_mut(&mut node.name);
+    if let Some(ty) = &mut node.ty {
+        v.visit_type_mut(ty);
+    }
+    v.visit_expr_mut(&mut node.value);
+}
+
+/// Visits the children of a type declaration.
+pub fn visit_type_decl_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut TypeDecl<'a>) {
+    for marker in &mut node.markers {
+        v.visit_marker_mut(marker);
+    }
+    v.visit_id_mut(&mut node.name);
+    if let Some(ty) = &mut node.value {
+        v.visit_type_mut(ty);
+    }
+}
+
+/// Visits the children of an `else if` or `else` branch.
+pub fn visit_if_branch_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut IfBranch<'a>) {
+    match node {
+        IfBranch::ElseIf { cond, block, .. } => {
+            v.visit_expr_mut(cond);
+            v.visit_block_mut(block);
+        }
+        IfBranch::Else { block, .. } => v.visit_block_mut(block),
+    }
+}
+
+/// Visits the children of an `if` statement.
+pub fn visit_if_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut If<'a>) {
+    v.visit_expr_mut(&mut node.cond);
+    v.visit_block_mut(&mut node.block);
+    for if_branch in &mut node.branches {
+        v.visit_if_branch_mut(if_branch);
+    }
+}
+
+/// Visits the children of a `while` loop.
+pub fn visit_while_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut While<'a>) {
+    if let Some(id) = &mut node.label {
+        v.visit_id_mut(id);
+    }
+    v.visit_expr_mut(&mut node.cond);
+    v.visit_block_mut(&mut node.block);
+}
+
+/// Visits the children of a case of a `match` statement.
+pub fn visit_match_case_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut MatchCase<'a>) {
+    v.visit_id_mut(&mut node.name);
+    v.visit_type_mut(&mut node.ty);
+    v.visit_block_mut(&mut node.block);
+}
+
+/// Visits the children of a `match` statement.
+pub fn visit_match_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Match<'a>) {
+    v.visit_expr_mut(&mut node.subject);
+    for match_case in &mut node.cases {
+        v.visit_match_case_mut(match_case);
+    }
+}
+
+/// Visits the children of a `break` statement.
+pub fn visit_break_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Break<'a>) {
+    if let Some(id) = &mut node.label {
+        v.visit_id_mut(id);
+    }
+}
+
+/// Visits the children of a `continue` statement.
+pub fn visit_continue_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Continue<'a>) {
+    if let Some(id) = &mut node.label {
+        v.visit_id_mut(id);
+    }
+}
+
+/// Visits the children of a `return` statement.
+pub fn visit_return_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Return<'a>) {
+    if let Some(expr) = &mut node.value {
+        v.visit_expr_mut(expr);
+    }
+}
+
+/// Visits the children of a `defer` statement.
+pub fn visit_defer_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Defer<'a>) {
+    v.visit_block_expr_mut(&mut node.item);
+}
+
+/// Visits the children of a flag condition.
+pub fn visit_flag_expr_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut FlagExpr<'a>) {
+    match node {
+        FlagExpr::Name(id) => v.visit_id_mut(id),
+        FlagExpr::Value { name, value, .. } => {
+            v.visit_id_mut(name);
+            v.visit_str_mut(value);
+        }
+        FlagExpr::Any { items, .. } => {
+            for flag_expr in items {
+                v.visit_flag_expr_mut(flag_expr);
+            }
+        }
+        FlagExpr::All { items, .. } => {
+            for flag_expr in items {
+                v.visit_flag_expr_mut(flag_expr);
+            }
+        }
+        FlagExpr::Not { item, .. } => v.visit_flag_expr_mut(item),
+    }
+}
+
+/// Visits the children of a flag on a statement.
+pub fn visit_flag_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Flag<'a>) {
+    v.visit_flag_expr_mut(&mut node.expr);
+}
+
+/// Visits the children of a statement in a block.
+pub fn visit_block_expr_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut BlockExpr<'a>) {
+    match node {
+        BlockExpr::Assign(assign) => v.visit_assign_mut(assign),
+        BlockExpr::Val(val) => v.visit_val_mut(val),
+        BlockExpr::Call(call) => v.visit_call_mut(call),
+        BlockExpr::If(if_) => v.visit_if_mut(if_),
+        BlockExpr::While(while_) => v.visit_while_mut(while_),
+        BlockExpr::Match(match_) => v.visit_match_mut(match_),
+        BlockExpr::TypeDecl(type_decl) => v.visit_type_decl_mut(type_decl),
+        BlockExpr::Break(break_) => v.visit_break_mut(break_),
+        BlockExpr::Continue(continue_) => v.visit_continue_mut(continue_),
+        BlockExpr::Return(return_) => v.visit_return_mut(return_),
+        BlockExpr::Defer(defer) => v.visit_defer_mut(defer),
+        BlockExpr::Asm(asm) => v.visit_asm_mut(asm),
+        BlockExpr::Block(flags, block) => {
+            for flag in flags {
+                v.visit_flag_mut(flag);
+            }
+            v.visit_block_mut(block);
+        }
+    }
+}
+
+/// Visits the children of a block.
+pub fn visit_block_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Block<'a>) {
+    for block_expr in &mut node.items {
+        v.visit_block_expr_mut(block_expr);
+    }
+}
+
+/// Visits the children of an item of an import.
+pub fn visit_import_item_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut ImportItem<'a>) {
+    v.visit_id_mut(&mut node.name);
+    if let Some(id) = &mut node.as_ {
+        v.visit_id_mut(id);
+    }
+}
+
+/// Visits the children of an import.
+pub fn visit_import_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Import<'a>) {
+    match node {
+        Import::Single { item, from, .. } => {
+            v.visit_import_item_mut(item);
+            if let Some(global_path) = from {
+                v.visit_global_path_mut(global_path);
+            }
+        }
+        Import::Multi { items, from, .. } => {
+            for import_item in items {
+                v.visit_import_item_mut(import_item);
+            }
+            v.visit_global_path_mut(from);
+        }
+        Import::Glob { from, .. } => v.visit_global_path_mut(from),
+    }
+}
+
+/// Visits the children of an item of a contract or `apply` statement.
+pub fn visit_application_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Application<'a>) {
+    match node {
+        Application::Val(val) => v.visit_val_mut(val),
+        Application::TypeDecl(type_decl) => v.visit_type_decl_mut(type_decl),
+    }
+}
+
+/// Visits the children of an `apply` statement.
+pub fn visit_apply_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Apply<'a>) {
+    for marker in &mut node.markers {
+        v.visit_marker_mut(marker);
+    }
+    v.visit_global_path_mut(&mut node.subject);
+    if let Some(global_path) = &mut node.to {
+        v.visit_global_path_mut(global_path);
+    }
+    for application in &mut node.items {
+        v.visit_application_mut(application);
+    }
+}
+
+/// Visits the children of an inline module.
+pub fn visit_module_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Module<'a>) {
+    v.visit_id_mut(&mut node.name);
+    for root_stmnt in &mut node.items {
+        v.visit_root_stmnt_mut(root_stmnt);
+    }
+}
+
+/// Visits the children of a statement in the root of a unit.
+pub fn visit_root_stmnt_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut RootStmnt<'a>) {
+    match node {
+        RootStmnt::Assign(assign) => v.visit_assign_mut(assign),
+        RootStmnt::Val(flags, val) => {
+            for flag in flags {
+                v.visit_flag_mut(flag);
+            }
+            v.visit_val_mut(val);
+        }
+        RootStmnt::Const(flags, const_) => {
+            for flag in flags {
+                v.visit_flag_mut(flag);
+            }
+            v.visit_const_mut(const_);
+        }
+        RootStmnt::Call(call) => v.visit_call_mut(call),
+        RootStmnt::Import(flags, import) => {
+            for flag in flags {
+                v.visit_flag_mut(flag);
+            }
+            v.visit_import_mut(import);
+        }
+        RootStmnt::If(if_) => v.visit_if_mut(if_),
+        RootStmnt::While(while_) => v.visit_while_mut(while_),
+        RootStmnt::Match(match_) => v.visit_match_mut(match_),
+        RootStmnt::TypeDecl(flags, type_decl) => {
+            for flag in flags {
+                v.visit_flag_mut(flag);
+            }
+            v.visit_type_decl_mut(type_decl);
+        }
+        RootStmnt::Apply(flags, apply) => {
+            for flag in flags {
+                v.visit_flag_mut(flag);
+            }
+            v.visit_apply_mut(apply);
+        }
+        RootStmnt::Block(flags, block) => {
+            for flag in flags {
+                v.visit_flag_mut(flag);
+            }
+            v.visit_block_mut(block);
+        }
+        RootStmnt::Module(flags, module) => {
+            for flag in flags {
+                v.visit_flag_mut(flag);
+            }
+            v.visit_module_mut(module);
+        }
+    }
+}
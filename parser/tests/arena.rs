@@ -0,0 +1,76 @@
+//! Checks lowering into the arena AST.
+
+use hail_parser::arena::{self, Expr, Node, RootStmnt, Unit};
+use hail_parser::intern::{kw, Interner};
+use hail_parser::{grammar, scanner};
+
+/// Parses and lowers a unit from a source which is dropped before the unit is returned.
+fn lower(source: &str, interner: &mut Interner) -> Unit {
+    let source = source.to_string();
+    let ast = grammar::RootStmntsParser::new().parse(&source, scanner::Asi::lex(&source)).unwrap();
+
+    arena::lower(&ast, interner)
+}
+
+fn val(stmnt: &RootStmnt) -> &arena::Val {
+    match stmnt {
+        RootStmnt::Val(_, val) => val,
+        stmnt => panic!("expected a val, found {:?}", stmnt),
+    }
+}
+
+#[test]
+fn interns_identifiers_and_keywords() {
+    let mut interner = Interner::new();
+    let first = lower("val total = count", &mut interner);
+    let second = lower("val count = total", &mut interner);
+
+    assert_eq!(interner.get("val"), Some(kw::VAL));
+    assert_eq!(interner.resolve(kw::ROUTINE), "routine");
+
+    assert_eq!(val(&first.items[0]).name.value, interner.get("total").unwrap());
+    assert_eq!(val(&second.items[0]).name.value, interner.get("count").unwrap());
+
+    match (&val(&first.items[0]).value, &val(&second.items[0]).value) {
+        (Some(Expr::Id(left)), Some(Expr::Id(right))) => {
+            assert_eq!(left.value, val(&second.items[0]).name.value);
+            assert_eq!(right.value, val(&first.items[0]).name.value);
+        },
+        values => panic!("expected identifiers, found {:?}", values),
+    }
+}
+
+#[test]
+fn numbers_nodes_parents_first() {
+    let mut interner = Interner::new();
+    let unit = lower("val x = 1\nval y = x", &mut interner);
+    let first = val(&unit.items[0]);
+    let second = val(&unit.items[1]);
+
+    assert_eq!(first.node_id().index(), 0);
+    assert_eq!(first.name.node_id().index(), 1);
+    assert_eq!(first.value.as_ref().unwrap().node_id().index(), 2);
+    assert_eq!(second.node_id().index(), 3);
+    assert_eq!(unit.nodes, 6);
+}
+
+#[test]
+fn stores_recursive_children_in_the_arena() {
+    let mut interner = Interner::new();
+    let unit = lower("val sum = a + b * c", &mut interner);
+
+    match val(&unit.items[0]).value.as_ref().unwrap() {
+        Expr::Binary(add) => {
+            match &unit.arena[add.left] {
+                Expr::Id(id) => assert_eq!(interner.resolve(id.value), "a"),
+                left => panic!("expected an identifier, found {:?}", left),
+            }
+
+            match &unit.arena[add.right] {
+                Expr::Binary(mul) => assert!(mul.node_id() > add.node_id()),
+                right => panic!("expected a binary expression, found {:?}", right),
+            }
+        },
+        value => panic!("expected a binary expression, found {:?}", value),
+    }
+}
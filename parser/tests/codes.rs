@@ -0,0 +1,55 @@
+//! Checks that the registered diagnostic codes are in order with explanations, and that the syntax examples report them.
+
+use std::path::Path;
+
+use hail_parser::codes::{self, CODES};
+
+/// Finds every string literal in a source which is a code, such as `"E0001"`.
+fn codes_in(source: &str) -> Vec<&str> {
+    source
+        .match_indices("\"E")
+        .filter_map(|(start, _)| source.get(start + 1..start + 7))
+        .filter(|literal| literal.ends_with('"') && literal[1..5].bytes().all(|byte| byte.is_ascii_digit()))
+        .map(|literal| &literal[..5])
+        .collect()
+}
+
+/// The erroneous example of an explanation, which is its first `hail` code block.
+fn example(explanation: &str) -> &str {
+    let start = explanation.find("```hail\n").expect("every explanation has an example") + "```hail\n".len();
+    let end = explanation[start..].find("```").unwrap();
+
+    &explanation[start..start + end]
+}
+
+#[test]
+fn codes_are_in_order_with_examples() {
+    for pair in CODES.windows(2) {
+        assert!(pair[0].code < pair[1].code, "{} is registered after {}", pair[1].code, pair[0].code);
+    }
+
+    for code in CODES {
+        assert!(!code.title.is_empty() && !code.title.ends_with('.'), "{} needs a short title", code.code);
+        assert!(!example(code.explanation).trim().is_empty(), "{} has an empty example", code.code);
+    }
+}
+
+#[test]
+fn syntax_examples_report_their_codes() {
+    let parser = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut syntax = vec![];
+    for file in ["scanner.rs", "diagnostics.rs", "grammar.lalrpop"] {
+        syntax.extend(codes_in(&std::fs::read_to_string(parser.join(file)).unwrap()).into_iter().map(String::from));
+    }
+
+    for code in CODES.iter().filter(|code| syntax.iter().any(|other| other == code.code)) {
+        let error = hail_parser::parse(example(code.explanation)).expect_err(code.code);
+        assert_eq!(error.code, Some(code.code));
+    }
+}
+
+#[test]
+fn looks_up_codes_ignoring_case() {
+    assert_eq!(codes::lookup("e0002").unwrap().code, "E0002");
+    assert!(codes::lookup("E9999").is_none());
+}
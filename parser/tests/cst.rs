@@ -0,0 +1,157 @@
+//! Checks that the lossless syntax tree gives back its source byte for byte, and that its typed view finds the same
+//! parts of the source as the AST.
+
+use hail_parser::ast::{BinaryOp, NumKind};
+use hail_parser::cst::{self, SyntaxKind, SyntaxNode};
+use hail_parser::cst_ast::{self, AstNode, Expr, Root, RootStmnt, Type};
+
+/// A unit with comments, blank lines, tabs and inserted semicolons.
+const SOURCE: &str = "// a comment before anything
+@os_linux import { print as log } from std::io
+
+type Printf = routine(&u8, ...) -> i32 // a trailing comment
+\tshared val total = 1 + 0x10
+
+val main = routine() {
+    asm(\"mov $0, $1\" : \"=r\"(sum) : \"r\"(total) : \"memory\" : volatile);
+    return
+}
+";
+
+fn root(syntax: &SyntaxNode) -> Root {
+    Root::cast(syntax.clone()).unwrap()
+}
+
+fn texts<N: AstNode>(nodes: Vec<N>) -> Vec<String> {
+    nodes.iter().map(|node| node.syntax().text()).collect()
+}
+
+#[test]
+fn round_trips_the_source() {
+    for source in [SOURCE, include_str!("../../test.hl")] {
+        let parse = cst::parse(source);
+
+        assert!(parse.error.is_none());
+        assert_eq!(parse.syntax.to_string(), source);
+    }
+}
+
+#[test]
+fn round_trips_crlf_line_breaks() {
+    let source = SOURCE.replace('\n', "\r\n");
+    let parse = cst::parse(&source);
+
+    assert!(parse.error.is_none());
+    assert_eq!(parse.syntax.to_string(), source);
+}
+
+#[test]
+fn round_trips_a_unit_that_fails_to_parse() {
+    let source = "val = = ]\n  // still here\n";
+    let parse = cst::parse(source);
+
+    assert!(parse.error.is_some());
+    assert_eq!(parse.syntax.to_string(), source);
+}
+
+#[test]
+fn keeps_the_statements_before_a_parse_error() {
+    let source = "val a = 1\nval b = (2 + 3)\nval = = ]\n";
+    let parse = cst::parse(source);
+
+    assert!(parse.error.is_some());
+    assert_eq!(parse.syntax.to_string(), source);
+    assert_eq!(texts(root(&parse.syntax).items()), ["val a = 1", "val b = (2 + 3)"]);
+}
+
+#[test]
+fn marks_inserted_semicolons() {
+    let parse = cst::parse(SOURCE);
+    let semis: Vec<_> =
+        parse.syntax.descendant_tokens().into_iter().filter(|token| token.kind() == SyntaxKind::Semi).collect();
+
+    assert!(semis.iter().any(|semi| semi.is_synthetic()));
+
+    for semi in semis {
+        assert_eq!(semi.text().is_empty(), semi.is_synthetic());
+    }
+}
+
+#[test]
+fn keeps_trivia() {
+    let parse = cst::parse(SOURCE);
+    let comments: Vec<_> = parse
+        .syntax
+        .descendant_tokens()
+        .into_iter()
+        .filter(|token| token.kind() == SyntaxKind::Comment)
+        .map(|token| token.text().to_string())
+        .collect();
+
+    assert_eq!(comments, ["// a comment before anything", "// a trailing comment"]);
+}
+
+/// Every node has the same text as the part of the source that its span covers.
+#[test]
+fn spans_match_text() {
+    let parse = cst::parse(SOURCE);
+
+    for node in parse.syntax.descendants() {
+        assert_eq!(node.text(), &SOURCE[node.span()]);
+    }
+
+    for token in parse.syntax.descendant_tokens() {
+        assert_eq!(token.text(), &SOURCE[token.span()]);
+    }
+}
+
+#[test]
+fn typed_view_finds_children() {
+    let parse = cst::parse(SOURCE);
+    let items = root(&parse.syntax).items();
+
+    assert_eq!(items.len(), 4);
+
+    let import = &items[0];
+    assert!(matches!(import, RootStmnt::Import(_)));
+    assert_eq!(import.flags().len(), 1);
+    assert_eq!(import.flags()[0].syntax().text(), "@os_linux");
+
+    assert!(matches!(&items[1], RootStmnt::TypeDecl(_)));
+    let printf = items[1].syntax().descendants().into_iter().find_map(cst_ast::RoutineType::cast).unwrap();
+    assert_eq!(printf.args().len(), 2);
+    assert_eq!(printf.returns().unwrap().syntax().text(), "i32");
+
+    let RootStmnt::Val(total) = &items[2] else { panic!("expected a variable") };
+    assert!(total.shared());
+    assert_eq!(total.name().unwrap().value().unwrap().text(), "total");
+
+    let Some(Expr::Binary(sum)) = total.value() else { panic!("expected a binary expression") };
+    assert_eq!(sum.op(), Some(BinaryOp::Plus));
+
+    let Some(Expr::Num(right)) = sum.right() else { panic!("expected a number") };
+    assert_eq!(right.kind(), Some(NumKind::XInt));
+    assert_eq!(right.value().unwrap().text(), "0x10");
+}
+
+#[test]
+fn typed_view_splits_asm_sections() {
+    let parse = cst::parse(SOURCE);
+    let asm = parse.syntax.descendants().into_iter().find_map(cst_ast::Asm::cast).unwrap();
+
+    assert_eq!(asm.template().unwrap().syntax().text(), "\"mov $0, $1\"");
+    assert_eq!(texts(asm.outputs()), ["\"=r\"(sum)"]);
+    assert_eq!(texts(asm.inputs()), ["\"r\"(total)"]);
+    assert_eq!(texts(asm.clobbers()), ["\"memory\""]);
+    assert_eq!(texts(asm.options()), ["volatile"]);
+}
+
+#[test]
+fn typed_enums_cast_through_their_variant() {
+    let parse = cst::parse("type Alias = std::u8\n");
+    let ty = parse.syntax.descendants().into_iter().find_map(Type::cast).unwrap();
+
+    assert!(matches!(ty, Type::Path(_)));
+    assert_eq!(ty.syntax().kind(), SyntaxKind::PathType);
+    assert_eq!(ty.syntax().parent().unwrap().kind(), SyntaxKind::TypePath);
+}
@@ -0,0 +1,146 @@
+//! Checks the diagnostics that the lexer and parser report, and how diagnostics render as source snippets.
+
+use hail_parser::diagnostics::{Diagnostic, Files};
+
+/// Parses a unit which should fail, rendering its diagnostic without color.
+fn render_error(source: &str) -> (Option<&'static str>, String) {
+    let mut files = Files::new();
+    let file = files.add("main.hl", source);
+    let diagnostic = hail_parser::parse(source).unwrap_err().with_file(file);
+
+    (diagnostic.code, diagnostic.render(&files, false))
+}
+
+#[test]
+fn reports_invalid_tokens() {
+    let (code, rendered) = render_error("val x = 1 ~ 2\n");
+
+    assert_eq!(code, Some("E0001"));
+    assert_eq!(
+        rendered,
+        "error[E0001]: invalid token `~`
+ --> main.hl:1:11
+  |
+1 | val x = 1 ~ 2
+  |           ^ not a valid token
+",
+    );
+}
+
+#[test]
+fn reports_unterminated_strings_where_they_start() {
+    let (code, rendered) = render_error("val x = \"one\nval y = 2\n");
+
+    assert_eq!(code, Some("E0001"));
+    assert!(rendered.starts_with("error[E0001]: unterminated string\n --> main.hl:1:9\n"));
+    assert!(rendered.ends_with("  = help: close it at the end of the line: `\"`\n"));
+}
+
+#[test]
+fn reports_unexpected_tokens() {
+    let (code, rendered) = render_error("val x = (1,\n    2 ]\n");
+
+    assert_eq!(code, Some("E0002"));
+    assert_eq!(
+        rendered,
+        "error[E0002]: unexpected `,`
+ --> main.hl:1:11
+  |
+1 | val x = (1,
+  |           ^ expected `)`
+",
+    );
+}
+
+#[test]
+fn reports_the_end_of_the_file() {
+    let (code, rendered) = render_error("val x = (1 +");
+
+    assert_eq!(code, Some("E0003"));
+    assert!(rendered.starts_with("error[E0003]: unexpected end of file\n --> main.hl:1:13\n"));
+}
+
+#[test]
+fn reports_unknown_flag_functions() {
+    let (code, rendered) = render_error("@bogus(a) val x = 1\n");
+
+    assert_eq!(code, Some("E0004"));
+    assert!(rendered.contains("  |  ^^^^^ expected `any`, `all` or `not`\n"));
+}
+
+#[test]
+fn renders_secondary_labels_notes_and_help() {
+    let source = "val x = 1\n\n\nval x = 2\n";
+    let mut files = Files::new();
+    let file = files.add("main.hl", source);
+    let diagnostic = Diagnostic::error("`x` is declared twice")
+        .with_file(file)
+        .with_primary(16..17, "declared again here")
+        .with_secondary(4..5, "first declared here")
+        .with_note("values share one namespace")
+        .with_help("rename one of them");
+
+    assert_eq!(
+        diagnostic.render(&files, false),
+        "error: `x` is declared twice
+ --> main.hl:4:5
+  |
+1 | val x = 1
+  |     - first declared here
+...
+4 | val x = 2
+  |     ^ declared again here
+  |
+  = note: values share one namespace
+  = help: rename one of them
+",
+    );
+}
+
+#[test]
+fn suggests_replacements() {
+    let mut files = Files::new();
+    let file = files.add("main.hl", "val x <- i32 = 1\n");
+    let diagnostic = Diagnostic::error("unexpected type")
+        .with_file(file)
+        .with_primary(5..12, "")
+        .with_suggestion("remove it", 5..12, "");
+
+    assert_eq!(diagnostic.notes[0].replacement.as_ref().unwrap().span, 5..12);
+    assert!(diagnostic.render(&files, false).ends_with("  = help: remove it\n"));
+}
+
+#[test]
+fn lines_labels_up_under_tabs() {
+    let source = "\tval x = y\n";
+    let mut files = Files::new();
+    let file = files.add("main.hl", source);
+    let diagnostic = Diagnostic::warning("unused").with_file(file).with_primary(9..10, "");
+
+    assert_eq!(
+        diagnostic.render(&files, false),
+        "warning: unused
+ --> main.hl:1:10
+  |
+1 |     val x = y
+  |             ^
+",
+    );
+}
+
+#[test]
+fn renders_diagnostics_without_a_file() {
+    let diagnostic = Diagnostic::error("unable to read `main.hl`").with_note("it does not exist");
+
+    assert_eq!(diagnostic.render(&Files::new(), false), "error: unable to read `main.hl`\n = note: it does not exist\n");
+}
+
+#[test]
+fn colors_only_when_asked() {
+    let mut files = Files::new();
+    let file = files.add("main.hl", "val x\n");
+    let diagnostic = Diagnostic::error("bad").with_file(file).with_primary(4..5, "here");
+
+    assert!(!diagnostic.render(&files, false).contains('\x1b'));
+    assert!(diagnostic.render(&files, true).contains("\x1b[1m\x1b[31merror\x1b[0m"));
+}
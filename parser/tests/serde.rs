@@ -0,0 +1,50 @@
+//! Checks that the AST survives a trip through JSON, in the shape that `docs/ast-json.md` describes.
+
+#![cfg(feature = "serde")]
+
+use hail_parser::ast::*;
+use hail_parser::{grammar, scanner};
+use serde_json::json;
+
+const SOURCE: &str = r#"
+@os_linux import { print as log } from std::io
+type Point = struct { x <- i32, shared y <- f32 }
+val origin = Point::{ x = 0, y = 0.5 }
+@target_os("linux") val greeting = "say \"hi\"\n"
+val main = routine() -> ?i32 {
+    if origin.x > 0 { return origin.x } else { return none }
+}
+"#;
+
+fn parse(source: &str) -> Vec<RootStmnt<'_>> {
+    grammar::RootStmntsParser::new().parse(source, scanner::Asi::lex(source)).unwrap()
+}
+
+#[test]
+fn round_trips_through_json() {
+    let ast = parse(SOURCE);
+    let text = serde_json::to_string(&ast).unwrap();
+    let back: Vec<RootStmnt> = serde_json::from_str(&text).unwrap();
+
+    assert_eq!(back, ast);
+}
+
+#[test]
+fn matches_the_documented_shape() {
+    let ast = parse("val x = true");
+    let value = serde_json::to_value(&ast).unwrap();
+
+    assert_eq!(
+        value,
+        json!([{
+            "Val": [[], {
+                "span": { "start": 0, "end": 12 },
+                "markers": [],
+                "shared": false,
+                "name": { "span": { "start": 4, "end": 5 }, "value": "x" },
+                "ty": null,
+                "value": { "Bool": { "span": { "start": 8, "end": 12 }, "value": true } },
+            }],
+        }]),
+    );
+}
@@ -0,0 +1,54 @@
+//! Checks `Spanned` and `SpanlessEq` on parsed trees.
+
+use hail_parser::ast::*;
+use hail_parser::spanned::{SpanlessEq, Spanned};
+use hail_parser::{grammar, scanner};
+
+fn parse(source: &str) -> Vec<RootStmnt<'_>> {
+    grammar::RootStmntsParser::new().parse(source, scanner::Asi::lex(source)).unwrap()
+}
+
+#[test]
+fn enums_report_the_span_of_their_node() {
+    let source = "@debug val x <- ?&i32 = f(1)\nmodule m { }";
+    let ast = parse(source);
+
+    assert_eq!(&source[ast[0].span().clone()], "val x <- ?&i32 = f(1)");
+    assert_eq!(&source[ast[1].span().clone()], "module m { }");
+
+    match &ast[0] {
+        RootStmnt::Val(_, val) => {
+            assert_eq!(&source[val.ty.as_ref().unwrap().span().clone()], "?&i32");
+            assert_eq!(&source[val.value.as_ref().unwrap().span().clone()], "f(1)");
+        },
+        stmnt => panic!("expected a val, found {:?}", stmnt),
+    }
+}
+
+#[test]
+fn span_mut_moves_the_node() {
+    let mut ast = parse("val x = 1");
+
+    *ast[0].span_mut() = 10..20;
+
+    assert_eq!(ast[0].span(), &(10..20));
+}
+
+#[test]
+fn spanless_eq_ignores_formatting() {
+    let left = parse("val add = routine(a <- i32, b <- i32) -> i32 { return a + b }");
+    let right = parse("val   add =\nroutine(\n    a <- i32,\n    b <- i32,\n) -> i32 {\n    return a + b\n}");
+
+    assert_ne!(left, right);
+    assert!(left.spanless_eq(&right));
+}
+
+#[test]
+fn spanless_eq_compares_structure() {
+    let base = parse("val add = routine(a <- i32, b <- i32) -> i32 { return a + b }");
+
+    assert!(!base.spanless_eq(&parse("val add = routine(a <- i32, b <- i32) -> i32 { return a - b }")));
+    assert!(!base.spanless_eq(&parse("val add = routine(a <- i32, b <- i64) -> i32 { return a + b }")));
+    assert!(!base.spanless_eq(&parse("shared val add = routine(a <- i32, b <- i32) -> i32 { return a + b }")));
+    assert!(!base.spanless_eq(&parse("val add = routine(a <- i32) -> i32 { return a }")));
+}
@@ -0,0 +1,292 @@
+//! Checks that `Visit`, `VisitMut` and `Fold` reach every node and every enum variant of the AST.
+//!
+//! The nodes and variants are read straight out of `ast.rs`, so adding a node without a method in each walker, or a
+//! variant without an example in `SOURCE`, fails here.
+
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+
+use hail_parser::ast::*;
+use hail_parser::fold::{self, Fold};
+use hail_parser::visit::{self, Visit};
+use hail_parser::visit_mut::{self, VisitMut};
+use hail_parser::{grammar, scanner};
+
+/// A unit that uses every node and every variant of the AST at least once.
+const SOURCE: &str = r#"
+@os_linux @!debug import { print as log, flush } from std::io
+import alloc
+@any(os_linux, not(debug), all(os_macos, target_arch("x86_64"))) shared import * from std
+
+#packed type Point = struct {
+    #align(4) x <- i32: 3,
+    shared y <- std::f32,
+}
+type Bits = union { a <- u32, b <- f32 }
+type Shape = enum { Empty, Circle <- f32 = 1 }
+type Writer = contract {
+    type Out
+    val write <- routine(&fluid Self, &u8) -> ?usize
+}
+type Callback = closure(shared i32, ...VarArg) -> !fluid i32: &dyn Writer
+type Printf = routine(&u8, ...)
+
+const LIMIT <- i32 = 3
+shared val total = 0
+count = 1
+start()
+if true { } else if false { } else { }
+while false { }
+match none { value <- i32 => { } }
+@debug { }
+
+#inline apply Writer to Point {
+    type Out = i32
+    val write = routine(self <- &fluid Self, data <- &u8) -> ?usize #inline(always) {
+        return none
+    }
+}
+
+module inner {
+    val nothing
+}
+
+val main = routine() {
+    val point = Point::{ x = -1 as i32, y = 2.5 }
+    val shape = Shape::(Shape::Circle)
+    val sum = point.x + 0x10
+    val wrapped = ok("text")
+    val failed = err(total)
+    val first = list[0]
+    val next = load()?
+    val empty = !full
+    val stack = asm("mov %rsp, $0" : "=r"(sp))
+    point.x += 1
+    update(point)
+    if sum > 1 { } else if sum < 0 { } else { }
+    outer: while true {
+        break outer
+        continue outer
+    }
+    match shape { circle <- f32 => { } }
+    type Local = i32
+    defer flush()
+    asm("mov $0, $1" : "=r"(sum) : "r"(LIMIT) : "memory" : volatile)
+    @os_windows { }
+    return
+}
+"#;
+
+/// Types in `ast.rs` which are plain data rather than nodes.
+const LEAVES: &[&str] = &["NumKind", "UnaryOp", "BinaryOp", "AssignOp"];
+
+/// Reads every node, and every `Enum::Variant` of the enum nodes, out of `ast.rs`.
+fn expected() -> BTreeSet<String> {
+    let mut out = BTreeSet::new();
+    let mut current = None;
+
+    for line in include_str!("../src/ast.rs").lines() {
+        let decl = line.strip_prefix("pub struct ").map(|rest| (rest, false))
+            .or_else(|| line.strip_prefix("pub enum ").map(|rest| (rest, true)));
+
+        if let Some((rest, is_enum)) = decl {
+            let name: String = rest.chars().take_while(|c| c.is_alphanumeric()).collect();
+
+            if LEAVES.contains(&name.as_str()) {
+                current = None;
+            } else {
+                out.insert(name.clone());
+                current = if is_enum { Some(name) } else { None };
+            }
+        } else if line == "}" {
+            current = None;
+        } else if let Some(name) = &current {
+            // Variants are the only lines of an enum at one level of indentation that start with a capital.
+            if let Some(rest) = line.strip_prefix("    ") {
+                if rest.starts_with(|c: char| c.is_ascii_uppercase()) {
+                    let variant: String = rest.chars().take_while(|c| c.is_alphanumeric()).collect();
+                    out.insert(format!("{}::{}", name, variant));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn parse(source: &str) -> Vec<RootStmnt<'_>> {
+    grammar::RootStmntsParser::new().parse(source, scanner::Asi::lex(source)).unwrap()
+}
+
+/// Records the name, and variant if it has one, of every node that a walker reaches.
+#[derive(Default)]
+struct Recorder {
+    seen: BTreeSet<String>,
+}
+
+impl Recorder {
+    fn record(&mut self, name: &str, node: &impl Debug) {
+        let debug = format!("{:?}", node);
+        let variant: String = debug.chars().take_while(|c| c.is_alphanumeric()).collect();
+
+        self.seen.insert(name.to_string());
+        self.seen.insert(format!("{}::{}", name, variant));
+    }
+
+    /// The recorded names which are nodes or variants of the AST.
+    fn finish(self, expected: &BTreeSet<String>) -> BTreeSet<String> {
+        self.seen.into_iter().filter(|name| expected.contains(name)).collect()
+    }
+}
+
+macro_rules! record {
+    ($($visit:ident, $visit_mut:ident, $fold:ident: $name:ident $(<$lt:lifetime>)?;)*) => {
+        impl<'a> Visit<'a> for Recorder {
+            $(fn $visit(&mut self, node: &$name $(<$lt>)?) {
+                self.record(stringify!($name), node);
+                visit::$visit(self, node);
+            })*
+        }
+
+        impl<'a> VisitMut<'a> for Recorder {
+            $(fn $visit_mut(&mut self, node: &mut $name $(<$lt>)?) {
+                self.record(stringify!($name), node);
+                visit_mut::$visit_mut(self, node);
+            })*
+        }
+
+        impl<'a> Fold<'a> for Recorder {
+            $(fn $fold(&mut self, node: $name $(<$lt>)?) -> $name $(<$lt>)? {
+                self.record(stringify!($name), &node);
+                fold::$fold(self, node)
+            })*
+        }
+    };
+}
+
+record! {
+    visit_bool, visit_bool_mut, fold_bool: Bool;
+    visit_id, visit_id_mut, fold_id: Id<'a>;
+    visit_num, visit_num_mut, fold_num: Num<'a>;
+    visit_str, visit_str_mut, fold_str: Str<'a>;
+    visit_global_path, visit_global_path_mut, fold_global_path: GlobalPath<'a>;
+    visit_marker, visit_marker_mut, fold_marker: Marker<'a>;
+    visit_routine_arg, visit_routine_arg_mut, fold_routine_arg: RoutineArg<'a>;
+    visit_routine, visit_routine_mut, fold_routine: Routine<'a>;
+    visit_path_type, visit_path_type_mut, fold_path_type: PathType<'a>;
+    visit_routine_type, visit_routine_type_mut, fold_routine_type: RoutineType<'a>;
+    visit_struct_prop, visit_struct_prop_mut, fold_struct_prop: StructProp<'a>;
+    visit_struct_type, visit_struct_type_mut, fold_struct_type: StructType<'a>;
+    visit_union_type, visit_union_type_mut, fold_union_type: UnionType<'a>;
+    visit_enum_prop, visit_enum_prop_mut, fold_enum_prop: EnumProp<'a>;
+    visit_enum_type, visit_enum_type_mut, fold_enum_type: EnumType<'a>;
+    visit_contract_type, visit_contract_type_mut, fold_contract_type: ContractType<'a>;
+    visit_shared_type, visit_shared_type_mut, fold_shared_type: SharedType<'a>;
+    visit_fluid_type, visit_fluid_type_mut, fold_fluid_type: FluidType<'a>;
+    visit_ref_type, visit_ref_type_mut, fold_ref_type: RefType<'a>;
+    visit_dyn_type, visit_dyn_type_mut, fold_dyn_type: DynType<'a>;
+    visit_variadic_type, visit_variadic_type_mut, fold_variadic_type: VariadicType<'a>;
+    visit_opt_type, visit_opt_type_mut, fold_opt_type: OptType<'a>;
+    visit_res_type, visit_res_type_mut, fold_res_type: ResType<'a>;
+    visit_type, visit_type_mut, fold_type: Type<'a>;
+    visit_path, visit_path_mut, fold_path: Path<'a>;
+    visit_access, visit_access_mut, fold_access: Access<'a>;
+    visit_call, visit_call_mut, fold_call: Call<'a>;
+    visit_unary, visit_unary_mut, fold_unary: Unary<'a>;
+    visit_as, visit_as_mut, fold_as: As<'a>;
+    visit_binary, visit_binary_mut, fold_binary: Binary<'a>;
+    visit_construct_prop, visit_construct_prop_mut, fold_construct_prop: ConstructProp<'a>;
+    visit_construct, visit_construct_mut, fold_construct: Construct<'a>;
+    visit_construct_enum, visit_construct_enum_mut, fold_construct_enum: ConstructEnum<'a>;
+    visit_wrap, visit_wrap_mut, fold_wrap: Wrap<'a>;
+    visit_none_lit, visit_none_lit_mut, fold_none_lit: NoneLit;
+    visit_try, visit_try_mut, fold_try: Try<'a>;
+    visit_index, visit_index_mut, fold_index: Index<'a>;
+    visit_asm_operand, visit_asm_operand_mut, fold_asm_operand: AsmOperand<'a>;
+    visit_asm, visit_asm_mut, fold_asm: Asm<'a>;
+    visit_expr, visit_expr_mut, fold_expr: Expr<'a>;
+    visit_assign, visit_assign_mut, fold_assign: Assign<'a>;
+    visit_val, visit_val_mut, fold_val: Val<'a>;
+    visit_const, visit_const_mut, fold_const: Const<'a>;
+    visit_type_decl, visit_type_decl_mut, fold_type_decl: TypeDecl<'a>;
+    visit_if_branch, visit_if_branch_mut, fold_if_branch: IfBranch<'a>;
+    visit_if, visit_if_mut, fold_if: If<'a>;
+    visit_while, visit_while_mut, fold_while: While<'a>;
+    visit_match_case, visit_match_case_mut, fold_match_case: MatchCase<'a>;
+    visit_match, visit_match_mut, fold_match: Match<'a>;
+    visit_break, visit_break_mut, fold_break: Break<'a>;
+    visit_continue, visit_continue_mut, fold_continue: Continue<'a>;
+    visit_return, visit_return_mut, fold_return: Return<'a>;
+    visit_defer, visit_defer_mut, fold_defer: Defer<'a>;
+    visit_flag_expr, visit_flag_expr_mut, fold_flag_expr: FlagExpr<'a>;
+    visit_flag, visit_flag_mut, fold_flag: Flag<'a>;
+    visit_block_expr, visit_block_expr_mut, fold_block_expr: BlockExpr<'a>;
+    visit_block, visit_block_mut, fold_block: Block<'a>;
+    visit_import_item, visit_import_item_mut, fold_import_item: ImportItem<'a>;
+    visit_import, visit_import_mut, fold_import: Import<'a>;
+    visit_application, visit_application_mut, fold_application: Application<'a>;
+    visit_apply, visit_apply_mut, fold_apply: Apply<'a>;
+    visit_module, visit_module_mut, fold_module: Module<'a>;
+    visit_root_stmnt, visit_root_stmnt_mut, fold_root_stmnt: RootStmnt<'a>;
+}
+
+#[test]
+fn visit_reaches_every_node() {
+    let expected = expected();
+    let ast = parse(SOURCE);
+    let mut recorder = Recorder::default();
+
+    for stmnt in &ast {
+        recorder.visit_root_stmnt(stmnt);
+    }
+
+    assert_eq!(recorder.finish(&expected), expected);
+}
+
+#[test]
+fn visit_mut_reaches_every_node() {
+    let expected = expected();
+    let mut ast = parse(SOURCE);
+    let mut recorder = Recorder::default();
+
+    for stmnt in &mut ast {
+        recorder.visit_root_stmnt_mut(stmnt);
+    }
+
+    assert_eq!(recorder.finish(&expected), expected);
+}
+
+#[test]
+fn fold_reaches_every_node_and_rebuilds_the_tree() {
+    let expected = expected();
+    let ast = parse(SOURCE);
+    let mut recorder = Recorder::default();
+    let folded: Vec<_> = ast.clone().into_iter().map(|stmnt| recorder.fold_root_stmnt(stmnt)).collect();
+
+    assert_eq!(folded, ast);
+    assert_eq!(recorder.finish(&expected), expected);
+}
+
+/// Walks in source order, by checking that identifiers come out in the order they are written.
+#[test]
+fn visit_is_in_source_order() {
+    struct Ids(Vec<usize>);
+
+    impl<'a> Visit<'a> for Ids {
+        fn visit_id(&mut self, node: &Id<'a>) {
+            self.0.push(node.span.start);
+        }
+    }
+
+    let ast = parse(SOURCE);
+    let mut ids = Ids(Vec::new());
+
+    for stmnt in &ast {
+        ids.visit_root_stmnt(stmnt);
+    }
+
+    let mut sorted = ids.0.clone();
+    sorted.sort_unstable();
+
+    assert_eq!(ids.0, sorted);
+}
@@ -0,0 +1,188 @@
+//! The registry of attributes (markers) that the compiler understands.
+
+use hail_parser::ast;
+
+/// The shape of an argument passed to an attribute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttrArg {
+    /// An integer literal, such as `16`.
+    Int,
+
+    /// A string literal, such as `"foo"`.
+    Str,
+
+    /// One of a fixed set of identifiers, such as `always`.
+    Id(&'static [&'static str]),
+
+    /// Any expression, such as `SIZE % 8 == 0`.
+    Expr,
+}
+
+impl AttrArg {
+    /// Checks whether an expression has the shape of this argument.
+    pub fn accepts(&self, expr: &ast::Expr) -> bool {
+        match (self, expr) {
+            (AttrArg::Int, ast::Expr::Num(num)) => num.kind != ast::NumKind::Float,
+            (AttrArg::Str, ast::Expr::Str(_)) => true,
+            (AttrArg::Id(ids), ast::Expr::Id(id)) => ids.contains(&id.value),
+            (AttrArg::Expr, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Describes the argument for diagnostics.
+    pub fn describe(&self) -> String {
+        match self {
+            AttrArg::Int => "integer".into(),
+            AttrArg::Str => "string".into(),
+            AttrArg::Id(ids) => ids.join(" | "),
+            AttrArg::Expr => "expression".into(),
+        }
+    }
+}
+
+/// A declaration that an attribute can be applied to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttrTarget {
+    /// A routine literal.
+    Routine,
+
+    /// A `val` declaration.
+    Val,
+
+    /// A `const` declaration.
+    Const,
+
+    /// A `type` declaration.
+    Type,
+
+    /// An `apply` statement.
+    Apply,
+
+    /// A property of a struct or union.
+    Field,
+
+    /// An enum property.
+    Variant,
+}
+
+impl AttrTarget {
+    /// Describes the target for diagnostics.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            AttrTarget::Routine => "a routine",
+            AttrTarget::Val => "a variable",
+            AttrTarget::Const => "a constant",
+            AttrTarget::Type => "a type declaration",
+            AttrTarget::Apply => "an apply statement",
+            AttrTarget::Field => "a property",
+            AttrTarget::Variant => "an enum property",
+        }
+    }
+}
+
+/// An attribute known to the compiler.
+#[derive(Clone, Debug)]
+pub struct Attr {
+    /// The name of the attribute, without the `#`.
+    pub name: &'static str,
+
+    /// The arguments that the attribute takes.
+    pub args: &'static [AttrArg],
+
+    /// Whether or not the arguments may be left out entirely.
+    pub optional: bool,
+
+    /// The declarations that the attribute can be applied to.
+    pub targets: &'static [AttrTarget],
+}
+
+impl Attr {
+    /// Checks whether the provided arguments match the arguments of this attribute.
+    pub fn accepts(&self, args: &[ast::Expr]) -> bool {
+        if args.is_empty() && self.optional {
+            return true;
+        }
+
+        args.len() == self.args.len() && self.args.iter().zip(args).all(|(arg, expr)| arg.accepts(expr))
+    }
+
+    /// Describes how the attribute should be written, for diagnostics.
+    pub fn signature(&self) -> String {
+        if self.args.is_empty() {
+            return format!("#{}", self.name);
+        }
+
+        let args = self.args.iter().map(AttrArg::describe).collect::<Vec<_>>().join(", ");
+        format!("#{}({})", self.name, args)
+    }
+}
+
+/// The representations that `#repr` accepts, the C layout followed by the integer types that can store an enum's
+/// tag.
+const REPRS: &[&str] = &["C", "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize"];
+
+/// Every attribute that the compiler understands.
+pub const ATTRS: &[Attr] = &[
+    Attr {
+        name: "align",
+        args: &[AttrArg::Int],
+        optional: false,
+        targets: &[AttrTarget::Val, AttrTarget::Type, AttrTarget::Field],
+    },
+    Attr {
+        name: "link_name",
+        args: &[AttrArg::Str],
+        optional: false,
+        targets: &[AttrTarget::Routine, AttrTarget::Val],
+    },
+    Attr {
+        name: "deprecated",
+        args: &[AttrArg::Str],
+        optional: true,
+        targets: &[
+            AttrTarget::Routine,
+            AttrTarget::Val,
+            AttrTarget::Const,
+            AttrTarget::Type,
+            AttrTarget::Apply,
+            AttrTarget::Field,
+            AttrTarget::Variant,
+        ],
+    },
+    Attr {
+        name: "inline",
+        args: &[AttrArg::Id(&["always", "never"])],
+        optional: true,
+        targets: &[AttrTarget::Routine, AttrTarget::Val],
+    },
+    Attr {
+        name: "pun",
+        args: &[],
+        optional: false,
+        targets: &[AttrTarget::Val, AttrTarget::Field],
+    },
+    Attr {
+        name: "packed",
+        args: &[],
+        optional: false,
+        targets: &[AttrTarget::Type],
+    },
+    Attr {
+        name: "repr",
+        args: &[AttrArg::Id(REPRS)],
+        optional: false,
+        targets: &[AttrTarget::Type],
+    },
+    Attr {
+        name: "static_assert",
+        args: &[AttrArg::Expr],
+        optional: false,
+        targets: &[AttrTarget::Val, AttrTarget::Const, AttrTarget::Type],
+    },
+];
+
+/// Finds the attribute with the provided name.
+pub fn lookup(name: &str) -> Option<&'static Attr> {
+    ATTRS.iter().find(|attr| attr.name == name)
+}
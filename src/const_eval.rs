@@ -0,0 +1,782 @@
+//! The compile time evaluator, which computes constants and checks static assertions over HIR.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use hail_parser::ast::{AssignOp, BinaryOp, NumKind, Span, UnaryOp};
+
+use crate::hir;
+use crate::layout::{Layout, Target};
+
+/// The most steps that evaluating a constant may take, so that runaway loops are caught.
+pub const STEP_LIMIT: usize = 1_000_000;
+
+/// The deepest that routine calls may nest while evaluating a constant.
+pub const CALL_LIMIT: usize = 256;
+
+/// An integer type built in to hail.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IntType {
+    /// The name of the type.
+    pub name: &'static str,
+
+    /// Whether or not the type is signed.
+    pub signed: bool,
+
+    /// The width of the type in bits.
+    pub bits: u32,
+}
+
+/// The type that integers without a known type are checked against.
+const DEFAULT_INT: IntType = IntType { name: "i64", signed: true, bits: 64 };
+
+impl IntType {
+    /// Finds the integer type with the provided name, where `isize` and `usize` are as wide as a pointer of the
+    /// target.
+    pub fn from_name(name: &str, target: &Target) -> Option<IntType> {
+        let pointer = target.pointer_size as u32 * 8;
+        let (name, signed, bits) = match name {
+            "i8" => ("i8", true, 8),
+            "i16" => ("i16", true, 16),
+            "i32" => ("i32", true, 32),
+            "i64" => ("i64", true, 64),
+            "isize" => ("isize", true, pointer),
+            "u8" => ("u8", false, 8),
+            "u16" => ("u16", false, 16),
+            "u32" => ("u32", false, 32),
+            "u64" => ("u64", false, 64),
+            "usize" => ("usize", false, pointer),
+            _ => return None,
+        };
+
+        Some(IntType { name, signed, bits })
+    }
+
+    /// The smallest value of the type.
+    pub fn min(&self) -> i128 {
+        if self.signed { -(1 << (self.bits - 1)) } else { 0 }
+    }
+
+    /// The largest value of the type.
+    pub fn max(&self) -> i128 {
+        if self.signed { (1 << (self.bits - 1)) - 1 } else { (1 << self.bits) - 1 }
+    }
+
+    /// Whether or not the value fits in the type.
+    pub fn contains(&self, value: i128) -> bool {
+        (self.min()..=self.max()).contains(&value)
+    }
+
+    /// Truncates the value to the width of the type, as a cast does.
+    pub fn wrap(&self, value: i128) -> i128 {
+        let value = value & ((1 << self.bits) - 1);
+        if value > self.max() { value - (1 << self.bits) } else { value }
+    }
+}
+
+/// A value computed at compile time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    /// An integer, with its type if it is known.
+    Int(i128, Option<IntType>),
+
+    /// A floating point number.
+    Float(f64),
+
+    /// A boolean.
+    Bool(bool),
+}
+
+impl Value {
+    /// Describes the type of the value for diagnostics.
+    pub fn describe(&self) -> String {
+        match self {
+            Value::Int(_, Some(ty)) => format!("`{}`", ty.name),
+            Value::Int(_, None) => "integer".into(),
+            Value::Float(_) => "float".into(),
+            Value::Bool(_) => "`bool`".into(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(value, _) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{:?}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// An error found while evaluating a constant.
+#[derive(Clone, Debug)]
+pub struct ConstError {
+    /// The location of the error.
+    pub span: Span,
+
+    /// A description of the error.
+    pub message: String,
+
+    /// The code of the error from [`hail_parser::codes`].
+    pub code: &'static str,
+}
+
+type Result<T> = std::result::Result<T, ConstError>;
+
+/// Creates an error at the provided location.
+fn error<T>(span: &Span, message: String) -> Result<T> {
+    Err(ConstError { span: span.clone(), message, code: "E0022" })
+}
+
+/// Creates an error for a discriminant which doesn't fit its enum, at the provided location.
+fn discriminant_error<T>(span: &Span, message: String) -> Result<T> {
+    Err(ConstError { span: span.clone(), message, code: "E0012" })
+}
+
+/// How control flow leaves a statement.
+enum Flow {
+    /// Continue with the next statement.
+    Next,
+
+    /// Break out of a loop, with its label.
+    Break(Option<String>),
+
+    /// Skip to the next iteration of a loop, with its label.
+    Continue(Option<String>),
+
+    /// Return from the routine.
+    Return(Option<Value>),
+}
+
+/// The variables of a routine being called at compile time, by scope.
+type Locals = Vec<Vec<(String, Option<Value>)>>;
+
+/// Evaluates the constants of a module.
+pub struct ConstEval<'m> {
+    /// The module being evaluated.
+    module: &'m hir::Module,
+
+    /// The target that layouts are computed for.
+    target: Target,
+
+    /// The values of the constants evaluated so far.
+    values: HashMap<String, Value>,
+
+    /// The discriminants of the enums evaluated so far, in the order of their variants.
+    discriminants: HashMap<String, Vec<i128>>,
+
+    /// The constants currently being evaluated, to catch cycles.
+    evaluating: Vec<String>,
+
+    /// The constants which have already failed to evaluate, so their errors are only reported once.
+    failed: HashSet<String>,
+
+    /// The steps taken so far by the current constant.
+    steps: usize,
+
+    /// The depth of routine calls.
+    calls: usize,
+}
+
+impl<'m> ConstEval<'m> {
+    /// Creates an evaluator for the provided module.
+    pub fn new(module: &'m hir::Module, target: Target) -> Self {
+        Self {
+            module,
+            target,
+            values: HashMap::new(),
+            discriminants: HashMap::new(),
+            evaluating: vec![],
+            failed: HashSet::new(),
+            steps: 0,
+            calls: 0,
+        }
+    }
+
+    /// Consumes the evaluator, returning the values of the constants and the discriminants of the enums it
+    /// evaluated.
+    pub fn into_values(self) -> (HashMap<String, Value>, HashMap<String, Vec<i128>>) {
+        (self.values, self.discriminants)
+    }
+
+    /// Evaluates an expression outside of any constant, such as the argument of a static assertion.
+    pub fn eval_root(&mut self, expr: &hir::Expr) -> Result<Value> {
+        self.steps = 0;
+        self.eval(&mut vec![], expr)
+    }
+
+    /// Evaluates the constant with the provided name.
+    pub fn eval_const(&mut self, span: &Span, name: &str) -> Result<Value> {
+        if let Some(value) = self.values.get(name) {
+            return Ok(*value);
+        }
+
+        let (ty, expr) = match self.module.item(name).map(|item| &item.kind) {
+            Some(hir::ItemKind::Const(ty, expr)) => (ty, expr),
+            _ => return error(span, format!("`{}` is not a constant", name)),
+        };
+
+        if self.failed.contains(name) {
+            return error(span, format!("constant `{}` could not be evaluated", name));
+        }
+
+        if self.evaluating.iter().any(|other| other == name) {
+            return error(span, format!("constant `{}` depends on itself", name));
+        }
+
+        self.evaluating.push(name.into());
+        let value = self.eval(&mut vec![], expr).and_then(|value| match ty {
+            Some(ty) => self.coerce(&expr.span, value, ty),
+            None => Ok(value),
+        });
+        self.evaluating.pop();
+
+        if value.is_err() {
+            self.failed.insert(name.into());
+        }
+
+        let value = value?;
+        self.values.insert(name.into(), value);
+        Ok(value)
+    }
+
+    /// Evaluates the discriminants of the enum declared with the provided name.
+    ///
+    /// Variants without an explicit value take the value after the previous variant, starting at zero.
+    pub fn eval_discriminants(&mut self, span: &Span, name: &str) -> Result<Vec<i128>> {
+        if let Some(discriminants) = self.discriminants.get(name) {
+            return Ok(discriminants.clone());
+        }
+
+        let (attrs, variants) = match self.module.item(name) {
+            Some(hir::Item { attrs, kind: hir::ItemKind::Type(Some(hir::Type { kind: hir::TypeKind::Enum(variants), .. })), .. }) => (attrs, variants),
+            _ => return error(span, format!("`{}` is not an enum", name)),
+        };
+
+        if self.failed.contains(name) {
+            return error(span, format!("the discriminants of `{}` could not be evaluated", name));
+        }
+
+        if self.evaluating.iter().any(|other| other == name) {
+            return error(span, format!("the discriminants of `{}` depend on themselves", name));
+        }
+
+        self.evaluating.push(name.into());
+        let discriminants = self.eval_variants(name, attrs, variants);
+        self.evaluating.pop();
+
+        if discriminants.is_err() {
+            self.failed.insert(name.into());
+        }
+
+        let discriminants = discriminants?;
+        self.discriminants.insert(name.into(), discriminants.clone());
+        Ok(discriminants)
+    }
+
+    /// Evaluates the discriminant of each variant of an enum, checking that they fit in its tag and are unique.
+    fn eval_variants(&mut self, name: &str, attrs: &[hir::Attr], variants: &[hir::Variant]) -> Result<Vec<i128>> {
+        let tag = hir::enum_tag(attrs, variants).and_then(|tag| IntType::from_name(tag, &self.target)).unwrap_or(DEFAULT_INT);
+        let mut discriminants: Vec<i128> = vec![];
+
+        for variant in variants {
+            let value = match &variant.value {
+                Some(value) => match self.eval(&mut vec![], value)? {
+                    Value::Int(value, _) => value,
+                    other => return error(&value.span, format!("expected an integer discriminant, found {}", other.describe())),
+                },
+                None => match discriminants.last() {
+                    Some(previous) => previous + 1,
+                    None => 0,
+                },
+            };
+
+            if !tag.contains(value) {
+                return discriminant_error(&variant.span, format!("discriminant `{}` of `{}::{}` does not fit in `{}`", value, name, variant.name, tag.name));
+            }
+
+            if let Some(other) = discriminants.iter().position(|other| *other == value) {
+                return discriminant_error(&variant.span, format!("discriminant `{}` is already used by `{}::{}`", value, name, variants[other].name));
+            }
+
+            discriminants.push(value);
+        }
+
+        Ok(discriminants)
+    }
+
+    /// Counts a step of evaluation, failing once the step limit is reached.
+    fn step(&mut self, span: &Span) -> Result<()> {
+        self.steps += 1;
+        if self.steps > STEP_LIMIT {
+            return error(span, format!("constant evaluation took more than {} steps", STEP_LIMIT));
+        }
+
+        Ok(())
+    }
+
+    /// Converts a value to the provided type, as when it is stored.
+    fn coerce(&self, span: &Span, value: Value, ty: &hir::Type) -> Result<Value> {
+        let name = match &ty.unmodified().kind {
+            hir::TypeKind::Name(name) => name.as_str(),
+            _ => return error(span, format!("values of type `{}` cannot be computed at compile time", ty)),
+        };
+
+        match (IntType::from_name(name, &self.target), value) {
+            (Some(int), Value::Int(value, known)) => {
+                if known.is_some_and(|known| known != int) {
+                    return error(span, format!("expected `{}`, found `{}`", int.name, known.unwrap().name));
+                }
+
+                if !int.contains(value) {
+                    return error(span, format!("`{}` overflows `{}`", value, int.name));
+                }
+
+                Ok(Value::Int(value, Some(int)))
+            },
+            (None, Value::Int(value, None)) if hir::FLOAT_TYPES.contains(&name) => Ok(Value::Float(value as f64)),
+            (None, Value::Float(value)) if name == "f32" => Ok(Value::Float(value as f32 as f64)),
+            (None, Value::Float(_)) if name == "f64" => Ok(value),
+            (None, Value::Bool(_)) if name == "bool" => Ok(value),
+            _ if !ty.is_prim() => error(span, format!("values of type `{}` cannot be computed at compile time", ty)),
+            _ => error(span, format!("expected `{}`, found {}", name, value.describe())),
+        }
+    }
+
+    /// Finds a variable of a routine being called.
+    fn local<'l>(locals: &'l mut Locals, name: &str) -> Option<&'l mut Option<Value>> {
+        locals.iter_mut().rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|(other, _)| other == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Evaluates an expression.
+    fn eval(&mut self, locals: &mut Locals, expr: &hir::Expr) -> Result<Value> {
+        self.step(&expr.span)?;
+
+        match &expr.kind {
+            hir::ExprKind::Bool(value) => Ok(Value::Bool(*value)),
+            hir::ExprKind::Num(kind, value) => parse_num(&expr.span, kind, value),
+            hir::ExprKind::Name(name) => match Self::local(locals, name) {
+                Some(Some(value)) => Ok(*value),
+                Some(None) => error(&expr.span, format!("`{}` is used before it is given a value", name)),
+                None => self.eval_const(&expr.span, name),
+            },
+            hir::ExprKind::Unary(op, subject) => {
+                let value = self.eval(locals, subject)?;
+                unary(&expr.span, op, value)
+            },
+            hir::ExprKind::Path(subject, variant) => {
+                let name = match &subject.kind {
+                    hir::ExprKind::Name(name) => name,
+                    _ => return error(&expr.span, "this expression cannot be evaluated at compile time".into()),
+                };
+
+                let discriminants = self.eval_discriminants(&subject.span, name)?;
+                let (attrs, variants) = match self.module.item(name) {
+                    Some(hir::Item { attrs, kind: hir::ItemKind::Type(Some(hir::Type { kind: hir::TypeKind::Enum(variants), .. })), .. }) => (attrs, variants),
+                    _ => unreachable!(),
+                };
+
+                let index = match variants.iter().position(|other| &other.name == variant) {
+                    Some(index) => index,
+                    None => return error(&expr.span, format!("`{}` has no variant `{}`", name, variant)),
+                };
+
+                if variants[index].ty.is_some() {
+                    return error(&expr.span, format!("`{}::{}` has a payload and cannot be used at compile time", name, variant));
+                }
+
+                let tag = hir::enum_tag(attrs, variants).and_then(|tag| IntType::from_name(tag, &self.target));
+                Ok(Value::Int(discriminants[index], tag))
+            },
+            hir::ExprKind::As(subject, ty) => {
+                let value = self.eval(locals, subject)?;
+                cast(&expr.span, value, ty, &self.target)
+            },
+            hir::ExprKind::Binary(BinaryOp::AmpAmp, left, right) => match self.eval(locals, left)? {
+                Value::Bool(false) => Ok(Value::Bool(false)),
+                Value::Bool(true) => self.eval_bool(locals, right),
+                value => error(&left.span, format!("expected `bool`, found {}", value.describe())),
+            },
+            hir::ExprKind::Binary(BinaryOp::PipePipe, left, right) => match self.eval(locals, left)? {
+                Value::Bool(true) => Ok(Value::Bool(true)),
+                Value::Bool(false) => self.eval_bool(locals, right),
+                value => error(&left.span, format!("expected `bool`, found {}", value.describe())),
+            },
+            hir::ExprKind::Binary(op, left, right) => {
+                let left = self.eval(locals, left)?;
+                let right = self.eval(locals, right)?;
+                binary(&expr.span, op, left, right)
+            },
+            hir::ExprKind::Call(callee, args) => {
+                // `size_of` and `align_of` are built in, unless the module declares its own.
+                if let hir::ExprKind::Name(name) = &callee.kind {
+                    if (name == "size_of" || name == "align_of") && self.module.item(name).is_none() {
+                        return self.layout_of(&expr.span, name, args);
+                    }
+                }
+
+                let routine = match &callee.kind {
+                    hir::ExprKind::Name(name) => match self.module.item(name).map(|item| &item.kind) {
+                        Some(hir::ItemKind::Val(_, Some(hir::Expr { kind: hir::ExprKind::Routine(routine), .. }))) => routine,
+                        _ => return error(&callee.span, format!("`{}` is not a routine that can be called at compile time", name)),
+                    },
+                    _ => return error(&callee.span, "only routines declared in the module can be called at compile time".into()),
+                };
+
+                let mut values = vec![];
+                for arg in args {
+                    values.push(self.eval(locals, arg)?);
+                }
+
+                self.call(&expr.span, routine, values)
+            },
+            _ => error(&expr.span, "this expression cannot be evaluated at compile time".into()),
+        }
+    }
+
+    /// Evaluates an expression which must be a boolean.
+    fn eval_bool(&mut self, locals: &mut Locals, expr: &hir::Expr) -> Result<Value> {
+        match self.eval(locals, expr)? {
+            Value::Bool(value) => Ok(Value::Bool(value)),
+            value => error(&expr.span, format!("expected `bool`, found {}", value.describe())),
+        }
+    }
+
+    /// Evaluates `size_of(T)` or `align_of(T)`.
+    fn layout_of(&self, span: &Span, query: &str, args: &[hir::Expr]) -> Result<Value> {
+        let ty = match args {
+            [hir::Expr { span, kind: hir::ExprKind::Name(name) }] => hir::Type { span: span.clone(), kind: hir::TypeKind::Name(name.clone()) },
+            _ => return error(span, format!("`{}` takes the name of a type", query)),
+        };
+
+        let layout = match Layout::of(self.module, &self.target, &ty) {
+            Some(layout) => layout,
+            None => return error(&ty.span, format!("the layout of `{}` is not known at compile time", ty)),
+        };
+
+        let value = match query {
+            "size_of" => layout.size,
+            _ => layout.align,
+        };
+
+        Ok(Value::Int(value as i128, IntType::from_name("usize", &self.target)))
+    }
+
+    /// Calls a routine at compile time.
+    fn call(&mut self, span: &Span, routine: &hir::Routine, args: Vec<Value>) -> Result<Value> {
+        if args.len() != routine.args.len() {
+            return error(span, format!("expected {} arguments, found {}", routine.args.len(), args.len()));
+        }
+
+        if self.calls >= CALL_LIMIT {
+            return error(span, format!("routine calls nested more than {} deep during constant evaluation", CALL_LIMIT));
+        }
+
+        let mut scope = vec![];
+        for (arg, value) in routine.args.iter().zip(args) {
+            scope.push((arg.name.clone(), Some(self.coerce(span, value, &arg.ty)?)));
+        }
+
+        self.calls += 1;
+        let flow = self.exec_block(&mut vec![scope], &routine.block);
+        self.calls -= 1;
+
+        match (flow?, &routine.returns) {
+            (Flow::Return(Some(value)), Some(ty)) => self.coerce(span, value, ty),
+            _ => error(span, "routines called at compile time must return a value".into()),
+        }
+    }
+
+    /// Executes a block in a new scope.
+    fn exec_block(&mut self, locals: &mut Locals, block: &hir::Block) -> Result<Flow> {
+        locals.push(vec![]);
+        let flow = self.exec_stmts(locals, &block.stmts);
+        locals.pop();
+        flow
+    }
+
+    /// Executes a list of statements, stopping when control flow leaves them.
+    fn exec_stmts(&mut self, locals: &mut Locals, stmts: &[hir::Stmt]) -> Result<Flow> {
+        for stmt in stmts {
+            match self.exec(locals, stmt)? {
+                Flow::Next => {},
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    /// Executes a statement.
+    fn exec(&mut self, locals: &mut Locals, stmt: &hir::Stmt) -> Result<Flow> {
+        self.step(&stmt.span)?;
+
+        match &stmt.kind {
+            hir::StmtKind::Val(name, ty, value) => {
+                let value = match value {
+                    Some(value) => {
+                        let computed = self.eval(locals, value)?;
+                        Some(match ty {
+                            Some(ty) => self.coerce(&value.span, computed, ty)?,
+                            None => computed,
+                        })
+                    },
+                    None => None,
+                };
+
+                locals.last_mut().unwrap().push((name.clone(), value));
+            },
+            hir::StmtKind::Assign(op, target, value) => {
+                let name = match &target.kind {
+                    hir::ExprKind::Name(name) if Self::local(locals, name).is_some() => name,
+                    _ => return error(&target.span, "routines called at compile time can only assign to their own variables".into()),
+                };
+
+                let mut value = self.eval(locals, value)?;
+                if let Some(op) = assign_op(op) {
+                    let current = self.eval(locals, target)?;
+                    value = binary(&stmt.span, &op, current, value)?;
+                }
+
+                let slot = Self::local(locals, name).unwrap();
+                if let (Some(Value::Int(_, Some(ty))), Value::Int(value, None)) = (*slot, value) {
+                    if !ty.contains(value) {
+                        return error(&stmt.span, format!("`{}` overflows `{}`", value, ty.name));
+                    }
+                }
+
+                *slot = Some(value);
+            },
+            hir::StmtKind::Expr(expr) => {
+                self.eval(locals, expr)?;
+            },
+            hir::StmtKind::If(cond, then, else_) => {
+                if let Value::Bool(true) = self.eval_bool(locals, cond)? {
+                    return self.exec_block(locals, then);
+                } else if let Some(else_) = else_ {
+                    return self.exec_block(locals, else_);
+                }
+            },
+            hir::StmtKind::While(label, cond, block) => {
+                while let Value::Bool(true) = self.eval_bool(locals, cond)? {
+                    match self.exec_block(locals, block)? {
+                        Flow::Break(None) => break,
+                        Flow::Break(Some(other)) if label.as_ref() == Some(&other) => break,
+                        Flow::Continue(None) => continue,
+                        Flow::Continue(Some(other)) if label.as_ref() == Some(&other) => continue,
+                        Flow::Next => {},
+                        flow => return Ok(flow),
+                    }
+                }
+            },
+            hir::StmtKind::Break(label) => return Ok(Flow::Break(label.clone())),
+            hir::StmtKind::Continue(label) => return Ok(Flow::Continue(label.clone())),
+            hir::StmtKind::Return(value) => {
+                let value = match value {
+                    Some(value) => Some(self.eval(locals, value)?),
+                    None => None,
+                };
+
+                return Ok(Flow::Return(value));
+            },
+            hir::StmtKind::Block(block) => return self.exec_block(locals, block),
+            hir::StmtKind::Type(..) | hir::StmtKind::Match(..) => {
+                return error(&stmt.span, "this statement cannot be executed at compile time".into());
+            },
+        }
+
+        Ok(Flow::Next)
+    }
+}
+
+/// Parses a number literal.
+fn parse_num(span: &Span, kind: &NumKind, value: &str) -> Result<Value> {
+    let parsed = match kind {
+        NumKind::Float => return Ok(Value::Float(value.parse().unwrap_or(f64::NAN))),
+        NumKind::Int => value.parse::<i128>().ok(),
+        NumKind::XInt => i128::from_str_radix(&value[2..], 16).ok(),
+        NumKind::BInt => i128::from_str_radix(&value[2..], 2).ok(),
+    };
+
+    let u64 = IntType { name: "u64", signed: false, bits: 64 };
+    match parsed {
+        Some(value) if DEFAULT_INT.contains(value) => Ok(Value::Int(value, None)),
+        Some(value) if u64.contains(value) => Ok(Value::Int(value, Some(u64))),
+        _ => error(span, format!("integer literal `{}` is too large", value)),
+    }
+}
+
+/// The binary operator that a compound assignment applies.
+fn assign_op(op: &AssignOp) -> Option<BinaryOp> {
+    Some(match op {
+        AssignOp::Eq => return None,
+        AssignOp::PlusEq => BinaryOp::Plus,
+        AssignOp::MinEq => BinaryOp::Min,
+        AssignOp::StarEq => BinaryOp::Star,
+        AssignOp::SlashEq => BinaryOp::Slash,
+        AssignOp::PercEq => BinaryOp::Perc,
+        AssignOp::AmpEq => BinaryOp::Amp,
+        AssignOp::PipeEq => BinaryOp::Pipe,
+        AssignOp::CaretEq => BinaryOp::Caret,
+        AssignOp::LtLtEq => BinaryOp::LtLt,
+        AssignOp::GtGtEq => BinaryOp::GtGt,
+    })
+}
+
+/// Checks that an integer result fits in its type, where `None` is a result which overflowed even `i128`.
+fn checked(span: &Span, value: Option<i128>, ty: Option<IntType>) -> Result<Value> {
+    let int = ty.unwrap_or(DEFAULT_INT);
+    match value {
+        Some(value) if int.contains(value) => Ok(Value::Int(value, ty)),
+        _ => error(span, format!("arithmetic overflow, the result does not fit in `{}`", int.name)),
+    }
+}
+
+/// Applies a unary operator.
+fn unary(span: &Span, op: &UnaryOp, value: Value) -> Result<Value> {
+    match (op, value) {
+        (UnaryOp::Min, Value::Int(value, ty)) => checked(span, value.checked_neg(), ty),
+        (UnaryOp::Min, Value::Float(value)) => Ok(Value::Float(-value)),
+        (UnaryOp::Bang, Value::Bool(value)) => Ok(Value::Bool(!value)),
+        (UnaryOp::Bang, Value::Int(value, ty)) => Ok(Value::Int(ty.unwrap_or(DEFAULT_INT).wrap(!value), ty)),
+        _ => error(span, format!("this operator cannot be applied to {} at compile time", value.describe())),
+    }
+}
+
+/// Casts a value with `as`.
+fn cast(span: &Span, value: Value, ty: &hir::Type, target: &Target) -> Result<Value> {
+    let name = match &ty.unmodified().kind {
+        hir::TypeKind::Name(name) => name.as_str(),
+        _ => return error(span, format!("cannot cast to `{}` at compile time", ty)),
+    };
+
+    match (IntType::from_name(name, target), value) {
+        (Some(int), Value::Int(value, _)) => Ok(Value::Int(int.wrap(value), Some(int))),
+        (Some(int), Value::Bool(value)) => Ok(Value::Int(value as i128, Some(int))),
+        (Some(int), Value::Float(value)) => {
+            let truncated = value.trunc();
+            if !truncated.is_finite() || !int.contains(truncated as i128) {
+                return error(span, format!("`{}` overflows `{}`", value, int.name));
+            }
+
+            Ok(Value::Int(truncated as i128, Some(int)))
+        },
+        (None, Value::Int(value, _)) if name == "f32" => Ok(Value::Float(value as f32 as f64)),
+        (None, Value::Int(value, _)) if name == "f64" => Ok(Value::Float(value as f64)),
+        (None, Value::Float(value)) if name == "f32" => Ok(Value::Float(value as f32 as f64)),
+        (None, Value::Float(_)) if name == "f64" => Ok(value),
+        (None, Value::Bool(_)) if name == "bool" => Ok(value),
+        _ => error(span, format!("cannot cast {} to `{}` at compile time", value.describe(), ty)),
+    }
+}
+
+/// Applies a binary operator, other than `&&` and `||`.
+fn binary(span: &Span, op: &BinaryOp, left: Value, right: Value) -> Result<Value> {
+    let mismatch = || error(span, format!("mismatched operands, {} and {}", left.describe(), right.describe()));
+
+    match (left, right) {
+        (Value::Int(a, left_ty), Value::Int(b, right_ty)) => {
+            if left_ty.is_some() && right_ty.is_some() && left_ty != right_ty {
+                return mismatch();
+            }
+
+            let ty = left_ty.or(right_ty);
+            let int = ty.unwrap_or(DEFAULT_INT);
+            match op {
+                BinaryOp::Plus => checked(span, a.checked_add(b), ty),
+                BinaryOp::Min => checked(span, a.checked_sub(b), ty),
+                BinaryOp::Star => checked(span, a.checked_mul(b), ty),
+                BinaryOp::Slash | BinaryOp::Perc if b == 0 => error(span, "division by zero".into()),
+                BinaryOp::Slash => checked(span, a.checked_div(b), ty),
+                BinaryOp::Perc => checked(span, a.checked_rem(b), ty),
+                BinaryOp::LtLt | BinaryOp::GtGt if !(0..int.bits as i128).contains(&b) => {
+                    error(span, format!("shift by {} overflows `{}`", b, int.name))
+                },
+                BinaryOp::LtLt => Ok(Value::Int(int.wrap(a << b), ty)),
+                BinaryOp::GtGt => Ok(Value::Int(a >> b, ty)),
+                BinaryOp::Amp => Ok(Value::Int(a & b, ty)),
+                BinaryOp::Pipe => Ok(Value::Int(a | b, ty)),
+                BinaryOp::Caret => Ok(Value::Int(a ^ b, ty)),
+                BinaryOp::EqEq => Ok(Value::Bool(a == b)),
+                BinaryOp::BangEq => Ok(Value::Bool(a != b)),
+                BinaryOp::Lt => Ok(Value::Bool(a < b)),
+                BinaryOp::Gt => Ok(Value::Bool(a > b)),
+                BinaryOp::LtEq => Ok(Value::Bool(a <= b)),
+                BinaryOp::GtEq => Ok(Value::Bool(a >= b)),
+                BinaryOp::AmpAmp | BinaryOp::PipePipe | BinaryOp::QuestQuest => mismatch(),
+            }
+        },
+        (Value::Float(a), Value::Float(b)) => match op {
+            BinaryOp::Plus => Ok(Value::Float(a + b)),
+            BinaryOp::Min => Ok(Value::Float(a - b)),
+            BinaryOp::Star => Ok(Value::Float(a * b)),
+            BinaryOp::Slash | BinaryOp::Perc if b == 0.0 => error(span, "division by zero".into()),
+            BinaryOp::Slash => Ok(Value::Float(a / b)),
+            BinaryOp::Perc => Ok(Value::Float(a % b)),
+            BinaryOp::EqEq => Ok(Value::Bool(a == b)),
+            BinaryOp::BangEq => Ok(Value::Bool(a != b)),
+            BinaryOp::Lt => Ok(Value::Bool(a < b)),
+            BinaryOp::Gt => Ok(Value::Bool(a > b)),
+            BinaryOp::LtEq => Ok(Value::Bool(a <= b)),
+            BinaryOp::GtEq => Ok(Value::Bool(a >= b)),
+            _ => mismatch(),
+        },
+        (Value::Bool(a), Value::Bool(b)) => match op {
+            BinaryOp::EqEq => Ok(Value::Bool(a == b)),
+            BinaryOp::BangEq => Ok(Value::Bool(a != b)),
+            BinaryOp::Amp => Ok(Value::Bool(a & b)),
+            BinaryOp::Pipe => Ok(Value::Bool(a | b)),
+            BinaryOp::Caret => Ok(Value::Bool(a ^ b)),
+            _ => mismatch(),
+        },
+        _ => mismatch(),
+    }
+}
+
+/// Evaluates every constant and static assertion in a module, storing the values of the constants in it.
+pub fn const_eval(module: &mut hir::Module, target: Target) -> Vec<ConstError> {
+    let mut errors = vec![];
+    let mut eval = ConstEval::new(module, target);
+
+    for item in &module.items {
+        if let hir::ItemKind::Const(..) = item.kind {
+            if eval.failed.contains(&item.name) {
+                continue;
+            }
+
+            eval.steps = 0;
+            if let Err(e) = eval.eval_const(&item.span, &item.name) {
+                errors.push(e);
+            }
+        }
+
+        if let hir::ItemKind::Type(Some(hir::Type { kind: hir::TypeKind::Enum(_), .. })) = item.kind {
+            if !eval.failed.contains(&item.name) {
+                eval.steps = 0;
+                if let Err(e) = eval.eval_discriminants(&item.span, &item.name) {
+                    errors.push(e);
+                }
+            }
+        }
+
+        for attr in item.attrs.iter().filter(|attr| attr.name == "static_assert") {
+            // a malformed assertion has already been reported by the attribute check.
+            let Some(arg) = attr.args.first() else { continue };
+            match eval.eval_root(arg) {
+                Ok(Value::Bool(true)) => {},
+                Ok(Value::Bool(false)) => errors.push(ConstError { span: attr.span.clone(), message: "static assertion failed".into(), code: "E0022" }),
+                Ok(value) => errors.push(ConstError { span: arg.span.clone(), message: format!("expected `bool`, found {}", value.describe()), code: "E0022" }),
+                Err(e) => errors.push(e),
+            }
+        }
+    }
+
+    let (values, discriminants) = eval.into_values();
+    module.consts = values;
+    module.discriminants = discriminants;
+    errors
+}
@@ -0,0 +1,253 @@
+//! Reports diagnostics to the user.
+
+use std::io::IsTerminal;
+use std::process::ExitCode;
+
+use clap::ValueEnum;
+use hail_parser::ast::Span;
+use hail_parser::diagnostics::{Diagnostic, FileId, Files, LabelStyle};
+use serde::Serialize;
+
+/// The version of the JSON that diagnostics are emitted as with `--error-format=json`, which goes up whenever a field
+/// is removed or changes meaning.
+///
+/// The format is described in `docs/diagnostics-json.md`.
+pub const JSON_VERSION: u32 = 1;
+
+/// The formats that diagnostics can be emitted in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ErrorFormat {
+    /// Snippets of the source with the problems underlined, for people.
+    Human,
+
+    /// One JSON object per line, for tools.
+    Json,
+
+    /// One line per diagnostic, as `file:line:col: error: message`.
+    Short,
+}
+
+/// Emits diagnostics to stderr, counting them so that the compiler knows how to exit.
+pub struct Emitter {
+    /// The sources that diagnostics point into.
+    pub files: Files,
+
+    /// The format that diagnostics are emitted in.
+    format: ErrorFormat,
+
+    /// Whether or not diagnostics are rendered in color.
+    color: bool,
+
+    /// The number of errors emitted.
+    errors: usize,
+
+    /// The number of warnings emitted.
+    warnings: usize,
+}
+
+impl Emitter {
+    /// Creates an emitter, which renders in color if stderr is a terminal and `NO_COLOR` is not set.
+    pub fn new(format: ErrorFormat) -> Self {
+        Self {
+            files: Files::new(),
+            format,
+            color: std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            errors: 0,
+            warnings: 0,
+        }
+    }
+
+    /// Emits a diagnostic.
+    pub fn emit(&mut self, diagnostic: &Diagnostic) {
+        if diagnostic.is_error() {
+            self.errors += 1;
+        } else {
+            self.warnings += 1;
+        }
+
+        match self.format {
+            ErrorFormat::Human => eprintln!("{}", diagnostic.render(&self.files, self.color)),
+            ErrorFormat::Json => eprintln!("{}", serde_json::to_string(&JsonDiagnostic::new(&self.files, diagnostic)).unwrap()),
+            ErrorFormat::Short => eprintln!("{}", self.short(diagnostic)),
+        }
+    }
+
+    /// Renders a diagnostic on one line, prefixed with where its primary label is.
+    fn short(&self, diagnostic: &Diagnostic) -> String {
+        let header = match diagnostic.code {
+            Some(code) => format!("{}[{}]: {}", diagnostic.severity.name(), code, diagnostic.message),
+            None => format!("{}: {}", diagnostic.severity.name(), diagnostic.message),
+        };
+
+        match (diagnostic.file, diagnostic.primary()) {
+            (Some(file), Some(label)) => {
+                let (line, column) = self.files.line_col(file, label.span.start);
+                format!("{}:{}:{}: {}", self.files.name(file), line, column, header)
+            },
+            (Some(file), None) => format!("{}: {}", self.files.name(file), header),
+            (None, _) => header,
+        }
+    }
+
+    /// Emits a summary of the diagnostics emitted, and returns the status that the compiler should exit with.
+    pub fn finish(&self) -> ExitCode {
+        let plural = |count: usize, noun: &str| format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" });
+
+        // tools reading JSON count the diagnostics themselves.
+        let summary = match (self.errors, self.warnings) {
+            _ if self.format == ErrorFormat::Json => None,
+            (0, 0) => None,
+            (0, warnings) => Some(Diagnostic::warning(format!("{} emitted", plural(warnings, "warning")))),
+            (errors, 0) => Some(Diagnostic::error(format!("aborting due to {}", plural(errors, "previous error")))),
+            (errors, warnings) => Some(Diagnostic::error(format!(
+                "aborting due to {}; {} emitted",
+                plural(errors, "previous error"),
+                plural(warnings, "warning"),
+            ))),
+        };
+
+        if let Some(summary) = summary {
+            match self.format {
+                ErrorFormat::Short => eprintln!("{}", self.short(&summary)),
+                _ => eprint!("{}", summary.render(&self.files, self.color)),
+            }
+        }
+
+        if self.errors > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+    }
+}
+
+impl Default for Emitter {
+    fn default() -> Self {
+        Self::new(ErrorFormat::Human)
+    }
+}
+
+/// A diagnostic as JSON.
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    /// The version of the format, which is always [`JSON_VERSION`].
+    version: u32,
+
+    /// `"error"` or `"warning"`.
+    severity: &'static str,
+
+    /// The code of the diagnostic, such as `"E0001"`, if it has one.
+    code: Option<&'static str>,
+
+    /// A description of the problem.
+    message: &'a str,
+
+    /// The name of the file that the spans point into, if the diagnostic is about a file.
+    file: Option<&'a str>,
+
+    /// The spans that the diagnostic points to.
+    spans: Vec<JsonSpan<'a>>,
+
+    /// The notes and help lines under the diagnostic.
+    children: Vec<JsonChild<'a>>,
+
+    /// The diagnostic as `--error-format=human` renders it, without color.
+    rendered: String,
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    fn new(files: &'a Files, diagnostic: &'a Diagnostic) -> Self {
+        let spans = match diagnostic.file {
+            Some(file) => diagnostic
+                .labels
+                .iter()
+                .map(|label| JsonSpan {
+                    range: JsonRange::new(files, file, &label.span),
+                    primary: label.style == LabelStyle::Primary,
+                    label: Some(label.message.as_str()).filter(|message| !message.is_empty()),
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        let children = diagnostic
+            .notes
+            .iter()
+            .map(|note| JsonChild {
+                severity: note.kind.name(),
+                message: &note.message,
+                replacement: match (diagnostic.file, &note.replacement) {
+                    (Some(file), Some(replacement)) => Some(JsonReplacement {
+                        range: JsonRange::new(files, file, &replacement.span),
+                        text: &replacement.text,
+                    }),
+                    _ => None,
+                },
+            })
+            .collect();
+
+        Self {
+            version: JSON_VERSION,
+            severity: diagnostic.severity.name(),
+            code: diagnostic.code,
+            message: &diagnostic.message,
+            file: diagnostic.file.map(|file| files.name(file)),
+            spans,
+            children,
+            rendered: diagnostic.render(files, false),
+        }
+    }
+}
+
+/// A span of a file as JSON, as byte offsets and as lines and columns. Lines and columns start at one, columns count
+/// characters, and the ends are exclusive.
+#[derive(Serialize)]
+struct JsonRange {
+    byte_start: usize,
+    byte_end: usize,
+    line_start: usize,
+    column_start: usize,
+    line_end: usize,
+    column_end: usize,
+}
+
+impl JsonRange {
+    fn new(files: &Files, file: FileId, span: &Span) -> Self {
+        let (line_start, column_start) = files.line_col(file, span.start);
+        let (line_end, column_end) = files.line_col(file, span.end);
+
+        Self { byte_start: span.start, byte_end: span.end, line_start, column_start, line_end, column_end }
+    }
+}
+
+/// A labeled span of a diagnostic as JSON.
+#[derive(Serialize)]
+struct JsonSpan<'a> {
+    #[serde(flatten)]
+    range: JsonRange,
+
+    /// Whether the span marks the cause of the diagnostic.
+    primary: bool,
+
+    /// The message of the span, if it has one.
+    label: Option<&'a str>,
+}
+
+/// A note or help line of a diagnostic as JSON.
+#[derive(Serialize)]
+struct JsonChild<'a> {
+    /// `"note"` or `"help"`.
+    severity: &'static str,
+
+    /// The text of the line.
+    message: &'a str,
+
+    /// The edit that the line suggests, if any.
+    replacement: Option<JsonReplacement<'a>>,
+}
+
+/// A suggested edit as JSON.
+#[derive(Serialize)]
+struct JsonReplacement<'a> {
+    #[serde(flatten)]
+    range: JsonRange,
+
+    /// The text to replace the span with.
+    text: &'a str,
+}
@@ -0,0 +1,98 @@
+//! Compile time flags, and the evaluator for conditional compilation.
+
+use std::collections::{HashMap, HashSet};
+
+use hail_parser::ast;
+use target_lexicon::Triple;
+
+/// The flags enabled for a compilation.
+#[derive(Clone, Debug, Default)]
+pub struct Flags {
+    /// Flags which are simply set, such as `os_windows`.
+    names: HashSet<String>,
+
+    /// Key/value flags, such as `target_arch=x86_64`.
+    values: HashMap<String, String>,
+}
+
+impl Flags {
+    /// Creates the default flags for the provided target.
+    pub fn for_target(target: &Triple) -> Self {
+        let mut flags = Self::default();
+        flags.set_value("target_arch", &target.architecture.to_string());
+        flags.set_value("target_os", &target.operating_system.to_string());
+        flags
+    }
+
+    /// Sets a flag.
+    pub fn set(&mut self, name: &str) {
+        self.names.insert(name.into());
+    }
+
+    /// Sets the value of a key/value flag, replacing the old value.
+    pub fn set_value(&mut self, key: &str, value: &str) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    /// Sets a flag from the command line, either `name` or `key=value`.
+    pub fn set_arg(&mut self, arg: &str) -> Result<(), String> {
+        let (key, value) = match arg.split_once('=') {
+            Some((key, value)) => (key, Some(value)),
+            None => (arg, None),
+        };
+
+        if key.is_empty() || value == Some("") {
+            return Err(format!("invalid flag `{}`, expected `name` or `key=value`", arg));
+        }
+
+        match value {
+            Some(value) => self.set_value(key, value),
+            None => self.set(key),
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether a flag expression is satisfied.
+    pub fn eval(&self, expr: &ast::FlagExpr) -> bool {
+        match expr {
+            ast::FlagExpr::Name(name) => self.names.contains(name.value),
+            ast::FlagExpr::Value { name, value, .. } => {
+                self.values.get(name.value).map(String::as_str) == Some(unescape(&value.value).as_str())
+            },
+            ast::FlagExpr::Any { items, .. } => items.iter().any(|item| self.eval(item)),
+            ast::FlagExpr::All { items, .. } => items.iter().all(|item| self.eval(item)),
+            ast::FlagExpr::Not { item, .. } => !self.eval(item),
+        }
+    }
+
+    /// Checks whether all of the flags on a statement are satisfied.
+    pub fn eval_all(&self, flags: &[ast::Flag]) -> bool {
+        flags.iter().all(|flag| self.eval(&flag.expr) != flag.neg)
+    }
+}
+
+/// Gets the text of a string literal, stripping its quotes and replacing its escapes.
+fn unescape(literal: &str) -> String {
+    let mut text = String::new();
+    let mut chars = literal[1..literal.len() - 1].chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+
+        // the lexer only accepts a backslash which is followed by another character.
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some('0') => text.push('\0'),
+            Some(c) => text.push(c),
+            None => {},
+        }
+    }
+
+    text
+}
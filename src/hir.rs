@@ -0,0 +1,665 @@
+//! The high level intermediate representation of hail units.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use hail_parser::ast::{AssignOp, BinaryOp, NumKind, Span, UnaryOp};
+
+use crate::const_eval::Value;
+
+/// A property of a struct type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    /// The location of the property.
+    pub span: Span,
+
+    /// Whether or not the property is shared with other modules.
+    pub shared: bool,
+
+    /// The attributes applied to the property.
+    pub attrs: Vec<Attr>,
+
+    /// The name of the property.
+    pub name: String,
+
+    /// The type of the property.
+    pub ty: Type,
+
+    /// The width of the property in bits, if it is a bitfield.
+    pub bits: Option<u32>,
+}
+
+/// A property of an enum type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+    /// The location of the property.
+    pub span: Span,
+
+    /// The attributes applied to the property.
+    pub attrs: Vec<Attr>,
+
+    /// The name of the property.
+    pub name: String,
+
+    /// The type of the property, if any.
+    pub ty: Option<Type>,
+
+    /// The explicit discriminant of the property, if any.
+    pub value: Option<Expr>,
+}
+
+/// The kind of a type expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeKind {
+    /// A named type, such as `i32`.
+    Name(String),
+
+    /// A path to a type, such as `unit::MyStruct`.
+    Path(Box<Type>, String),
+
+    /// A routine type, which is a plain pointer to the routine's code and can be passed to C.
+    Routine(Vec<Type>, Option<Box<Type>>),
+
+    /// A closure type, `closure(T) -> R`, which pairs a pointer to the environment that a routine literal captured
+    /// with a pointer to its code.
+    Closure(Vec<Type>, Option<Box<Type>>),
+
+    /// A struct type.
+    Struct(Vec<Field>),
+
+    /// An untagged union type.
+    Union(Vec<Field>),
+
+    /// An enum type.
+    Enum(Vec<Variant>),
+
+    /// A contract type.
+    Contract(Vec<Item>),
+
+    /// A `shared` type.
+    Shared(Box<Type>),
+
+    /// A `fluid` type.
+    Fluid(Box<Type>),
+
+    /// A reference type, `&T`.
+    Ref(Box<Type>),
+
+    /// A contract object type, `dyn C`, which has no size and is only used behind a reference. A reference to a
+    /// contract object pairs a pointer to the value with a pointer to the vtable of its type.
+    Dyn(Box<Type>),
+
+    /// The variadic arguments at the end of a routine's arguments. `...` passes them the way C does, while hail
+    /// routines take `...VarArg`, a slice of arguments which are each tagged with their type.
+    Variadic(Option<Box<Type>>),
+
+    /// An option type, `?T`.
+    Opt(Box<Type>),
+
+    /// A result type, `!T:E`.
+    Res(Box<Type>, Box<Type>),
+}
+
+/// The integer types built in to hail.
+pub const INT_TYPES: &[&str] = &["i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize"];
+
+/// The floating point types built in to hail.
+pub const FLOAT_TYPES: &[&str] = &["f32", "f64"];
+
+/// The name of the type that the arguments passed to hail variadics are tagged with.
+pub const VAR_ARG: &str = "VarArg";
+
+/// The name that the `convert` routine of an application of `From` is lowered to. It is distinct for each type
+/// converted from, so that `From` can be applied to a type once for each of them.
+pub fn conversion_name(from: &Type) -> String {
+    format!("convert#{}", from)
+}
+
+/// The built in `VarArg` enum, which holds an argument passed to hail variadics along with the kind of value it
+/// is. Smaller integers are widened to 64 bits, and references are passed as `&u8`.
+pub fn var_arg() -> Type {
+    let variant = |name: &str, ty: TypeKind| Variant {
+        span: 0..0,
+        attrs: vec![],
+        name: name.into(),
+        ty: Some(Type { span: 0..0, kind: ty }),
+        value: None,
+    };
+    let name = |name: &str| TypeKind::Name(name.into());
+
+    Type {
+        span: 0..0,
+        kind: TypeKind::Enum(vec![
+            variant("Int", name("i64")),
+            variant("UInt", name("u64")),
+            variant("Float", name("f64")),
+            variant("Bool", name("bool")),
+            variant("Ref", TypeKind::Ref(Box::new(Type { span: 0..0, kind: name("u8") }))),
+        ]),
+    }
+}
+
+/// A type expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Type {
+    /// The location of the type.
+    pub span: Span,
+
+    /// The kind of the type.
+    pub kind: TypeKind,
+}
+
+impl Type {
+    /// The type without any `shared` or `fluid` modifiers.
+    pub fn unmodified(&self) -> &Type {
+        match &self.kind {
+            TypeKind::Shared(ty) | TypeKind::Fluid(ty) => ty.unmodified(),
+            _ => self,
+        }
+    }
+
+    /// Whether or not the type is marked `fluid`, so that values of it can be changed.
+    pub fn is_fluid(&self) -> bool {
+        match &self.kind {
+            TypeKind::Fluid(_) => true,
+            TypeKind::Shared(ty) => ty.is_fluid(),
+            _ => false,
+        }
+    }
+
+    /// Whether or not the type is marked `shared`.
+    pub fn is_shared(&self) -> bool {
+        match &self.kind {
+            TypeKind::Shared(_) => true,
+            TypeKind::Fluid(ty) => ty.is_shared(),
+            _ => false,
+        }
+    }
+
+    /// Whether or not the type is a built in primitive, such as `i32` or `bool`.
+    pub fn is_prim(&self) -> bool {
+        match &self.unmodified().kind {
+            TypeKind::Name(name) => INT_TYPES.contains(&name.as_str()) || FLOAT_TYPES.contains(&name.as_str()) || name == "bool",
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TypeKind::Name(name) => write!(f, "{}", name),
+            TypeKind::Path(left, right) => write!(f, "{}::{}", left, right),
+            TypeKind::Routine(args, returns) | TypeKind::Closure(args, returns) => {
+                match &self.kind {
+                    TypeKind::Closure(..) => write!(f, "closure(")?,
+                    _ => write!(f, "routine(")?,
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")?;
+
+                match returns {
+                    Some(returns) => write!(f, " -> {}", returns),
+                    None => Ok(()),
+                }
+            },
+            TypeKind::Struct(_) => write!(f, "struct {{ .. }}"),
+            TypeKind::Union(_) => write!(f, "union {{ .. }}"),
+            TypeKind::Enum(_) => write!(f, "enum {{ .. }}"),
+            TypeKind::Contract(_) => write!(f, "contract {{ .. }}"),
+            TypeKind::Shared(ty) => write!(f, "shared {}", ty),
+            TypeKind::Fluid(ty) => write!(f, "fluid {}", ty),
+            TypeKind::Ref(ty) => write!(f, "&{}", ty),
+            TypeKind::Dyn(ty) => write!(f, "dyn {}", ty),
+            TypeKind::Variadic(Some(ty)) => write!(f, "...{}", ty),
+            TypeKind::Variadic(None) => write!(f, "..."),
+            TypeKind::Opt(ty) => write!(f, "?{}", ty),
+            TypeKind::Res(ok, err) => write!(f, "!{}:{}", ok, err),
+        }
+    }
+}
+
+/// An argument of a routine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arg {
+    /// The location of the argument.
+    pub span: Span,
+
+    /// The name of the argument.
+    pub name: String,
+
+    /// The type of the argument.
+    pub ty: Type,
+}
+
+/// A routine literal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Routine {
+    /// The location of the routine.
+    pub span: Span,
+
+    /// The arguments of the routine.
+    pub args: Vec<Arg>,
+
+    /// The type that the routine returns.
+    pub returns: Option<Type>,
+
+    /// The body of the routine.
+    pub block: Block,
+
+    /// The locals of the routines around the literal that it captures.
+    pub captures: Vec<Capture>,
+}
+
+/// How a routine literal captures a local of the routines around it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CaptureMode {
+    /// The literal reads the local through a reference.
+    Ref,
+
+    /// The literal changes the local through a `fluid` reference.
+    FluidRef,
+
+    /// The literal holds its own copy of a `shared` local.
+    Value,
+}
+
+/// A local captured by a routine literal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Capture {
+    /// The location of the first use of the local in the literal.
+    pub span: Span,
+
+    /// The name of the local.
+    pub name: String,
+
+    /// How the local is captured.
+    pub mode: CaptureMode,
+}
+
+/// An operand of an inline assembly block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsmOperand {
+    /// The location of the operand.
+    pub span: Span,
+
+    /// The constraint of the operand, without its quotes.
+    pub constraint: String,
+
+    /// The value bound to the operand.
+    pub expr: Expr,
+}
+
+/// An inline assembly block, kept as written. Blocks are only checked and stored, as hail has no code generator
+/// yet, so nothing passes them through to emitted code.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Asm {
+    /// The assembly template, without its quotes.
+    pub template: String,
+
+    /// The output operands of the block.
+    pub outputs: Vec<AsmOperand>,
+
+    /// The input operands of the block.
+    pub inputs: Vec<AsmOperand>,
+
+    /// The registers and memory clobbered by the block, without their quotes.
+    pub clobbers: Vec<String>,
+
+    /// The options of the block.
+    pub options: Vec<String>,
+}
+
+/// The early return taken by a `?` expression on `none` or `err`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TryExit {
+    /// The routine that converts the error to the error type of the routine, found through an applied `From` contract.
+    pub convert: Option<Expr>,
+
+    /// The deferred statements that run before returning.
+    pub defers: Vec<Stmt>,
+}
+
+/// The kind of an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExprKind {
+    /// A boolean literal.
+    Bool(bool),
+
+    /// A number literal.
+    Num(NumKind, String),
+
+    /// A string literal, including its quotes.
+    Str(String),
+
+    /// A name, such as a variable.
+    Name(String),
+
+    /// A path, such as `unit::item`.
+    Path(Box<Expr>, String),
+
+    /// A property access, such as `value.prop`.
+    Access(Box<Expr>, String),
+
+    /// A call.
+    Call(Box<Expr>, Vec<Expr>),
+
+    /// A unary expression.
+    Unary(UnaryOp, Box<Expr>),
+
+    /// An `as` expression.
+    As(Box<Expr>, Type),
+
+    /// A binary expression.
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+
+    /// A routine literal.
+    Routine(Box<Routine>),
+
+    /// A struct constructor.
+    Construct(Box<Expr>, Vec<(String, Expr)>),
+
+    /// An enum constructor.
+    ConstructEnum(Box<Expr>, Box<Expr>),
+
+    /// An inline assembly block.
+    Asm(Box<Asm>),
+
+    /// An `ok(value)` result constructor.
+    Ok(Box<Expr>),
+
+    /// An `err(value)` result constructor.
+    Err(Box<Expr>),
+
+    /// A `none` option.
+    None,
+
+    /// A `?` expression.
+    Try(Box<Expr>, Box<TryExit>),
+
+    /// An index expression, `value[index]`, on a type which does not overload indexing.
+    Index(Box<Expr>, Box<Expr>),
+
+    /// A reference converted to a reference to a contract object, `&T` to `&dyn C`, which pairs it with the vtable
+    /// of `T` for `C`.
+    Upcast(Box<Expr>, Type),
+
+    /// The variadic arguments of a call to a hail routine, which are passed as a slice of `VarArg`s.
+    VarArgs(Vec<Expr>),
+
+    /// A call to a method of a contract object through its vtable, with the reference to the object followed by
+    /// the name of the method and its arguments.
+    Dispatch(Box<Expr>, String, Vec<Expr>),
+}
+
+/// An expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expr {
+    /// The location of the expression.
+    pub span: Span,
+
+    /// The kind of the expression.
+    pub kind: ExprKind,
+}
+
+/// A case of a `match` statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchCase {
+    /// The location of the case.
+    pub span: Span,
+
+    /// The name that the value is bound to.
+    pub name: String,
+
+    /// The type that the case matches.
+    pub ty: Type,
+
+    /// The block of the case.
+    pub block: Block,
+}
+
+/// The kind of a statement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StmtKind {
+    /// An assignment.
+    Assign(AssignOp, Expr, Expr),
+
+    /// A variable declaration.
+    Val(String, Option<Type>, Option<Expr>),
+
+    /// A type declaration.
+    Type(String, Option<Type>),
+
+    /// An expression evaluated for its side effects.
+    Expr(Expr),
+
+    /// An `if` statement; `else if` branches are nested in the `else` block.
+    If(Expr, Block, Option<Block>),
+
+    /// A `while` statement, with its label.
+    While(Option<String>, Expr, Block),
+
+    /// A `match` statement.
+    Match(Expr, Vec<MatchCase>),
+
+    /// A `break` statement, with its label.
+    Break(Option<String>),
+
+    /// A `continue` statement, with its label.
+    Continue(Option<String>),
+
+    /// A `return` statement.
+    Return(Option<Expr>),
+
+    /// A nested block.
+    Block(Block),
+}
+
+/// A statement.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stmt {
+    /// The location of the statement.
+    pub span: Span,
+
+    /// The kind of the statement.
+    pub kind: StmtKind,
+}
+
+impl Stmt {
+    /// Whether or not control flow never continues past this statement.
+    pub fn diverges(&self) -> bool {
+        matches!(self.kind, StmtKind::Break(_) | StmtKind::Continue(_) | StmtKind::Return(_))
+    }
+}
+
+/// A code block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    /// The location of the block.
+    pub span: Span,
+
+    /// The statements in the block.
+    pub stmts: Vec<Stmt>,
+}
+
+/// An attribute applied to an item.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attr {
+    /// The location of the attribute.
+    pub span: Span,
+
+    /// The name of the attribute, without the `#`.
+    pub name: String,
+
+    /// The arguments of the attribute.
+    pub args: Vec<Expr>,
+}
+
+/// Finds the attribute with the provided name.
+pub fn find_attr<'a>(attrs: &'a [Attr], name: &str) -> Option<&'a Attr> {
+    attrs.iter().find(|attr| attr.name == name)
+}
+
+/// Parses an integer literal of any base.
+pub fn parse_int(kind: &NumKind, value: &str) -> Option<u64> {
+    match kind {
+        NumKind::Int => value.parse().ok(),
+        NumKind::XInt => u64::from_str_radix(&value[2..], 16).ok(),
+        NumKind::BInt => u64::from_str_radix(&value[2..], 2).ok(),
+        NumKind::Float => None,
+    }
+}
+
+/// The representation named by the `#repr` attribute of a declaration, such as `C` or `u8`.
+pub fn repr(attrs: &[Attr]) -> Option<&str> {
+    find_attr(attrs, "repr").and_then(|attr| match attr.args.first().map(|arg| &arg.kind) {
+        Some(ExprKind::Name(name)) => Some(name.as_str()),
+        _ => None,
+    })
+}
+
+/// The alignment requested by the `#align` attribute of a declaration or property.
+pub fn align(attrs: &[Attr]) -> Option<u64> {
+    find_attr(attrs, "align").and_then(|attr| match attr.args.first().map(|arg| &arg.kind) {
+        Some(ExprKind::Num(kind, value)) => parse_int(kind, value),
+        _ => None,
+    })
+}
+
+/// The integer type that stores the tag of an enum, given the attributes of its declaration.
+///
+/// This is the type named by `#repr`, or `i32` when the enum is `#repr(C)` or any property has an explicit
+/// discriminant so that C enums can be mirrored. Otherwise it is `None`, and the size of the tag depends only on
+/// the number of properties.
+pub fn enum_tag<'a>(attrs: &'a [Attr], variants: &[Variant]) -> Option<&'a str> {
+    match repr(attrs) {
+        Some("C") => Some("i32"),
+        Some(repr) => Some(repr),
+        None if variants.iter().any(|variant| variant.value.is_some()) => Some("i32"),
+        None => None,
+    }
+}
+
+/// The kind of an item in the root of a module.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemKind {
+    /// A `val` declaration, which is a static of the module. Statics can only be assigned to when their type is
+    /// `fluid`.
+    Val(Option<Type>, Option<Expr>),
+
+    /// A `const` declaration.
+    Const(Option<Type>, Expr),
+
+    /// A `type` declaration.
+    Type(Option<Type>),
+
+    /// An imported item, with the path of the module it was imported from followed by its name.
+    Import(Vec<String>),
+
+    /// An inline module.
+    Module(Box<Module>),
+}
+
+/// An item declared in the root of a module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+    /// The location of the item.
+    pub span: Span,
+
+    /// Whether or not the item is shared with other modules.
+    pub shared: bool,
+
+    /// The attributes applied to the item.
+    pub attrs: Vec<Attr>,
+
+    /// The name of the item.
+    pub name: String,
+
+    /// The kind of the item.
+    pub kind: ItemKind,
+}
+
+/// An `apply` statement, which attaches items to a type, optionally fulfilling a contract.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Apply {
+    /// The location of the statement.
+    pub span: Span,
+
+    /// The contract being applied, if any.
+    pub contract: Option<Type>,
+
+    /// The type that the items are applied to.
+    pub subject: Type,
+
+    /// The items being applied.
+    pub items: Vec<Item>,
+}
+
+/// How a method receives the value that it is called on, from the type of its `self` argument.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Receiver {
+    /// By value, `self <- T`.
+    Value,
+
+    /// By reference, `self <- &T`.
+    Ref,
+
+    /// By a reference that can change the value, `self <- &fluid T`.
+    FluidRef,
+}
+
+/// A step of a module's initializer.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Init {
+    /// Gives a static the value it was declared with.
+    Static(String),
+
+    /// Runs the initializer of an inline module.
+    Module(String),
+
+    /// Runs a statement from the root of the module.
+    Stmt(Stmt),
+}
+
+/// A module which has been lowered to HIR.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    /// The name of the module.
+    pub name: String,
+
+    /// The items in the root of the module.
+    pub items: Vec<Item>,
+
+    /// The `apply` statements in the root of the module.
+    pub applies: Vec<Apply>,
+
+    /// The values of the module's constants, once they have been evaluated.
+    pub consts: HashMap<String, Value>,
+
+    /// The discriminants of the module's enum types by the name of the type, in the order of their properties.
+    pub discriminants: HashMap<String, Vec<i128>>,
+
+    /// The initializer of the module, which runs once before the program starts, after the initializers of the
+    /// modules it imports. Statics and statements run in the order they were declared.
+    pub init: Vec<Init>,
+
+    /// The files of the modules that this module imports from, with the location of the first import of each.
+    pub deps: Vec<(PathBuf, Span)>,
+
+    /// The types that contract objects are made from along with their contracts, each of which needs a vtable.
+    pub vtables: Vec<(Type, Type)>,
+}
+
+impl Module {
+    /// Finds the item with the provided name.
+    pub fn item(&self, name: &str) -> Option<&Item> {
+        self.items.iter().find(|item| item.name == name)
+    }
+}
@@ -1,9 +1,24 @@
 //! Toolkit for lowering a hail AST to hail HIR.
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
 use hail_parser::ast;
+use hail_parser::diagnostics::{Diagnostic, FileId, Files};
+use hail_parser::visit::{self, Visit};
+
+use crate::attrs::{self, AttrTarget};
+use crate::const_eval;
+use crate::flags::Flags;
+use crate::hir;
+use crate::layout::Target;
+use crate::ops::{self, OpContract};
 
 /// A unit being lowered from AST to HIR.
 pub struct HirLowerUnit<'a> {
+    /// The name of the module that this unit declares.
+    pub name: String,
+
     /// The directory that this unit is stored in.
     pub dir: String,
 
@@ -14,42 +29,2908 @@ pub struct HirLowerUnit<'a> {
 /// A context which HIR units are stored in.
 pub struct HirLowerContext {
     /// The flags that are enabled.
-    pub flags: Vec<String>,
+    pub flags: Flags,
+
+    /// The target being compiled for.
+    pub target: Target,
 
     /// Search paths for libraries.
     pub libs: Vec<String>,
+
+    /// The modules that have been loaded, by the file they were loaded from.
+    pub modules: HashMap<PathBuf, hir::Module>,
+
+    /// The types declared in the root of the module being lowered.
+    pub types: HashMap<String, hir::Type>,
+
+    /// The types of the values declared in the root of the module being lowered, where they are known.
+    pub vals: HashMap<String, hir::Type>,
+
+    /// The `From` contracts applied in the module being lowered, as the types converted from and to.
+    pub conversions: Vec<(hir::Type, hir::Type)>,
+
+    /// The statics declared in the root of the module being lowered, and whether or not each is `fluid`.
+    pub statics: HashMap<String, bool>,
+
+    /// The items applied to types in the module being lowered, by the type they are applied to.
+    pub applied: HashMap<String, Vec<Applied>>,
+
+    /// The contracts applied to types in the module being lowered, by the type they are applied to.
+    pub contracts: HashMap<String, Vec<String>>,
+
+    /// The contract object types used in the module being lowered with whether or not each is behind a reference,
+    /// which are checked once every type in the module is known.
+    pub dyns: Vec<(hir::Type, bool)>,
+
+    /// The types that contract objects are made from in the module being lowered, along with their contracts.
+    pub vtables: Vec<(hir::Type, hir::Type)>,
+
+    /// The routine literals being lowered inside of other routines, from the outermost to the innermost.
+    pub closures: Vec<Closure>,
+
+    /// The inline modules being lowered, from the root of the unit to the innermost.
+    pub inline: Vec<String>,
+
+    /// The properties used on types which aren't declared in the module using them, which are checked once the
+    /// imports of the unit are resolved.
+    pub props: Vec<PropUse>,
+
+    /// The files of the modules imported by the unit whose imports are being resolved.
+    pub deps: Vec<(PathBuf, ast::Span)>,
+
+    /// The files of the loaded modules in the order that their initializers run, before the initializer of the
+    /// unit itself.
+    pub init_order: Vec<PathBuf>,
+
+    /// The source of every unit that has been loaded, which diagnostics point into.
+    pub files: Files,
+
+    /// The files that the loaded modules were read from, by the path they were loaded from.
+    pub sources: HashMap<PathBuf, FileId>,
+
+    /// The file of the unit being lowered, which errors are reported in.
+    pub file: FileId,
+
+    /// The errors found while lowering.
+    pub errors: Vec<Diagnostic>,
+
+    /// The warnings found while lowering, which don't stop compilation.
+    pub warnings: Vec<Diagnostic>,
 }
 
-/// Loads the imports from a lowering unit.
-fn hir_lower_imports<'a>(ctx: &mut HirLowerContext, unit: &HirLowerUnit<'a>) {
-    'imports: for item in &unit.ast {
-        match item {
-            ast::RootStmnt::Import(flags, imp) => {
-                // Make sure all the required flags are enabled.
-                for flag in flags {
-                    if ctx.flags.contains(&flag.name.value.into()) {
-                        if flag.neg {
-                            continue 'imports;
-                        }
-                    } else {
-                        if flag.neg {
-                            continue;
-                        }
+/// An item applied to a type, which is reached as `T::item`, or called as `value.item()` if it is a method.
+#[derive(Clone, Debug)]
+pub struct Applied {
+    /// The name of the item.
+    pub name: String,
+
+    /// The type of the item, if it is known before type checking.
+    pub ty: Option<hir::Type>,
+
+    /// How the item receives the value it is called on, if it is a method.
+    pub receiver: Option<hir::Receiver>,
+
+    /// The contract that the item was applied through, if any.
+    pub contract: Option<String>,
+}
+
+/// A routine literal being lowered, which captures the locals of the routines around it that its body refers to.
+pub struct Closure {
+    /// The locals in scope where the literal appears, with their types where they are known.
+    locals: Vec<(String, Option<hir::Type>)>,
+
+    /// The locals that the literal captures, with the location of their first use and whether or not the literal
+    /// changes them.
+    captures: Vec<(ast::Span, String, bool)>,
+}
+
+/// A property used on a value of a type which isn't declared in the module using it, such as `p.x` or
+/// `Point::{ x = 1 }` where `Point` is imported.
+pub struct PropUse {
+    /// The inline modules that the property is used in, from the root of the unit.
+    module: Vec<String>,
+
+    /// The name of the type that the property belongs to, as it is known in the module using it.
+    ty: String,
+
+    /// The name of the property.
+    name: String,
+
+    /// The location of the use.
+    span: ast::Span,
+}
+
+/// The declarations in the root of a module, which are out of scope in the modules nested in it.
+struct ModuleDecls {
+    /// The types declared in the root of the module.
+    types: HashMap<String, hir::Type>,
+
+    /// The types of the values declared in the root of the module, where they are known.
+    vals: HashMap<String, hir::Type>,
+
+    /// The `From` contracts applied in the module.
+    conversions: Vec<(hir::Type, hir::Type)>,
+
+    /// The statics declared in the root of the module, and whether or not each is `fluid`.
+    statics: HashMap<String, bool>,
+
+    /// The items applied to types in the module.
+    applied: HashMap<String, Vec<Applied>>,
+
+    /// The contracts applied to types in the module.
+    contracts: HashMap<String, Vec<String>>,
+
+    /// The contract object types used in the module, which haven't been checked yet.
+    dyns: Vec<(hir::Type, bool)>,
+
+    /// The types that contract objects are made from in the module, along with their contracts.
+    vtables: Vec<(hir::Type, hir::Type)>,
+}
+
+impl HirLowerContext {
+    /// Creates a context for lowering the unit stored in the provided file.
+    pub fn new(flags: Flags, target: Target, libs: Vec<String>, files: Files, file: FileId) -> Self {
+        Self {
+            flags,
+            target,
+            libs,
+            modules: HashMap::new(),
+            types: HashMap::new(),
+            vals: HashMap::new(),
+            conversions: vec![],
+            statics: HashMap::new(),
+            applied: HashMap::new(),
+            contracts: HashMap::new(),
+            dyns: vec![],
+            vtables: vec![],
+            closures: vec![],
+            inline: vec![],
+            props: vec![],
+            deps: vec![],
+            init_order: vec![],
+            files,
+            sources: HashMap::new(),
+            file,
+            errors: vec![],
+            warnings: vec![],
+        }
+    }
+
+    /// Takes the declarations of the module being lowered, so that another module can be lowered without them.
+    fn take_decls(&mut self) -> ModuleDecls {
+        ModuleDecls {
+            types: std::mem::take(&mut self.types),
+            vals: std::mem::take(&mut self.vals),
+            conversions: std::mem::take(&mut self.conversions),
+            statics: std::mem::take(&mut self.statics),
+            applied: std::mem::take(&mut self.applied),
+            contracts: std::mem::take(&mut self.contracts),
+            dyns: std::mem::take(&mut self.dyns),
+            vtables: std::mem::take(&mut self.vtables),
+        }
+    }
+
+    /// Restores the declarations of a module once another module has been lowered.
+    fn restore_decls(&mut self, decls: ModuleDecls) {
+        self.types = decls.types;
+        self.vals = decls.vals;
+        self.conversions = decls.conversions;
+        self.statics = decls.statics;
+        self.applied = decls.applied;
+        self.contracts = decls.contracts;
+        self.dyns = decls.dyns;
+        self.vtables = decls.vtables;
+    }
+
+    /// Reports an error with a code from [`hail_parser::codes`] at the provided location.
+    fn error(&mut self, code: &'static str, span: ast::Span, message: String) {
+        self.report(Diagnostic::error(message).with_code(code).with_file(self.file).with_primary(span, ""));
+    }
+
+    /// Reports a warning with a code from [`hail_parser::codes`] at the provided location.
+    fn warn(&mut self, code: &'static str, span: ast::Span, message: String) {
+        self.report(Diagnostic::warning(message).with_code(code).with_file(self.file).with_primary(span, ""));
+    }
+
+    /// Reports a diagnostic, as an error or a warning depending on its severity.
+    fn report(&mut self, diagnostic: Diagnostic) {
+        let list = if diagnostic.is_error() { &mut self.errors } else { &mut self.warnings };
+
+        // the signatures of values are lowered more than once, which would report the same diagnostic again.
+        if !list.contains(&diagnostic) {
+            list.push(diagnostic);
+        }
+    }
+}
+
+/// Checks whether all of the provided flags are satisfied.
+fn hir_lower_flags(ctx: &HirLowerContext, flags: &[ast::Flag]) -> bool {
+    ctx.flags.eval_all(flags)
+}
+
+/// The kind of a scope that statements are lowered in.
+#[derive(Clone, Debug, PartialEq)]
+enum ScopeKind {
+    /// The body of a routine, with the type it returns.
+    Routine(Option<hir::Type>),
+
+    /// A plain block.
+    Block,
+
+    /// The body of a loop, with the loop's label.
+    Loop(Option<String>),
+
+    /// A deferred statement, which control flow cannot escape from.
+    Defer,
+
+    /// The value of a declaration marked `#pun`, which can read union properties freely.
+    Pun,
+}
+
+/// A scope that statements are lowered in.
+struct Scope {
+    /// The kind of the scope.
+    kind: ScopeKind,
+
+    /// The statements deferred in this scope, in the order they were declared.
+    defers: Vec<hir::Stmt>,
+
+    /// The names declared outside of the statements deferred in this scope that they use, which can't be declared
+    /// again while the statements are pending. The scope of a deferred statement collects the names that it uses.
+    deferred: Vec<String>,
+
+    /// The variables declared in this scope, with their type annotations.
+    vals: Vec<(String, Option<hir::Type>)>,
+}
+
+impl Scope {
+    /// Creates an empty scope.
+    fn new(kind: ScopeKind) -> Self {
+        Self { kind, defers: vec![], deferred: vec![], vals: vec![] }
+    }
+}
+
+/// Lowers a type expression.
+fn hir_lower_type(ctx: &mut HirLowerContext, ty: &ast::Type) -> hir::Type {
+    let (span, kind) = match ty {
+        ast::Type::Id(id) => (id.span.clone(), hir::TypeKind::Name(id.value.into())),
+        ast::Type::Path(path) => (
+            path.span.clone(),
+            hir::TypeKind::Path(Box::new(hir_lower_type(ctx, &path.left)), path.right.value.into()),
+        ),
+        ast::Type::Routine(routine) => (
+            routine.span.clone(),
+            hir::TypeKind::Routine(
+                hir_lower_arg_types(ctx, &routine.args, true),
+                routine.returns.as_ref().map(|returns| Box::new(hir_lower_type(ctx, returns))),
+            ),
+        ),
+        ast::Type::Closure(closure) => (
+            closure.span.clone(),
+            hir::TypeKind::Closure(
+                hir_lower_arg_types(ctx, &closure.args, false),
+                closure.returns.as_ref().map(|returns| Box::new(hir_lower_type(ctx, returns))),
+            ),
+        ),
+        ast::Type::Struct(struct_) => (struct_.span.clone(), hir::TypeKind::Struct(hir_lower_fields(ctx, &struct_.props))),
+        ast::Type::Union(union) => (union.span.clone(), hir::TypeKind::Union(hir_lower_fields(ctx, &union.props))),
+        ast::Type::Enum(enum_) => (
+            enum_.span.clone(),
+            hir::TypeKind::Enum(enum_.props.iter().map(|prop| hir::Variant {
+                span: prop.span.clone(),
+                attrs: hir_lower_attr_list(ctx, &prop.markers),
+                name: prop.name.value.into(),
+                ty: prop.ty.as_ref().map(|ty| hir_lower_type(ctx, ty)),
+                value: prop.value.as_ref().map(|value| hir_lower_expr(ctx, &mut vec![], value)),
+            }).collect()),
+        ),
+        ast::Type::Contract(contract) => (
+            contract.span.clone(),
+            hir::TypeKind::Contract(contract.items.iter().map(|item| hir_lower_application(ctx, item)).collect()),
+        ),
+        ast::Type::Shared(ty) => (ty.span.clone(), hir::TypeKind::Shared(Box::new(hir_lower_type(ctx, &ty.subject)))),
+        ast::Type::Fluid(ty) => (ty.span.clone(), hir::TypeKind::Fluid(Box::new(hir_lower_type(ctx, &ty.subject)))),
+        ast::Type::Ref(ty) => (ty.span.clone(), hir::TypeKind::Ref(Box::new(hir_lower_ref_subject(ctx, &ty.subject)))),
+        ast::Type::Dyn(ty) => (ty.span.clone(), hir_lower_dyn(ctx, ty, false)),
+        ast::Type::Variadic(ty) => {
+            ctx.error("E0005", ty.span.clone(), "variadic arguments can only be the last argument of a routine".into());
+            return hir_lower_variadic(ctx, ty, true, 1);
+        },
+        ast::Type::Opt(ty) => (ty.span.clone(), hir::TypeKind::Opt(Box::new(hir_lower_type(ctx, &ty.subject)))),
+        ast::Type::Res(ty) => (
+            ty.span.clone(),
+            hir::TypeKind::Res(Box::new(hir_lower_type(ctx, &ty.ok)), Box::new(hir_lower_type(ctx, &ty.err))),
+        ),
+    };
+
+    hir::Type { span, kind }
+}
+
+/// Lowers the types of the arguments of a routine, the last of which may be variadic. C variadics are only allowed
+/// for plain routines, as closures and routine literals are called the way hail calls routines.
+fn hir_lower_arg_types<'a, 't: 'a>(ctx: &mut HirLowerContext, args: impl IntoIterator<Item = &'a ast::Type<'t>>, c_variadic: bool) -> Vec<hir::Type> {
+    let args = args.into_iter().collect::<Vec<_>>();
+    let fixed = args.len().saturating_sub(1);
+
+    args.iter()
+        .enumerate()
+        .map(|(index, arg)| match arg {
+            ast::Type::Variadic(ty) if index == fixed => hir_lower_variadic(ctx, ty, c_variadic, fixed),
+            _ => hir_lower_type(ctx, arg),
+        })
+        .collect()
+}
+
+/// Lowers the variadic arguments of a routine, which follow the provided number of fixed arguments.
+fn hir_lower_variadic(ctx: &mut HirLowerContext, ty: &ast::VariadicType, c_variadic: bool, fixed: usize) -> hir::Type {
+    let inner = ty.ty.as_ref().map(|inner| hir_lower_type(ctx, inner));
+    let message = match &inner {
+        None if !c_variadic => Some(format!("only plain routine types can take C variadics, `...`, hail routines take `...{}`", hir::VAR_ARG)),
+        None if fixed == 0 => Some("C variadic routines must take at least one argument before `...`".to_string()),
+        Some(inner) if inner.to_string() != hir::VAR_ARG => {
+            Some(format!("hail variadics must be passed as `...{}`, not `...{}`", hir::VAR_ARG, inner))
+        },
+        _ => None,
+    };
+
+    if let Some(message) = message {
+        ctx.error("E0005", ty.span.clone(), message);
+    }
+
+    hir::Type { span: ty.span.clone(), kind: hir::TypeKind::Variadic(inner.map(Box::new)) }
+}
+
+/// Lowers the type behind a reference, which may be a contract object.
+fn hir_lower_ref_subject(ctx: &mut HirLowerContext, ty: &ast::Type) -> hir::Type {
+    let (span, kind) = match ty {
+        ast::Type::Dyn(ty) => (ty.span.clone(), hir_lower_dyn(ctx, ty, true)),
+        ast::Type::Shared(ty) => (ty.span.clone(), hir::TypeKind::Shared(Box::new(hir_lower_ref_subject(ctx, &ty.subject)))),
+        ast::Type::Fluid(ty) => (ty.span.clone(), hir::TypeKind::Fluid(Box::new(hir_lower_ref_subject(ctx, &ty.subject)))),
+        _ => return hir_lower_type(ctx, ty),
+    };
+
+    hir::Type { span, kind }
+}
+
+/// Lowers a contract object type, leaving it to be checked once every type in the module is known.
+fn hir_lower_dyn(ctx: &mut HirLowerContext, ty: &ast::DynType, behind_ref: bool) -> hir::TypeKind {
+    let contract = hir_lower_type(ctx, &ty.subject);
+    let kind = hir::TypeKind::Dyn(Box::new(contract));
+    ctx.dyns.push((hir::Type { span: ty.span.clone(), kind: kind.clone() }, behind_ref));
+
+    kind
+}
+
+/// Lowers the properties of a struct or union type.
+fn hir_lower_fields(ctx: &mut HirLowerContext, props: &[ast::StructProp]) -> Vec<hir::Field> {
+    props.iter()
+        .map(|prop| {
+            let ty = hir_lower_type(ctx, &prop.ty);
+            hir::Field {
+                span: prop.span.clone(),
+                shared: prop.shared,
+                attrs: hir_lower_attr_list(ctx, &prop.markers),
+                name: prop.name.value.into(),
+                bits: prop.bits.as_ref().and_then(|bits| hir_lower_bits(ctx, &prop.name, &ty, bits)),
+                ty,
+            }
+        })
+        .collect()
+}
+
+/// Lowers the width of a bitfield, checking that it fits in the type of the property.
+fn hir_lower_bits(ctx: &mut HirLowerContext, name: &ast::Id, ty: &hir::Type, bits: &ast::Num) -> Option<u32> {
+    let size = match &ty.unmodified().kind {
+        hir::TypeKind::Name(name) => match const_eval::IntType::from_name(name, &ctx.target) {
+            Some(int) => int.bits,
+            None => 0,
+        },
+        _ => 0,
+    };
+
+    if size == 0 {
+        ctx.error("E0006", ty.span.clone(), format!("bitfields must have an integer type, not `{}`", ty));
+        return None;
+    }
+
+    match hir::parse_int(&bits.kind, bits.value) {
+        Some(width) if width > 0 && width <= size as u64 => Some(width as u32),
+        Some(0) => {
+            ctx.error("E0006", bits.span.clone(), format!("bitfield `{}` must be at least one bit wide", name.value));
+            None
+        },
+        _ => {
+            ctx.error("E0006", bits.span.clone(), format!("bitfield `{}` is {} bits wide, but `{}` only has {} bits", name.value, bits.value, ty, size));
+            None
+        },
+    }
+}
+
+/// Lowers a routine literal, capturing the locals in the scopes around it that its body refers to.
+fn hir_lower_routine(ctx: &mut HirLowerContext, outer: &[Scope], routine: &ast::Routine) -> hir::Routine {
+    let tys = hir_lower_arg_types(ctx, routine.args.iter().map(|arg| &arg.ty), false);
+    let mut lowered = hir::Routine {
+        span: routine.span.clone(),
+        args: routine.args.iter().zip(tys).map(|(arg, ty)| hir::Arg {
+            span: arg.span.clone(),
+            name: arg.name.value.into(),
+            ty,
+        }).collect(),
+        returns: routine.returns.as_ref().map(|returns| hir_lower_type(ctx, returns)),
+        block: hir::Block { span: routine.block.span.clone(), stmts: vec![] },
+        captures: vec![],
+    };
+
+    // control flow can't leave a routine, so its body starts with a fresh set of scopes. the locals around it are
+    // only reachable by capturing them.
+    let locals = outer.iter().flat_map(|scope| scope.vals.iter().cloned()).collect();
+    ctx.closures.push(Closure { locals, captures: vec![] });
+
+    let mut scopes = vec![Scope::new(ScopeKind::Routine(lowered.returns.clone()))];
+    scopes[0].vals = lowered.args.iter().map(|arg| (arg.name.clone(), Some(arg.ty.clone()))).collect();
+    // a declaration marked with `#pun` covers the reads in the routines it declares.
+    if outer.iter().any(|scope| scope.kind == ScopeKind::Pun) {
+        scopes.push(Scope::new(ScopeKind::Pun));
+    }
+    lowered.block = hir_lower_block(ctx, &mut scopes, &routine.block, ScopeKind::Block);
+
+    let closure = ctx.closures.pop().expect("the literal's closure is pushed above");
+    for (span, name, changed) in closure.captures {
+        let ty = closure.locals.iter().rev().find(|(local, _)| *local == name).and_then(|(_, ty)| ty.clone());
+
+        // locals are captured by reference, unless they are `shared` and only read, which copies the share.
+        let mode = match (changed, &ty) {
+            (true, Some(ty)) if !ty.is_fluid() => {
+                let message = format!("`{}` is changed by a routine literal which captures it, but its type is not `fluid`", name);
+                ctx.error("E0007", span.clone(), message);
+                hir::CaptureMode::FluidRef
+            },
+            (true, _) => hir::CaptureMode::FluidRef,
+            (false, Some(ty)) if ty.is_shared() => hir::CaptureMode::Value,
+            (false, _) => hir::CaptureMode::Ref,
+        };
+
+        lowered.captures.push(hir::Capture { span, name, mode });
+    }
+
+    lowered
+}
+
+/// Records a local of the routines around a routine literal that its body refers to, along with whether or not
+/// the body changes it. Literals nested between the local and the use capture it as well.
+fn hir_lower_capture(ctx: &mut HirLowerContext, scopes: &[Scope], name: &str, span: &ast::Span, changed: bool) {
+    if hir_lower_lookup(scopes, name).is_some() {
+        return;
+    }
+
+    let declared = match ctx.closures.iter().rposition(|closure| closure.locals.iter().any(|(local, _)| local == name)) {
+        Some(declared) => declared,
+        None => return,
+    };
+
+    for closure in &mut ctx.closures[declared..] {
+        match closure.captures.iter_mut().find(|(_, captured, _)| captured == name) {
+            Some(capture) => capture.2 |= changed,
+            None => closure.captures.push((span.clone(), name.into(), changed)),
+        }
+    }
+}
+
+/// Finds the type of a local captured from the routines around a routine literal.
+fn hir_lower_captured<'c>(ctx: &'c HirLowerContext, name: &str) -> Option<&'c Option<hir::Type>> {
+    ctx.closures
+        .iter()
+        .rev()
+        .find_map(|closure| closure.locals.iter().rev().find(|(local, _)| local == name).map(|(_, ty)| ty))
+}
+
+/// Whether or not a name refers to a local, either in scope or captured by a routine literal, rather than an item
+/// of the module.
+fn hir_lower_is_local(ctx: &HirLowerContext, scopes: &[Scope], name: &str) -> bool {
+    hir_lower_lookup(scopes, name).is_some() || hir_lower_captured(ctx, name).is_some()
+}
+
+/// Lowers an expression.
+fn hir_lower_expr(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, expr: &ast::Expr) -> hir::Expr {
+    let boxed = |ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, expr: &ast::Expr| Box::new(hir_lower_expr(ctx, scopes, expr));
 
-                        continue 'imports;
+    let (span, kind) = match expr {
+        ast::Expr::Bool(bool) => (bool.span.clone(), hir::ExprKind::Bool(bool.value)),
+        ast::Expr::Id(id) => {
+            hir_lower_capture(ctx, scopes, id.value, &id.span, false);
+            hir_lower_defer_use(scopes, id.value);
+            (id.span.clone(), hir::ExprKind::Name(id.value.into()))
+        },
+        ast::Expr::Num(num) => (num.span.clone(), hir::ExprKind::Num(num.kind.clone(), num.value.into())),
+        ast::Expr::Str(str) => (str.span.clone(), hir::ExprKind::Str(str.value.to_string())),
+        ast::Expr::Path(path) => {
+            let left = boxed(ctx, scopes, &path.left);
+            hir_lower_type_item(ctx, scopes, &left, &path.right);
+
+            (path.span.clone(), hir::ExprKind::Path(left, path.right.value.into()))
+        },
+        ast::Expr::Access(access) => {
+            let left = boxed(ctx, scopes, &access.left);
+            hir_lower_union_read(ctx, scopes, &left, &access.right);
+            hir_lower_access_use(ctx, scopes, &left, &access.right);
+
+            (access.span.clone(), hir::ExprKind::Access(left, access.right.value.into()))
+        },
+        ast::Expr::Call(call) => (call.span.clone(), hir_lower_call(ctx, scopes, call)),
+        ast::Expr::Unary(unary) => (unary.span.clone(), hir_lower_unary(ctx, scopes, unary)),
+        ast::Expr::As(as_) => {
+            let subject = boxed(ctx, scopes, &as_.subject);
+            let ty = hir_lower_type(ctx, &as_.ty);
+            hir_lower_cast(ctx, scopes, &subject, &ty);
+
+            match hir_lower_coerce(ctx, scopes, *subject, &ty) {
+                hir::Expr { kind: hir::ExprKind::Upcast(subject, ty), .. } => (as_.span.clone(), hir::ExprKind::Upcast(subject, ty)),
+                subject => (as_.span.clone(), hir::ExprKind::As(Box::new(subject), ty)),
+            }
+        },
+        ast::Expr::Binary(binary) => (binary.span.clone(), hir_lower_binary(ctx, scopes, binary)),
+        ast::Expr::Routine(routine) => {
+            let routine = hir_lower_routine(ctx, scopes, routine);
+            for capture in &routine.captures {
+                hir_lower_defer_use(scopes, &capture.name);
+            }
+
+            (routine.span.clone(), hir::ExprKind::Routine(Box::new(routine)))
+        },
+        ast::Expr::Construct(construct) => {
+            let subject = boxed(ctx, scopes, &construct.subject);
+            if let hir::ExprKind::Name(ty) = &subject.kind {
+                if !hir_lower_is_local(ctx, scopes, ty) {
+                    for item in &construct.items {
+                        hir_lower_prop_use(ctx, ty, &item.name);
                     }
                 }
-                
-                println!("IMPORT!!");
+            }
+
+            let items = construct.items.iter().map(|item| (item.name.value.into(), hir_lower_expr(ctx, scopes, &item.value))).collect();
+            (construct.span.clone(), hir::ExprKind::Construct(subject, items))
+        },
+        ast::Expr::Asm(asm) => (asm.span.clone(), hir::ExprKind::Asm(Box::new(hir_lower_asm(ctx, scopes, asm)))),
+        ast::Expr::ConstructEnum(construct) => (
+            construct.span.clone(),
+            hir::ExprKind::ConstructEnum(boxed(ctx, scopes, &construct.subject), boxed(ctx, scopes, &construct.item)),
+        ),
+        ast::Expr::Ok(ok) => (ok.span.clone(), hir::ExprKind::Ok(boxed(ctx, scopes, &ok.item))),
+        ast::Expr::Err(err) => (err.span.clone(), hir::ExprKind::Err(boxed(ctx, scopes, &err.item))),
+        ast::Expr::None(none) => (none.span.clone(), hir::ExprKind::None),
+        ast::Expr::Try(try_) => (try_.span.clone(), hir_lower_try(ctx, scopes, try_)),
+        ast::Expr::Index(index) => (index.span.clone(), hir_lower_index(ctx, scopes, index, false)),
+    };
+
+    hir::Expr { span, kind }
+}
+
+/// Lowers a call expression.
+fn hir_lower_call(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, call: &ast::Call) -> hir::ExprKind {
+    let access = match &*call.left {
+        ast::Expr::Access(access) => access,
+        _ => {
+            let callee = hir_lower_expr(ctx, scopes, &call.left);
+            let args = call.args.iter().map(|arg| hir_lower_expr(ctx, scopes, arg)).collect();
+            let args = hir_lower_coerce_args(ctx, scopes, &callee, args);
+            return hir::ExprKind::Call(Box::new(callee), args);
+        },
+    };
 
+    // `value.item(..)` calls a method applied to the type of the value, unless the value has a property with
+    // that name.
+    let receiver = hir_lower_expr(ctx, scopes, &access.left);
+    if let Some((hir::TypeKind::Dyn(contract), by_ref)) = hir_lower_receiver_type(ctx, scopes, &receiver).map(|(ty, by_ref)| (ty.kind, by_ref)) {
+        hir_lower_dispatch(ctx, &contract, by_ref, access);
 
+        let args = call.args.iter().map(|arg| hir_lower_expr(ctx, scopes, arg)).collect();
+        return hir::ExprKind::Dispatch(Box::new(receiver), access.right.value.into(), args);
+    }
+
+    let callee = match hir_lower_method(ctx, scopes, &receiver, access) {
+        Some((callee, receiver)) => {
+            let mut args = vec![receiver];
+            args.extend(call.args.iter().map(|arg| hir_lower_expr(ctx, scopes, arg)));
+            let args = hir_lower_coerce_args(ctx, scopes, &callee, args);
+            return hir::ExprKind::Call(Box::new(callee), args);
+        },
+        None => {
+            hir_lower_union_read(ctx, scopes, &receiver, &access.right);
+            hir_lower_access_use(ctx, scopes, &receiver, &access.right);
+            hir::Expr { span: access.span.clone(), kind: hir::ExprKind::Access(Box::new(receiver), access.right.value.into()) }
+        },
+    };
+
+    let args = call.args.iter().map(|arg| hir_lower_expr(ctx, scopes, arg)).collect();
+    let args = hir_lower_coerce_args(ctx, scopes, &callee, args);
+    hir::ExprKind::Call(Box::new(callee), args)
+}
+
+/// Checks that a method called on a contract object is declared by its contract, and that a method which takes
+/// `&fluid Self` is called through a `fluid` reference.
+fn hir_lower_dispatch(ctx: &mut HirLowerContext, contract: &hir::Type, by_ref: Option<bool>, access: &ast::Access) {
+    let items = match ctx.types.get(&contract.to_string()).map(|decl| &hir_lower_resolve(ctx, decl).kind) {
+        Some(hir::TypeKind::Contract(items)) => items,
+        _ => return,
+    };
+
+    let name = access.right.value;
+    let (code, message) = match items.iter().find(|item| item.name == name).map(|item| &item.kind) {
+        None => ("E0008", format!("`dyn {}` has no method named `{}`", contract, name)),
+        Some(hir::ItemKind::Val(Some(ty), _)) => match &ty.kind {
+            hir::TypeKind::Routine(args, _) if args.first().is_some_and(|arg| match &arg.kind {
+                hir::TypeKind::Ref(inner) => inner.is_fluid(),
+                _ => false,
+            }) && by_ref == Some(false) => {
+                ("E0007", format!("`{}::{}` takes `&fluid Self`, but is called through a reference that is not `fluid`", contract, name))
             },
-            _ => {},
+            _ => return,
+        },
+        Some(_) => return,
+    };
+
+    ctx.error(code, access.span.clone(), message);
+}
+
+/// Finds the contract of a reference to a contract object type, `&dyn C`.
+fn hir_lower_dyn_contract<'t>(ctx: &'t HirLowerContext, ty: &'t hir::Type) -> Option<&'t hir::Type> {
+    match &hir_lower_resolve(ctx, ty).kind {
+        hir::TypeKind::Ref(inner) => match &inner.unmodified().kind {
+            hir::TypeKind::Dyn(contract) => Some(contract),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Converts a reference to a value into a reference to a contract object where one is expected, checking that the
+/// contract is applied to the type of the value and recording the vtable that the conversion needs.
+///
+/// Returns `None` if the value doesn't need to be converted, or its type isn't known before type checking.
+fn hir_lower_upcast(ctx: &mut HirLowerContext, scopes: &[Scope], value: &hir::Expr, ty: &hir::Type) -> Option<hir::Expr> {
+    let contract = hir_lower_dyn_contract(ctx, ty)?.clone();
+    let fluid = match &hir_lower_resolve(ctx, ty).kind {
+        hir::TypeKind::Ref(inner) => inner.is_fluid(),
+        _ => false,
+    };
+
+    let (from, by_ref) = hir_lower_receiver_type(ctx, scopes, value)?;
+    if matches!(from.kind, hir::TypeKind::Dyn(_)) || !hir_lower_is_declared(ctx, &from) {
+        return None;
+    }
+
+    let message = match by_ref {
+        None => format!("only references can be used as `&dyn {}`, not `{}`", contract, from),
+        Some(false) if fluid => format!("a reference that is not `fluid` cannot be used as `&fluid dyn {}`", contract),
+        Some(_) if !ctx.contracts.get(&from.to_string()).is_some_and(|applied| applied.contains(&contract.to_string())) => {
+            format!("`{}` does not apply `{}`, so it cannot be used as `dyn {}`", from, contract, contract)
+        },
+        Some(_) => {
+            if !ctx.vtables.iter().any(|(ty, other)| ty.to_string() == from.to_string() && other.to_string() == contract.to_string()) {
+                ctx.vtables.push((from, contract));
+            }
+
+            return Some(hir::Expr { span: value.span.clone(), kind: hir::ExprKind::Upcast(Box::new(value.clone()), ty.clone()) });
+        },
+    };
+
+    ctx.error("E0009", value.span.clone(), message);
+    None
+}
+
+/// Converts a value to the type it is used as, where it is known, such as `&T` to `&dyn C`. Closures can't be used
+/// as plain routines, since the pointer to their code can't reach the locals they capture.
+fn hir_lower_coerce(ctx: &mut HirLowerContext, scopes: &[Scope], value: hir::Expr, ty: &hir::Type) -> hir::Expr {
+    if matches!(hir_lower_resolve(ctx, ty).kind, hir::TypeKind::Routine(..)) {
+        let message = match (&value.kind, hir_lower_type_of(ctx, scopes, &value).map(|ty| hir_lower_resolve(ctx, &ty).kind.clone())) {
+            (hir::ExprKind::Routine(routine), _) if !routine.captures.is_empty() => Some(format!(
+                "this routine literal captures `{}`, so it can only be used as a `closure`, not the plain routine `{}`",
+                routine.captures[0].name,
+                ty,
+            )),
+            (_, Some(hir::TypeKind::Closure(..))) => Some(format!("a `closure` cannot be used as the plain routine `{}`", ty)),
+            _ => None,
+        };
+
+        if let Some(message) = message {
+            ctx.error("E0010", value.span.clone(), message);
         }
     }
+
+    hir_lower_upcast(ctx, scopes, &value, ty).unwrap_or(value)
 }
 
-/// Lowers a hail AST to hail HIR.
-pub fn hir_lower<'a>(ctx: &mut HirLowerContext, unit: &HirLowerUnit<'a>) {
-    hir_lower_imports(ctx, unit);
-}
\ No newline at end of file
+/// Converts the arguments of a call to the types of the arguments that the routine being called expects, and
+/// passes the arguments after the fixed ones as its variadics.
+fn hir_lower_coerce_args(ctx: &mut HirLowerContext, scopes: &[Scope], callee: &hir::Expr, mut args: Vec<hir::Expr>) -> Vec<hir::Expr> {
+    let mut params = match hir_lower_type_of(ctx, scopes, callee).map(|ty| hir_lower_resolve(ctx, &ty).kind.clone()) {
+        Some(hir::TypeKind::Routine(params, _) | hir::TypeKind::Closure(params, _)) => params,
+        _ => return args,
+    };
+
+    let variadic = match params.last().map(|param| &param.kind) {
+        Some(hir::TypeKind::Variadic(ty)) => {
+            let variadic = ty.is_some();
+            params.pop();
+            Some((variadic, args.split_off(params.len().min(args.len()))))
+        },
+        _ => None,
+    };
+
+    let mut args = args.into_iter()
+        .enumerate()
+        .map(|(index, arg)| match params.get(index) {
+            Some(param) => hir_lower_coerce(ctx, scopes, arg, param),
+            None => arg,
+        })
+        .collect::<Vec<_>>();
+
+    match variadic {
+        Some((false, rest)) => args.extend(rest.into_iter().map(|arg| hir_lower_promote(ctx, scopes, arg))),
+        Some((true, rest)) => {
+            for arg in &rest {
+                hir_lower_var_arg(ctx, scopes, arg);
+            }
+
+            let span = match (rest.first(), rest.last()) {
+                (Some(first), Some(last)) => first.span.start..last.span.end,
+                _ => callee.span.end..callee.span.end,
+            };
+            args.push(hir::Expr { span, kind: hir::ExprKind::VarArgs(rest) });
+        },
+        None => {},
+    }
+
+    args
+}
+
+/// Applies C's default argument promotions to an argument passed through C variadics, where its type is known.
+/// `f32` is passed as `f64`, while `bool` and integers smaller than `i32` are passed as `i32`.
+fn hir_lower_promote(ctx: &HirLowerContext, scopes: &[Scope], arg: hir::Expr) -> hir::Expr {
+    let ty = match hir_lower_type_of(ctx, scopes, &arg) {
+        // modifiers such as `fluid` don't change how a value is passed.
+        Some(ty) => hir_lower_resolve(ctx, ty.unmodified()).unmodified().to_string(),
+        None => return arg,
+    };
+
+    let promoted = match ty.as_str() {
+        "f32" => "f64",
+        "bool" | "i8" | "i16" | "u8" | "u16" => "i32",
+        _ => return arg,
+    };
+
+    let span = arg.span.clone();
+    hir::Expr { span: span.clone(), kind: hir::ExprKind::As(Box::new(arg), hir::Type { span, kind: hir::TypeKind::Name(promoted.into()) }) }
+}
+
+/// Checks that an argument passed through hail variadics can be held by a `VarArg`.
+fn hir_lower_var_arg(ctx: &mut HirLowerContext, scopes: &[Scope], arg: &hir::Expr) {
+    let ty = match hir_lower_type_of(ctx, scopes, arg) {
+        Some(ty) => ty,
+        None => return,
+    };
+
+    let resolved = hir_lower_resolve(ctx, &ty);
+    if !resolved.is_prim() && !matches!(resolved.kind, hir::TypeKind::Ref(_)) {
+        let message = format!("`{}` cannot be passed as a variadic argument, only integers, floats, `bool` and references can", ty);
+        ctx.error("E0005", arg.span.clone(), message);
+    }
+}
+
+/// Checks that `T::item` names a property of an enum or an item applied to a type declared in the module.
+fn hir_lower_type_item(ctx: &mut HirLowerContext, scopes: &[Scope], left: &hir::Expr, item: &ast::Id) {
+    let name = match &left.kind {
+        hir::ExprKind::Name(name) if hir_lower_lookup(scopes, name).is_none() => name,
+        _ => return,
+    };
+
+    let decl = match ctx.types.get(name) {
+        Some(decl) => decl,
+        None => return,
+    };
+
+    let variant = match &hir_lower_resolve(ctx, decl).kind {
+        hir::TypeKind::Enum(variants) => variants.iter().any(|variant| variant.name == item.value),
+        _ => false,
+    };
+    let applied = ctx.applied.get(name).is_some_and(|items| items.iter().any(|applied| applied.name == item.value));
+
+    if !variant && !applied {
+        ctx.error("E0008", item.span.clone(), format!("`{}` has no item named `{}`", name, item.value));
+    }
+}
+
+/// Resolves a method call, returning the method along with the value it is called on, referenced or
+/// dereferenced to match the method's receiver.
+fn hir_lower_method(ctx: &mut HirLowerContext, scopes: &[Scope], receiver: &hir::Expr, access: &ast::Access) -> Option<(hir::Expr, hir::Expr)> {
+    let (ty, by_ref) = hir_lower_receiver_type(ctx, scopes, receiver)?;
+
+    let name = access.right.value;
+    let span = access.span.clone();
+    let item = ctx.applied.get(&ty.to_string()).and_then(|items| items.iter().find(|item| item.name == name));
+    let receiver_kind = match item {
+        Some(Applied { receiver: Some(receiver), .. }) => *receiver,
+        Some(_) => {
+            ctx.error("E0008", span, format!("`{0}::{1}` has no `self` argument, so it must be called as `{0}::{1}(..)`", ty, name));
+            return None;
+        },
+        None => {
+            let fields = match &hir_lower_resolve(ctx, &ty).kind {
+                hir::TypeKind::Struct(fields) | hir::TypeKind::Union(fields) => Some(fields),
+                _ => None,
+            };
+
+            // properties holding routines are called through an access instead.
+            if fields.is_some_and(|fields| fields.iter().any(|field| field.name == name)) {
+                return None;
+            }
+
+            if hir_lower_is_declared(ctx, &ty) {
+                ctx.error("E0008", span, format!("`{}` has no method named `{}`", ty, name));
+            }
+
+            return None;
+        },
+    };
+
+    let receiver = hir_lower_adjust_receiver(ctx, scopes, &span, &ty, name, receiver, receiver_kind, by_ref)?;
+    let callee = hir::Expr {
+        span: access.span.clone(),
+        kind: hir::ExprKind::Path(Box::new(hir::Expr { span: receiver.span.clone(), kind: hir::ExprKind::Name(ty.to_string()) }), name.into()),
+    };
+
+    Some((callee, receiver))
+}
+
+/// Finds the type that methods are looked up on for a value, along with whether the value is a reference and, if
+/// so, whether the reference is `fluid`.
+fn hir_lower_receiver_type(ctx: &HirLowerContext, scopes: &[Scope], receiver: &hir::Expr) -> Option<(hir::Type, Option<bool>)> {
+    let ty = hir_lower_type_of(ctx, scopes, receiver)?;
+    match &ty.unmodified().kind {
+        hir::TypeKind::Ref(inner) => Some((inner.unmodified().clone(), Some(inner.is_fluid()))),
+        _ => Some((ty.unmodified().clone(), None)),
+    }
+}
+
+/// Whether or not a type is a primitive or declared in the module, so that the items applied to it are known.
+fn hir_lower_is_declared(ctx: &HirLowerContext, ty: &hir::Type) -> bool {
+    ty.is_prim() || matches!(&ty.kind, hir::TypeKind::Name(decl) if ctx.types.contains_key(decl))
+}
+
+/// References or dereferences the value that a method is called on to match how the method receives it.
+#[allow(clippy::too_many_arguments)]
+fn hir_lower_adjust_receiver(
+    ctx: &mut HirLowerContext,
+    scopes: &[Scope],
+    span: &ast::Span,
+    ty: &hir::Type,
+    name: &str,
+    receiver: &hir::Expr,
+    kind: hir::Receiver,
+    by_ref: Option<bool>,
+) -> Option<hir::Expr> {
+    let borrow = |op, subject: hir::Expr| hir::Expr { span: subject.span.clone(), kind: hir::ExprKind::Unary(op, Box::new(subject)) };
+    let receiver = match (kind, by_ref) {
+        (hir::Receiver::Value, Some(_)) => borrow(ast::UnaryOp::Star, receiver.clone()),
+        (hir::Receiver::Value, None) | (hir::Receiver::Ref, Some(_)) | (hir::Receiver::FluidRef, Some(true)) => receiver.clone(),
+        (hir::Receiver::Ref, None) => borrow(ast::UnaryOp::Borrow, receiver.clone()),
+        (hir::Receiver::FluidRef, Some(false)) => {
+            ctx.error("E0007", span.clone(), format!("`{}::{}` takes `&fluid {}`, but is called through a reference that is not `fluid`", ty, name, ty));
+            return None;
+        },
+        (hir::Receiver::FluidRef, None) => {
+            if let hir::ExprKind::Name(static_) = &receiver.kind {
+                hir_lower_capture(ctx, scopes, static_, &receiver.span, true);
+                if !hir_lower_is_local(ctx, scopes, static_) && ctx.statics.get(static_) == Some(&false) {
+                    ctx.error("E0007", span.clone(), format!("`{}::{}` changes the static `{}`, but its type is not `fluid`", ty, name, static_));
+                }
+            }
+
+            borrow(ast::UnaryOp::Borrow, borrow(ast::UnaryOp::Fluid, receiver.clone()))
+        },
+    };
+
+    Some(receiver)
+}
+
+/// How an operator applies to the type of its operand.
+enum Overload {
+    /// The type applies the operator's contract, so the operator calls the contract's routine.
+    Call(hir::Expr),
+
+    /// The type is declared in the module, but doesn't apply the operator's contract.
+    Missing(hir::Type),
+
+    /// The operator keeps its built in meaning, or the type of the operand isn't known before type checking.
+    Builtin,
+}
+
+/// Resolves an operator on a type which may overload it, calling the contract's routine with the operand as `self`
+/// followed by the rest of the arguments.
+fn hir_lower_overload(
+    ctx: &mut HirLowerContext,
+    scopes: &[Scope],
+    span: &ast::Span,
+    contract: &OpContract,
+    operand: &hir::Expr,
+    args: &[hir::Expr],
+) -> Overload {
+    let (ty, by_ref) = match hir_lower_receiver_type(ctx, scopes, operand) {
+        Some((ty, by_ref)) if hir_lower_is_declared(ctx, &ty) => (ty, by_ref),
+        _ => return Overload::Builtin,
+    };
+
+    let item = ctx.applied.get(&ty.to_string()).and_then(|items| {
+        items.iter().find(|item| item.contract.as_deref() == Some(contract.name) && item.name == contract.method)
+    });
+
+    let receiver_kind = match item {
+        Some(Applied { receiver: Some(receiver), .. }) => *receiver,
+        // the application itself has already been reported as malformed.
+        Some(_) => return Overload::Builtin,
+        None => return match &hir_lower_resolve(ctx, &ty).kind {
+            hir::TypeKind::Struct(_) | hir::TypeKind::Union(_) => Overload::Missing(ty),
+            // enums without payloads compare by their discriminants.
+            hir::TypeKind::Enum(_) if !matches!(contract.name, "Eq" | "Ord") => Overload::Missing(ty),
+            _ if matches!(contract.name, "Index" | "IndexFluid") => Overload::Missing(ty),
+            _ => Overload::Builtin,
+        },
+    };
+
+    let receiver = match hir_lower_adjust_receiver(ctx, scopes, span, &ty, contract.method, operand, receiver_kind, by_ref) {
+        Some(receiver) => receiver,
+        None => return Overload::Builtin,
+    };
+
+    let callee = hir::Expr {
+        span: span.clone(),
+        kind: hir::ExprKind::Path(Box::new(hir::Expr { span: span.clone(), kind: hir::ExprKind::Name(ty.to_string()) }), contract.method.into()),
+    };
+
+    let mut call_args = vec![receiver];
+    call_args.extend(args.iter().cloned());
+    Overload::Call(hir::Expr { span: span.clone(), kind: hir::ExprKind::Call(Box::new(callee), call_args) })
+}
+
+/// Reports an operator used on a type which doesn't apply its contract.
+fn hir_lower_missing_contract(ctx: &mut HirLowerContext, span: &ast::Span, ty: &hir::Type, contract: &OpContract) {
+    ctx.error("E0011", span.clone(), format!("`{}` does not apply `{}`, so the operator cannot be used on it", ty, contract.name));
+}
+
+/// Lowers a binary operator, calling the routine of its contract if the left operand's type overloads it.
+fn hir_lower_binary(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, binary: &ast::Binary) -> hir::ExprKind {
+    let left = hir_lower_expr(ctx, scopes, &binary.left);
+    let right = hir_lower_expr(ctx, scopes, &binary.right);
+
+    let contract = match ops::binary(&binary.op) {
+        Some(contract) => contract,
+        None => return hir::ExprKind::Binary(binary.op.clone(), Box::new(left), Box::new(right)),
+    };
+
+    let call = match hir_lower_overload(ctx, scopes, &binary.span, contract, &left, std::slice::from_ref(&right)) {
+        Overload::Call(call) => call,
+        Overload::Missing(ty) => {
+            hir_lower_missing_contract(ctx, &binary.span, &ty, contract);
+            return hir::ExprKind::Binary(binary.op.clone(), Box::new(left), Box::new(right));
+        },
+        Overload::Builtin => return hir::ExprKind::Binary(binary.op.clone(), Box::new(left), Box::new(right)),
+    };
+
+    match binary.op {
+        ast::BinaryOp::BangEq => hir::ExprKind::Unary(ast::UnaryOp::Bang, Box::new(call)),
+        // `cmp` returns how the left operand orders against the right, which is compared against zero.
+        ast::BinaryOp::Lt | ast::BinaryOp::Gt | ast::BinaryOp::LtEq | ast::BinaryOp::GtEq => {
+            let zero = hir::Expr { span: binary.span.clone(), kind: hir::ExprKind::Num(ast::NumKind::Int, "0".into()) };
+            hir::ExprKind::Binary(binary.op.clone(), Box::new(call), Box::new(zero))
+        },
+        _ => call.kind,
+    }
+}
+
+/// Lowers a unary operator, calling the routine of its contract if the operand's type overloads it.
+fn hir_lower_unary(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, unary: &ast::Unary) -> hir::ExprKind {
+    let subject = hir_lower_expr(ctx, scopes, &unary.subject);
+
+    // `&fluid local` lets the local be changed through the reference.
+    if let (ast::UnaryOp::Borrow, hir::ExprKind::Unary(ast::UnaryOp::Fluid, local)) = (&unary.op, &subject.kind) {
+        if let hir::ExprKind::Name(name) = &local.kind {
+            hir_lower_capture(ctx, scopes, name, &local.span, true);
+        }
+    }
+    if let Some(contract) = ops::unary(&unary.op) {
+        match hir_lower_overload(ctx, scopes, &unary.span, contract, &subject, &[]) {
+            Overload::Call(call) => return call.kind,
+            Overload::Missing(ty) => hir_lower_missing_contract(ctx, &unary.span, &ty, contract),
+            Overload::Builtin => {},
+        }
+    }
+
+    hir::ExprKind::Unary(unary.op.clone(), Box::new(subject))
+}
+
+/// Lowers an index expression through `Index`, or through `IndexFluid` when the element is assigned to. The
+/// contract's routine returns a reference to the element, which is dereferenced.
+fn hir_lower_index(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, index: &ast::Index, fluid: bool) -> hir::ExprKind {
+    let subject = hir_lower_expr(ctx, scopes, &index.subject);
+    let item = hir_lower_expr(ctx, scopes, &index.index);
+
+    let contract = ops::lookup(if fluid { "IndexFluid" } else { "Index" }).expect("indexing contracts are built in");
+    match hir_lower_overload(ctx, scopes, &index.span, contract, &subject, std::slice::from_ref(&item)) {
+        Overload::Call(call) => hir::ExprKind::Unary(ast::UnaryOp::Star, Box::new(call)),
+        Overload::Missing(ty) => {
+            hir_lower_missing_contract(ctx, &index.span, &ty, contract);
+            hir::ExprKind::Index(Box::new(subject), Box::new(item))
+        },
+        Overload::Builtin => hir::ExprKind::Index(Box::new(subject), Box::new(item)),
+    }
+}
+
+/// The options that an inline assembly block may have.
+const ASM_OPTIONS: &[&str] = &["volatile", "nostack", "nomem", "readonly", "pure", "intel"];
+
+/// Removes the quotes from a string literal.
+fn unquote(str: &str) -> &str {
+    &str[1..str.len() - 1]
+}
+
+/// Finds the type annotation of a variable in scope.
+fn hir_lower_lookup<'s>(scopes: &'s [Scope], name: &str) -> Option<&'s Option<hir::Type>> {
+    scopes
+        .iter()
+        .rev()
+        .find_map(|scope| scope.vals.iter().rev().find(|(val, _)| val == name).map(|(_, ty)| ty))
+}
+
+/// Follows a type through the type declarations in the root of the module.
+fn hir_lower_resolve<'t>(ctx: &'t HirLowerContext, mut ty: &'t hir::Type) -> &'t hir::Type {
+    // the depth limit stops cyclic declarations from looping forever.
+    for _ in 0..64 {
+        match &ty.unmodified().kind {
+            hir::TypeKind::Name(name) if !ty.is_prim() => match ctx.types.get(name) {
+                Some(decl) => ty = decl,
+                None => break,
+            },
+            _ => break,
+        }
+    }
+
+    ty.unmodified()
+}
+
+/// Finds the type of an expression, where it is known without type checking.
+fn hir_lower_type_of(ctx: &HirLowerContext, scopes: &[Scope], expr: &hir::Expr) -> Option<hir::Type> {
+    match &expr.kind {
+        hir::ExprKind::Name(name) => match hir_lower_lookup(scopes, name).or_else(|| hir_lower_captured(ctx, name)) {
+            Some(ty) => ty.clone(),
+            None => ctx.vals.get(name).cloned(),
+        },
+        hir::ExprKind::Routine(routine) => {
+            let args = routine.args.iter().map(|arg| arg.ty.clone()).collect();
+            let returns = routine.returns.clone().map(Box::new);
+            let kind = match routine.captures.is_empty() {
+                true => hir::TypeKind::Routine(args, returns),
+                false => hir::TypeKind::Closure(args, returns),
+            };
+
+            Some(hir::Type { span: routine.span.clone(), kind })
+        },
+        hir::ExprKind::Path(left, name) => match &left.kind {
+            hir::ExprKind::Name(ty) if hir_lower_lookup(scopes, ty).is_none() => ctx.applied
+                .get(ty)
+                .and_then(|items| items.iter().find(|item| &item.name == name))
+                .and_then(|item| item.ty.clone()),
+            _ => None,
+        },
+        hir::ExprKind::Call(callee, _) => {
+            let ty = hir_lower_type_of(ctx, scopes, callee)?;
+            match &hir_lower_resolve(ctx, &ty).kind {
+                hir::TypeKind::Routine(_, returns) | hir::TypeKind::Closure(_, returns) => returns.as_deref().cloned(),
+                _ => None,
+            }
+        },
+        hir::ExprKind::Access(left, name) => {
+            let ty = hir_lower_type_of(ctx, scopes, left)?;
+            let ty = match &ty.unmodified().kind {
+                hir::TypeKind::Ref(inner) => inner.unmodified().clone(),
+                _ => ty.unmodified().clone(),
+            };
+
+            match &hir_lower_resolve(ctx, &ty).kind {
+                hir::TypeKind::Struct(fields) | hir::TypeKind::Union(fields) => {
+                    fields.iter().find(|field| &field.name == name).map(|field| field.ty.clone())
+                },
+                _ => None,
+            }
+        },
+        hir::ExprKind::Unary(ast::UnaryOp::Star, subject) => match hir_lower_type_of(ctx, scopes, subject)?.unmodified().kind.clone() {
+            hir::TypeKind::Ref(inner) => Some(*inner),
+            _ => None,
+        },
+        hir::ExprKind::Unary(ast::UnaryOp::Borrow, subject) => {
+            let ty = match &subject.kind {
+                hir::ExprKind::Unary(ast::UnaryOp::Fluid, subject) => {
+                    let ty = hir_lower_type_of(ctx, scopes, subject)?;
+                    hir::Type { span: expr.span.clone(), kind: hir::TypeKind::Fluid(Box::new(ty)) }
+                },
+                _ => hir_lower_type_of(ctx, scopes, subject)?,
+            };
+
+            Some(hir::Type { span: expr.span.clone(), kind: hir::TypeKind::Ref(Box::new(ty)) })
+        },
+        hir::ExprKind::Upcast(_, ty) => Some(ty.clone()),
+        hir::ExprKind::Index(subject, _) => match hir_lower_type_of(ctx, scopes, subject)?.unmodified().kind.clone() {
+            hir::TypeKind::Variadic(Some(ty)) => Some(*ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Makes sure that enums are only cast to integers, and only when none of their properties have payloads.
+fn hir_lower_cast(ctx: &mut HirLowerContext, scopes: &[Scope], subject: &hir::Expr, ty: &hir::Type) {
+    // `Enum::Prop` refers to a property of an enum type declared in the module.
+    let enum_ty = match &subject.kind {
+        hir::ExprKind::Path(left, _) => match &left.kind {
+            hir::ExprKind::Name(name) if hir_lower_lookup(scopes, name).is_none() => ctx.types.get(name).cloned(),
+            _ => None,
+        },
+        _ => hir_lower_type_of(ctx, scopes, subject),
+    };
+
+    let variants = match enum_ty.as_ref().map(|enum_ty| &hir_lower_resolve(ctx, enum_ty).kind) {
+        Some(hir::TypeKind::Enum(variants)) => variants,
+        _ => return,
+    };
+
+    let message = if variants.iter().any(|variant| variant.ty.is_some()) {
+        "only enums without payloads can be cast to integers".to_string()
+    } else if !hir::INT_TYPES.contains(&hir_lower_resolve(ctx, ty).to_string().as_str()) {
+        format!("enums can only be cast to integer types, not `{}`", ty)
+    } else {
+        return;
+    };
+
+    ctx.error("E0012", subject.span.clone(), message);
+}
+
+/// Lowers the target of an assignment, where writing to a union property isn't a read of it.
+fn hir_lower_place(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, expr: &ast::Expr) -> hir::Expr {
+    match expr {
+        ast::Expr::Access(access) => {
+            let left = hir_lower_expr(ctx, scopes, &access.left);
+            hir_lower_access_use(ctx, scopes, &left, &access.right);
+
+            hir::Expr { span: access.span.clone(), kind: hir::ExprKind::Access(Box::new(left), access.right.value.into()) }
+        },
+        ast::Expr::Index(index) => hir::Expr { span: index.span.clone(), kind: hir_lower_index(ctx, scopes, index, true) },
+        _ => hir_lower_expr(ctx, scopes, expr),
+    }
+}
+
+/// Lowers an assignment. Compound assignments call the routine of their contract if the target's type overloads
+/// them, falling back to the contract of the binary operator, as in `a = T::add(a, b)`.
+fn hir_lower_assign(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, assign: &ast::Assign) -> hir::StmtKind {
+    let left = match (&assign.op, &assign.left) {
+        (ast::AssignOp::Eq, _) | (_, ast::Expr::Index(_)) => hir_lower_place(ctx, scopes, &assign.left),
+        // compound assignments read the property before writing it.
+        _ => hir_lower_expr(ctx, scopes, &assign.left),
+    };
+    let right = hir_lower_expr(ctx, scopes, &assign.right);
+
+    let (contract, op) = match ops::assign(&assign.op) {
+        Some(contract) => contract,
+        None => return hir::StmtKind::Assign(assign.op.clone(), left, right),
+    };
+
+    let missing = match hir_lower_overload(ctx, scopes, &assign.span, contract, &left, std::slice::from_ref(&right)) {
+        Overload::Call(call) => return hir::StmtKind::Expr(call),
+        Overload::Missing(ty) => Some(ty),
+        Overload::Builtin => None,
+    };
+
+    let binary = ops::binary(&op).expect("compound assignments have a binary operator");
+    match hir_lower_overload(ctx, scopes, &assign.span, binary, &left, std::slice::from_ref(&right)) {
+        Overload::Call(call) => hir::StmtKind::Assign(ast::AssignOp::Eq, left, call),
+        Overload::Missing(_) | Overload::Builtin => {
+            if let Some(ty) = missing {
+                hir_lower_missing_contract(ctx, &assign.span, &ty, contract);
+            }
+
+            hir::StmtKind::Assign(assign.op.clone(), left, right)
+        },
+    }
+}
+
+/// Finds the variable that a write to a place changes, such as `point` in `point.x`.
+fn hir_lower_written<'e, 'a>(mut target: &'e ast::Expr<'a>) -> Option<&'e ast::Id<'a>> {
+    loop {
+        target = match target {
+            ast::Expr::Access(access) => &access.left,
+            ast::Expr::Index(index) => &index.subject,
+            ast::Expr::Id(id) => return Some(id),
+            _ => return None,
+        };
+    }
+}
+
+/// Checks that a write to a place doesn't change a static which isn't `fluid`, and records the captured local it
+/// changes if it is in a routine literal.
+fn hir_lower_static_write(ctx: &mut HirLowerContext, scopes: &[Scope], target: &ast::Expr, span: &ast::Span) {
+    let name = match hir_lower_written(target) {
+        Some(id) if hir_lower_lookup(scopes, id.value).is_none() => id.value,
+        _ => return,
+    };
+
+    hir_lower_capture(ctx, scopes, name, span, true);
+    if !hir_lower_is_local(ctx, scopes, name) && ctx.statics.get(name) == Some(&false) {
+        ctx.error("E0007", span.clone(), format!("cannot assign to static `{}`, its type is not `fluid`", name));
+    }
+}
+
+/// Checks that an output operand of an inline assembly block doesn't write to a local of the routine whose type
+/// is known not to be `fluid`.
+fn hir_lower_local_write(ctx: &mut HirLowerContext, scopes: &[Scope], target: &ast::Expr) {
+    let id = match hir_lower_written(target) {
+        Some(id) => id,
+        None => return,
+    };
+
+    if let Some(Some(ty)) = hir_lower_lookup(scopes, id.value) {
+        if !ty.is_fluid() {
+            ctx.error("E0007", id.span.clone(), format!("cannot write to `{}` through an output operand, its type is not `fluid`", id.value));
+        }
+    }
+}
+
+/// Records a property read from or written to a value, if the type of the value is known.
+fn hir_lower_access_use(ctx: &mut HirLowerContext, scopes: &[Scope], subject: &hir::Expr, name: &ast::Id) {
+    let ty = match hir_lower_type_of(ctx, scopes, subject) {
+        Some(ty) => ty,
+        None => return,
+    };
+
+    let mut ty = ty.unmodified();
+    if let hir::TypeKind::Ref(inner) = &ty.kind {
+        ty = inner.unmodified();
+    }
+
+    if let hir::TypeKind::Name(ty) = &ty.kind {
+        hir_lower_prop_use(ctx, ty, name);
+    }
+}
+
+/// Records a property of a type which isn't declared in the module being lowered, so that it can be checked
+/// against the visibility of the property once the type is imported.
+fn hir_lower_prop_use(ctx: &mut HirLowerContext, ty: &str, name: &ast::Id) {
+    if ctx.types.contains_key(ty) || hir::INT_TYPES.contains(&ty) || hir::FLOAT_TYPES.contains(&ty) {
+        return;
+    }
+
+    ctx.props.push(PropUse { module: ctx.inline.clone(), ty: ty.into(), name: name.value.into(), span: name.span.clone() });
+}
+
+/// Whether or not a declaration is marked as intentionally punning union properties.
+fn hir_lower_is_pun(markers: &[ast::Marker]) -> bool {
+    markers.iter().any(|marker| marker.name.value == "pun")
+}
+
+/// Warns about reading a property of a union, unless the property or the declaration is marked with `#pun`.
+fn hir_lower_union_read(ctx: &mut HirLowerContext, scopes: &[Scope], subject: &hir::Expr, name: &ast::Id) {
+    if scopes.iter().any(|scope| scope.kind == ScopeKind::Pun) {
+        return;
+    }
+
+    let ty = match hir_lower_type_of(ctx, scopes, subject) {
+        Some(ty) => ty,
+        None => return,
+    };
+
+    let mut ty = ty.unmodified();
+    if let hir::TypeKind::Ref(inner) = &hir_lower_resolve(ctx, ty).kind {
+        ty = inner.unmodified();
+    }
+
+    let punned = match &hir_lower_resolve(ctx, ty).kind {
+        hir::TypeKind::Union(fields) => fields
+            .iter()
+            .find(|field| field.name == name.value)
+            .is_some_and(|field| !field.attrs.iter().any(|attr| attr.name == "pun")),
+        _ => false,
+    };
+
+    if punned {
+        let message = format!(
+            "reading `{}` from union `{}` reinterprets its memory, mark the property or declaration with `#pun` if this is intentional",
+            name.value,
+            ty,
+        );
+        ctx.warn("E0013", name.span.clone(), message);
+    }
+}
+
+/// Converts a named type to the expression that refers to it, such as `unit::MyError`.
+fn hir_lower_type_expr(ty: &hir::Type) -> Option<hir::Expr> {
+    let kind = match &ty.kind {
+        hir::TypeKind::Name(name) => hir::ExprKind::Name(name.clone()),
+        hir::TypeKind::Path(left, right) => hir::ExprKind::Path(Box::new(hir_lower_type_expr(left)?), right.clone()),
+        _ => return None,
+    };
+
+    Some(hir::Expr { span: ty.span.clone(), kind })
+}
+
+/// Lowers a `?` expression, which returns early from the enclosing routine on `none` or `err`.
+fn hir_lower_try(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, try_: &ast::Try) -> hir::ExprKind {
+    let subject = hir_lower_expr(ctx, scopes, &try_.subject);
+    let mut exit = hir::TryExit { convert: None, defers: vec![] };
+
+    if scopes.iter().any(|scope| scope.kind == ScopeKind::Defer) {
+        ctx.error("E0014", try_.span.clone(), "cannot use `?` in a deferred statement, as it may return".into());
+        return hir::ExprKind::Try(Box::new(subject), Box::new(exit));
+    }
+
+    let returns = match scopes.first().map(|scope| &scope.kind) {
+        Some(ScopeKind::Routine(returns)) => returns.clone(),
+        _ => {
+            ctx.error("E0014", try_.span.clone(), "`?` can only be used inside of a routine".into());
+            return hir::ExprKind::Try(Box::new(subject), Box::new(exit));
+        },
+    };
+
+    // the error type of the routine, or `None` if it returns an option.
+    let err = match returns.as_ref().map(|ty| &hir_lower_resolve(ctx, ty).kind) {
+        Some(hir::TypeKind::Opt(_)) => None,
+        Some(hir::TypeKind::Res(_, err)) => Some((**err).clone()),
+        _ => {
+            ctx.error("E0014", try_.span.clone(), "`?` can only be used in a routine that returns `?T` or `!T:E`".into());
+            return hir::ExprKind::Try(Box::new(subject), Box::new(exit));
+        },
+    };
+
+    if let Some(ty) = hir_lower_type_of(ctx, scopes, &subject) {
+        let resolved = hir_lower_resolve(ctx, &ty).clone();
+        match (&resolved.kind, &err) {
+            (hir::TypeKind::Opt(_), None) => {},
+            (hir::TypeKind::Res(_, from), Some(to)) if from.to_string() != to.to_string() => {
+                let converts = ctx.conversions.iter().any(|(a, b)| a.to_string() == from.to_string() && b.to_string() == to.to_string());
+                match hir_lower_type_expr(to) {
+                    Some(to_expr) if converts => {
+                        exit.convert = Some(hir::Expr {
+                            span: try_.span.clone(),
+                            kind: hir::ExprKind::Path(Box::new(to_expr), hir::conversion_name(from)),
+                        });
+                    },
+                    _ => {
+                        let message = format!("cannot convert `{}` to `{}`, as `From` is not applied to `{}` for it", from, to, to);
+                        ctx.error("E0014", try_.span.clone(), message);
+                    },
+                }
+            },
+            (hir::TypeKind::Res(..), Some(_)) => {},
+            (hir::TypeKind::Opt(_), Some(_)) => {
+                ctx.error("E0014", try_.span.clone(), "cannot use `?` on an option in a routine that returns a result".into());
+            },
+            (hir::TypeKind::Res(..), None) => {
+                ctx.error("E0014", try_.span.clone(), "cannot use `?` on a result in a routine that returns an option".into());
+            },
+            // types from other modules can't be resolved yet.
+            (hir::TypeKind::Name(_) | hir::TypeKind::Path(..), _) if !resolved.is_prim() => {},
+            _ => ctx.error("E0014", try_.span.clone(), format!("`?` cannot be applied to a value of type `{}`", ty)),
+        }
+    }
+
+    exit.defers = hir_lower_exit_defers(scopes, scopes.len());
+    hir::ExprKind::Try(Box::new(subject), Box::new(exit))
+}
+
+/// Lowers an operand of an inline assembly block, making sure it matches its constraint.
+fn hir_lower_asm_operand(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, operand: &ast::AsmOperand, output: bool) -> hir::AsmOperand {
+    let constraint = unquote(&operand.constraint.value);
+    let expr = match output {
+        // an output operand is written to like the target of an assignment, and `+` operands are read first.
+        true => {
+            hir_lower_static_write(ctx, scopes, &operand.expr, &operand.span);
+            hir_lower_local_write(ctx, scopes, &operand.expr);
+
+            match constraint.starts_with('+') {
+                true => hir_lower_expr(ctx, scopes, &operand.expr),
+                false => hir_lower_place(ctx, scopes, &operand.expr),
+            }
+        },
+        false => hir_lower_expr(ctx, scopes, &operand.expr),
+    };
+
+    if output != (constraint.starts_with('=') || constraint.starts_with('+')) {
+        let message = match output {
+            true => format!("output operand constraint `{}` must start with `=` or `+`", constraint),
+            false => format!("input operand constraint `{}` cannot start with `=` or `+`", constraint),
+        };
+        ctx.error("E0015", operand.constraint.span.clone(), message);
+    }
+
+    if output && !matches!(expr.kind, hir::ExprKind::Name(_) | hir::ExprKind::Access(..) | hir::ExprKind::Unary(ast::UnaryOp::Star, _)) {
+        ctx.error("E0015", expr.span.clone(), "output operands must be a variable, property or dereference".into());
+    }
+
+    // memory operands can be of any type, everything else has to fit in a register.
+    if let Some(ty) = hir_lower_type_of(ctx, scopes, &expr) {
+        let resolved = hir_lower_resolve(ctx, &ty);
+        let scalar = resolved.is_prim() || matches!(resolved.kind, hir::TypeKind::Ref(_) | hir::TypeKind::Routine(..));
+        let known = scalar || !matches!(resolved.kind, hir::TypeKind::Name(_) | hir::TypeKind::Path(..));
+
+        if known && !scalar && !constraint.contains('m') {
+            let message = format!("operand has type `{}`, which does not fit in a register", ty);
+            ctx.error("E0015", expr.span.clone(), message);
+        }
+    }
+
+    hir::AsmOperand {
+        span: operand.span.clone(),
+        constraint: constraint.into(),
+        expr,
+    }
+}
+
+/// Lowers an inline assembly block, checking its operands and options. The block is stored in HIR for a code
+/// generator to emit, which hail doesn't have yet.
+fn hir_lower_asm(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, asm: &ast::Asm) -> hir::Asm {
+    for option in &asm.options {
+        if !ASM_OPTIONS.contains(&option.value) {
+            ctx.error("E0015", option.span.clone(), format!("unknown inline assembly option `{}`", option.value));
+        }
+    }
+
+    hir::Asm {
+        template: unquote(&asm.template.value).into(),
+        outputs: asm.outputs.iter().map(|operand| hir_lower_asm_operand(ctx, scopes, operand, true)).collect(),
+        inputs: asm.inputs.iter().map(|operand| hir_lower_asm_operand(ctx, scopes, operand, false)).collect(),
+        clobbers: asm.clobbers.iter().map(|clobber| unquote(&clobber.value).into()).collect(),
+        options: asm.options.iter().map(|option| option.value.into()).collect(),
+    }
+}
+
+/// Records a name used by a deferred statement which isn't declared inside of it.
+fn hir_lower_defer_use(scopes: &mut [Scope], name: &str) {
+    let defer = match scopes.iter().rposition(|scope| scope.kind == ScopeKind::Defer) {
+        Some(defer) => defer,
+        None => return,
+    };
+
+    let declared = scopes[defer..].iter().any(|scope| scope.vals.iter().any(|(val, _)| val == name));
+    if !declared && !scopes[defer].deferred.iter().any(|used| used == name) {
+        scopes[defer].deferred.push(name.into());
+    }
+}
+
+/// Collects the statements deferred in the scopes being exited, in the order they must run.
+fn hir_lower_exit_defers(scopes: &[Scope], depth: usize) -> Vec<hir::Stmt> {
+    scopes[scopes.len() - depth..]
+        .iter()
+        .rev()
+        .flat_map(|scope| scope.defers.iter().rev().cloned())
+        .collect()
+}
+
+/// Lowers a `break` or `continue` statement, running the statements deferred in the scopes that it exits.
+fn hir_lower_loop_exit(
+    ctx: &mut HirLowerContext,
+    scopes: &[Scope],
+    span: &ast::Span,
+    keyword: &str,
+    label: Option<&str>,
+    stmts: &mut Vec<hir::Stmt>,
+) -> bool {
+    for (depth, scope) in scopes.iter().rev().enumerate() {
+        match &scope.kind {
+            ScopeKind::Defer => {
+                ctx.error("E0016", span.clone(), format!("cannot `{}` out of a deferred statement", keyword));
+                return false;
+            },
+            ScopeKind::Loop(found) if label.is_none() || found.as_deref() == label => {
+                stmts.extend(hir_lower_exit_defers(scopes, depth + 1));
+                return true;
+            },
+            _ => {},
+        }
+    }
+
+    match label {
+        Some(label) => ctx.error("E0016", span.clone(), format!("no loop labeled `{}` to `{}`", label, keyword)),
+        None => ctx.error("E0016", span.clone(), format!("cannot `{}` outside of a loop", keyword)),
+    }
+
+    false
+}
+
+/// Lowers a statement in a block into the provided list of statements.
+fn hir_lower_block_expr(
+    ctx: &mut HirLowerContext,
+    scopes: &mut Vec<Scope>,
+    item: &ast::BlockExpr,
+    stmts: &mut Vec<hir::Stmt>,
+) {
+    let (span, kind) = match item {
+        ast::BlockExpr::Assign(assign) => {
+            hir_lower_static_write(ctx, scopes, &assign.left, &assign.span);
+
+            (assign.span.clone(), hir_lower_assign(ctx, scopes, assign))
+        },
+        ast::BlockExpr::Val(val) => {
+            let ty = val.ty.as_ref().map(|ty| hir_lower_type(ctx, ty));
+
+            let pun = hir_lower_is_pun(&val.markers);
+            if pun {
+                scopes.push(Scope::new(ScopeKind::Pun));
+            }
+
+            let value = val.value.as_ref().map(|value| hir_lower_expr(ctx, scopes, value));
+            if pun {
+                scopes.pop();
+            }
+
+            let value = match (&ty, value) {
+                (Some(ty), Some(value)) => Some(hir_lower_coerce(ctx, scopes, value, ty)),
+                (_, value) => value,
+            };
+
+            // routine literals know their own type, which says whether or not they capture.
+            let local_ty = match (&ty, &value) {
+                (None, Some(value @ hir::Expr { kind: hir::ExprKind::Routine(_), .. })) => hir_lower_type_of(ctx, scopes, value),
+                _ => ty.clone(),
+            };
+
+            // deferred statements are lowered by name, so a declaration would change what a pending one refers to.
+            if scopes.iter().any(|scope| scope.deferred.iter().any(|used| used == val.name.value) && scope.kind != ScopeKind::Defer) {
+                let message = format!("cannot declare `{}` again while a deferred statement which uses it is pending", val.name.value);
+                ctx.error("E0016", val.name.span.clone(), message);
+            }
+            scopes.last_mut().unwrap().vals.push((val.name.value.into(), local_ty));
+
+            (val.span.clone(), hir::StmtKind::Val(val.name.value.into(), ty, value))
+        },
+        ast::BlockExpr::TypeDecl(ty) => (
+            ty.span.clone(),
+            hir::StmtKind::Type(ty.name.value.into(), ty.value.as_ref().map(|value| hir_lower_type(ctx, value))),
+        ),
+        ast::BlockExpr::Call(call) => (
+            call.span.clone(),
+            hir::StmtKind::Expr(hir::Expr { span: call.span.clone(), kind: hir_lower_call(ctx, scopes, call) }),
+        ),
+        ast::BlockExpr::Asm(asm) => (
+            asm.span.clone(),
+            hir::StmtKind::Expr(hir::Expr {
+                span: asm.span.clone(),
+                kind: hir::ExprKind::Asm(Box::new(hir_lower_asm(ctx, scopes, asm))),
+            }),
+        ),
+        ast::BlockExpr::If(if_) => (if_.span.clone(), hir_lower_if(ctx, scopes, if_)),
+        ast::BlockExpr::While(while_) => (
+            while_.span.clone(),
+            hir::StmtKind::While(
+                while_.label.as_ref().map(|label| label.value.into()),
+                hir_lower_expr(ctx, scopes, &while_.cond),
+                hir_lower_block(ctx, scopes, &while_.block, ScopeKind::Loop(while_.label.as_ref().map(|label| label.value.into()))),
+            ),
+        ),
+        ast::BlockExpr::Match(match_) => (match_.span.clone(), hir_lower_match(ctx, scopes, match_)),
+        ast::BlockExpr::Break(break_) => {
+            let label = break_.label.as_ref().map(|label| label.value);
+            if !hir_lower_loop_exit(ctx, scopes, &break_.span, "break", label, stmts) {
+                return;
+            }
+
+            (break_.span.clone(), hir::StmtKind::Break(label.map(Into::into)))
+        },
+        ast::BlockExpr::Continue(continue_) => {
+            let label = continue_.label.as_ref().map(|label| label.value);
+            if !hir_lower_loop_exit(ctx, scopes, &continue_.span, "continue", label, stmts) {
+                return;
+            }
+
+            (continue_.span.clone(), hir::StmtKind::Continue(label.map(Into::into)))
+        },
+        ast::BlockExpr::Return(return_) => {
+            if scopes.iter().any(|scope| scope.kind == ScopeKind::Defer) {
+                ctx.error("E0016", return_.span.clone(), "cannot `return` out of a deferred statement".into());
+                return;
+            }
+
+            let value = return_.value.as_ref().map(|value| hir_lower_expr(ctx, scopes, value));
+            let defers = hir_lower_exit_defers(scopes, scopes.len());
+            let value = match value {
+                // the returned value is evaluated before the deferred statements run.
+                Some(value) if !defers.is_empty() => {
+                    stmts.push(hir::Stmt {
+                        span: value.span.clone(),
+                        kind: hir::StmtKind::Val(RETURN_VAL.into(), None, Some(value.clone())),
+                    });
+
+                    Some(hir::Expr { span: value.span, kind: hir::ExprKind::Name(RETURN_VAL.into()) })
+                },
+                value => value,
+            };
+
+            stmts.extend(defers);
+            (return_.span.clone(), hir::StmtKind::Return(value))
+        },
+        ast::BlockExpr::Defer(defer) => {
+            if let ast::BlockExpr::Val(_) | ast::BlockExpr::TypeDecl(_) = &*defer.item {
+                ctx.error("E0016", defer.span.clone(), "only statements can be deferred, not declarations".into());
+                return;
+            }
+
+            scopes.push(Scope::new(ScopeKind::Defer));
+            let mut deferred = vec![];
+            hir_lower_block_expr(ctx, scopes, &defer.item, &mut deferred);
+            let scope = scopes.pop().unwrap();
+            deferred.extend(scope.defers.into_iter().rev());
+
+            // the names used by the statement are pending in its scope, and in any deferred statement around it.
+            for name in &scope.deferred {
+                hir_lower_defer_use(scopes, name);
+            }
+            let pending = &mut scopes.last_mut().unwrap().deferred;
+            for name in scope.deferred {
+                if !pending.contains(&name) {
+                    pending.push(name);
+                }
+            }
+
+            let stmt = match deferred.len() {
+                1 => deferred.remove(0),
+                _ => hir::Stmt {
+                    span: defer.span.clone(),
+                    kind: hir::StmtKind::Block(hir::Block { span: defer.span.clone(), stmts: deferred }),
+                },
+            };
+
+            scopes.last_mut().unwrap().defers.push(stmt);
+            return;
+        },
+        ast::BlockExpr::Block(flags, block) => {
+            if !hir_lower_flags(ctx, flags) {
+                return;
+            }
+
+            (block.span.clone(), hir::StmtKind::Block(hir_lower_block(ctx, scopes, block, ScopeKind::Block)))
+        },
+    };
+
+    stmts.push(hir::Stmt { span, kind });
+}
+
+/// The name of the temporary that holds a returned value while deferred statements run.
+const RETURN_VAL: &str = "#ret";
+
+/// Lowers an `if` statement, nesting `else if` branches in `else` blocks.
+fn hir_lower_if(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, if_: &ast::If) -> hir::StmtKind {
+    let cond = hir_lower_expr(ctx, scopes, &if_.cond);
+    let block = hir_lower_block(ctx, scopes, &if_.block, ScopeKind::Block);
+
+    let mut else_ = None;
+    for branch in if_.branches.iter().rev() {
+        else_ = Some(match branch {
+            ast::IfBranch::Else { block, .. } => hir_lower_block(ctx, scopes, block, ScopeKind::Block),
+            ast::IfBranch::ElseIf { span, cond, block } => {
+                let cond = hir_lower_expr(ctx, scopes, cond);
+                let block = hir_lower_block(ctx, scopes, block, ScopeKind::Block);
+
+                hir::Block {
+                    span: span.clone(),
+                    stmts: vec![hir::Stmt { span: span.clone(), kind: hir::StmtKind::If(cond, block, else_) }],
+                }
+            },
+        });
+    }
+
+    hir::StmtKind::If(cond, block, else_)
+}
+
+/// Lowers a `match` statement.
+fn hir_lower_match(ctx: &mut HirLowerContext, scopes: &mut Vec<Scope>, match_: &ast::Match) -> hir::StmtKind {
+    let subject = hir_lower_expr(ctx, scopes, &match_.subject);
+    let cases = match_.cases.iter().map(|case| hir::MatchCase {
+        span: case.span.clone(),
+        name: case.name.value.into(),
+        ty: hir_lower_type(ctx, &case.ty),
+        block: hir_lower_block(ctx, scopes, &case.block, ScopeKind::Block),
+    }).collect();
+
+    hir::StmtKind::Match(subject, cases)
+}
+
+/// Lowers a block, running its deferred statements in reverse order if control flow reaches its end.
+fn hir_lower_block(
+    ctx: &mut HirLowerContext,
+    scopes: &mut Vec<Scope>,
+    block: &ast::Block,
+    kind: ScopeKind,
+) -> hir::Block {
+    scopes.push(Scope::new(kind));
+
+    let mut stmts = vec![];
+    for item in &block.items {
+        hir_lower_block_expr(ctx, scopes, item, &mut stmts);
+    }
+
+    let scope = scopes.pop().unwrap();
+    if !stmts.last().is_some_and(hir::Stmt::diverges) {
+        stmts.extend(scope.defers.into_iter().rev());
+    }
+
+    hir::Block { span: block.span.clone(), stmts }
+}
+
+/// Lowers an item in an `apply` statement or contract.
+fn hir_lower_application(ctx: &mut HirLowerContext, item: &ast::Application) -> hir::Item {
+    match item {
+        ast::Application::Val(val) => hir_lower_val(ctx, val),
+        ast::Application::TypeDecl(ty) => hir_lower_type_decl(ctx, ty),
+    }
+}
+
+/// Lowers the markers of a declaration to attributes.
+fn hir_lower_attr_list(ctx: &mut HirLowerContext, markers: &[ast::Marker]) -> Vec<hir::Attr> {
+    markers.iter()
+        .map(|marker| hir::Attr {
+            span: marker.span.clone(),
+            name: marker.name.value.into(),
+            args: marker.args.iter().map(|arg| hir_lower_expr(ctx, &mut vec![], arg)).collect(),
+        })
+        .collect()
+}
+
+/// Lowers a path in the root of a unit to a type.
+fn hir_lower_global_path(path: &ast::GlobalPath) -> hir::Type {
+    match path {
+        ast::GlobalPath::Id(id) => hir::Type { span: id.span.clone(), kind: hir::TypeKind::Name(id.value.into()) },
+        ast::GlobalPath::Branch { span, path, id } => hir::Type {
+            span: span.clone(),
+            kind: hir::TypeKind::Path(Box::new(hir_lower_global_path(path)), id.value.into()),
+        },
+    }
+}
+
+/// Lowers an `apply` statement.
+fn hir_lower_apply(ctx: &mut HirLowerContext, apply: &ast::Apply) -> hir::Apply {
+    let (contract, subject) = match &apply.to {
+        Some(to) => (Some(hir_lower_global_path(&apply.subject)), hir_lower_global_path(to)),
+        None => (None, hir_lower_global_path(&apply.subject)),
+    };
+
+    let mut items = apply.items.iter().map(|item| hir_lower_application(ctx, item)).collect::<Vec<_>>();
+    if hir_lower_converts_to(ctx, apply).is_some() {
+        for item in &mut items {
+            if let hir::ItemKind::Val(_, Some(hir::Expr { kind: hir::ExprKind::Routine(routine), .. })) = &item.kind {
+                if item.name == "convert" && routine.args.len() == 1 {
+                    item.name = hir::conversion_name(&routine.args[0].ty);
+                }
+            }
+        }
+    }
+
+    hir::Apply { span: apply.span.clone(), contract, subject, items }
+}
+
+/// The type that an `apply` statement converts to, if it is an application of the built in `From` contract.
+fn hir_lower_converts_to(ctx: &HirLowerContext, apply: &ast::Apply) -> Option<hir::Type> {
+    match (&apply.subject, &apply.to) {
+        // a type declared in the module shadows the built in contract.
+        (ast::GlobalPath::Id(id), Some(to)) if id.value == "From" && !ctx.types.contains_key("From") => {
+            Some(hir_lower_global_path(to))
+        },
+        _ => None,
+    }
+}
+
+/// Records the conversion declared by an application of the `From` contract.
+///
+/// `From` can be applied to a type once for each type converted from, and `?` calls the conversion that matches
+/// the error it returns.
+fn hir_lower_conversion(ctx: &mut HirLowerContext, apply: &ast::Apply) {
+    let Some(to) = hir_lower_converts_to(ctx, apply) else { return };
+
+    let from = apply.items.iter().find_map(|item| match item {
+        ast::Application::Val(ast::Val { name, value: Some(ast::Expr::Routine(routine)), .. })
+            if name.value == "convert" && routine.args.len() == 1 => Some(&routine.args[0].ty),
+        _ => None,
+    });
+
+    match from {
+        Some(from) => {
+            let from = hir_lower_type(ctx, from);
+            let converts = ctx.conversions.iter().any(|(a, b)| a.to_string() == from.to_string() && b.to_string() == to.to_string());
+            if converts {
+                ctx.error("E0017", apply.span.clone(), format!("`From` is already applied to `{}` for `{}`", to, from));
+                return;
+            }
+
+            ctx.conversions.push((from, to));
+        },
+        None => ctx.error("E0017", apply.span.clone(), "applications of `From` must declare `val convert = routine(value <- T) -> ..`".into()),
+    }
+}
+
+/// Collects the items of an `apply` statement, so that they can be reached through the type they are applied to.
+fn hir_lower_applied(ctx: &mut HirLowerContext, apply: &ast::Apply) {
+    let subject = hir_lower_global_path(apply.to.as_ref().unwrap_or(&apply.subject));
+    let contract = apply.to.as_ref().map(|_| hir_lower_global_path(&apply.subject).to_string());
+    if let Some(contract) = &contract {
+        let declared = ctx.types.get(contract).map(|decl| matches!(hir_lower_resolve(ctx, decl).kind, hir::TypeKind::Contract(_)));
+        if declared == Some(false) {
+            let message = format!("`{}` is not a contract, so it cannot be applied to `{}`", contract, subject);
+            ctx.error("E0017", hir_lower_global_path(&apply.subject).span, message);
+            return;
+        }
+
+        ctx.contracts.entry(subject.to_string()).or_default().push(contract.clone());
+    }
+
+    let converts = hir_lower_converts_to(ctx, apply).is_some();
+    for item in &apply.items {
+        let (name, ty, receiver) = match item {
+            ast::Application::Val(val) => {
+                let receiver = match &val.value {
+                    Some(ast::Expr::Routine(routine)) => routine.args.first()
+                        .filter(|arg| arg.name.value == "self")
+                        .and_then(|arg| hir_lower_receiver(ctx, &subject, arg)),
+                    _ => None,
+                };
+
+                (&val.name, hir_lower_val_type(ctx, val), receiver)
+            },
+            ast::Application::TypeDecl(ty) => (&ty.name, None, None),
+        };
+
+        // conversions are called through `?` rather than by name, and are recorded by `hir_lower_conversion`.
+        if converts && name.value == "convert" {
+            continue;
+        }
+
+        let items = ctx.applied.entry(subject.to_string()).or_default();
+        if items.iter().any(|other| other.name == name.value) {
+            let message = format!("`{}` already has an item named `{}` applied to it", subject, name.value);
+            ctx.error("E0017", name.span.clone(), message);
+            continue;
+        }
+
+        items.push(Applied { name: name.value.into(), ty, receiver, contract: contract.clone() });
+    }
+
+    // a contract declared in the module shadows the built in one with the same name.
+    let op = contract.as_deref().filter(|name| !ctx.types.contains_key(*name)).and_then(ops::lookup);
+    if let Some(op) = op {
+        hir_lower_op_contract(ctx, apply, &subject, op);
+    }
+}
+
+/// Checks that an application of an operator contract declares the routine the operator is lowered to, with
+/// `self` as its first argument.
+fn hir_lower_op_contract(ctx: &mut HirLowerContext, apply: &ast::Apply, subject: &hir::Type, contract: &OpContract) {
+    let declared = apply.items.iter().any(|item| match item {
+        ast::Application::Val(ast::Val { name, value: Some(ast::Expr::Routine(routine)), .. }) => {
+            name.value == contract.method
+                && routine.args.len() == contract.args
+                && routine.args.first().is_some_and(|arg| arg.name.value == "self")
+        },
+        _ => false,
+    });
+
+    if !declared {
+        let args = if contract.args == 2 { ", other <- .." } else { "" };
+        let message = format!("applications of `{}` must declare `val {} = routine(self <- {}{})`", contract.name, contract.method, subject, args);
+        ctx.error("E0017", apply.span.clone(), message);
+    }
+}
+
+/// Finds how a method receives the value it is called on, from the type of its `self` argument.
+fn hir_lower_receiver(ctx: &mut HirLowerContext, subject: &hir::Type, arg: &ast::RoutineArg) -> Option<hir::Receiver> {
+    // the spans of the types differ, so they are compared by how they are written.
+    let ty = hir_lower_type(ctx, &arg.ty);
+    let receiver = match &ty.kind {
+        hir::TypeKind::Ref(inner) => match &inner.kind {
+            hir::TypeKind::Fluid(inner) if inner.to_string() == subject.to_string() => Some(hir::Receiver::FluidRef),
+            _ if inner.to_string() == subject.to_string() => Some(hir::Receiver::Ref),
+            _ => None,
+        },
+        _ if ty.to_string() == subject.to_string() => Some(hir::Receiver::Value),
+        _ => None,
+    };
+
+    if receiver.is_none() {
+        let message = format!("`self` must be `{0}`, `&{0}` or `&fluid {0}`, not `{1}`", subject, ty);
+        ctx.error("E0017", arg.span.clone(), message);
+    }
+
+    receiver
+}
+
+/// The type of a value declared in the root of a unit, if it is known before type checking.
+fn hir_lower_val_type(ctx: &mut HirLowerContext, val: &ast::Val) -> Option<hir::Type> {
+    match (&val.ty, &val.value) {
+        (Some(ty), _) => Some(hir_lower_type(ctx, ty)),
+        (None, Some(ast::Expr::Routine(routine))) => Some(hir::Type {
+            span: routine.span.clone(),
+            kind: hir::TypeKind::Routine(
+                hir_lower_arg_types(ctx, routine.args.iter().map(|arg| &arg.ty), false),
+                routine.returns.as_ref().map(|returns| Box::new(hir_lower_type(ctx, returns))),
+            ),
+        }),
+        _ => None,
+    }
+}
+
+/// Lowers a variable declaration to an item.
+fn hir_lower_val(ctx: &mut HirLowerContext, val: &ast::Val) -> hir::Item {
+    let mut scopes = match hir_lower_is_pun(&val.markers) {
+        true => vec![Scope::new(ScopeKind::Pun)],
+        false => vec![],
+    };
+
+    let ty = val.ty.as_ref().map(|ty| hir_lower_type(ctx, ty));
+    let value = val.value.as_ref().map(|value| hir_lower_expr(ctx, &mut scopes, value));
+    let value = match (&ty, value) {
+        (Some(ty), Some(value)) => Some(hir_lower_coerce(ctx, &scopes, value, ty)),
+        (_, value) => value,
+    };
+
+    hir::Item {
+        span: val.span.clone(),
+        shared: val.shared,
+        attrs: hir_lower_attr_list(ctx, &val.markers),
+        name: val.name.value.into(),
+        kind: hir::ItemKind::Val(ty, value),
+    }
+}
+
+/// Lowers a constant declaration to an item.
+fn hir_lower_const(ctx: &mut HirLowerContext, const_: &ast::Const) -> hir::Item {
+    hir::Item {
+        span: const_.span.clone(),
+        shared: const_.shared,
+        attrs: hir_lower_attr_list(ctx, &const_.markers),
+        name: const_.name.value.into(),
+        kind: hir::ItemKind::Const(
+            const_.ty.as_ref().map(|ty| hir_lower_type(ctx, ty)),
+            hir_lower_expr(ctx, &mut vec![], &const_.value),
+        ),
+    }
+}
+
+/// Lowers a type declaration to an item.
+fn hir_lower_type_decl(ctx: &mut HirLowerContext, ty: &ast::TypeDecl) -> hir::Item {
+    hir::Item {
+        span: ty.span.clone(),
+        shared: ty.shared,
+        attrs: hir_lower_attr_list(ctx, &ty.markers),
+        name: ty.name.value.into(),
+        kind: hir::ItemKind::Type(ty.value.as_ref().map(|value| hir_lower_type(ctx, value))),
+    }
+}
+
+/// Lowers the items and `apply` statements declared in the root of a unit into a module.
+fn hir_lower_items(ctx: &mut HirLowerContext, stmnts: &[ast::RootStmnt], module: &mut hir::Module) {
+    // types are lowered first, so that the values can refer to types declared after them.
+    ctx.types.clear();
+    ctx.vals.clear();
+    ctx.conversions.clear();
+    ctx.statics.clear();
+    ctx.applied.clear();
+    ctx.contracts.clear();
+    ctx.dyns.clear();
+    ctx.vtables.clear();
+    let mut types = vec![];
+    for stmnt in stmnts {
+        if let ast::RootStmnt::TypeDecl(flags, ty) = stmnt {
+            if hir_lower_flags(ctx, flags) {
+                let item = hir_lower_type_decl(ctx, ty);
+                if let hir::ItemKind::Type(Some(value)) = &item.kind {
+                    ctx.types.insert(item.name.clone(), value.clone());
+                }
+
+                types.push(item);
+            }
+        }
+    }
+
+    ctx.types.entry(hir::VAR_ARG.into()).or_insert_with(hir::var_arg);
+
+    // the signatures of values and conversions are also needed before any routine bodies are lowered.
+    for stmnt in stmnts {
+        match stmnt {
+            ast::RootStmnt::Val(flags, val) if hir_lower_flags(ctx, flags) => {
+                let ty = hir_lower_val_type(ctx, val);
+                ctx.statics.insert(val.name.value.into(), ty.as_ref().is_some_and(hir::Type::is_fluid));
+
+                if let Some(ty) = ty {
+                    ctx.vals.insert(val.name.value.into(), ty);
+                }
+            },
+            ast::RootStmnt::Apply(flags, apply) if hir_lower_flags(ctx, flags) => {
+                hir_lower_conversion(ctx, apply);
+                hir_lower_applied(ctx, apply);
+            },
+            _ => {},
+        }
+    }
+
+    let items = &mut module.items;
+    let mut types = types.into_iter();
+    // the statements in the root of the module run in its initializer.
+    let mut scopes = vec![Scope::new(ScopeKind::Block)];
+    let mut stmts = vec![];
+    for stmnt in stmnts {
+        match stmnt {
+            ast::RootStmnt::Val(flags, val) if hir_lower_flags(ctx, flags) => {
+                // routines are known before the program starts, so they don't need to be initialized.
+                if val.value.as_ref().is_some_and(|value| !matches!(value, ast::Expr::Routine(_))) {
+                    module.init.push(hir::Init::Static(val.name.value.into()));
+                }
+
+                items.push(hir_lower_val(ctx, val));
+            },
+            ast::RootStmnt::Const(flags, const_) if hir_lower_flags(ctx, flags) => items.push(hir_lower_const(ctx, const_)),
+            ast::RootStmnt::TypeDecl(flags, _) if hir_lower_flags(ctx, flags) => items.extend(types.next()),
+            ast::RootStmnt::Import(flags, imp) if hir_lower_flags(ctx, flags) => {
+                let (shared, imported) = match imp {
+                    ast::Import::Single { shared, item, .. } => (*shared, std::slice::from_ref(item)),
+                    ast::Import::Multi { shared, items, .. } => (*shared, items.as_slice()),
+                    // the items of a glob import are only known once the imports are resolved.
+                    ast::Import::Glob { .. } => continue,
+                };
+
+                for item in imported {
+                    items.push(hir::Item {
+                        span: item.span.clone(),
+                        shared,
+                        attrs: vec![],
+                        name: item.as_.as_ref().unwrap_or(&item.name).value.into(),
+                        kind: hir::ItemKind::Import(vec![]),
+                    });
+                }
+            },
+            ast::RootStmnt::Module(flags, decl) if hir_lower_flags(ctx, flags) => {
+                // the declarations of the parent module are out of scope in the inline module.
+                let decls = ctx.take_decls();
+                ctx.inline.push(decl.name.value.into());
+                let inline = hir_lower_module(ctx, decl.name.value.into(), &decl.items);
+                ctx.inline.pop();
+                ctx.restore_decls(decls);
+
+                if !inline.init.is_empty() {
+                    module.init.push(hir::Init::Module(decl.name.value.into()));
+                }
+
+                items.push(hir::Item {
+                    span: decl.span.clone(),
+                    shared: decl.shared,
+                    attrs: vec![],
+                    name: decl.name.value.into(),
+                    kind: hir::ItemKind::Module(Box::new(inline)),
+                });
+            },
+            ast::RootStmnt::Apply(flags, apply) if hir_lower_flags(ctx, flags) => {
+                let apply = hir_lower_apply(ctx, apply);
+                module.applies.push(apply);
+            },
+            ast::RootStmnt::Assign(assign) => hir_lower_block_expr(ctx, &mut scopes, &ast::BlockExpr::Assign(assign.clone()), &mut stmts),
+            ast::RootStmnt::Call(call) => hir_lower_block_expr(ctx, &mut scopes, &ast::BlockExpr::Call(call.clone()), &mut stmts),
+            ast::RootStmnt::If(if_) => hir_lower_block_expr(ctx, &mut scopes, &ast::BlockExpr::If(if_.clone()), &mut stmts),
+            ast::RootStmnt::While(while_) => hir_lower_block_expr(ctx, &mut scopes, &ast::BlockExpr::While(while_.clone()), &mut stmts),
+            ast::RootStmnt::Match(match_) => hir_lower_block_expr(ctx, &mut scopes, &ast::BlockExpr::Match(match_.clone()), &mut stmts),
+            ast::RootStmnt::Block(flags, block) if hir_lower_flags(ctx, flags) => {
+                let block = hir_lower_block(ctx, &mut scopes, block, ScopeKind::Block);
+                stmts.push(hir::Stmt { span: block.span.clone(), kind: hir::StmtKind::Block(block) });
+            },
+            _ => {},
+        }
+
+        module.init.extend(stmts.drain(..).map(hir::Init::Stmt));
+    }
+
+    // the types of values are lowered more than once, so each contract object type is only checked once.
+    let mut unsized_ = vec![];
+    let mut checked = vec![];
+    for (ty, behind_ref) in std::mem::take(&mut ctx.dyns) {
+        if !behind_ref && !unsized_.contains(&ty.span) {
+            unsized_.push(ty.span.clone());
+            ctx.error("E0009", ty.span.clone(), "contract objects have no size, so `dyn` types can only be used behind a reference".into());
+        }
+
+        if let hir::TypeKind::Dyn(contract) = ty.kind {
+            if !checked.contains(&contract.to_string()) {
+                checked.push(contract.to_string());
+                hir_lower_object_safety(ctx, &contract);
+            }
+        }
+    }
+
+    module.vtables = std::mem::take(&mut ctx.vtables);
+}
+
+/// Checks that a contract object type names a contract which can be called through a vtable.
+///
+/// Every item of the contract must be a routine which takes `self` by reference as its first argument and doesn't
+/// refer to `Self` anywhere else, since the type behind the object isn't known where it is called.
+fn hir_lower_object_safety(ctx: &mut HirLowerContext, contract: &hir::Type) {
+    let name = contract.to_string();
+    let items = match ctx.types.get(&name).map(|decl| &hir_lower_resolve(ctx, decl).kind) {
+        Some(hir::TypeKind::Contract(items)) => items.clone(),
+        Some(_) => {
+            ctx.error("E0009", contract.span.clone(), format!("`dyn` can only be applied to contracts, but `{}` is not one", name));
+            return;
+        },
+        // contracts from other modules are checked when their module is lowered.
+        None => return,
+    };
+
+    for item in &items {
+        let signature = match &item.kind {
+            hir::ItemKind::Val(Some(ty), _) => match &hir_lower_resolve(ctx, ty).kind {
+                hir::TypeKind::Routine(args, returns) => Some((args.clone(), returns.clone())),
+                _ => None,
+            },
+            hir::ItemKind::Val(None, Some(hir::Expr { kind: hir::ExprKind::Routine(routine), .. })) => Some((
+                routine.args.iter().map(|arg| arg.ty.clone()).collect(),
+                routine.returns.clone().map(Box::new),
+            )),
+            _ => None,
+        };
+
+        let reason = match (&item.kind, signature) {
+            (hir::ItemKind::Type(_), _) => format!("it declares the type `{}`", item.name),
+            (_, None) => format!("`{}` is not a routine", item.name),
+            (_, Some((args, returns))) => {
+                let by_ref = args.first().is_some_and(|arg| match &arg.unmodified().kind {
+                    hir::TypeKind::Ref(inner) => inner.unmodified().to_string() == "Self",
+                    _ => false,
+                });
+
+                if !by_ref {
+                    format!("`{}` does not take `self` as `&Self` or `&fluid Self`", item.name)
+                } else if args.iter().skip(1).chain(returns.as_deref()).any(hir_lower_mentions_self) {
+                    format!("`{}` refers to `Self` outside of its `self` argument", item.name)
+                } else {
+                    continue;
+                }
+            },
+        };
+
+        ctx.error("E0009", contract.span.clone(), format!("`{0}` cannot be used as `dyn {0}`, because {1}", name, reason));
+    }
+}
+
+/// Whether or not a type refers to `Self`.
+fn hir_lower_mentions_self(ty: &hir::Type) -> bool {
+    match &ty.kind {
+        hir::TypeKind::Name(name) => name == "Self",
+        hir::TypeKind::Path(left, _) => hir_lower_mentions_self(left),
+        hir::TypeKind::Routine(args, returns) | hir::TypeKind::Closure(args, returns) => {
+            args.iter().chain(returns.as_deref()).any(hir_lower_mentions_self)
+        },
+        hir::TypeKind::Struct(fields) | hir::TypeKind::Union(fields) => fields.iter().any(|field| hir_lower_mentions_self(&field.ty)),
+        hir::TypeKind::Enum(variants) => variants.iter().filter_map(|variant| variant.ty.as_ref()).any(hir_lower_mentions_self),
+        hir::TypeKind::Contract(_) => false,
+        hir::TypeKind::Shared(ty)
+        | hir::TypeKind::Fluid(ty)
+        | hir::TypeKind::Ref(ty)
+        | hir::TypeKind::Dyn(ty)
+        | hir::TypeKind::Variadic(Some(ty))
+        | hir::TypeKind::Opt(ty) => hir_lower_mentions_self(ty),
+        hir::TypeKind::Variadic(None) => false,
+        hir::TypeKind::Res(ok, err) => hir_lower_mentions_self(ok) || hir_lower_mentions_self(err),
+    }
+}
+
+/// Lowers the root of a unit to a module, then evaluates its constants.
+fn hir_lower_module(ctx: &mut HirLowerContext, name: String, stmnts: &[ast::RootStmnt]) -> hir::Module {
+    let mut module = hir::Module {
+        name,
+        items: vec![],
+        applies: vec![],
+        consts: HashMap::new(),
+        discriminants: HashMap::new(),
+        init: vec![],
+        deps: vec![],
+        vtables: vec![],
+    };
+    hir_lower_items(ctx, stmnts, &mut module);
+
+    for e in const_eval::const_eval(&mut module, ctx.target) {
+        ctx.error(e.code, e.span, e.message);
+    }
+
+    module
+}
+
+/// A module that an import path refers to.
+#[derive(Clone, Debug)]
+struct ModuleRef {
+    /// The file that the root of the module was loaded from, or `None` for the unit being lowered.
+    file: Option<PathBuf>,
+
+    /// The inline modules to follow from the root of the file.
+    inline: Vec<String>,
+
+    /// The full path of the module, which the items imported from it are named by.
+    path: Vec<String>,
+}
+
+/// Finds the source file of a module, searching the unit's directory before the library paths.
+fn find_module(ctx: &HirLowerContext, unit: &HirLowerUnit, name: &str) -> Option<PathBuf> {
+    std::iter::once(&unit.dir)
+        .chain(ctx.libs.iter())
+        .map(|dir| Path::new(dir).join(format!("{}.hl", name)))
+        .find(|path| path.is_file())
+}
+
+/// Loads the module stored in the provided file, returning the file that it was loaded from.
+fn load_module(ctx: &mut HirLowerContext, path: PathBuf, name: &ast::Id) -> Option<PathBuf> {
+    if !ctx.modules.contains_key(&path) {
+        load_module_file(ctx, &path, name)?;
+    }
+
+    ctx.deps.push((path.clone(), name.span.clone()));
+    Some(path)
+}
+
+/// Parses and lowers the module stored in a file, then resolves its imports.
+fn load_module_file(ctx: &mut HirLowerContext, path: &Path, name: &ast::Id) -> Option<()> {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(_) => {
+            ctx.error("E0018", name.span.clone(), format!("unable to read module `{}` from `{}`", name.value, path.display()));
+            return None;
+        },
+    };
+
+    let (line, column) = ctx.files.line_col(ctx.file, name.span.start);
+    let imported = format!("{}:{}:{}", ctx.files.name(ctx.file), line, column);
+    let file = ctx.files.add(path.display().to_string(), source.as_str());
+    ctx.sources.insert(path.to_path_buf(), file);
+
+    let ast = match hail_parser::parse(&source) {
+        Ok(ast) => ast,
+        Err(diagnostic) => {
+            ctx.report(diagnostic.with_file(file).with_note(format!("in module `{}`, imported at {}", name.value, imported)));
+            return None;
+        },
+    };
+
+    // the module's own errors are reported in its file, and the declarations of the unit are out of scope in it.
+    let importer = std::mem::replace(&mut ctx.file, file);
+    let decls = ctx.take_decls();
+    hir_lower_attrs(ctx, &ast);
+    let mut module = hir_lower_module(ctx, name.value.into(), &ast);
+    ctx.restore_decls(decls);
+
+    // the module is stored before its imports are resolved, so that modules which import each other can find it.
+    ctx.modules.insert(path.to_path_buf(), module.clone());
+
+    let unit = HirLowerUnit {
+        name: name.value.into(),
+        dir: match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy().into(),
+            _ => "./".into(),
+        },
+        ast,
+    };
+    hir_lower_imports(ctx, &unit, &mut module);
+    ctx.modules.insert(path.to_path_buf(), module);
+    ctx.file = importer;
+
+    Some(())
+}
+
+/// Finds the module that a reference points to.
+fn hir_lower_module_at<'m>(ctx: &'m HirLowerContext, root: &'m hir::Module, at: &ModuleRef) -> &'m hir::Module {
+    let mut module = match &at.file {
+        Some(file) => &ctx.modules[file],
+        None => root,
+    };
+
+    for name in &at.inline {
+        match module.item(name).map(|item| &item.kind) {
+            Some(hir::ItemKind::Module(inline)) => module = inline,
+            _ => break,
+        }
+    }
+
+    module
+}
+
+/// Finds an inline module to add imported items to.
+fn hir_lower_module_mut<'m>(module: &'m mut hir::Module, inline: &[String]) -> &'m mut hir::Module {
+    let found = inline.split_first().and_then(|(name, rest)| {
+        let index = module.items.iter().position(|item| &item.name == name && matches!(item.kind, hir::ItemKind::Module(_)));
+        index.map(|index| (index, rest))
+    });
+
+    match found {
+        Some((index, rest)) => match &mut module.items[index].kind {
+            hir::ItemKind::Module(inline) => hir_lower_module_mut(inline, rest),
+            _ => unreachable!(),
+        },
+        None => module,
+    }
+}
+
+/// Whether or not an item can be imported into a module; private items are only visible within the module
+/// that declares them, and the modules nested inside of it.
+fn hir_lower_visible(at: &ModuleRef, from: &[String], shared: bool) -> bool {
+    shared || (at.file.is_none() && from.starts_with(&at.inline))
+}
+
+/// Finds a submodule of a module, either declared inline or stored in a directory named after the module.
+fn hir_lower_submodule(
+    ctx: &mut HirLowerContext,
+    unit: &HirLowerUnit,
+    root: &hir::Module,
+    from: &[String],
+    parent: &ModuleRef,
+    name: &ast::Id,
+) -> Option<ModuleRef> {
+    let mut child = parent.clone();
+    child.path.push(name.value.into());
+
+    let module = hir_lower_module_at(ctx, root, parent);
+    let message = match module.item(name.value) {
+        Some(item) => match &item.kind {
+            hir::ItemKind::Module(_) if hir_lower_visible(parent, from, item.shared) => {
+                child.inline.push(name.value.into());
+                return Some(child);
+            },
+            hir::ItemKind::Module(_) => format!("module `{}` is private to `{}`", name.value, parent.path.join("::")),
+            _ => format!("`{}` is not a module", child.path.join("::")),
+        },
+        None => format!("module `{}` has no item named `{}`", parent.path.join("::"), name.value),
+    };
+
+    let dir = match (&parent.file, parent.inline.is_empty()) {
+        (Some(file), true) => Some(file.with_extension("")),
+        (None, true) => Some(Path::new(&unit.dir).join(&unit.name)),
+        _ => None,
+    };
+
+    match dir.map(|dir| dir.join(format!("{}.hl", name.value))) {
+        Some(file) if file.is_file() => {
+            let file = load_module(ctx, file, name)?;
+            Some(ModuleRef { file: Some(file), inline: vec![], path: child.path })
+        },
+        _ => {
+            ctx.error("E0018", name.span.clone(), message);
+            None
+        },
+    }
+}
+
+/// Resolves the path of the module that items are imported from, relative to the module importing them.
+fn hir_lower_module_path(
+    ctx: &mut HirLowerContext,
+    unit: &HirLowerUnit,
+    root: &hir::Module,
+    from: &[String],
+    path: &ast::GlobalPath,
+) -> Option<ModuleRef> {
+    let mut ids = vec![];
+    let mut branch = path;
+    while let ast::GlobalPath::Branch { path, id, .. } = branch {
+        ids.push(id);
+        branch = path;
+    }
+    if let ast::GlobalPath::Id(id) = branch {
+        ids.push(id);
+    }
+    ids.reverse();
+
+    let here = ModuleRef {
+        file: None,
+        inline: from.to_vec(),
+        path: std::iter::once(&unit.name).chain(from).cloned().collect(),
+    };
+
+    let first = ids[0];
+    let mut at = match first.value {
+        "self" | "super" => here,
+        // inline modules of the importing module shadow the modules stored in files.
+        name if matches!(
+            hir_lower_module_at(ctx, root, &here).item(name).map(|item| &item.kind),
+            Some(hir::ItemKind::Module(_)),
+        ) => hir_lower_submodule(ctx, unit, root, from, &here, first)?,
+        name => match find_module(ctx, unit, name) {
+            Some(file) => ModuleRef { file: Some(load_module(ctx, file, first)?), inline: vec![], path: vec![name.into()] },
+            None => {
+                ctx.error("E0018", first.span.clone(), format!("unable to find module `{}`", name));
+                return None;
+            },
+        },
+    };
+
+    for (i, id) in ids.into_iter().enumerate() {
+        match id.value {
+            "self" if i == 0 => {},
+            "super" if at.file.is_none() && !at.inline.is_empty() => {
+                at.inline.pop();
+                at.path.pop();
+            },
+            "super" => {
+                ctx.error("E0018", id.span.clone(), format!("`super` cannot refer above the root of `{}`", at.path.join("::")));
+                return None;
+            },
+            "self" => {
+                ctx.error("E0018", id.span.clone(), "`self` can only start an import path".into());
+                return None;
+            },
+            _ if i == 0 => {},
+            _ => at = hir_lower_submodule(ctx, unit, root, from, &at, id)?,
+        }
+    }
+
+    Some(at)
+}
+
+/// Resolves an item imported from a module, returning its full path.
+fn hir_lower_import_item(
+    ctx: &mut HirLowerContext,
+    unit: &HirLowerUnit,
+    root: &hir::Module,
+    from: &[String],
+    at: &ModuleRef,
+    name: &ast::Id,
+) -> Option<Vec<String>> {
+    let module = hir_lower_module_at(ctx, root, at);
+    match module.item(name.value) {
+        Some(item) if hir_lower_visible(at, from, item.shared) => {
+            let mut path = at.path.clone();
+            path.push(name.value.into());
+            Some(path)
+        },
+        Some(_) => {
+            let message = format!(
+                "`{}` is private to module `{}`, mark it as `shared` to import it",
+                name.value,
+                at.path.join("::"),
+            );
+            ctx.error("E0019", name.span.clone(), message);
+            None
+        },
+        // submodules stored in files are only loaded once they are imported.
+        None => hir_lower_submodule(ctx, unit, root, from, at, name).map(|module| module.path),
+    }
+}
+
+/// Resolves the imports of a module and the inline modules nested in it, collecting the imported items along
+/// with the module that imports them and whether or not they were imported by a glob.
+fn hir_lower_module_imports(
+    ctx: &mut HirLowerContext,
+    unit: &HirLowerUnit,
+    root: &hir::Module,
+    from: &mut Vec<String>,
+    stmnts: &[ast::RootStmnt],
+    imported: &mut Vec<(Vec<String>, hir::Item, bool)>,
+) {
+    for stmnt in stmnts {
+        match stmnt {
+            ast::RootStmnt::Import(flags, imp) if hir_lower_flags(ctx, flags) => {
+                let (shared, items, path) = match imp {
+                    // `import name` imports a module rather than an item of one.
+                    ast::Import::Single { shared, item, from: None, .. } => {
+                        let path = ast::GlobalPath::Id(item.name.clone());
+                        if let Some(at) = hir_lower_module_path(ctx, unit, root, from, &path) {
+                            imported.push((from.clone(), hir::Item {
+                                span: item.span.clone(),
+                                shared: *shared,
+                                attrs: vec![],
+                                name: item.as_.as_ref().unwrap_or(&item.name).value.into(),
+                                kind: hir::ItemKind::Import(at.path),
+                            }, false));
+                        }
+
+                        continue;
+                    },
+                    ast::Import::Single { shared, item, from: Some(path), .. } => (*shared, std::slice::from_ref(item), path),
+                    ast::Import::Multi { shared, items, from: path, .. } => (*shared, items.as_slice(), path),
+                    ast::Import::Glob { span, shared, from: path } => {
+                        let at = match hir_lower_module_path(ctx, unit, root, from, path) {
+                            Some(at) => at,
+                            None => continue,
+                        };
+
+                        let module = hir_lower_module_at(ctx, root, &at);
+                        for item in module.items.iter().filter(|item| hir_lower_visible(&at, from, item.shared)) {
+                            let mut path = at.path.clone();
+                            path.push(item.name.clone());
+
+                            imported.push((from.clone(), hir::Item {
+                                span: span.clone(),
+                                shared: *shared,
+                                attrs: vec![],
+                                name: item.name.clone(),
+                                kind: hir::ItemKind::Import(path),
+                            }, true));
+                        }
+
+                        continue;
+                    },
+                };
+
+                let at = match hir_lower_module_path(ctx, unit, root, from, path) {
+                    Some(at) => at,
+                    None => continue,
+                };
+
+                for item in items {
+                    if let Some(path) = hir_lower_import_item(ctx, unit, root, from, &at, &item.name) {
+                        imported.push((from.clone(), hir::Item {
+                            span: item.span.clone(),
+                            shared,
+                            attrs: vec![],
+                            name: item.as_.as_ref().unwrap_or(&item.name).value.into(),
+                            kind: hir::ItemKind::Import(path),
+                        }, false));
+                    }
+                }
+            },
+            ast::RootStmnt::Module(flags, module) if hir_lower_flags(ctx, flags) => {
+                from.push(module.name.value.into());
+                hir_lower_module_imports(ctx, unit, root, from, &module.items, imported);
+                from.pop();
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Resolves the imports of a lowering unit, filling in the imported items of its modules.
+fn hir_lower_imports<'a>(ctx: &mut HirLowerContext, unit: &HirLowerUnit<'a>, root: &mut hir::Module) {
+    // the modules loaded by the imports use properties of their own.
+    let props = std::mem::take(&mut ctx.props);
+    let saved = std::mem::take(&mut ctx.deps);
+    let mut imported = vec![];
+    hir_lower_module_imports(ctx, unit, root, &mut vec![], &unit.ast, &mut imported);
+
+    for (file, span) in std::mem::replace(&mut ctx.deps, saved) {
+        if !root.deps.iter().any(|(other, _)| *other == file) {
+            root.deps.push((file, span));
+        }
+    }
+
+    for (from, item, glob) in imported {
+        let module = hir_lower_module_mut(root, &from);
+
+        // items declared in a module take precedence over the items of a glob import.
+        if glob {
+            if module.item(&item.name).is_none() {
+                module.items.push(item);
+            }
+        } else if let Some(slot) = module.items.iter_mut().find(|other| other.name == item.name && other.kind == hir::ItemKind::Import(vec![])) {
+            *slot = item;
+        }
+    }
+
+    for prop in props {
+        hir_lower_prop_visibility(ctx, unit, root, prop);
+    }
+}
+
+/// Checks that a property of an imported type is visible to the module that uses it. Like items, properties are
+/// private to the module that declares their type, and the modules nested inside of it, unless they are `shared`.
+fn hir_lower_prop_visibility(ctx: &mut HirLowerContext, unit: &HirLowerUnit, root: &hir::Module, prop: PropUse) {
+    let here = ModuleRef { file: None, inline: prop.module.clone(), path: vec![] };
+    let path = match hir_lower_module_at(ctx, root, &here).item(&prop.ty).map(|item| &item.kind) {
+        Some(hir::ItemKind::Import(path)) => path.clone(),
+        _ => return,
+    };
+
+    // the path of an imported item starts with the unit or the module file that it is declared in.
+    let (ty, module) = match path.split_last() {
+        Some((ty, module)) if !module.is_empty() => (ty, module),
+        _ => return,
+    };
+    let file = match module[0] == unit.name {
+        true => None,
+        false => match root.deps.iter().find(|(file, _)| ctx.modules.get(file).is_some_and(|dep| dep.name == module[0])) {
+            Some((file, _)) => Some(file.clone()),
+            None => return,
+        },
+    };
+    let at = ModuleRef { file, inline: module[1..].to_vec(), path: module.to_vec() };
+
+    let shared = match hir_lower_module_at(ctx, root, &at).item(ty).map(|item| &item.kind) {
+        Some(hir::ItemKind::Type(Some(hir::Type { kind: hir::TypeKind::Struct(fields) | hir::TypeKind::Union(fields), .. }))) => {
+            match fields.iter().find(|field| field.name == prop.name) {
+                Some(field) => field.shared,
+                None => return,
+            }
+        },
+        _ => return,
+    };
+
+    if !hir_lower_visible(&at, &prop.module, shared) {
+        let message = format!(
+            "property `{}` of `{}` is private to module `{}`, mark it as `shared` to use it",
+            prop.name,
+            prop.ty,
+            at.path.join("::"),
+        );
+        ctx.error("E0019", prop.span, message);
+    }
+}
+
+/// Orders the loaded modules so that each is initialized after the modules it imports. The unit stored in the
+/// provided file is initialized last, so modules which import it are part of a cycle.
+fn hir_lower_init_order(ctx: &mut HirLowerContext, unit: PathBuf, root: &hir::Module) {
+    let mut order = vec![];
+    hir_lower_init_visit(ctx, &root.deps, &mut vec![unit], &mut order);
+    ctx.init_order = order;
+}
+
+/// Adds the modules behind a list of imports to the initialization order, after the modules they import.
+///
+/// Modules which import each other have no order to be initialized in, which is an error unless none of them
+/// have anything to initialize.
+fn hir_lower_init_visit(ctx: &mut HirLowerContext, deps: &[(PathBuf, ast::Span)], stack: &mut Vec<PathBuf>, order: &mut Vec<PathBuf>) {
+    for (file, span) in deps {
+        if order.contains(file) {
+            continue;
+        }
+
+        if let Some(start) = stack.iter().position(|other| other == file) {
+            let cycle = &stack[start..];
+            if cycle.iter().any(|file| !ctx.modules[file].init.is_empty()) {
+                let names = cycle.iter()
+                    .chain(std::iter::once(file))
+                    .map(|file| ctx.modules[file].name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                // the import which closes the cycle is in the module on top of the stack, or in the unit itself.
+                let file = stack.last().map_or(ctx.file, |importer| ctx.sources[importer]);
+                let diagnostic = Diagnostic::error(format!("modules import each other, so they cannot be initialized in order: {}", names));
+                ctx.report(diagnostic.with_code("E0020").with_file(file).with_primary(span.clone(), ""));
+            }
+
+            continue;
+        }
+
+        stack.push(file.clone());
+        let deps = ctx.modules[file].deps.clone();
+        hir_lower_init_visit(ctx, &deps, stack, order);
+        stack.pop();
+        order.push(file.clone());
+    }
+}
+
+/// Validates markers against the attribute registry.
+fn hir_lower_markers(ctx: &mut HirLowerContext, markers: &[ast::Marker], target: AttrTarget) {
+    for marker in markers {
+        let attr = match attrs::lookup(marker.name.value) {
+            Some(attr) => attr,
+            None => {
+                ctx.error("E0021", marker.name.span.clone(), format!("unknown attribute `#{}`", marker.name.value));
+                continue;
+            },
+        };
+
+        if !attr.targets.contains(&target) {
+            ctx.error("E0021", marker.span.clone(), format!("attribute `#{}` cannot be applied to {}", attr.name, target.describe()));
+        } else if !attr.accepts(&marker.args) {
+            ctx.error("E0021", marker.span.clone(), format!("invalid arguments for `#{}`, expected `{}`", attr.name, attr.signature()));
+        }
+    }
+}
+
+/// Checks that the alignments requested by `#align` markers are powers of two.
+fn hir_lower_align_markers(ctx: &mut HirLowerContext, markers: &[ast::Marker]) {
+    for marker in markers.iter().filter(|marker| marker.name.value == "align") {
+        if let [ast::Expr::Num(num)] = &marker.args[..] {
+            if !hir::parse_int(&num.kind, num.value).is_some_and(u64::is_power_of_two) {
+                ctx.error("E0021", num.span.clone(), format!("alignment `{}` is not a power of two", num.value));
+            }
+        }
+    }
+}
+
+/// Validates the markers of the properties in a type expression.
+fn hir_lower_type_markers(ctx: &mut HirLowerContext, ty: &ast::Type) {
+    match ty {
+        ast::Type::Id(_) => {},
+        ast::Type::Path(path) => hir_lower_type_markers(ctx, &path.left),
+        ast::Type::Routine(routine) | ast::Type::Closure(routine) => {
+            for arg in &routine.args {
+                hir_lower_type_markers(ctx, arg);
+            }
+
+            if let Some(returns) = &routine.returns {
+                hir_lower_type_markers(ctx, returns);
+            }
+        },
+        ast::Type::Struct(struct_) => {
+            for prop in &struct_.props {
+                hir_lower_markers(ctx, &prop.markers, AttrTarget::Field);
+                hir_lower_align_markers(ctx, &prop.markers);
+                hir_lower_type_markers(ctx, &prop.ty);
+            }
+        },
+        ast::Type::Union(union) => {
+            for prop in &union.props {
+                hir_lower_markers(ctx, &prop.markers, AttrTarget::Field);
+                hir_lower_align_markers(ctx, &prop.markers);
+                hir_lower_type_markers(ctx, &prop.ty);
+            }
+        },
+        ast::Type::Enum(enum_) => {
+            for prop in &enum_.props {
+                hir_lower_markers(ctx, &prop.markers, AttrTarget::Variant);
+
+                if let Some(ty) = &prop.ty {
+                    hir_lower_type_markers(ctx, ty);
+                }
+            }
+        },
+        ast::Type::Contract(contract) => {
+            for item in &contract.items {
+                hir_lower_application_markers(ctx, item);
+            }
+        },
+        ast::Type::Shared(ty) => hir_lower_type_markers(ctx, &ty.subject),
+        ast::Type::Fluid(ty) => hir_lower_type_markers(ctx, &ty.subject),
+        ast::Type::Ref(ty) => hir_lower_type_markers(ctx, &ty.subject),
+        ast::Type::Dyn(ty) => hir_lower_type_markers(ctx, &ty.subject),
+        ast::Type::Variadic(ty) => {
+            if let Some(ty) = &ty.ty {
+                hir_lower_type_markers(ctx, ty);
+            }
+        },
+        ast::Type::Opt(ty) => hir_lower_type_markers(ctx, &ty.subject),
+        ast::Type::Res(ty) => {
+            hir_lower_type_markers(ctx, &ty.ok);
+            hir_lower_type_markers(ctx, &ty.err);
+        },
+    }
+}
+
+/// Validates the markers of a variable declaration.
+fn hir_lower_val_markers(ctx: &mut HirLowerContext, val: &ast::Val) {
+    hir_lower_markers(ctx, &val.markers, AttrTarget::Val);
+
+    if let Some(ty) = &val.ty {
+        hir_lower_type_markers(ctx, ty);
+    }
+
+    if let Some(value) = &val.value {
+        LocalMarkers { ctx }.visit_expr(value);
+    }
+}
+
+/// Validates the markers of the routine literals in an expression, and of the declarations in their bodies.
+struct LocalMarkers<'c> {
+    ctx: &'c mut HirLowerContext,
+}
+
+impl<'a> Visit<'a> for LocalMarkers<'_> {
+    fn visit_block_expr(&mut self, node: &ast::BlockExpr<'a>) {
+        match node {
+            ast::BlockExpr::Block(flags, _) if !hir_lower_flags(self.ctx, flags) => {},
+            ast::BlockExpr::TypeDecl(ty) => hir_lower_type_decl_markers(self.ctx, ty),
+            _ => visit::visit_block_expr(self, node),
+        }
+    }
+
+    fn visit_val(&mut self, node: &ast::Val<'a>) {
+        hir_lower_markers(self.ctx, &node.markers, AttrTarget::Val);
+
+        if let Some(ty) = &node.ty {
+            hir_lower_type_markers(self.ctx, ty);
+        }
+
+        if let Some(value) = &node.value {
+            self.visit_expr(value);
+        }
+    }
+
+    fn visit_routine(&mut self, node: &ast::Routine<'a>) {
+        hir_lower_markers(self.ctx, &node.markers, AttrTarget::Routine);
+
+        for arg in &node.args {
+            hir_lower_type_markers(self.ctx, &arg.ty);
+        }
+
+        if let Some(returns) = &node.returns {
+            hir_lower_type_markers(self.ctx, returns);
+        }
+
+        self.visit_block(&node.block);
+    }
+}
+
+/// Validates the markers of a type declaration.
+fn hir_lower_type_decl_markers(ctx: &mut HirLowerContext, ty: &ast::TypeDecl) {
+    hir_lower_markers(ctx, &ty.markers, AttrTarget::Type);
+
+    let record = matches!(ty.value, Some(ast::Type::Struct(_) | ast::Type::Union(_)));
+    let enum_ = matches!(ty.value, Some(ast::Type::Enum(_)));
+    for marker in &ty.markers {
+        match (marker.name.value, marker.args.first()) {
+            ("repr", Some(ast::Expr::Id(id))) if id.value == "C" && !record && !enum_ => {
+                ctx.error("E0021", marker.span.clone(), "`#repr(C)` can only be applied to structs, unions and enums".into());
+            },
+            ("repr", Some(ast::Expr::Id(id))) if id.value != "C" && !enum_ => {
+                ctx.error("E0021", marker.span.clone(), "integer representations can only be applied to enums".into());
+            },
+            ("packed", _) => {
+                if !record {
+                    ctx.error("E0021", marker.span.clone(), "`#packed` can only be applied to structs and unions".into());
+                }
+
+                if ty.markers.iter().any(|other| other.name.value == "align") {
+                    ctx.error("E0021", marker.span.clone(), "`#packed` and `#align` cannot be applied to the same type".into());
+                }
+            },
+            _ => {},
+        }
+    }
+
+    hir_lower_align_markers(ctx, &ty.markers);
+
+    if let Some(value) = &ty.value {
+        hir_lower_type_markers(ctx, value);
+    }
+}
+
+/// Validates the markers of an item in an `apply` statement or contract.
+fn hir_lower_application_markers(ctx: &mut HirLowerContext, item: &ast::Application) {
+    match item {
+        ast::Application::Val(val) => hir_lower_val_markers(ctx, val),
+        ast::Application::TypeDecl(ty) => hir_lower_type_decl_markers(ctx, ty),
+    }
+}
+
+/// Validates the markers of the declarations in the root of a module.
+fn hir_lower_attrs(ctx: &mut HirLowerContext, stmnts: &[ast::RootStmnt]) {
+    for item in stmnts {
+        match item {
+            ast::RootStmnt::Val(flags, val) if hir_lower_flags(ctx, flags) => hir_lower_val_markers(ctx, val),
+            ast::RootStmnt::TypeDecl(flags, ty) if hir_lower_flags(ctx, flags) => hir_lower_type_decl_markers(ctx, ty),
+            ast::RootStmnt::Const(flags, const_) if hir_lower_flags(ctx, flags) => {
+                hir_lower_markers(ctx, &const_.markers, AttrTarget::Const);
+
+                if let Some(ty) = &const_.ty {
+                    hir_lower_type_markers(ctx, ty);
+                }
+            },
+            ast::RootStmnt::Apply(flags, apply) if hir_lower_flags(ctx, flags) => {
+                hir_lower_markers(ctx, &apply.markers, AttrTarget::Apply);
+
+                for item in &apply.items {
+                    hir_lower_application_markers(ctx, item);
+                }
+            },
+            ast::RootStmnt::Module(flags, module) if hir_lower_flags(ctx, flags) => hir_lower_attrs(ctx, &module.items),
+            // the statements of the module's initializer declare locals and routine literals of their own.
+            ast::RootStmnt::Assign(assign) => LocalMarkers { ctx }.visit_assign(assign),
+            ast::RootStmnt::Call(call) => LocalMarkers { ctx }.visit_call(call),
+            ast::RootStmnt::If(if_) => LocalMarkers { ctx }.visit_if(if_),
+            ast::RootStmnt::While(while_) => LocalMarkers { ctx }.visit_while(while_),
+            ast::RootStmnt::Match(match_) => LocalMarkers { ctx }.visit_match(match_),
+            ast::RootStmnt::Block(flags, block) if hir_lower_flags(ctx, flags) => LocalMarkers { ctx }.visit_block(block),
+            _ => {},
+        }
+    }
+}
+
+/// Lowers a hail AST to hail HIR.
+pub fn hir_lower<'a>(ctx: &mut HirLowerContext, unit: &HirLowerUnit<'a>) -> hir::Module {
+    hir_lower_attrs(ctx, &unit.ast);
+
+    let mut module = hir_lower_module(ctx, unit.name.clone(), &unit.ast);
+
+    // the unit is stored as the module of its own file, so that modules which import it find it rather than
+    // loading it a second time.
+    let path = Path::new(&unit.dir).join(format!("{}.hl", unit.name));
+    ctx.sources.insert(path.clone(), ctx.file);
+    ctx.modules.insert(path.clone(), module.clone());
+
+    hir_lower_imports(ctx, unit, &mut module);
+    hir_lower_init_order(ctx, path, &module);
+    module
+}
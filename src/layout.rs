@@ -0,0 +1,338 @@
+//! The memory layout of hail types.
+
+use std::fmt;
+
+use target_lexicon::{Architecture, OperatingSystem, Triple};
+
+use crate::hir;
+
+/// The details of a compilation target which affect the layout of types.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Target {
+    /// The size of a pointer, in bytes.
+    pub pointer_size: u64,
+
+    /// The alignment of `i64`, `u64` and `f64`, in bytes, which C gives less than their size on some 32-bit targets.
+    pub align_64: u64,
+}
+
+impl Target {
+    /// Finds the layout details of the provided target, assuming 64-bit pointers if its width is unknown.
+    pub fn of(triple: &Triple) -> Self {
+        // the System V ABI for 32-bit x86 only aligns 8 byte scalars to 4, while Windows keeps them at 8.
+        let align_64 = match (triple.architecture, triple.operating_system) {
+            (Architecture::X86_32(_), os) if os != OperatingSystem::Windows => 4,
+            _ => 8,
+        };
+
+        Self { pointer_size: triple.pointer_width().map(|width| width.bytes() as u64).unwrap_or(8), align_64 }
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Self::of(&Triple::host())
+    }
+}
+
+/// The size and alignment of a type, in bytes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Layout {
+    /// The size of the type, which is always a multiple of its alignment.
+    pub size: u64,
+
+    /// The alignment of the type.
+    pub align: u64,
+}
+
+/// Where a property is placed within a struct or union.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldLayout {
+    /// The name of the property.
+    pub name: String,
+
+    /// The offset of the property from the start of the struct, in bytes.
+    pub offset: u64,
+
+    /// The layout of the property, or of the storage it shares with other bitfields.
+    pub layout: Layout,
+
+    /// The offset and width of the property in bits within its storage, if it is a bitfield.
+    pub bits: Option<(u32, u32)>,
+}
+
+/// The layout of a struct or union along with the placement of each of its properties, in declaration order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordLayout {
+    /// The layout of the whole struct or union.
+    pub layout: Layout,
+
+    /// The placement of each property.
+    pub fields: Vec<FieldLayout>,
+}
+
+impl fmt::Display for RecordLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "size {}, align {}", self.layout.size, self.layout.align)?;
+
+        let mut fields = self.fields.iter().collect::<Vec<_>>();
+        fields.sort_by_key(|field| (field.offset, field.bits.map(|(offset, _)| offset)));
+        for field in fields {
+            match field.bits {
+                Some((offset, width)) => writeln!(f, "  {:>4}  {} (bits {}..{} of {} bytes)", field.offset, field.name, offset, offset + width, field.layout.size)?,
+                None => writeln!(f, "  {:>4}  {} ({} bytes, align {})", field.offset, field.name, field.layout.size, field.layout.align)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Rounds an offset up to the next multiple of an alignment.
+fn align_to(offset: u64, align: u64) -> u64 {
+    offset.div_ceil(align) * align
+}
+
+/// A run of properties placed together, either a single property or consecutive bitfields sharing storage.
+struct Unit {
+    /// The layout of the storage.
+    layout: Layout,
+
+    /// Whether or not the storage holds bitfields.
+    bitfield: bool,
+
+    /// The number of bits of the storage in use.
+    used: u32,
+
+    /// The index and bits of each property in the storage.
+    fields: Vec<(usize, Option<(u32, u32)>)>,
+}
+
+impl Layout {
+    /// The layout of a primitive with the same size and alignment.
+    fn scalar(size: u64) -> Self {
+        Self { size, align: size }
+    }
+
+    /// The layout of a struct with the provided properties, laid out in order.
+    fn sequence(fields: impl IntoIterator<Item = Layout>) -> Self {
+        let mut size = 0;
+        let mut align = 1;
+        for field in fields {
+            size = align_to(size, field.align) + field.size;
+            align = align.max(field.align);
+        }
+
+        Self { size: align_to(size, align), align }
+    }
+
+    /// The layout of a union of the provided properties, which all start at the same address.
+    fn overlap(fields: impl IntoIterator<Item = Layout>) -> Self {
+        let mut size = 0;
+        let mut align = 1;
+        for field in fields {
+            size = size.max(field.size);
+            align = align.max(field.align);
+        }
+
+        Self { size: align_to(size, align), align }
+    }
+
+    /// The layout of a tag large enough for the provided number of variants.
+    fn tag(variants: usize) -> Self {
+        match variants {
+            0..=0x100 => Self::scalar(1),
+            0x101..=0x10000 => Self::scalar(2),
+            _ => Self::scalar(4),
+        }
+    }
+
+    /// The layout of a tagged type, with the tag followed by the payloads.
+    fn tagged(tag: Layout, payloads: impl IntoIterator<Item = Layout>) -> Self {
+        Self::sequence([tag, Self::overlap(payloads)])
+    }
+
+    /// Raises the alignment of a layout to at least the provided alignment.
+    fn aligned(self, align: Option<u64>) -> Self {
+        let align = self.align.max(align.unwrap_or(1));
+        Self { size: align_to(self.size, align), align }
+    }
+
+    /// Computes the layout of a type, resolving named types through the type declarations in a module.
+    ///
+    /// Returns `None` if the layout cannot be known, such as for contracts, types from other modules, or types
+    /// which contain themselves.
+    pub fn of(module: &hir::Module, target: &Target, ty: &hir::Type) -> Option<Self> {
+        Self::of_depth(module, target, ty, &[], 0)
+    }
+
+    /// Computes the layout of a struct or union type along with the placement of its properties.
+    pub fn record(module: &hir::Module, target: &Target, ty: &hir::Type) -> Option<RecordLayout> {
+        Self::record_depth(module, target, ty, &[], 0)
+    }
+
+    /// Computes the layout of the vtable that a contract object of the provided contract points to.
+    ///
+    /// A vtable starts with the size and alignment of the type behind the object as `usize`s, followed by a pointer
+    /// to each routine of the contract in the order they are declared. Each routine takes a pointer to the value as
+    /// its first argument. For C interop, a vtable of `contract { val area <- routine(&Self) -> f64 }` is
+    ///
+    /// ```c
+    /// struct Shape_vtable { size_t size; size_t align; double (*area)(const void *self); };
+    /// struct dyn_Shape { const void *data; const struct Shape_vtable *vtable; };
+    /// ```
+    ///
+    /// where `dyn_Shape` is the layout of `&dyn Shape`.
+    pub fn vtable(module: &hir::Module, target: &Target, contract: &hir::Type) -> Option<RecordLayout> {
+        let items = match &contract.kind {
+            hir::TypeKind::Contract(items) => items,
+            hir::TypeKind::Name(name) => match module.item(name) {
+                Some(hir::Item { kind: hir::ItemKind::Type(Some(decl)), .. }) => return Self::vtable(module, target, decl),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let pointer = Self::scalar(target.pointer_size);
+        let names = ["size", "align"].into_iter()
+            .map(String::from)
+            .chain(items.iter().filter(|item| matches!(item.kind, hir::ItemKind::Val(..))).map(|item| item.name.clone()))
+            .collect::<Vec<_>>();
+
+        Some(RecordLayout {
+            layout: Self::sequence(std::iter::repeat_n(pointer, names.len())),
+            fields: names.into_iter()
+                .enumerate()
+                .map(|(index, name)| FieldLayout { name, offset: index as u64 * pointer.size, layout: pointer, bits: None })
+                .collect(),
+        })
+    }
+
+    /// Computes the layout of a type with the attributes of its declaration, giving up on types nested too deep
+    /// to be anything but recursive.
+    fn of_depth(module: &hir::Module, target: &Target, ty: &hir::Type, attrs: &[hir::Attr], depth: usize) -> Option<Self> {
+        if depth > 64 {
+            return None;
+        }
+
+        let of = |ty: &hir::Type| Self::of_depth(module, target, ty, &[], depth + 1);
+        match &ty.kind {
+            hir::TypeKind::Name(name) => match name.as_str() {
+                "bool" | "i8" | "u8" => Some(Self::scalar(1)),
+                "i16" | "u16" => Some(Self::scalar(2)),
+                "i32" | "u32" | "f32" => Some(Self::scalar(4)),
+                "i64" | "u64" | "f64" => Some(Self { size: 8, align: target.align_64 }),
+                "isize" | "usize" => Some(Self::scalar(target.pointer_size)),
+                _ => match module.item(name) {
+                    Some(hir::Item { kind: hir::ItemKind::Type(Some(decl)), attrs, .. }) => {
+                        Some(Self::of_depth(module, target, decl, attrs, depth + 1)?.aligned(hir::align(attrs)))
+                    },
+                    None if name == hir::VAR_ARG => of(&hir::var_arg()),
+                    _ => None,
+                },
+            },
+            hir::TypeKind::Path(..) | hir::TypeKind::Contract(_) | hir::TypeKind::Dyn(_) => None,
+            // a reference to a contract object is a pointer to the value followed by a pointer to its vtable.
+            hir::TypeKind::Ref(ty) if matches!(ty.unmodified().kind, hir::TypeKind::Dyn(_)) => {
+                Some(Self::sequence([Self::scalar(target.pointer_size); 2]))
+            },
+            hir::TypeKind::Routine(..) | hir::TypeKind::Ref(_) => Some(Self::scalar(target.pointer_size)),
+            // hail variadics are a pointer to the first `VarArg` followed by the number of them, while C variadics are
+            // laid out by the C calling convention.
+            hir::TypeKind::Variadic(Some(_)) => Some(Self::sequence([Self::scalar(target.pointer_size); 2])),
+            hir::TypeKind::Variadic(None) => None,
+            // a closure is a pointer to its environment followed by a pointer to its code.
+            hir::TypeKind::Closure(..) => Some(Self::sequence([Self::scalar(target.pointer_size); 2])),
+            hir::TypeKind::Struct(_) | hir::TypeKind::Union(_) => Some(Self::record_depth(module, target, ty, attrs, depth)?.layout),
+            hir::TypeKind::Enum(variants) => {
+                let payloads = variants.iter().filter_map(|variant| variant.ty.as_ref()).map(of).collect::<Option<Vec<_>>>()?;
+                let tag = match hir::enum_tag(attrs, variants) {
+                    Some(tag) => of(&hir::Type { span: ty.span.clone(), kind: hir::TypeKind::Name(tag.into()) })?,
+                    None => Self::tag(variants.len()),
+                };
+
+                Some(Self::tagged(tag, payloads))
+            },
+            hir::TypeKind::Shared(ty) | hir::TypeKind::Fluid(ty) => Self::of_depth(module, target, ty, attrs, depth + 1),
+            // references are never null, so `none` is stored as a null pointer.
+            hir::TypeKind::Opt(ty) if matches!(ty.unmodified().kind, hir::TypeKind::Ref(_)) => of(ty),
+            hir::TypeKind::Opt(ty) => Some(Self::tagged(Self::tag(2), [of(ty)?])),
+            hir::TypeKind::Res(ok, err) => Some(Self::tagged(Self::tag(2), [of(ok)?, of(err)?])),
+        }
+    }
+
+    /// Computes the layout of a struct or union type with the attributes of its declaration.
+    ///
+    /// Unless the declaration is `#repr(C)` or `#packed`, the properties of a struct are reordered by decreasing
+    /// alignment to leave as little padding as possible. Consecutive bitfields of the same size share storage
+    /// while they fit.
+    fn record_depth(module: &hir::Module, target: &Target, ty: &hir::Type, attrs: &[hir::Attr], depth: usize) -> Option<RecordLayout> {
+        if depth > 64 {
+            return None;
+        }
+
+        let (fields, union) = match &ty.kind {
+            hir::TypeKind::Struct(fields) => (fields, false),
+            hir::TypeKind::Union(fields) => (fields, true),
+            hir::TypeKind::Shared(ty) | hir::TypeKind::Fluid(ty) => return Self::record_depth(module, target, ty, attrs, depth + 1),
+            hir::TypeKind::Name(name) => match module.item(name) {
+                Some(hir::Item { kind: hir::ItemKind::Type(Some(decl)), attrs, .. }) => {
+                    let mut record = Self::record_depth(module, target, decl, attrs, depth + 1)?;
+                    record.layout = record.layout.aligned(hir::align(attrs));
+                    return Some(record);
+                },
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let packed = hir::find_attr(attrs, "packed").is_some();
+        let mut units: Vec<Unit> = vec![];
+        for (index, field) in fields.iter().enumerate() {
+            let mut layout = Self::of_depth(module, target, &field.ty, &[], depth + 1)?;
+            if packed {
+                layout.align = 1;
+            }
+
+            let layout = layout.aligned(hir::align(&field.attrs));
+            let width = match field.bits {
+                Some(width) => width,
+                None => {
+                    units.push(Unit { layout, bitfield: false, used: 0, fields: vec![(index, None)] });
+                    continue;
+                },
+            };
+
+            match units.last_mut() {
+                Some(unit) if !union && unit.bitfield && unit.layout == layout && unit.used + width <= layout.size as u32 * 8 => {
+                    unit.fields.push((index, Some((unit.used, width))));
+                    unit.used += width;
+                },
+                _ => units.push(Unit { layout, bitfield: true, used: width, fields: vec![(index, Some((0, width)))] }),
+            }
+        }
+
+        if !union && !packed && hir::repr(attrs) != Some("C") {
+            units.sort_by_key(|unit| std::cmp::Reverse(unit.layout.align));
+        }
+
+        let mut placed = vec![];
+        let mut size = 0;
+        let mut align = 1;
+        for unit in &units {
+            let offset = if union { 0 } else { align_to(size, unit.layout.align) };
+            size = size.max(offset + unit.layout.size);
+            align = align.max(unit.layout.align);
+
+            for (index, bits) in &unit.fields {
+                placed.push((*index, FieldLayout { name: fields[*index].name.clone(), offset, layout: unit.layout, bits: *bits }));
+            }
+        }
+
+        placed.sort_by_key(|(index, _)| *index);
+        Some(RecordLayout {
+            layout: Layout { size: align_to(size, align), align },
+            fields: placed.into_iter().map(|(_, field)| field).collect(),
+        })
+    }
+}
@@ -1,10 +1,27 @@
 // TODO: array types & array values
 
+pub mod attrs;
+pub mod const_eval;
+pub mod emitter;
+pub mod flags;
+pub mod hir;
 pub mod hir_lower;
+pub mod layout;
+pub mod ops;
 
-use clap::{Parser, Subcommand};
+#[cfg(test)]
+mod tests;
+
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use hail_parser::diagnostics::{Diagnostic, FileId};
+use serde::Serialize;
 use target_lexicon::Triple;
 
+use crate::emitter::{Emitter, ErrorFormat};
+
 #[derive(Clone, Debug, Subcommand)]
 enum Command {
     #[clap(arg_required_else_help = true)]
@@ -16,7 +33,7 @@ enum Command {
 
         /// The flags to compile with.
         #[clap(short = 'f', long = "flag")]
-        #[clap(help = "registers a flag for conditional compilation")]
+        #[clap(help = "registers a flag (`name` or `key=value`) for conditional compilation")]
         flags: Vec<String>,
 
         /// Directories that hail should search for modules.
@@ -24,10 +41,83 @@ enum Command {
         #[clap(help = "registers a path to search for modules")]
         libs: Vec<String>,
 
+        /// The target triple to compile for.
+        #[clap(long = "target")]
+        #[clap(help = "the target triple to compile for, defaulting to the host")]
+        target: Option<String>,
+
         #[clap(long = "bench")]
         #[clap(help = "display how long hail spends in each pass")]
         bench: bool,
     },
+
+    #[clap(arg_required_else_help = true)]
+    #[clap(about = "displays the memory layout of a struct or union, or the vtable of a contract")]
+    Layout {
+        /// The input file which declares the type.
+        #[clap(help = "the hail source file which declares the type")]
+        input: String,
+
+        /// The type to display, such as `Header` or `net::Header`.
+        #[clap(help = "the name of the type, with the path to its module if it is declared in one")]
+        ty: String,
+
+        /// The flags to compile with.
+        #[clap(short = 'f', long = "flag")]
+        #[clap(help = "registers a flag (`name` or `key=value`) for conditional compilation")]
+        flags: Vec<String>,
+
+        /// Directories that hail should search for modules.
+        #[clap(short = 'M', long = "lib")]
+        #[clap(help = "registers a path to search for modules")]
+        libs: Vec<String>,
+
+        /// The target triple to compute the layout for.
+        #[clap(long = "target")]
+        #[clap(help = "the target triple to compute the layout for, defaulting to the host")]
+        target: Option<String>,
+    },
+
+    #[clap(arg_required_else_help = true)]
+    #[clap(about = "displays the AST of a source file")]
+    Ast {
+        /// The input file to parse.
+        #[clap(help = "the hail source file to parse")]
+        input: String,
+
+        /// The format to display the AST in.
+        #[clap(long = "format", value_enum, default_value = "json")]
+        #[clap(help = "the format to display the AST in")]
+        format: AstFormat,
+    },
+
+    #[clap(arg_required_else_help = true)]
+    #[clap(about = "explains an error code, such as `E0001`")]
+    Explain {
+        /// The code to explain.
+        #[clap(help = "the code to explain, such as `E0001`")]
+        code: String,
+    },
+}
+
+/// The formats that `hail ast` can display the AST in.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum AstFormat {
+    /// JSON, following the schema in `docs/ast-json.md`.
+    Json,
+
+    /// Rust's debug formatting, which is not stable between versions.
+    Debug,
+}
+
+/// The JSON that `hail ast` prints, as described in `docs/ast-json.md`.
+#[derive(Serialize)]
+struct AstJson<'a> {
+    /// The version of the schema.
+    version: u32,
+
+    /// The statements at the root of the unit.
+    items: &'a [hail_parser::ast::RootStmnt<'a>],
 }
 
 /// Arguments for the command line.
@@ -38,60 +128,173 @@ enum Command {
 struct Args {
     #[clap(subcommand)]
     command: Command,
+
+    /// The format to emit warnings and errors in.
+    #[clap(long = "error-format", global = true, value_enum, default_value = "human")]
+    #[clap(help = "the format to emit warnings and errors in")]
+    error_format: ErrorFormat,
 }
 
-// TODO: load flags for different targets
+/// Reads a source file, adding it to the files that diagnostics point into.
+fn read(emitter: &mut Emitter, input: &str) -> Result<(String, FileId), ()> {
+    match std::fs::read_to_string(input) {
+        Ok(source) => {
+            let file = emitter.files.add(input, source.as_str());
+            Ok((source, file))
+        },
+        Err(e) => {
+            emitter.emit(&Diagnostic::error(format!("unable to read `{}`: {}", input, e)));
+            Err(())
+        },
+    }
+}
 
-fn main() -> Result<(), ()> {
-    let args = Args::parse();
+/// Parses a source file and lowers it to HIR, emitting any warnings and errors.
+fn lower(emitter: &mut Emitter, input: &str, flags: &[String], libs: Vec<String>, target: Option<&str>, bench: bool) -> Result<(hir::Module, layout::Target), ()> {
+    let triple = match target {
+        Some(target) => match Triple::from_str(target) {
+            Ok(triple) => triple,
+            Err(e) => {
+                emitter.emit(&Diagnostic::error(format!("invalid target `{}`: {}", target, e)));
+                return Err(());
+            },
+        },
+        None => Triple::host(),
+    };
+
+    let mut target_flags = flags::Flags::for_target(&triple);
+    for flag in flags {
+        if let Err(e) = target_flags.set_arg(flag) {
+            emitter.emit(&Diagnostic::error(e));
+            return Err(());
+        }
+    }
+
+    let (file, id) = read(emitter, input)?;
+    let source = file.as_str();
+
+    let ast = {
+        let start = std::time::Instant::now();
+        let ast = match hail_parser::parse(source) {
+            Ok(ast) => ast,
+            Err(diagnostic) => {
+                emitter.emit(&diagnostic.with_file(id));
+                return Err(());
+            },
+        };
+        let end = start.elapsed();
+
+        //dbg!(ast);
+        if bench {
+            println!("Parsed in {}ms", end.as_nanos() as f64 / 1_000_000f64);
+        }
+        
+        ast
+    };
+
+    let path = std::path::Path::new(input);
+    let target = layout::Target::of(&triple);
+    let mut ctx = hir_lower::HirLowerContext::new(target_flags, target, libs, std::mem::take(&mut emitter.files), id);
+    let unit = hir_lower::HirLowerUnit{
+        name: path.file_stem().map(|stem| stem.to_string_lossy().into()).unwrap_or_default(),
+        dir: match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy().into(),
+            _ => "./".into(),
+        },
+        ast,
+    };
+
+    let start = std::time::Instant::now();
+    let module = hir_lower::hir_lower(&mut ctx, &unit);
+    let end = start.elapsed();
+
+    if bench {
+        println!("Lowered to HIR in {}ms", end.as_nanos() as f64 / 1_000_000f64);
+    }
+
+    emitter.files = std::mem::take(&mut ctx.files);
+    for diagnostic in ctx.warnings.iter().chain(&ctx.errors) {
+        emitter.emit(diagnostic);
+    }
+
+    if !ctx.errors.is_empty() {
+        return Err(());
+    }
 
-    match args.command {
-        Command::Build { input, flags, libs, bench } => {
-            let file = match std::fs::read_to_string(input) {
-                Ok(f) => f,
-                Err(e) => {
-                    println!("Unable to open input file.");
+    Ok((module, ctx.target))
+}
+
+/// Runs a command, emitting any warnings and errors.
+fn run(emitter: &mut Emitter, command: Command) -> Result<(), ()> {
+    match command {
+        Command::Build { input, flags, libs, target, bench } => {
+            lower(emitter, &input, &flags, libs, target.as_deref(), bench)?;
+        },
+        Command::Layout { input, ty, flags, libs, target } => {
+            let (module, target) = lower(emitter, &input, &flags, libs, target.as_deref(), false)?;
+
+            // walk down through the inline modules named by the path.
+            let mut segments = ty.split("::").collect::<Vec<_>>();
+            let name = segments.pop().unwrap_or_default();
+            let mut module = &module;
+            for segment in segments {
+                module = match module.item(segment).map(|item| &item.kind) {
+                    Some(hir::ItemKind::Module(inner)) => inner,
+                    _ => {
+                        emitter.emit(&Diagnostic::error(format!("`{}` is not a module in `{}`", segment, input)));
+                        return Err(());
+                    },
+                };
+            }
+
+            let ty = hir::Type { span: 0..0, kind: hir::TypeKind::Name(name.into()) };
+            if let Some(record) = layout::Layout::record(module, &target, &ty) {
+                print!("{}\n{}", name, record);
+            } else if let Some(vtable) = layout::Layout::vtable(module, &target, &ty) {
+                print!("vtable of dyn {}\n{}", name, vtable);
+            } else {
+                emitter.emit(&Diagnostic::error(format!("`{}` is not a struct, union or contract with a known layout", name)));
+                return Err(());
+            }
+        },
+        Command::Ast { input, format } => {
+            let (source, file) = read(emitter, &input)?;
+            let ast = match hail_parser::parse(&source) {
+                Ok(ast) => ast,
+                Err(diagnostic) => {
+                    emitter.emit(&diagnostic.with_file(file));
                     return Err(());
                 },
             };
-            let source = file.as_str();
-
-            let ast = {
-                let start = std::time::Instant::now();
-                let parser = hail_parser::grammar::RootStmntsParser::new();
-                let ast = parser.parse(source, hail_parser::scanner::Asi::lex(source)).unwrap();
-                let end = start.elapsed();
-    
-                //dbg!(ast);
-                if bench {
-                    println!("Parsed in {}ms", end.as_nanos() as f64 / 1_000_000f64);
-                }
-                
-                ast
-            };
 
-            {
-                let mut ctx = hir_lower::HirLowerContext {
-                    flags,
-                    libs,
-                };
-                let unit = hir_lower::HirLowerUnit{
-                    dir: "./".into(),
-                    ast,
-                };
-
-                let start = std::time::Instant::now();
-                hir_lower::hir_lower(&mut ctx, &unit);
-                let end = start.elapsed();
+            match format {
+                AstFormat::Json => {
+                    let unit = AstJson { version: hail_parser::ast::SCHEMA_VERSION, items: &ast };
 
-                if bench {
-                    println!("Lowered to HIR in {}ms", end.as_nanos() as f64 / 1_000_000f64);
-                }
+                    println!("{}", serde_json::to_string_pretty(&unit).unwrap());
+                },
+                AstFormat::Debug => println!("{:#?}", ast),
             }
-        }
+        },
+        Command::Explain { code } => match hail_parser::codes::lookup(&code) {
+            Some(code) => print!("{}: {}\n\n{}", code.code, code.title, code.explanation),
+            None => {
+                emitter.emit(&Diagnostic::error(format!("`{}` is not a hail error code", code)).with_help("codes look like `E0001`"));
+                return Err(());
+            },
+        },
     }
 
     Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let mut emitter = Emitter::new(args.error_format);
+
+    // every error has been emitted by the time a command fails, so the emitter knows how to exit.
+    let _ = run(&mut emitter, args.command);
+    emitter.finish()
 
     // let input = std::fs::read_to_string("test.hl").unwrap();
     // let source = input.as_str();
@@ -0,0 +1,107 @@
+//! The built in contracts that operators are overloaded through.
+
+use hail_parser::ast::{AssignOp, BinaryOp, UnaryOp};
+
+/// A contract which gives an operator a meaning for the type it is applied to.
+#[derive(Clone, Debug)]
+pub struct OpContract {
+    /// The name of the contract, such as `Add`.
+    pub name: &'static str,
+
+    /// The routine that applications of the contract must declare.
+    pub method: &'static str,
+
+    /// The number of arguments that the routine takes, including `self`.
+    pub args: usize,
+}
+
+/// Declares an operator contract.
+const fn contract(name: &'static str, method: &'static str, args: usize) -> OpContract {
+    OpContract { name, method, args }
+}
+
+/// Every operator contract that the compiler understands.
+///
+/// `Eq` backs both `==` and `!=`, and `Ord` backs the ordering operators through `cmp`, which returns a negative,
+/// zero or positive `i32`. `Index` returns a reference to the element, and `IndexFluid` a `fluid` reference for
+/// when an element is assigned to.
+pub const CONTRACTS: &[OpContract] = &[
+    contract("Add", "add", 2),
+    contract("Sub", "sub", 2),
+    contract("Mul", "mul", 2),
+    contract("Div", "div", 2),
+    contract("Rem", "rem", 2),
+    contract("BitAnd", "bit_and", 2),
+    contract("BitOr", "bit_or", 2),
+    contract("BitXor", "bit_xor", 2),
+    contract("Shl", "shl", 2),
+    contract("Shr", "shr", 2),
+    contract("Neg", "neg", 1),
+    contract("Not", "not", 1),
+    contract("Eq", "eq", 2),
+    contract("Ord", "cmp", 2),
+    contract("Index", "index", 2),
+    contract("IndexFluid", "index_fluid", 2),
+    contract("AddAssign", "add_assign", 2),
+    contract("SubAssign", "sub_assign", 2),
+    contract("MulAssign", "mul_assign", 2),
+    contract("DivAssign", "div_assign", 2),
+    contract("RemAssign", "rem_assign", 2),
+    contract("BitAndAssign", "bit_and_assign", 2),
+    contract("BitOrAssign", "bit_or_assign", 2),
+    contract("BitXorAssign", "bit_xor_assign", 2),
+    contract("ShlAssign", "shl_assign", 2),
+    contract("ShrAssign", "shr_assign", 2),
+];
+
+/// Finds the operator contract with the provided name.
+pub fn lookup(name: &str) -> Option<&'static OpContract> {
+    CONTRACTS.iter().find(|contract| contract.name == name)
+}
+
+/// The contract that overloads a binary operator, if it can be overloaded.
+pub fn binary(op: &BinaryOp) -> Option<&'static OpContract> {
+    lookup(match op {
+        BinaryOp::Plus => "Add",
+        BinaryOp::Min => "Sub",
+        BinaryOp::Star => "Mul",
+        BinaryOp::Slash => "Div",
+        BinaryOp::Perc => "Rem",
+        BinaryOp::Amp => "BitAnd",
+        BinaryOp::Pipe => "BitOr",
+        BinaryOp::Caret => "BitXor",
+        BinaryOp::LtLt => "Shl",
+        BinaryOp::GtGt => "Shr",
+        BinaryOp::EqEq | BinaryOp::BangEq => "Eq",
+        BinaryOp::Lt | BinaryOp::Gt | BinaryOp::LtEq | BinaryOp::GtEq => "Ord",
+        BinaryOp::AmpAmp | BinaryOp::PipePipe | BinaryOp::QuestQuest => return None,
+    })
+}
+
+/// The contract that overloads a unary operator, if it can be overloaded.
+pub fn unary(op: &UnaryOp) -> Option<&'static OpContract> {
+    lookup(match op {
+        UnaryOp::Min => "Neg",
+        UnaryOp::Bang => "Not",
+        UnaryOp::Star | UnaryOp::Borrow | UnaryOp::Fluid | UnaryOp::Shared => return None,
+    })
+}
+
+/// The contract that overloads a compound assignment, along with the binary operator it falls back to.
+pub fn assign(op: &AssignOp) -> Option<(&'static OpContract, BinaryOp)> {
+    let (name, op) = match op {
+        AssignOp::Eq => return None,
+        AssignOp::PlusEq => ("AddAssign", BinaryOp::Plus),
+        AssignOp::MinEq => ("SubAssign", BinaryOp::Min),
+        AssignOp::StarEq => ("MulAssign", BinaryOp::Star),
+        AssignOp::SlashEq => ("DivAssign", BinaryOp::Slash),
+        AssignOp::PercEq => ("RemAssign", BinaryOp::Perc),
+        AssignOp::AmpEq => ("BitAndAssign", BinaryOp::Amp),
+        AssignOp::PipeEq => ("BitOrAssign", BinaryOp::Pipe),
+        AssignOp::CaretEq => ("BitXorAssign", BinaryOp::Caret),
+        AssignOp::LtLtEq => ("ShlAssign", BinaryOp::LtLt),
+        AssignOp::GtGtEq => ("ShrAssign", BinaryOp::GtGt),
+    };
+
+    Some((lookup(name)?, op))
+}
@@ -0,0 +1,61 @@
+//! Checks the validation of inline assembly operands, and that output operands are lowered as writes.
+
+use super::lower;
+use crate::hir::{CaptureMode, ExprKind, StmtKind};
+
+#[test]
+fn captures_outputs_as_changed() {
+    let lowered = lower("val f = routine(count <- fluid i32, step <- i32) {
+    val g = routine() {
+        asm(\"add $0, $1\" : \"+r\"(count) : \"r\"(step))
+    }
+}");
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    let modes = match &lowered.routine("f").block.stmts[0].kind {
+        StmtKind::Val(_, _, Some(value)) => match &value.kind {
+            ExprKind::Routine(literal) => literal.captures.iter().map(|capture| (capture.name.as_str(), capture.mode)).collect::<Vec<_>>(),
+            other => panic!("expected a routine literal, found {:?}", other),
+        },
+        other => panic!("expected a declaration, found {:?}", other),
+    };
+    assert_eq!(modes, [("count", CaptureMode::FluidRef), ("step", CaptureMode::Ref)]);
+}
+
+#[test]
+fn reports_outputs_which_are_not_fluid() {
+    let lowered = lower("val total <- i32 = 0
+val f = routine(x <- i32) {
+    asm(\"mov $0, 1\" : \"=r\"(total))
+    asm(\"mov $0, 1\" : \"=r\"(x))
+    val g = routine() {
+        asm(\"mov $0, 1\" : \"=r\"(x))
+    }
+}");
+
+    assert_eq!(lowered.errors(), [
+        "cannot assign to static `total`, its type is not `fluid`",
+        "cannot write to `x` through an output operand, its type is not `fluid`",
+        "`x` is changed by a routine literal which captures it, but its type is not `fluid`",
+    ]);
+    assert!(lowered.errors.iter().all(|error| error.code == Some("E0007")));
+}
+
+#[test]
+fn reports_invalid_operands() {
+    let lowered = lower("type Point = struct { x <- i32, y <- i32 }
+val f = routine(a <- fluid i32, p <- Point, r <- &Point) {
+    asm(\"mov $0, $1\" : \"r\"(a) : \"=r\"(a))
+    asm(\"mov $0, 1\" : \"=r\"(1))
+    asm(\"mov $0, $1\" : \"=r\"(a) : \"r\"(p), \"r\"(p.x), \"r\"(*r), \"m\"(*r))
+}");
+
+    assert_eq!(lowered.errors(), [
+        "output operand constraint `r` must start with `=` or `+`",
+        "input operand constraint `=r` cannot start with `=` or `+`",
+        "output operands must be a variable, property or dereference",
+        "operand has type `Point`, which does not fit in a register",
+        "operand has type `Point`, which does not fit in a register",
+    ]);
+    assert!(lowered.errors.iter().all(|error| error.code == Some("E0015")));
+}
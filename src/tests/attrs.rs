@@ -0,0 +1,62 @@
+//! Checks that markers are validated against the attribute registry, in the unit and in the modules it imports.
+
+use super::lower_files;
+
+#[test]
+fn reports_unknown_and_misplaced_attributes() {
+    let lowered = lower_files(&[("main.hl", "#frobnicate\nval x = 1\n#inline\ntype T = i32")]);
+
+    assert_eq!(lowered.errors(), ["unknown attribute `#frobnicate`", "attribute `#inline` cannot be applied to a type declaration"]);
+}
+
+#[test]
+fn reports_attributes_in_imported_module_files() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { square } from math"),
+        ("math.hl", "#frobnicate\nshared val square = routine(x <- i32) -> i32 {\n    return x * x\n}"),
+    ]);
+
+    assert_eq!(lowered.errors(), ["unknown attribute `#frobnicate`"]);
+}
+
+#[test]
+fn reports_attributes_of_local_declarations() {
+    let lowered = lower_files(&[("main.hl", "val each = routine(callback <- routine()) {}
+val f = routine() {
+    #frobnicate
+    val x = 1
+    each(routine() #packed {
+        #packed
+        val y = 2
+    })
+}")]);
+
+    assert_eq!(lowered.errors(), [
+        "unknown attribute `#frobnicate`",
+        "attribute `#packed` cannot be applied to a routine",
+        "attribute `#packed` cannot be applied to a variable",
+    ]);
+}
+
+#[test]
+fn reports_attributes_in_the_initializer_of_a_module() {
+    let lowered = lower_files(&[("main.hl", "val ready = true
+val each = routine(callback <- routine()) {}
+if ready {
+    #frobnicate
+    val x = 1
+}
+each(routine() #packed {})
+module inner {
+    while ready {
+        #packed
+        val y = 2
+    }
+}")]);
+
+    assert_eq!(lowered.errors(), [
+        "unknown attribute `#frobnicate`",
+        "attribute `#packed` cannot be applied to a routine",
+        "attribute `#packed` cannot be applied to a variable",
+    ]);
+}
@@ -0,0 +1,88 @@
+//! Checks how routine literals capture the locals around them, and where they can be used as plain routines.
+
+use super::lower;
+use crate::hir::{ExprKind, Routine, StmtKind};
+
+/// Finds the routine literal declared as the local with the provided name at the top of a routine's block.
+fn literal<'r>(routine: &'r Routine, name: &str) -> &'r Routine {
+    routine.block.stmts.iter()
+        .find_map(|stmt| match &stmt.kind {
+            StmtKind::Val(local, _, Some(value)) if local == name => match &value.kind {
+                ExprKind::Routine(literal) => Some(&**literal),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("`{}` is not a routine literal", name))
+}
+
+/// Lists the captures of a routine literal as `name mode`.
+fn captures(routine: &Routine) -> Vec<String> {
+    routine.captures.iter().map(|capture| format!("{} {:?}", capture.name, capture.mode)).collect()
+}
+
+#[test]
+fn captures_locals_by_how_they_are_used() {
+    let lowered = lower("val f = routine(count <- fluid i32, label <- shared i32, limit <- i32) {
+    val g = routine() -> i32 {
+        count = count + 1
+        return label + limit
+    }
+    val h = routine(limit <- i32) -> i32 {
+        return limit
+    }
+}");
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    let f = lowered.routine("f");
+    assert_eq!(captures(literal(f, "g")), ["count FluidRef", "label Value", "limit Ref"]);
+    // arguments of the literal shadow the locals around it.
+    assert_eq!(captures(literal(f, "h")), Vec::<String>::new());
+}
+
+#[test]
+fn captures_through_the_literals_in_between() {
+    let lowered = lower("val f = routine(x <- i32) {
+    val g = routine() {
+        val y <- i32 = 1
+        val h = routine() -> i32 {
+            return x + y
+        }
+    }
+}");
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    let g = literal(lowered.routine("f"), "g");
+    assert_eq!(captures(g), ["x Ref"]);
+    assert_eq!(captures(literal(g, "h")), ["x Ref", "y Ref"]);
+}
+
+#[test]
+fn reports_changes_to_captured_locals_which_are_not_fluid() {
+    let lowered = lower("val f = routine(limit <- i32) {\n    val g = routine() {\n        limit = 2\n    }\n}");
+
+    assert_eq!(lowered.errors(), ["`limit` is changed by a routine literal which captures it, but its type is not `fluid`"]);
+}
+
+#[test]
+fn reports_closures_used_as_plain_routines() {
+    let lowered = lower("val each = routine(callback <- routine(i32) -> i32) {}
+val each_closure = routine(callback <- closure(i32) -> i32) {}
+val f = routine(x <- i32, callback <- closure(i32) -> i32) {
+    each(routine(v <- i32) -> i32 {
+        return v
+    })
+    each_closure(routine(v <- i32) -> i32 {
+        return v + x
+    })
+    each(routine(v <- i32) -> i32 {
+        return v + x
+    })
+    each(callback)
+}");
+
+    assert_eq!(lowered.errors(), [
+        "this routine literal captures `x`, so it can only be used as a `closure`, not the plain routine `routine(i32) -> i32`",
+        "a `closure` cannot be used as the plain routine `routine(i32) -> i32`",
+    ]);
+}
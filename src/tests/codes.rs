@@ -0,0 +1,205 @@
+//! Checks that every code reported by the lexer, parser and compiler is registered, and that each pass of lowering
+//! reports its diagnostics under the code that explains them.
+
+use std::path::{Path, PathBuf};
+
+use hail_parser::codes::{self, CODES};
+
+use super::{lower, lower_files, Lowered};
+
+/// The sources of the lexer, the parser and the compiler, which the codes are reported from.
+fn sources() -> Vec<PathBuf> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut files = vec![];
+    for dir in [root.join("parser/src"), root.join("src")] {
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if matches!(path.extension().and_then(|ext| ext.to_str()), Some("rs" | "lalrpop")) {
+                files.push(path);
+            }
+        }
+    }
+
+    files
+}
+
+/// Finds every string literal in a source which is a code, such as `"E0001"`.
+fn codes_in(source: &str) -> Vec<&str> {
+    source
+        .match_indices("\"E")
+        .filter_map(|(start, _)| source.get(start + 1..start + 7))
+        .filter(|literal| literal.ends_with('"') && literal[1..5].bytes().all(|byte| byte.is_ascii_digit()))
+        .map(|literal| &literal[..5])
+        .collect()
+}
+
+/// The codes of the errors, in the order they were reported.
+fn codes(lowered: &Lowered) -> Vec<&'static str> {
+    lowered.errors.iter().map(|error| error.code.expect("error without a code")).collect()
+}
+
+#[test]
+fn every_reported_code_is_registered() {
+    let mut reported = 0;
+    for file in sources() {
+        let source = std::fs::read_to_string(&file).unwrap();
+        for code in codes_in(&source) {
+            assert!(codes::lookup(code).is_some(), "{} is reported in {} but has no explanation", code, file.display());
+            reported += 1;
+        }
+    }
+
+    assert!(reported > CODES.len());
+}
+
+#[test]
+fn reports_changes_which_are_not_fluid_under_e0007() {
+    let statics = lower("val count <- i32 = 0\nval bump = routine() {\n    count = 1\n}");
+    let captures = lower("val f = routine(limit <- i32) {\n    val g = routine() {\n        limit = 2\n    }\n}");
+
+    assert_eq!(codes(&statics), ["E0007"]);
+    assert_eq!(codes(&captures), ["E0007"]);
+}
+
+#[test]
+fn reports_methods_which_cannot_be_called_under_e0007_and_e0008() {
+    let lowered = lower("type Point = struct { x <- i32 }
+apply Point {
+    val bump = routine(self <- &fluid Point) {
+        self.x = 1
+    }
+}
+val f = routine(r <- &Point) {
+    r.bump()
+    r.missing()
+}");
+
+    assert_eq!(codes(&lowered), ["E0007", "E0008"]);
+}
+
+#[test]
+fn reports_contract_objects_under_e0009() {
+    let lowered = lower("type Shape = contract {\n    type Unit = f64\n    val area <- routine(&Self) -> f64\n}
+val f = routine(shape <- &dyn Shape, plain <- dyn Shape) {}");
+
+    assert_eq!(codes(&lowered), ["E0009", "E0009"]);
+}
+
+#[test]
+fn reports_closures_used_as_plain_routines_under_e0010() {
+    let lowered = lower("val each = routine(callback <- routine(i32) -> i32) {}
+val f = routine(x <- i32, callback <- closure(i32) -> i32) {
+    each(routine(v <- i32) -> i32 {
+        return v + x
+    })
+    each(callback)
+}");
+
+    assert_eq!(codes(&lowered), ["E0010", "E0010"]);
+}
+
+#[test]
+fn reports_missing_operator_contracts_under_e0011() {
+    let lowered = lower("type Plain = struct { v <- i32 }\nval f = routine(p <- Plain) {\n    val a = p + p\n    val b = -p\n}");
+
+    assert_eq!(codes(&lowered), ["E0011", "E0011"]);
+}
+
+#[test]
+fn reports_invalid_discriminants_under_e0012() {
+    let lowered = lower("#repr(u8)\ntype Big = enum { A = 255, B }\ntype Twice = enum { A = 1, B = 1 }");
+
+    assert_eq!(codes(&lowered), ["E0012", "E0012"]);
+}
+
+#[test]
+fn warns_about_union_reads_under_e0013() {
+    let lowered = lower("type Bits = union { float <- f32, int <- u32 }\nval f = routine(b <- Bits) -> u32 {\n    return b.int\n}");
+
+    assert!(!lowered.warnings.is_empty());
+    assert!(lowered.warnings.iter().all(|warning| warning.code == Some("E0013")));
+}
+
+#[test]
+fn reports_misplaced_try_and_missing_conversions_under_e0014() {
+    let lowered = lower("type IoError = struct { code <- i32 }
+type AppError = struct { message <- i32 }
+val read = routine() -> !i32:IoError {
+    return ok(1)
+}
+val f = routine(x <- ?i32) {
+    val a = x?
+}
+val g = routine() -> !i32:AppError {
+    val a = read()?
+    return ok(a)
+}");
+
+    assert_eq!(codes(&lowered), ["E0014", "E0014"]);
+}
+
+#[test]
+fn reports_exits_from_deferred_statements_under_e0016() {
+    let lowered = lower("val f = routine() {\n    defer { return }\n    defer val x = 1\n}");
+
+    assert_eq!(codes(&lowered), ["E0016", "E0016"]);
+}
+
+#[test]
+fn reports_invalid_applications_under_e0017() {
+    let lowered = lower("type Point = struct { x <- i32 }
+type From = struct { x <- i32 }
+type Money = struct { cents <- i64 }
+apply Point {
+    val len = routine(self <- &i32) -> i32 {
+        return 0
+    }
+}
+apply From to Point {}
+apply Add to Money {
+    val plus = routine(self <- Money, other <- Money) -> Money {
+        return self
+    }
+}");
+
+    assert_eq!(codes(&lowered), ["E0017", "E0017", "E0017"]);
+}
+
+#[test]
+fn reports_imports_under_e0018_and_e0019() {
+    let lowered = lower("module outer {\n    val hidden = 2\n}\nimport { hidden } from outer\nimport x from super\nimport y from nowhere");
+    let files = lower_files(&[
+        ("main.hl", "import { square } from math"),
+        ("math.hl", "val square = routine(x <- i32) -> i32 {\n    return x * x\n}"),
+    ]);
+
+    assert_eq!(codes(&lowered), ["E0019", "E0018", "E0018"]);
+    assert_eq!(codes(&files), ["E0019"]);
+}
+
+#[test]
+fn reports_cycles_of_initializers_under_e0020() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { a } from a"),
+        ("a.hl", "import { b } from b\nshared val a <- i32 = 1"),
+        ("b.hl", "import { a } from a\nshared val b <- i32 = 2"),
+    ]);
+
+    assert_eq!(codes(&lowered), ["E0020"]);
+}
+
+#[test]
+fn reports_attributes_under_e0021() {
+    let lowered = lower("#frobnicate\nval x = 1\n#packed\nconst Y = 2\n#static_assert\nconst Z = 3");
+    let files = lower_files(&[("main.hl", "import { x } from lib"), ("lib.hl", "#frobnicate\nshared val x = 1")]);
+
+    assert_eq!(codes(&lowered), ["E0021", "E0021", "E0021"]);
+    assert_eq!(codes(&files), ["E0021"]);
+}
+
+#[test]
+fn reports_constant_evaluation_under_e0022() {
+    let lowered = lower("const X = 0xFFFFFFFFFFFFFFFF * 0xFFFFFFFFFFFFFFFF\nconst Y = 10 / (4 - 4)");
+
+    assert_eq!(codes(&lowered), ["E0022", "E0022"]);
+}
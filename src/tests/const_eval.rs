@@ -0,0 +1,81 @@
+//! Checks the values of constants and the errors reported when they can't be evaluated.
+
+use super::{lower, lower_for};
+use crate::const_eval::Value;
+
+/// Lowers a unit, returning the value of the constant `X`.
+fn value_of(source: &str) -> Option<Value> {
+    let lowered = lower(source);
+    lowered.module.consts.get("X").copied()
+}
+
+#[test]
+fn evaluates_constants() {
+    assert!(matches!(value_of("const X <- u32 = 6 * 7"), Some(Value::Int(42, Some(int))) if int.name == "u32"));
+    assert_eq!(value_of("const X = 1.5 * 2.0"), Some(Value::Float(3.0)));
+}
+
+#[test]
+fn reports_overflow_beyond_i128() {
+    let lowered = lower("const X = 0xFFFFFFFFFFFFFFFF * 0xFFFFFFFFFFFFFFFF");
+
+    assert_eq!(lowered.errors(), ["arithmetic overflow, the result does not fit in `u64`"]);
+}
+
+#[test]
+fn reports_static_assertions_without_an_argument_once() {
+    let lowered = lower("#static_assert\nconst X = 1");
+
+    assert_eq!(lowered.errors(), ["invalid arguments for `#static_assert`, expected `#static_assert(expression)`"]);
+}
+
+#[test]
+fn sizes_follow_the_pointer_width_of_the_target() {
+    let source = "const X <- usize = 0x100000000";
+
+    assert!(lower(source).errors.is_empty());
+    assert_eq!(lower_for(source, "i686-unknown-linux-gnu").errors(), ["`4294967296` overflows `usize`"]);
+}
+
+#[test]
+fn reports_overflow_of_the_declared_type() {
+    let lowered = lower("const X <- u8 = 200 + 100");
+
+    assert_eq!(lowered.errors(), ["`300` overflows `u8`"]);
+}
+
+#[test]
+fn reports_division_by_zero() {
+    assert_eq!(lower("const X = 10 / (4 - 4)").errors(), ["division by zero"]);
+    assert_eq!(lower("const X = 10 % 0").errors(), ["division by zero"]);
+    assert_eq!(lower("const X = 1.0 / 0.0").errors(), ["division by zero"]);
+    assert_eq!(lower("const X = 1.5 % 0.0").errors(), ["division by zero"]);
+}
+
+#[test]
+fn stops_runaway_loops_at_the_step_limit() {
+    let lowered = lower("val spin = routine(n <- i32) -> i32 {\n    while true {}\n    return n\n}\nconst X = spin(1)");
+
+    assert_eq!(lowered.errors(), [format!("constant evaluation took more than {} steps", crate::const_eval::STEP_LIMIT)]);
+}
+
+#[test]
+fn reports_constants_which_depend_on_themselves_once() {
+    let lowered = lower("const X = Y + 1\nconst Y = X");
+
+    assert_eq!(lowered.errors(), ["constant `X` depends on itself"]);
+}
+
+#[test]
+fn checks_static_assertions() {
+    let lowered = lower("#static_assert(X == 3)\nconst X = 1 + 2\n#static_assert(X > 3)\nconst Y = 1");
+
+    assert_eq!(lowered.errors(), ["static assertion failed"]);
+}
+
+#[test]
+fn reports_discriminants_which_do_not_fit_or_repeat() {
+    let lowered = lower("#repr(u8)\ntype Big = enum { A = 255, B }\ntype Twice = enum { A = 1, B = 1 }");
+
+    assert_eq!(lowered.errors(), ["discriminant `256` of `Big::B` does not fit in `u8`", "discriminant `1` is already used by `Twice::A`"]);
+}
@@ -0,0 +1,127 @@
+//! Checks that deferred statements run on every path out of their scope.
+
+use super::lower;
+use crate::hir::{Block, ExprKind, StmtKind};
+
+/// Lists the statements of a block as the names of the routines they call and the keywords they are, with the
+/// blocks nested in them in braces.
+fn trace(block: &Block) -> Vec<String> {
+    let mut lines = vec![];
+    for stmt in &block.stmts {
+        match &stmt.kind {
+            StmtKind::Expr(expr) => match &expr.kind {
+                ExprKind::Call(callee, _) => match &callee.kind {
+                    ExprKind::Name(name) => lines.push(format!("{}()", name)),
+                    other => panic!("unexpected callee {:?}", other),
+                },
+                other => panic!("unexpected expression {:?}", other),
+            },
+            StmtKind::Val(name, ..) => lines.push(format!("val {}", name)),
+            StmtKind::If(_, then, otherwise) => {
+                lines.push(format!("if {{ {} }}", trace(then).join("; ")));
+                if let Some(otherwise) = otherwise {
+                    lines.push(format!("else {{ {} }}", trace(otherwise).join("; ")));
+                }
+            },
+            StmtKind::While(_, _, body) => lines.push(format!("while {{ {} }}", trace(body).join("; "))),
+            StmtKind::Block(inner) => lines.push(format!("{{ {} }}", trace(inner).join("; "))),
+            StmtKind::Break(_) => lines.push("break".into()),
+            StmtKind::Continue(_) => lines.push("continue".into()),
+            StmtKind::Return(_) => lines.push("return".into()),
+            other => panic!("unexpected statement {:?}", other),
+        }
+    }
+
+    lines
+}
+
+/// Declares the routines that deferred statements call, before the provided source.
+fn lower_routine(source: &str) -> Vec<String> {
+    let lowered = lower(&format!("val a = routine() {{}}\nval b = routine() {{}}\nval c = routine() {{}}\n{}", source));
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+
+    trace(&lowered.routine("f").block)
+}
+
+#[test]
+fn runs_deferred_statements_at_the_end_of_their_scope_in_reverse() {
+    let trace = lower_routine("val f = routine() {\n    defer a()\n    defer b()\n    c()\n}");
+
+    assert_eq!(trace, ["c()", "b()", "a()"]);
+}
+
+#[test]
+fn runs_deferred_statements_before_returning() {
+    let trace = lower_routine("val f = routine(x <- bool) -> bool {\n    defer a()\n    if x { return false }\n    return x\n}");
+
+    // the returned value is stored before the deferred statement runs, in case it changes it.
+    assert_eq!(trace, ["if { val #ret; a(); return }", "val #ret", "a()", "return"]);
+}
+
+#[test]
+fn runs_the_deferred_statements_of_the_scopes_that_break_and_continue_leave() {
+    let trace = lower_routine(
+        "val f = routine(x <- bool) {
+    defer a()
+    outer: while true {
+        defer b()
+        while true {
+            defer c()
+            if x { break outer }
+            continue
+        }
+    }
+}",
+    );
+
+    assert_eq!(trace, [
+        "while { while { if { c(); b(); break }; c(); continue }; b() }",
+        "a()",
+    ]);
+}
+
+#[test]
+fn reports_exits_from_deferred_statements() {
+    let lowered = lower("val f = routine() {
+    while true {
+        defer { break }
+    }
+    defer { return }
+    defer val x = 1
+}");
+
+    assert_eq!(lowered.errors(), [
+        "cannot `break` out of a deferred statement",
+        "cannot `return` out of a deferred statement",
+        "only statements can be deferred, not declarations",
+    ]);
+}
+
+#[test]
+fn reports_declarations_which_shadow_the_names_of_pending_deferred_statements() {
+    let lowered = lower("val alloc = routine() -> i32 {\n    return 1\n}
+val free = routine(p <- i32) {}
+val f = routine(x <- bool) {
+    val p = alloc()
+    defer free(p)
+    val p = alloc()
+    if x {
+        val free = 2
+        return
+    }
+}
+val g = routine() {
+    {
+        val p = alloc()
+        defer { val q = p; free(q) }
+        val q = 1
+    }
+    val p = alloc()
+}");
+
+    assert_eq!(lowered.errors(), [
+        "cannot declare `p` again while a deferred statement which uses it is pending",
+        "cannot declare `free` again while a deferred statement which uses it is pending",
+    ]);
+    assert!(lowered.errors.iter().all(|error| error.code == Some("E0016")));
+}
@@ -0,0 +1,104 @@
+//! Checks the conversion of references to contract objects, calls through them, and which contracts can be used
+//! as objects.
+
+use super::{lower, statements};
+
+/// Declares the contract `Shape`, which `Circle` applies and `Square` doesn't, and routines which take contract
+/// objects.
+const SHAPES: &str = "type Shape = contract {
+    val area <- routine(&Self) -> f64
+    val grow <- routine(&fluid Self, f64)
+}
+type Circle = struct { radius <- f64 }
+type Square = struct { side <- f64 }
+apply Shape to Circle {
+    val area = routine(self <- &Circle) -> f64 {
+        return self.radius
+    }
+    val grow = routine(self <- &fluid Circle, by <- f64) {
+        self.radius = by
+    }
+}
+val total = routine(shape <- &dyn Shape) -> f64 {
+    return shape.area()
+}
+val enlarge = routine(shape <- &fluid dyn Shape) {
+    shape.grow(2.0)
+}
+";
+
+#[test]
+fn upcasts_references_and_records_one_vtable_per_type_and_contract() {
+    let lowered = lower(&format!("{}val f = routine(c <- fluid Circle, r <- &Circle) {{
+    val a = total(&c)
+    val b = total(r)
+    enlarge(&fluid c)
+}}", SHAPES));
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(statements(&lowered, "f"), [
+        "total((&c as &dyn Shape))",
+        "total((r as &dyn Shape))",
+        "enlarge((&fluid c as &fluid dyn Shape))",
+    ]);
+
+    let vtables = lowered.module.vtables.iter().map(|(ty, contract)| format!("{} for {}", ty, contract)).collect::<Vec<_>>();
+    assert_eq!(vtables, ["Circle for Shape"]);
+}
+
+#[test]
+fn calls_methods_of_contract_objects_through_the_vtable() {
+    let lowered = lower(SHAPES);
+
+    assert_eq!(statements(&lowered, "enlarge"), ["dyn shape.grow(2.0)"]);
+}
+
+#[test]
+fn reports_values_which_cannot_be_upcast() {
+    let lowered = lower(&format!("{}val f = routine(c <- Circle, s <- Square, r <- &Circle) {{
+    val a = total(c)
+    val b = total(&s)
+    enlarge(r)
+    val d = r.area()
+}}
+val g = routine(shape <- &dyn Shape) {{
+    shape.grow(1.0)
+    val e = shape.perimeter()
+}}", SHAPES));
+
+    assert_eq!(lowered.errors(), [
+        "only references can be used as `&dyn Shape`, not `Circle`",
+        "`Square` does not apply `Shape`, so it cannot be used as `dyn Shape`",
+        "a reference that is not `fluid` cannot be used as `&fluid dyn Shape`",
+        "`Shape::grow` takes `&fluid Self`, but is called through a reference that is not `fluid`",
+        "`dyn Shape` has no method named `perimeter`",
+    ]);
+}
+
+#[test]
+fn reports_contract_objects_which_are_not_behind_a_reference() {
+    let lowered = lower("type Shape = contract {\n    val area <- routine(&Self) -> f64\n}\nval f = routine(shape <- dyn Shape) {}");
+
+    assert_eq!(lowered.errors(), ["contract objects have no size, so `dyn` types can only be used behind a reference"]);
+}
+
+#[test]
+fn reports_contracts_which_cannot_be_objects() {
+    let lowered = lower("type Circle = struct { radius <- f64 }
+type Shape = contract {
+    type Unit = f64
+    val sides <- i32
+    val scaled <- routine(Self, f64) -> f64
+    val copy <- routine(&Self) -> Self
+    val area <- routine(&Self) -> f64
+}
+val f = routine(shape <- &dyn Shape, circle <- &dyn Circle) {}");
+
+    assert_eq!(lowered.errors(), [
+        "`Shape` cannot be used as `dyn Shape`, because it declares the type `Unit`",
+        "`Shape` cannot be used as `dyn Shape`, because `sides` is not a routine",
+        "`Shape` cannot be used as `dyn Shape`, because `scaled` does not take `self` as `&Self` or `&fluid Self`",
+        "`Shape` cannot be used as `dyn Shape`, because `copy` refers to `Self` outside of its `self` argument",
+        "`dyn` can only be applied to contracts, but `Circle` is not one",
+    ]);
+}
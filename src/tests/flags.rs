@@ -0,0 +1,96 @@
+//! Checks the evaluation of flag expressions, and the flags set for a target and from the command line.
+
+use std::str::FromStr;
+
+use hail_parser::ast::RootStmnt;
+use target_lexicon::Triple;
+
+use crate::flags::Flags;
+
+/// Checks whether the provided flags, written before a declaration, are satisfied.
+fn eval(flags: &Flags, source: &str) -> bool {
+    let source = format!("{} val x = 1", source);
+    match &hail_parser::parse(&source).unwrap()[0] {
+        RootStmnt::Val(list, _) => flags.eval_all(list),
+        other => panic!("expected a declaration, found {:?}", other),
+    }
+}
+
+/// Creates the flags with the provided names set.
+fn names(names: &[&str]) -> Flags {
+    let mut flags = Flags::default();
+    for name in names {
+        flags.set(name);
+    }
+    flags
+}
+
+#[test]
+fn combines_flags_with_any_all_and_not() {
+    let flags = names(&["os_linux", "debug"]);
+
+    assert!(eval(&flags, "@any(os_windows, debug)"));
+    assert!(!eval(&flags, "@any(os_windows, os_macos)"));
+    assert!(eval(&flags, "@all(os_linux, debug)"));
+    assert!(!eval(&flags, "@all(os_linux, release)"));
+    assert!(eval(&flags, "@not(release)"));
+    assert!(!eval(&flags, "@not(debug)"));
+    assert!(!eval(&flags, "@!debug"));
+}
+
+#[test]
+fn nests_combinators() {
+    let flags = names(&["os_linux"]);
+
+    assert!(eval(&flags, "@any(os_windows, all(os_linux, not(debug)))"));
+    assert!(!eval(&flags, "@all(os_linux, any(debug, not(os_linux)))"));
+    assert!(eval(&flags, "@not(not(os_linux))"));
+}
+
+#[test]
+fn treats_empty_any_as_false_and_empty_all_as_true() {
+    let flags = Flags::default();
+
+    assert!(!eval(&flags, "@any()"));
+    assert!(eval(&flags, "@all()"));
+}
+
+#[test]
+fn sets_the_architecture_and_os_of_the_target() {
+    let x86_64 = Flags::for_target(&Triple::from_str("x86_64-unknown-linux-gnu").unwrap());
+    let aarch64 = Flags::for_target(&Triple::from_str("aarch64-apple-darwin").unwrap());
+
+    assert!(eval(&x86_64, "@target_arch(\"x86_64\")"));
+    assert!(eval(&x86_64, "@target_os(\"linux\")"));
+    assert!(!eval(&aarch64, "@target_arch(\"x86_64\")"));
+    assert!(eval(&aarch64, "@any(target_arch(\"x86_64\"), target_arch(\"aarch64\"))"));
+}
+
+#[test]
+fn sets_flags_from_arguments() {
+    let mut flags = Flags::default();
+    flags.set_arg("debug").unwrap();
+    flags.set_arg("opt_level=3").unwrap();
+    flags.set_arg("opt_level=2").unwrap();
+
+    assert!(eval(&flags, "@debug"));
+    assert!(eval(&flags, "@opt_level(\"2\")"));
+    assert!(!eval(&flags, "@opt_level(\"3\")"));
+}
+
+#[test]
+fn reports_arguments_without_a_key_or_value() {
+    let mut flags = Flags::default();
+
+    assert_eq!(flags.set_arg("=x"), Err("invalid flag `=x`, expected `name` or `key=value`".into()));
+    assert_eq!(flags.set_arg("key="), Err("invalid flag `key=`, expected `name` or `key=value`".into()));
+}
+
+#[test]
+fn unescapes_values() {
+    let mut flags = Flags::default();
+    flags.set_arg("vendor=a \"quoted\" name\\").unwrap();
+
+    assert!(eval(&flags, "@vendor(\"a \\\"quoted\\\" name\\\\\")"));
+    assert!(!eval(&flags, "@vendor(\"a \\\"quoted\\\" name\")"));
+}
@@ -0,0 +1,126 @@
+//! Checks how imports are resolved against inline modules and module files, and which items they can reach.
+
+use super::{lower, lower_files};
+use crate::hir;
+
+/// Lists the items imported into a module, as `name = path`.
+fn imports(module: &hir::Module) -> Vec<String> {
+    module.items.iter()
+        .filter_map(|item| match &item.kind {
+            hir::ItemKind::Import(path) => Some(format!("{} = {}", item.name, path.join("::"))),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds the inline module with the provided name.
+fn submodule<'m>(module: &'m hir::Module, name: &str) -> &'m hir::Module {
+    match module.item(name).map(|item| &item.kind) {
+        Some(hir::ItemKind::Module(module)) => module,
+        _ => panic!("`{}` is not a module", name),
+    }
+}
+
+#[test]
+fn glob_imports_bring_in_shared_items() {
+    let lowered = lower("module outer {
+    shared val a = 1
+    val hidden = 2
+    module inner {}
+}
+import * from outer
+import { a as b } from self::outer");
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(imports(&lowered.module), ["b = main::outer::a", "a = main::outer::a"]);
+}
+
+#[test]
+fn nested_modules_see_the_private_items_of_their_parents() {
+    let lowered = lower("module outer {
+    val hidden = 2
+    module inner {
+        import { hidden } from super
+    }
+}");
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(imports(submodule(submodule(&lowered.module, "outer"), "inner")), ["hidden = main::outer::hidden"]);
+}
+
+#[test]
+fn declared_items_shadow_glob_imports() {
+    let lowered = lower("module outer {\n    shared val a = 1\n}\nimport * from outer\nval a = 2");
+
+    assert!(imports(&lowered.module).is_empty());
+    assert!(matches!(lowered.module.item("a").unwrap().kind, hir::ItemKind::Val(..)));
+}
+
+#[test]
+fn reports_paths_which_do_not_resolve() {
+    let lowered = lower("module outer {\n    val hidden = 2\n}\nimport { hidden } from outer\nimport x from super\nimport y from nowhere");
+
+    assert_eq!(lowered.errors(), [
+        "`hidden` is private to module `main::outer`, mark it as `shared` to import it",
+        "`super` cannot refer above the root of `main`",
+        "unable to find module `nowhere`",
+    ]);
+}
+
+#[test]
+fn imports_shared_items_from_module_files() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { square, ORIGIN } from math\nimport { get } from net::http"),
+        ("math.hl", "shared val square = routine(x <- i32) -> i32 {\n    return x * x\n}\nshared val ORIGIN = 0"),
+        ("net.hl", ""),
+        ("net/http.hl", "shared val get = routine() {}"),
+    ]);
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(imports(&lowered.module), ["square = math::square", "ORIGIN = math::ORIGIN", "get = net::http::get"]);
+}
+
+#[test]
+fn items_of_module_files_are_private_by_default() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { square } from math"),
+        ("math.hl", "val square = routine(x <- i32) -> i32 {\n    return x * x\n}"),
+    ]);
+
+    assert_eq!(lowered.errors(), ["`square` is private to module `math`, mark it as `shared` to import it"]);
+}
+
+#[test]
+fn properties_of_imported_types_are_private_by_default() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { Point } from geo
+val f = routine(p <- Point, r <- &fluid Point) -> i32 {
+    val q = Point::{ x = 1, y = 2 }
+    r.y = 3
+    return p.x + p.y
+}"),
+        ("geo.hl", "shared type Point = struct { shared x <- i32, y <- i32 }"),
+    ]);
+
+    let message = "property `y` of `Point` is private to module `geo`, mark it as `shared` to use it";
+    assert_eq!(lowered.errors(), [message, message, message]);
+}
+
+#[test]
+fn nested_modules_see_the_private_properties_of_their_parents() {
+    let lowered = lower("module outer {
+    shared type Point = struct { x <- i32 }
+    module inner {
+        import { Point } from super
+        val f = routine(p <- Point) -> i32 {
+            return p.x
+        }
+    }
+}
+import { Point } from outer
+val g = routine(p <- Point) -> i32 {
+    return p.x
+}");
+
+    assert_eq!(lowered.errors(), ["property `x` of `Point` is private to module `main::outer`, mark it as `shared` to use it"]);
+}
@@ -0,0 +1,83 @@
+//! Checks the initializers of modules, the order that modules are initialized in, and changes to statics.
+
+use super::{lower, lower_files};
+use crate::hir::{Init, StmtKind};
+
+#[test]
+fn initializes_statics_modules_and_statements_in_order() {
+    let lowered = lower("val log = routine() {}
+val count <- fluid i32 = 0
+module inner {
+    val x = 1
+}
+log()
+count = 2");
+
+    let steps = lowered.module.init.iter()
+        .map(|init| match init {
+            Init::Static(name) => format!("static {}", name),
+            Init::Module(name) => format!("module {}", name),
+            Init::Stmt(stmt) => match stmt.kind {
+                StmtKind::Expr(_) => "call".into(),
+                StmtKind::Assign(..) => "assign".into(),
+                ref other => panic!("unexpected statement {:?}", other),
+            },
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    // `log` is a routine, which has nothing to initialize.
+    assert_eq!(steps, ["static count", "module inner", "call", "assign"]);
+}
+
+#[test]
+fn initializes_modules_after_the_modules_they_import() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { b } from b\nimport { a } from a"),
+        ("a.hl", "shared val a <- i32 = 1"),
+        ("b.hl", "import { a } from a\nshared val b <- i32 = 2"),
+    ]);
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(lowered.init_order, ["a", "b"]);
+}
+
+#[test]
+fn reports_cycles_of_modules_with_initializers() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { a } from a"),
+        ("a.hl", "import { b } from b\nshared val a <- i32 = 1"),
+        ("b.hl", "import { a } from a\nshared val b <- i32 = 2"),
+    ]);
+
+    assert_eq!(lowered.errors(), ["modules import each other, so they cannot be initialized in order: a -> b -> a"]);
+}
+
+#[test]
+fn allows_cycles_of_modules_without_initializers() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { A } from a"),
+        ("a.hl", "import { B } from b\nshared type A = struct { x <- i32 }"),
+        ("b.hl", "import { A } from a\nshared type B = struct { y <- i32 }"),
+    ]);
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+}
+
+#[test]
+fn reports_assignments_to_statics_which_are_not_fluid() {
+    let lowered = lower("val count <- i32 = 0\nval bump = routine() {\n    count = 1\n}");
+
+    assert_eq!(lowered.errors(), ["cannot assign to static `count`, its type is not `fluid`"]);
+}
+
+#[test]
+fn resolves_imports_of_the_unit_to_the_unit_itself() {
+    let lowered = lower_files(&[
+        ("main.hl", "import { A } from a\n#frobnicate\nshared type B = struct { x <- i32 }\nval f = routine(a <- A) -> i32 {\n    return a.b.x\n}"),
+        ("a.hl", "import { B } from main\nshared type A = struct { shared b <- B }"),
+    ]);
+
+    assert_eq!(lowered.errors(), ["unknown attribute `#frobnicate`"]);
+    assert_eq!(lowered.init_order, ["a"]);
+}
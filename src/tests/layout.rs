@@ -0,0 +1,105 @@
+//! Checks the layouts that `size_of` and `align_of` compute for different targets, the placement of struct
+//! properties, and the layouts of vtables.
+
+use std::str::FromStr;
+
+use target_lexicon::Triple;
+
+use super::{lower, lower_for};
+use crate::const_eval::Value;
+use crate::hir;
+use crate::layout::{Layout, Target};
+
+/// The C layout of a struct with a 64-bit property after a 32-bit one, as `size_of` and `align_of` compute it.
+const SOURCE: &str = "#repr(C)
+type Pair = struct { a <- u32, b <- f64 }
+const SIZE = size_of(Pair)
+const ALIGN = align_of(Pair)";
+
+/// Lowers a unit, returning the values of the integer constants `SIZE` and `ALIGN`.
+fn size_and_align(lowered: super::Lowered) -> (i128, i128) {
+    match (lowered.module.consts["SIZE"], lowered.module.consts["ALIGN"]) {
+        (Value::Int(size, _), Value::Int(align, _)) => (size, align),
+        other => panic!("expected integers, found {:?}", other),
+    }
+}
+
+#[test]
+fn aligns_64_bit_scalars_by_target() {
+    assert_eq!(size_and_align(lower(SOURCE)), (16, 8));
+    assert_eq!(size_and_align(lower_for(SOURCE, "i686-unknown-linux-gnu")), (12, 4));
+    assert_eq!(size_and_align(lower_for(SOURCE, "i686-pc-windows-msvc")), (16, 8));
+}
+
+/// Lowers a unit, returning the size and alignment of the type `S` for the default target, and each of its
+/// properties as `name offset`, followed by `:start+width` for bitfields.
+fn struct_layout(source: &str) -> (u64, u64, Vec<String>) {
+    let lowered = lower(source);
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+
+    let target = Target { pointer_size: 8, align_64: 8 };
+    let record = Layout::record(&lowered.module, &target, &hir::Type { span: 0..0, kind: hir::TypeKind::Name("S".into()) }).unwrap();
+    let fields = record.fields.iter()
+        .map(|field| match field.bits {
+            Some((start, width)) => format!("{} {}:{}+{}", field.name, field.offset, start, width),
+            None => format!("{} {}", field.name, field.offset),
+        })
+        .collect();
+    (record.layout.size, record.layout.align, fields)
+}
+
+#[test]
+fn reorders_properties_unless_the_struct_is_c_or_packed() {
+    let props = "struct { a <- u8, b <- u64, c <- u16 }";
+
+    assert_eq!(struct_layout(&format!("type S = {}", props)), (16, 8, vec!["a 10".into(), "b 0".into(), "c 8".into()]));
+    assert_eq!(struct_layout(&format!("#repr(C)\ntype S = {}", props)), (24, 8, vec!["a 0".into(), "b 8".into(), "c 16".into()]));
+    assert_eq!(struct_layout(&format!("#packed\ntype S = {}", props)), (11, 1, vec!["a 0".into(), "b 1".into(), "c 9".into()]));
+}
+
+#[test]
+fn raises_alignment_with_align() {
+    assert_eq!(struct_layout("#align(16)\ntype S = struct { a <- u8 }"), (16, 16, vec!["a 0".into()]));
+}
+
+#[test]
+fn packs_consecutive_bitfields_while_they_fit() {
+    let layout = struct_layout("type S = struct { a <- u8 : 3, b <- u8 : 5, c <- u8 : 1 }");
+
+    assert_eq!(layout, (2, 1, vec!["a 0:0+3".into(), "b 0:3+5".into(), "c 1:0+1".into()]));
+}
+
+/// Lays out the vtable of the contract `Shape` for the target with the provided triple, as `name offset` for each
+/// entry, after its size and alignment.
+fn vtable(triple: &str) -> (u64, u64, Vec<String>) {
+    let lowered = lower_for("type Shape = contract {
+    val area <- routine(&Self) -> f64
+    val grow <- routine(&fluid Self, f64)
+    val sides <- routine(&Self) -> i32
+}", triple);
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+
+    let contract = hir::Type { span: 0..0, kind: hir::TypeKind::Name("Shape".into()) };
+    let target = Target::of(&Triple::from_str(triple).unwrap());
+    let vtable = Layout::vtable(&lowered.module, &target, &contract).unwrap();
+    let entries = vtable.fields.iter().map(|field| format!("{} {}", field.name, field.offset)).collect();
+    (vtable.layout.size, vtable.layout.align, entries)
+}
+
+#[test]
+fn lays_out_vtables_as_size_and_alignment_followed_by_routines_in_order() {
+    assert_eq!(vtable("x86_64-unknown-linux-gnu"), (40, 8, vec!["size 0".into(), "align 8".into(), "area 16".into(), "grow 24".into(), "sides 32".into()]));
+    assert_eq!(vtable("i686-unknown-linux-gnu"), (20, 4, vec!["size 0".into(), "align 4".into(), "area 8".into(), "grow 12".into(), "sides 16".into()]));
+}
+
+#[test]
+fn sizes_references_to_contract_objects_as_two_pointers() {
+    let source = "type Shape = contract {
+    val area <- routine(&Self) -> f64
+}
+type Holder = struct { shape <- &dyn Shape }
+const X = size_of(Holder)";
+
+    assert!(matches!(lower(source).module.consts["X"], Value::Int(16, _)));
+    assert!(matches!(lower_for(source, "i686-unknown-linux-gnu").module.consts["X"], Value::Int(8, _)));
+}
@@ -0,0 +1,113 @@
+//! Checks how method calls and `T::item` resolve through `apply`, and how the receiver is adjusted to the method.
+
+use super::{lower, statements};
+
+/// Declares `Point` with a method for each kind of receiver, a routine without `self`, and a property holding a
+/// routine.
+const POINT: &str = "type Point = struct { x <- i32, y <- i32, scale <- routine(i32) -> i32 }
+apply Point {
+    val len = routine(self <- Point) -> i32 {
+        return self.x
+    }
+    val peek = routine(self <- &Point) -> i32 {
+        return self.y
+    }
+    val bump = routine(self <- &fluid Point) {
+        self.x = 1
+    }
+    val zero = routine() -> i32 {
+        return 0
+    }
+}
+";
+
+#[test]
+fn references_and_dereferences_receivers_to_match_the_method() {
+    let lowered = lower(&format!("{}val f = routine(p <- Point, r <- &Point, m <- &fluid Point) {{
+    val a = p.len()
+    val b = p.peek()
+    p.bump()
+    val c = r.len()
+    val d = r.peek()
+    m.bump()
+    val e = m.peek()
+}}", POINT));
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(statements(&lowered, "f"), [
+        "Point::len(p)",
+        "Point::peek(&p)",
+        "Point::bump(&fluid p)",
+        "Point::len(*r)",
+        "Point::peek(r)",
+        "Point::bump(m)",
+        "Point::peek(m)",
+    ]);
+}
+
+#[test]
+fn calls_properties_holding_routines_before_methods() {
+    let lowered = lower(&format!("{}val f = routine(p <- Point) {{\n    val a = p.scale(2)\n    val b = Point::zero()\n}}", POINT));
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(statements(&lowered, "f"), ["p.scale(2)", "Point::zero()"]);
+}
+
+#[test]
+fn keeps_the_first_of_two_methods_with_the_same_name() {
+    let lowered = lower("type Shape = contract {
+    val len <- routine(&Self) -> i32
+}
+type Line = struct { a <- i32 }
+apply Line {
+    val len = routine(self <- Line) -> i32 {
+        return self.a
+    }
+}
+apply Shape to Line {
+    val len = routine(self <- &Line) -> i32 {
+        return 0
+    }
+}
+val f = routine(l <- Line) {
+    val a = l.len()
+}");
+
+    assert_eq!(lowered.errors(), ["`Line` already has an item named `len` applied to it"]);
+    // the call is not ambiguous, it calls the method applied first, which takes `self` by value.
+    assert_eq!(statements(&lowered, "f"), ["Line::len(l)"]);
+}
+
+#[test]
+fn reports_methods_which_cannot_be_called() {
+    let lowered = lower(&format!("{}val origin <- Point = Point::{{ x = 0, y = 0 }}
+val f = routine(p <- Point, r <- &Point) {{
+    r.bump()
+    val a = p.zero()
+    val b = p.missing()
+    val c = Point::nothing
+    origin.bump()
+}}", POINT));
+
+    assert_eq!(lowered.errors(), [
+        "`Point::bump` takes `&fluid Point`, but is called through a reference that is not `fluid`",
+        "`Point::zero` has no `self` argument, so it must be called as `Point::zero(..)`",
+        "`Point` has no method named `missing`",
+        "`Point` has no item named `nothing`",
+        "`Point::bump` changes the static `origin`, but its type is not `fluid`",
+    ]);
+}
+
+#[test]
+fn reports_receivers_of_other_types() {
+    let lowered = lower("type Point = struct { x <- i32 }\napply Point {\n    val len = routine(self <- &i32) -> i32 {\n        return 0\n    }\n}");
+
+    assert_eq!(lowered.errors(), ["`self` must be `Point`, `&Point` or `&fluid Point`, not `&i32`"]);
+}
+
+#[test]
+fn reports_applications_of_types_which_are_not_contracts() {
+    let lowered = lower("type From = struct { x <- i32 }\ntype Meters = struct { value <- i32 }\napply From to Meters {}");
+
+    assert_eq!(lowered.errors(), ["`From` is not a contract, so it cannot be applied to `Meters`"]);
+}
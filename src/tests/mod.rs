@@ -0,0 +1,153 @@
+//! Tests of lowering, which lower hail source to HIR and check the module and the diagnostics that come out of it.
+
+mod asm;
+mod attrs;
+mod closures;
+mod codes;
+mod const_eval;
+mod defer;
+mod dyns;
+mod flags;
+mod imports;
+mod init;
+mod layout;
+mod methods;
+mod operators;
+mod results;
+mod unions;
+mod variadics;
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use hail_parser::ast::{AssignOp, UnaryOp};
+use hail_parser::diagnostics::{Diagnostic, Files};
+use target_lexicon::Triple;
+
+use crate::flags::Flags;
+use crate::hir;
+use crate::hir_lower::{self, HirLowerContext, HirLowerUnit};
+use crate::layout::Target;
+
+/// The target that units are lowered for unless a test picks another.
+const TRIPLE: &str = "x86_64-unknown-linux-gnu";
+
+/// A unit which has been lowered, with the diagnostics reported while lowering it.
+pub struct Lowered {
+    /// The module of the unit.
+    pub module: hir::Module,
+
+    /// The names of the loaded modules, in the order that they are initialized.
+    pub init_order: Vec<String>,
+
+    /// The errors reported while lowering.
+    pub errors: Vec<Diagnostic>,
+
+    /// The warnings reported while lowering.
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl Lowered {
+    /// The messages of the errors, in the order they were reported.
+    pub fn errors(&self) -> Vec<&str> {
+        self.errors.iter().map(|error| error.message.as_str()).collect()
+    }
+
+    /// The messages of the warnings, in the order they were reported.
+    pub fn warnings(&self) -> Vec<&str> {
+        self.warnings.iter().map(|warning| warning.message.as_str()).collect()
+    }
+
+    /// Finds the routine declared as a `val` with the provided name.
+    pub fn routine(&self, name: &str) -> &hir::Routine {
+        match self.module.item(name).map(|item| &item.kind) {
+            Some(hir::ItemKind::Val(_, Some(hir::Expr { kind: hir::ExprKind::Routine(routine), .. }))) => routine,
+            _ => panic!("`{}` is not a routine", name),
+        }
+    }
+}
+
+/// Lists the statements of the routine with the provided name, writing each expression back out with [`show`],
+/// each `val` as the value it is declared with, and each assignment as `place = value`.
+pub fn statements(lowered: &Lowered, routine: &str) -> Vec<String> {
+    lowered.routine(routine).block.stmts.iter()
+        .map(|stmt| match &stmt.kind {
+            hir::StmtKind::Expr(expr) | hir::StmtKind::Val(_, _, Some(expr)) => show(expr),
+            hir::StmtKind::Assign(AssignOp::Eq, left, right) => format!("{} = {}", show(left), show(right)),
+            other => panic!("unexpected statement {:?}", other),
+        })
+        .collect()
+}
+
+/// Writes an expression back out in the form of hail source, so that tests can check what it was lowered to.
+pub fn show(expr: &hir::Expr) -> String {
+    let list = |exprs: &[hir::Expr]| exprs.iter().map(show).collect::<Vec<_>>().join(", ");
+    match &expr.kind {
+        hir::ExprKind::Bool(value) => value.to_string(),
+        hir::ExprKind::Num(_, value) | hir::ExprKind::Str(value) | hir::ExprKind::Name(value) => value.clone(),
+        hir::ExprKind::Path(left, right) => format!("{}::{}", show(left), right),
+        hir::ExprKind::Access(left, right) => format!("{}.{}", show(left), right),
+        hir::ExprKind::Call(callee, args) => format!("{}({})", show(callee), list(args)),
+        hir::ExprKind::Unary(op, subject) => {
+            let op = match op {
+                UnaryOp::Min => "-",
+                UnaryOp::Star => "*",
+                UnaryOp::Bang => "!",
+                UnaryOp::Borrow => "&",
+                UnaryOp::Fluid => "fluid ",
+                UnaryOp::Shared => "shared ",
+            };
+
+            format!("{}{}", op, show(subject))
+        },
+        hir::ExprKind::Binary(op, left, right) => format!("({} {:?} {})", show(left), op, show(right)),
+        hir::ExprKind::Index(subject, index) => format!("{}[{}]", show(subject), show(index)),
+        hir::ExprKind::Upcast(subject, ty) => format!("({} as {})", show(subject), ty),
+        hir::ExprKind::Dispatch(object, method, args) => format!("dyn {}.{}({})", show(object), method, list(args)),
+        other => panic!("cannot show {:?}", other),
+    }
+}
+
+/// Lowers a unit for the default target.
+pub fn lower(source: &str) -> Lowered {
+    lower_for(source, TRIPLE)
+}
+
+/// Lowers a unit for the target with the provided triple.
+pub fn lower_for(source: &str, triple: &str) -> Lowered {
+    lower_unit(PathBuf::from("./"), source, triple)
+}
+
+/// Writes the provided files to a new directory and lowers `main.hl` from it, so that it can import the others.
+pub fn lower_files(files: &[(&str, &str)]) -> Lowered {
+    static DIRS: AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::temp_dir().join(format!("hail-test-{}-{}", std::process::id(), DIRS.fetch_add(1, Ordering::Relaxed)));
+    for (name, source) in files {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+
+    let source = std::fs::read_to_string(dir.join("main.hl")).unwrap();
+    let lowered = lower_unit(dir.clone(), &source, TRIPLE);
+    std::fs::remove_dir_all(dir).unwrap();
+    lowered
+}
+
+/// Parses and lowers the unit `main` stored in the provided directory.
+fn lower_unit(dir: PathBuf, source: &str, triple: &str) -> Lowered {
+    let triple = Triple::from_str(triple).unwrap();
+    let mut files = Files::new();
+    let file = files.add("main.hl", source);
+    let ast = hail_parser::parse(source).unwrap_or_else(|e| panic!("{}", e.with_file(file).render(&files, false)));
+
+    let mut ctx = HirLowerContext::new(Flags::for_target(&triple), Target::of(&triple), vec![], files, file);
+    let unit = HirLowerUnit { name: "main".into(), dir: dir.to_string_lossy().into(), ast };
+    let module = hir_lower::hir_lower(&mut ctx, &unit);
+
+    let init_order = ctx.init_order.iter().map(|file| ctx.modules[file].name.clone()).collect();
+
+    Lowered { module, init_order, errors: ctx.errors, warnings: ctx.warnings }
+}
@@ -0,0 +1,118 @@
+//! Checks that operators on types which apply the built in operator contracts call the contracts' routines.
+
+use super::{lower, statements};
+
+/// Declares `Money`, which applies the arithmetic and comparison contracts, and `Ledger`, which applies indexing and
+/// a compound assignment.
+const MONEY: &str = "type Money = struct { cents <- i64 }
+apply Add to Money {
+    val add = routine(self <- Money, other <- Money) -> Money {
+        return self
+    }
+}
+apply Neg to Money {
+    val neg = routine(self <- Money) -> Money {
+        return self
+    }
+}
+apply Eq to Money {
+    val eq = routine(self <- &Money, other <- Money) -> bool {
+        return true
+    }
+}
+apply Ord to Money {
+    val cmp = routine(self <- &Money, other <- Money) -> i32 {
+        return 0
+    }
+}
+type Ledger = struct { first <- Money }
+apply Index to Ledger {
+    val index = routine(self <- &Ledger, at <- i32) -> &Money {
+        return &self.first
+    }
+}
+apply IndexFluid to Ledger {
+    val index_fluid = routine(self <- &fluid Ledger, at <- i32) -> &fluid Money {
+        return &fluid self.first
+    }
+}
+apply AddAssign to Ledger {
+    val add_assign = routine(self <- &fluid Ledger, other <- Money) {}
+}
+";
+
+#[test]
+fn calls_the_routines_of_applied_contracts() {
+    let lowered = lower(&format!("{}val f = routine(a <- fluid Money, b <- Money, l <- fluid Ledger) {{
+    val sum = a + b
+    val neg = -a
+    val eq = a == b
+    val ne = a != b
+    val lt = a < b
+    val first = l[0]
+    l[1] = b
+    l += b
+    a += b
+}}", MONEY));
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(statements(&lowered, "f"), [
+        "Money::add(a, b)",
+        "Money::neg(a)",
+        "Money::eq(&a, b)",
+        "!Money::eq(&a, b)",
+        "(Money::cmp(&a, b) Lt 0)",
+        "*Ledger::index(&l, 0)",
+        "*Ledger::index_fluid(&fluid l, 1) = b",
+        "Ledger::add_assign(&fluid l, b)",
+        // without `AddAssign`, a compound assignment falls back to the binary operator.
+        "a = Money::add(a, b)",
+    ]);
+}
+
+#[test]
+fn keeps_the_built_in_meaning_of_operators_on_primitives_and_enums() {
+    let lowered = lower("type Color = enum { Red, Green }
+val f = routine(x <- i32, c <- Color) {
+    val a = x + 1
+    val b = c == Color::Red
+}");
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(statements(&lowered, "f"), ["(x Plus 1)", "(c EqEq Color::Red)"]);
+}
+
+#[test]
+fn reports_operators_on_types_which_do_not_apply_their_contract() {
+    let lowered = lower("type Plain = struct { v <- i32 }
+type Color = enum { Red, Green }
+val f = routine(p <- fluid Plain, c <- Color) {
+    val a = p + p
+    val b = -p
+    val d = p == p
+    val e = p[0]
+    p += p
+    val g = c + c
+}");
+
+    assert_eq!(lowered.errors(), [
+        "`Plain` does not apply `Add`, so the operator cannot be used on it",
+        "`Plain` does not apply `Neg`, so the operator cannot be used on it",
+        "`Plain` does not apply `Eq`, so the operator cannot be used on it",
+        "`Plain` does not apply `Index`, so the operator cannot be used on it",
+        "`Plain` does not apply `AddAssign`, so the operator cannot be used on it",
+        "`Color` does not apply `Add`, so the operator cannot be used on it",
+    ]);
+}
+
+#[test]
+fn reports_applications_without_the_contracts_routine() {
+    let lowered = lower("type Money = struct { cents <- i64 }
+apply Add to Money {
+    val plus = routine(self <- Money, other <- Money) -> Money {
+        return self
+    }
+}");
+
+    assert_eq!(lowered.errors(), ["applications of `Add` must declare `val add = routine(self <- Money, other <- ..)`"]);
+}
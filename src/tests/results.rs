@@ -0,0 +1,152 @@
+//! Checks the lowering of options and results: their constructors, `?`, `??`, and conversions through `From`.
+
+use super::lower;
+use crate::hir::{Expr, ExprKind, StmtKind};
+use hail_parser::ast::BinaryOp;
+
+/// Declares two error types which convert to `AppError`, and routines which fail with each of them.
+const ERRORS: &str = "type IoError = struct { code <- i32 }
+type ParseError = struct { line <- i32 }
+type AppError = struct { message <- i32 }
+val read = routine() -> !i32:IoError {
+    return ok(1)
+}
+val parse = routine() -> !i32:ParseError {
+    return ok(2)
+}
+";
+
+/// Lists the values of the locals declared at the top of the block of the routine `f`.
+fn locals(source: &str) -> Vec<Expr> {
+    let lowered = lower(source);
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+
+    lowered.routine("f").block.stmts.iter()
+        .filter_map(|stmt| match &stmt.kind {
+            StmtKind::Val(_, _, Some(value)) => Some(value.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The name of the routine that a `?` converts its error with, as `Type::routine`.
+fn converted_with(expr: &Expr) -> Option<String> {
+    match &expr.kind {
+        ExprKind::Try(_, exit) => exit.convert.as_ref().map(|convert| match &convert.kind {
+            ExprKind::Path(ty, name) => match &ty.kind {
+                ExprKind::Name(ty) => format!("{}::{}", ty, name),
+                other => panic!("unexpected type {:?}", other),
+            },
+            other => panic!("unexpected conversion {:?}", other),
+        }),
+        other => panic!("expected `?`, found {:?}", other),
+    }
+}
+
+#[test]
+fn lowers_constructors_and_unwrap_or() {
+    let values = locals("val f = routine(x <- ?i32) {
+    val a <- !i32:i32 = ok(1)
+    val b <- !i32:i32 = err(2)
+    val c <- ?i32 = none
+    val d = x ?? 0
+}");
+
+    assert!(matches!(values[0].kind, ExprKind::Ok(_)));
+    assert!(matches!(values[1].kind, ExprKind::Err(_)));
+    assert!(matches!(values[2].kind, ExprKind::None));
+    assert!(matches!(values[3].kind, ExprKind::Binary(BinaryOp::QuestQuest, ..)));
+}
+
+#[test]
+fn converts_errors_through_each_applied_from() {
+    let source = format!("{}apply From to AppError {{
+    val convert = routine(error <- IoError) -> AppError {{
+        return AppError::{{ message = error.code }}
+    }}
+}}
+apply From to AppError {{
+    val convert = routine(error <- ParseError) -> AppError {{
+        return AppError::{{ message = error.line }}
+    }}
+}}
+val f = routine() -> !i32:AppError {{
+    val a = read()?
+    val b = parse()?
+    return ok(a + b)
+}}", ERRORS);
+
+    let values = locals(&source);
+    assert_eq!(converted_with(&values[0]).as_deref(), Some("AppError::convert#IoError"));
+    assert_eq!(converted_with(&values[1]).as_deref(), Some("AppError::convert#ParseError"));
+
+    let lowered = lower(&source);
+    let names = lowered.module.applies.iter().map(|apply| apply.items[0].name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["convert#IoError", "convert#ParseError"]);
+}
+
+#[test]
+fn does_not_convert_matching_errors() {
+    let values = locals(&format!("{}val f = routine() -> !i32:IoError {{\n    val a = read()?\n    return ok(a)\n}}", ERRORS));
+
+    assert_eq!(converted_with(&values[0]), None);
+}
+
+#[test]
+fn reports_conversions_which_are_applied_twice_or_missing() {
+    let lowered = lower(&format!("{}apply From to AppError {{
+    val convert = routine(error <- IoError) -> AppError {{
+        return AppError::{{ message = error.code }}
+    }}
+}}
+apply From to AppError {{
+    val convert = routine(error <- IoError) -> AppError {{
+        return AppError::{{ message = 0 }}
+    }}
+}}
+val f = routine() -> !i32:AppError {{
+    val b = parse()?
+    return ok(b)
+}}", ERRORS));
+
+    assert_eq!(lowered.errors(), [
+        "`From` is already applied to `AppError` for `IoError`",
+        "cannot convert `ParseError` to `AppError`, as `From` is not applied to `AppError` for it",
+    ]);
+}
+
+#[test]
+fn reports_misplaced_try() {
+    let lowered = lower("val f = routine(x <- ?i32) -> !i32:i32 {
+    val a = x?
+    return ok(a)
+}
+val g = routine(x <- ?i32) {
+    val a = x?
+}
+val h = routine(x <- !i32:i32) -> ?i32 {
+    defer {
+        val b = x?
+    }
+    val a = x?
+    return none
+}");
+
+    assert_eq!(lowered.errors(), [
+        "cannot use `?` on an option in a routine that returns a result",
+        "`?` can only be used in a routine that returns `?T` or `!T:E`",
+        "cannot use `?` in a deferred statement, as it may return",
+        "cannot use `?` on a result in a routine that returns an option",
+    ]);
+}
+
+#[test]
+fn ends_statements_after_a_trailing_try() {
+    let lowered = lower(&format!("{}val f = routine() -> !i32:IoError {{\n    val a = read()?\n    val b = a\n    return ok(b)\n}}", ERRORS));
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+
+    // statements have to be separated, so this only parses if a semicolon is inserted after `?`.
+    let stmts = &lowered.routine("f").block.stmts;
+    assert!(matches!(&stmts[0].kind, StmtKind::Val(_, _, Some(Expr { kind: ExprKind::Try(..), .. }))));
+    assert!(matches!(&stmts[1].kind, StmtKind::Val(name, ..) if name == "b"));
+}
@@ -0,0 +1,72 @@
+//! Checks the layout of unions, and the warning for reading a property of a union, which reinterprets the memory
+//! of the property written.
+
+use std::str::FromStr;
+
+use target_lexicon::Triple;
+
+use super::{lower, lower_for};
+use crate::hir;
+use crate::layout::{Layout, Target};
+
+/// Declares the union `Bits`, whose property `raw` is marked as meant to be punned.
+const BITS: &str = "type Bits = union { float <- f32, int <- u32, #pun raw <- u32 }
+type Pair = struct { a <- u32 }
+";
+
+#[test]
+fn warns_about_reading_properties_of_unions() {
+    let lowered = lower(&format!("{}val f = routine(b <- fluid Bits, r <- &Bits, p <- Pair) {{
+    b.float = 1.0
+    val x = b.int
+    val y = r.float
+    val z = p.a
+}}", BITS));
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(lowered.warnings(), [
+        "reading `int` from union `Bits` reinterprets its memory, mark the property or declaration with `#pun` if this is intentional",
+        "reading `float` from union `Bits` reinterprets its memory, mark the property or declaration with `#pun` if this is intentional",
+    ]);
+}
+
+#[test]
+fn does_not_warn_about_reads_marked_with_pun() {
+    let lowered = lower(&format!("{}val f = routine(b <- Bits) {{
+    val x = b.raw
+    #pun
+    val y = b.int
+}}
+#pun
+val g = routine(b <- Bits) -> u32 {{
+    return b.int
+}}", BITS));
+
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+    assert_eq!(lowered.warnings(), Vec::<&str>::new());
+}
+
+/// Lowers a unit, returning the size and alignment of the type `U` and the offsets of its properties.
+fn union_layout(source: &str, triple: &str) -> (u64, u64, Vec<u64>) {
+    let lowered = lower_for(source, triple);
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+
+    let target = Target::of(&Triple::from_str(triple).unwrap());
+    let record = Layout::record(&lowered.module, &target, &hir::Type { span: 0..0, kind: hir::TypeKind::Name("U".into()) }).unwrap();
+    (record.layout.size, record.layout.align, record.fields.iter().map(|field| field.offset).collect())
+}
+
+#[test]
+fn lays_out_unions_as_their_largest_property() {
+    let source = "type U = union { small <- u8, wide <- u64, mid <- u16 }";
+
+    assert_eq!(union_layout(source, "x86_64-unknown-linux-gnu"), (8, 8, vec![0, 0, 0]));
+    assert_eq!(union_layout(source, "i686-unknown-linux-gnu"), (8, 4, vec![0, 0, 0]));
+}
+
+#[test]
+fn rounds_unions_up_to_their_strictest_alignment() {
+    let source = "type Three = struct { a <- u8, b <- u8, c <- u8 }\ntype U = union { bytes <- Three, half <- u16 }";
+
+    assert_eq!(union_layout(source, "x86_64-unknown-linux-gnu"), (4, 2, vec![0, 0]));
+}
@@ -0,0 +1,42 @@
+//! Checks how arguments are passed through C and hail variadics.
+
+use super::lower;
+use crate::hir::{ExprKind, StmtKind};
+
+/// Lowers a call to `printf` with the provided declarations in scope, returning the types that each variadic
+/// argument is cast to by C's default argument promotions, or `None` where it is passed as it is.
+fn promotions(decls: &str, args: &str) -> Vec<Option<String>> {
+    let source = format!("val printf <- routine(&u8, ...) -> i32\nval main = routine() {{\n{}\nprintf(\"\", {})\n}}", decls, args);
+    let lowered = lower(&source);
+    assert_eq!(lowered.errors(), Vec::<&str>::new());
+
+    let call = match &lowered.routine("main").block.stmts.last().unwrap().kind {
+        StmtKind::Expr(expr) => expr,
+        other => panic!("expected a call, found {:?}", other),
+    };
+
+    match &call.kind {
+        ExprKind::Call(_, args) => args[1..]
+            .iter()
+            .map(|arg| match &arg.kind {
+                ExprKind::As(_, ty) => Some(ty.to_string()),
+                _ => None,
+            })
+            .collect(),
+        other => panic!("expected a call, found {:?}", other),
+    }
+}
+
+#[test]
+fn promotes_small_arguments() {
+    let promoted = promotions("val a <- u8 = 1\nval b <- f32 = 1.0\nval c <- i64 = 1", "a, b, c");
+
+    assert_eq!(promoted, [Some("i32".into()), Some("f64".into()), None]);
+}
+
+#[test]
+fn promotes_arguments_through_modifiers() {
+    let promoted = promotions("val a <- fluid u8 = 1\nval b <- shared f32 = 1.0\nval c <- fluid bool = true", "a, b, c");
+
+    assert_eq!(promoted, [Some("i32".into()), Some("f64".into()), Some("i32".into())]);
+}